@@ -14,6 +14,8 @@ use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::FileChange;
 use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::protocol::ReviewExportFormat;
+use codex_protocol::protocol::ReviewOutputEvent;
 use codex_protocol::protocol::SandboxPolicy;
 use codex_protocol::protocol::TurnAbortReason;
 use paste::paste;
@@ -94,6 +96,12 @@ client_request_definitions! {
         params: ListConversationsParams,
         response: ListConversationsResponse,
     },
+    /// Full-text search across recorded conversations: user and agent
+    /// messages, commands run and files patched.
+    SearchConversations {
+        params: SearchConversationsParams,
+        response: SearchConversationsResponse,
+    },
     /// Resume a recorded Codex conversation from a rollout file.
     ResumeConversation {
         params: ResumeConversationParams,
@@ -127,6 +135,12 @@ client_request_definitions! {
         params: GitDiffToRemoteParams,
         response: GitDiffToRemoteResponse,
     },
+    /// Render review findings (from an `exited_review_mode` event) as JSON,
+    /// SARIF, GitHub workflow annotations or a markdown report.
+    ExportReview {
+        params: ExportReviewParams,
+        response: ExportReviewResponse,
+    },
     LoginApiKey {
         params: LoginApiKeyParams,
         response: LoginApiKeyResponse,
@@ -290,6 +304,59 @@ pub struct ListConversationsResponse {
     pub next_cursor: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchConversationsParams {
+    /// Terms that must all appear in a conversation; double quotes group a phrase.
+    pub query: String,
+    /// Only match these parts of a conversation; defaults to all of them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kinds: Option<Vec<ConversationSearchHitKind>>,
+    /// Maximum number of conversations returned; defaults to a reasonable server-side value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+pub enum ConversationSearchHitKind {
+    UserMessage,
+    AgentMessage,
+    Command,
+    FilePatch,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationSearchHit {
+    pub kind: ConversationSearchHitKind,
+    /// Single-line excerpt around the match.
+    pub snippet: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationSearchResult {
+    pub conversation_id: ConversationId,
+    pub path: PathBuf,
+    pub preview: String,
+    /// RFC3339 timestamp string for the session start, if available.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    /// RFC3339 timestamp string for the most recent activity, if available.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    /// The best matching parts of the conversation, most relevant first.
+    pub hits: Vec<ConversationSearchHit>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchConversationsResponse {
+    /// Matching conversations, best matches first.
+    pub items: Vec<ConversationSearchResult>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct ResumeConversationParams {
@@ -363,6 +430,22 @@ pub struct GitDiffToRemoteParams {
 #[serde(rename_all = "camelCase")]
 pub struct CancelLoginChatGptResponse {}
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportReviewParams {
+    pub review: ReviewOutputEvent,
+    pub format: ReviewExportFormat,
+    /// Directory the review ran in. File paths inside its Git repository (or
+    /// inside `cwd` when it is not in one) are written relative to it.
+    pub cwd: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportReviewResponse {
+    pub content: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct LogoutChatGptParams {}
@@ -526,6 +609,8 @@ pub struct SandboxSettings {
     pub writable_roots: Vec<PathBuf>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub network_access: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub network_allowlist: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exclude_tmpdir_env_var: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
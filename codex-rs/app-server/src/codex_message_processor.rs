@@ -11,11 +11,16 @@ use codex_app_server_protocol::ArchiveConversationParams;
 use codex_app_server_protocol::ArchiveConversationResponse;
 use codex_app_server_protocol::AuthStatusChangeNotification;
 use codex_app_server_protocol::ClientRequest;
+use codex_app_server_protocol::ConversationSearchHit;
+use codex_app_server_protocol::ConversationSearchHitKind;
+use codex_app_server_protocol::ConversationSearchResult;
 use codex_app_server_protocol::ConversationSummary;
 use codex_app_server_protocol::ExecCommandApprovalParams;
 use codex_app_server_protocol::ExecCommandApprovalResponse;
 use codex_app_server_protocol::ExecOneOffCommandParams;
 use codex_app_server_protocol::ExecOneOffCommandResponse;
+use codex_app_server_protocol::ExportReviewParams;
+use codex_app_server_protocol::ExportReviewResponse;
 use codex_app_server_protocol::FuzzyFileSearchParams;
 use codex_app_server_protocol::FuzzyFileSearchResponse;
 use codex_app_server_protocol::GetUserAgentResponse;
@@ -38,6 +43,8 @@ use codex_app_server_protocol::RemoveConversationSubscriptionResponse;
 use codex_app_server_protocol::RequestId;
 use codex_app_server_protocol::Result as JsonRpcResult;
 use codex_app_server_protocol::ResumeConversationParams;
+use codex_app_server_protocol::SearchConversationsParams;
+use codex_app_server_protocol::SearchConversationsResponse;
 use codex_app_server_protocol::SendUserMessageParams;
 use codex_app_server_protocol::SendUserMessageResponse;
 use codex_app_server_protocol::SendUserTurnParams;
@@ -56,7 +63,9 @@ use codex_core::Cursor as RolloutCursor;
 use codex_core::INTERACTIVE_SESSION_SOURCES;
 use codex_core::NewConversation;
 use codex_core::RolloutRecorder;
+use codex_core::SearchHitKind;
 use codex_core::SessionMeta;
+use codex_core::SessionSearchQuery;
 use codex_core::auth::CLIENT_ID;
 use codex_core::auth::get_auth_file;
 use codex_core::auth::login_with_api_key;
@@ -72,6 +81,7 @@ use codex_core::default_client::get_codex_user_agent;
 use codex_core::exec::ExecParams;
 use codex_core::exec_env::create_env;
 use codex_core::get_platform_sandbox;
+use codex_core::git_info::get_git_repo_root;
 use codex_core::git_info::git_diff_to_remote;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::Event;
@@ -80,6 +90,7 @@ use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::InputItem as CoreInputItem;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
+use codex_core::review_format::export_review;
 use codex_login::ServerOptions as LoginServerOptions;
 use codex_login::ShutdownHandle;
 use codex_login::run_login_server;
@@ -167,6 +178,9 @@ impl CodexMessageProcessor {
             ClientRequest::ListConversations { request_id, params } => {
                 self.handle_list_conversations(request_id, params).await;
             }
+            ClientRequest::SearchConversations { request_id, params } => {
+                self.handle_search_conversations(request_id, params).await;
+            }
             ClientRequest::ResumeConversation { request_id, params } => {
                 self.handle_resume_conversation(request_id, params).await;
             }
@@ -191,6 +205,9 @@ impl CodexMessageProcessor {
             ClientRequest::GitDiffToRemote { request_id, params } => {
                 self.git_diff_to_origin(request_id, params.cwd).await;
             }
+            ClientRequest::ExportReview { request_id, params } => {
+                self.export_review(request_id, params).await;
+            }
             ClientRequest::LoginApiKey { request_id, params } => {
                 self.login_api_key(request_id, params).await;
             }
@@ -745,6 +762,80 @@ impl CodexMessageProcessor {
         self.outgoing.send_response(request_id, response).await;
     }
 
+    async fn handle_search_conversations(
+        &self,
+        request_id: RequestId,
+        params: SearchConversationsParams,
+    ) {
+        let SearchConversationsParams {
+            query,
+            kinds,
+            limit,
+        } = params;
+        let query = SessionSearchQuery {
+            text: query,
+            kinds: kinds
+                .unwrap_or_default()
+                .into_iter()
+                .map(|kind| match kind {
+                    ConversationSearchHitKind::UserMessage => SearchHitKind::UserMessage,
+                    ConversationSearchHitKind::AgentMessage => SearchHitKind::AgentMessage,
+                    ConversationSearchHitKind::Command => SearchHitKind::Command,
+                    ConversationSearchHitKind::FilePatch => SearchHitKind::FilePatch,
+                })
+                .collect(),
+            limit: limit.unwrap_or(25),
+            allowed_sources: INTERACTIVE_SESSION_SOURCES.to_vec(),
+        };
+
+        let results =
+            match RolloutRecorder::search_conversations(&self.config.codex_home, query).await {
+                Ok(results) => results,
+                Err(err) => {
+                    let error = JSONRPCErrorError {
+                        code: INTERNAL_ERROR_CODE,
+                        message: format!("failed to search conversations: {err}"),
+                        data: None,
+                    };
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+            };
+
+        let items = results
+            .into_iter()
+            .filter_map(|result| {
+                Some(ConversationSearchResult {
+                    conversation_id: result.conversation_id?,
+                    path: result.path,
+                    preview: result.preview.unwrap_or_default(),
+                    timestamp: result.created_at,
+                    updated_at: result.updated_at,
+                    hits: result
+                        .hits
+                        .into_iter()
+                        .map(|hit| ConversationSearchHit {
+                            kind: match hit.kind {
+                                SearchHitKind::UserMessage => {
+                                    ConversationSearchHitKind::UserMessage
+                                }
+                                SearchHitKind::AgentMessage => {
+                                    ConversationSearchHitKind::AgentMessage
+                                }
+                                SearchHitKind::Command => ConversationSearchHitKind::Command,
+                                SearchHitKind::FilePatch => ConversationSearchHitKind::FilePatch,
+                            },
+                            snippet: hit.snippet,
+                        })
+                        .collect(),
+                })
+            })
+            .collect();
+
+        let response = SearchConversationsResponse { items };
+        self.outgoing.send_response(request_id, response).await;
+    }
+
     async fn handle_resume_conversation(
         &self,
         request_id: RequestId,
@@ -1208,6 +1299,29 @@ impl CodexMessageProcessor {
         }
     }
 
+    async fn export_review(&self, request_id: RequestId, params: ExportReviewParams) {
+        let ExportReviewParams {
+            review,
+            format,
+            cwd,
+        } = params;
+        let root = get_git_repo_root(&cwd).unwrap_or(cwd);
+        match export_review(&review, format, &root) {
+            Ok(content) => {
+                let response = ExportReviewResponse { content };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to export review: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
     async fn fuzzy_file_search(&mut self, request_id: RequestId, params: FuzzyFileSearchParams) {
         let FuzzyFileSearchParams {
             query,
@@ -1353,6 +1467,7 @@ async fn derive_config_from_params(
         include_view_image_tool: None,
         show_raw_agent_reasoning: None,
         tools_web_search_request: None,
+        worktree: None,
     };
 
     let cli_overrides = cli_overrides
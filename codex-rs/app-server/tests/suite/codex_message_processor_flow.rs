@@ -481,6 +481,7 @@ async fn test_send_user_turn_updates_sandbox_and_cwd_between_turns() {
             sandbox_policy: SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![first_cwd.clone()],
                 network_access: false,
+                network_allowlist: vec![],
                 exclude_tmpdir_env_var: false,
                 exclude_slash_tmp: false,
             },
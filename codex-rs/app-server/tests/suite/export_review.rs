@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use app_test_support::McpProcess;
+use app_test_support::to_response;
+use codex_app_server_protocol::ExportReviewParams;
+use codex_app_server_protocol::ExportReviewResponse;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_protocol::protocol::ReviewCodeLocation;
+use codex_protocol::protocol::ReviewExportFormat;
+use codex_protocol::protocol::ReviewFinding;
+use codex_protocol::protocol::ReviewLineRange;
+use codex_protocol::protocol::ReviewOutputEvent;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn export_review_renders_github_annotations_relative_to_cwd() {
+    let codex_home = TempDir::new().unwrap_or_else(|err| panic!("create tempdir: {err}"));
+    let workspace = TempDir::new().unwrap_or_else(|err| panic!("create tempdir: {err}"));
+    let cwd: PathBuf = workspace.path().to_path_buf();
+
+    let mut mcp = McpProcess::new(codex_home.path())
+        .await
+        .expect("spawn mcp process");
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize())
+        .await
+        .expect("initialize timeout")
+        .expect("initialize request");
+
+    let review = ReviewOutputEvent {
+        findings: vec![ReviewFinding {
+            title: "[P2] Missing check".to_string(),
+            body: "`parse` accepts empty input.".to_string(),
+            confidence_score: 0.6,
+            priority: 2,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: cwd.join("src/parse.rs"),
+                line_range: ReviewLineRange { start: 4, end: 6 },
+            },
+        }],
+        ..Default::default()
+    };
+    let request_id = mcp
+        .send_export_review_request(ExportReviewParams {
+            review,
+            format: ReviewExportFormat::Github,
+            cwd,
+        })
+        .await
+        .expect("send exportReview");
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await
+    .expect("exportReview timeout")
+    .expect("exportReview response");
+
+    let received: ExportReviewResponse =
+        to_response(response).expect("deserialize exportReview response");
+    assert_eq!(
+        received,
+        ExportReviewResponse {
+            content: "::warning file=src/parse.rs,line=4,endLine=6,title=[P2] Missing check::`parse` accepts empty input.\n"
+                .to_string(),
+        }
+    );
+}
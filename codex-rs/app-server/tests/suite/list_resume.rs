@@ -3,6 +3,8 @@ use std::path::Path;
 
 use app_test_support::McpProcess;
 use app_test_support::to_response;
+use codex_app_server_protocol::ConversationSearchHit;
+use codex_app_server_protocol::ConversationSearchHitKind;
 use codex_app_server_protocol::JSONRPCNotification;
 use codex_app_server_protocol::JSONRPCResponse;
 use codex_app_server_protocol::ListConversationsParams;
@@ -11,6 +13,8 @@ use codex_app_server_protocol::NewConversationParams; // reused for overrides sh
 use codex_app_server_protocol::RequestId;
 use codex_app_server_protocol::ResumeConversationParams;
 use codex_app_server_protocol::ResumeConversationResponse;
+use codex_app_server_protocol::SearchConversationsParams;
+use codex_app_server_protocol::SearchConversationsResponse;
 use codex_app_server_protocol::ServerNotification;
 use codex_app_server_protocol::SessionConfiguredNotification;
 use pretty_assertions::assert_eq;
@@ -152,6 +156,59 @@ async fn test_list_and_resume_conversations() {
     assert!(!conversation_id.to_string().is_empty());
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_search_conversations() {
+    let codex_home = TempDir::new().expect("create temp dir");
+    create_fake_rollout(
+        codex_home.path(),
+        "2025-01-02T12-00-00",
+        "2025-01-02T12:00:00Z",
+        "Fix the flaky migration test",
+    );
+    create_fake_rollout(
+        codex_home.path(),
+        "2025-01-01T12-00-00",
+        "2025-01-01T12:00:00Z",
+        "Write the release notes",
+    );
+
+    let mut mcp = McpProcess::new(codex_home.path())
+        .await
+        .expect("spawn mcp process");
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize())
+        .await
+        .expect("init timeout")
+        .expect("init failed");
+
+    let req_id = mcp
+        .send_search_conversations_request(SearchConversationsParams {
+            query: "flaky MIGRATION".to_string(),
+            ..Default::default()
+        })
+        .await
+        .expect("send searchConversations");
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(req_id)),
+    )
+    .await
+    .expect("searchConversations timeout")
+    .expect("searchConversations resp");
+    let SearchConversationsResponse { items } =
+        to_response::<SearchConversationsResponse>(resp).expect("deserialize response");
+
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].preview, "Fix the flaky migration test");
+    assert_eq!(items[0].timestamp.as_deref(), Some("2025-01-02T12:00:00Z"));
+    assert_eq!(
+        items[0].hits,
+        vec![ConversationSearchHit {
+            kind: ConversationSearchHitKind::UserMessage,
+            snippet: "Fix the flaky migration test".to_string(),
+        }]
+    );
+}
+
 fn create_fake_rollout(codex_home: &Path, filename_ts: &str, meta_rfc3339: &str, preview: &str) {
     let uuid = Uuid::new_v4();
     // sessions/YYYY/MM/DD/ derived from filename_ts (YYYY-MM-DDThh-mm-ss)
@@ -3,6 +3,7 @@ mod auth;
 mod codex_message_processor_flow;
 mod config;
 mod create_conversation;
+mod export_review;
 mod fuzzy_file_search;
 mod interrupt;
 mod list_resume;
@@ -0,0 +1,215 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::approval_rules::ApprovalRule;
+use codex_core::approval_rules::ApprovalScope;
+use codex_core::approval_rules::CommandMatcher;
+use codex_core::approval_rules::add_approval_rule;
+use codex_core::approval_rules::load_approval_rules;
+use codex_core::approval_rules::remove_approval_rule;
+use codex_core::config::find_codex_home;
+
+/// Manage persisted command approval rules.
+///
+/// Subcommands:
+/// - `list`   — list rules (with `--json`)
+/// - `add`    — approve a command, prefix, or pattern globally or for a project
+/// - `revoke` — delete a rule by id
+#[derive(Debug, clap::Parser)]
+pub struct ApprovalsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: ApprovalsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ApprovalsSubcommand {
+    /// List persisted approval rules.
+    List(ListArgs),
+
+    /// Add an approval rule.
+    Add(AddArgs),
+
+    /// Revoke an approval rule by id.
+    Revoke(RevokeArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ListArgs {
+    /// Output the rules as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct AddArgs {
+    /// Match any command that starts with the given arguments.
+    #[arg(long, conflicts_with = "pattern")]
+    pub prefix: bool,
+
+    /// Treat each argument as a glob (`*`, `?`); a final `...` matches any
+    /// remaining arguments.
+    #[arg(long)]
+    pub pattern: bool,
+
+    /// Apply the rule in every directory instead of a single project.
+    #[arg(long, conflicts_with = "project")]
+    pub global: bool,
+
+    /// Project directory the rule is scoped to. Defaults to the git root of
+    /// the current directory.
+    #[arg(long, value_name = "DIR")]
+    pub project: Option<PathBuf>,
+
+    /// Command (argv) to approve.
+    #[arg(trailing_var_arg = true, num_args = 1.., required = true)]
+    pub command: Vec<String>,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct RevokeArgs {
+    /// Id of the rule to revoke, as shown by `codex approvals list`.
+    pub id: String,
+}
+
+impl ApprovalsCli {
+    pub async fn run(self) -> Result<()> {
+        let ApprovalsCli {
+            config_overrides,
+            subcommand,
+        } = self;
+        // Validate any provided overrides even though they are not currently applied.
+        config_overrides.parse_overrides().map_err(|e| anyhow!(e))?;
+
+        let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
+        match subcommand {
+            ApprovalsSubcommand::List(args) => run_list(&codex_home, args),
+            ApprovalsSubcommand::Add(args) => run_add(&codex_home, args),
+            ApprovalsSubcommand::Revoke(args) => run_revoke(&codex_home, args),
+        }
+    }
+}
+
+fn run_list(codex_home: &Path, args: ListArgs) -> Result<()> {
+    let rules = load_approval_rules(codex_home).context("failed to load approval rules")?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&rules)?);
+        return Ok(());
+    }
+
+    if rules.is_empty() {
+        println!("No approval rules saved yet. Try `codex approvals add --prefix -- cargo test`.");
+        return Ok(());
+    }
+
+    let rows: Vec<[String; 3]> = rules
+        .iter()
+        .map(|rule| {
+            [
+                rule.id.clone(),
+                rule.matcher.describe(),
+                scope_display(rule),
+            ]
+        })
+        .collect();
+    let mut widths = ["Id".len(), "Command".len(), "Scope".len()];
+    for row in &rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    println!(
+        "{:<id_w$}  {:<cmd_w$}  Scope",
+        "Id",
+        "Command",
+        id_w = widths[0],
+        cmd_w = widths[1],
+    );
+    for [id, command, scope] in rows {
+        println!(
+            "{id:<id_w$}  {command:<cmd_w$}  {scope}",
+            id_w = widths[0],
+            cmd_w = widths[1],
+        );
+    }
+
+    Ok(())
+}
+
+fn scope_display(rule: &ApprovalRule) -> String {
+    match &rule.scope {
+        ApprovalScope::Global => "global".to_string(),
+        ApprovalScope::Project { path } => path.display().to_string(),
+    }
+}
+
+fn run_add(codex_home: &Path, args: AddArgs) -> Result<()> {
+    let AddArgs {
+        prefix,
+        pattern,
+        global,
+        project,
+        command,
+    } = args;
+
+    if command.is_empty() {
+        bail!("a command is required");
+    }
+
+    let matcher = if prefix {
+        CommandMatcher::Prefix { command }
+    } else if pattern {
+        CommandMatcher::Pattern { pattern: command }
+    } else {
+        CommandMatcher::Exact { command }
+    };
+
+    let scope = if global {
+        ApprovalScope::Global
+    } else {
+        let dir = match project {
+            Some(dir) => dir,
+            None => std::env::current_dir().context("failed to resolve current directory")?,
+        };
+        let dir = dir
+            .canonicalize()
+            .with_context(|| format!("failed to resolve {}", dir.display()))?;
+        ApprovalScope::project_for_cwd(&dir)
+    };
+
+    let rule = add_approval_rule(codex_home, matcher, scope)
+        .with_context(|| format!("failed to write approval rules to {}", codex_home.display()))?;
+    println!(
+        "Added approval rule {} for {} ({}).",
+        rule.id,
+        rule.matcher.describe(),
+        scope_display(&rule)
+    );
+    Ok(())
+}
+
+fn run_revoke(codex_home: &Path, args: RevokeArgs) -> Result<()> {
+    let RevokeArgs { id } = args;
+    match remove_approval_rule(codex_home, &id).with_context(|| {
+        format!(
+            "failed to update approval rules in {}",
+            codex_home.display()
+        )
+    })? {
+        Some(rule) => println!(
+            "Revoked approval rule {id} for {}.",
+            rule.matcher.describe()
+        ),
+        None => println!("No approval rule with id '{id}' found."),
+    }
+    Ok(())
+}
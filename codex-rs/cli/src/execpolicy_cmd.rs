@@ -0,0 +1,143 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use codex_common::CliConfigOverrides;
+use codex_core::bash::parse_bash_lc_script;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::exec_policy::ExecPolicyVerdict;
+use codex_core::exec_policy::evaluate_exec_policy;
+use codex_core::exec_policy::load_exec_policy;
+use codex_core::exec_policy::policy_dirs;
+use codex_core::exec_policy::trusted_policy_project;
+use codex_execpolicy::CheckExplanation;
+use codex_execpolicy::ExecCall;
+use codex_execpolicy::MatchedExec;
+use codex_execpolicy::Redirect;
+
+/// Inspect the execpolicy that decides which commands run without approval.
+///
+/// Subcommands:
+/// - `check` — explain how a command is classified and which rule matched
+#[derive(Debug, clap::Parser)]
+pub struct ExecpolicyCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: ExecpolicySubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ExecpolicySubcommand {
+    /// Check a command against the built-in policy merged with the policy
+    /// files in `$CODEX_HOME/policy` and, for trusted projects,
+    /// `<repo>/.codex/policy`.
+    Check(CheckArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct CheckArgs {
+    /// Directory the command would run in; selects the repository whose
+    /// `.codex/policy` applies. Defaults to the current directory.
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Command (argv) to check. For `bash -lc "<script>"`, each command in
+    /// the script is checked.
+    #[arg(trailing_var_arg = true, num_args = 1.., required = true)]
+    pub command: Vec<String>,
+}
+
+impl ExecpolicyCli {
+    pub async fn run(self) -> Result<()> {
+        let ExecpolicyCli {
+            config_overrides,
+            subcommand,
+        } = self;
+        match subcommand {
+            ExecpolicySubcommand::Check(args) => run_check(config_overrides, args).await,
+        }
+    }
+}
+
+async fn run_check(config_overrides: CliConfigOverrides, args: CheckArgs) -> Result<()> {
+    let CheckArgs { cwd, command } = args;
+    let config = Config::load_with_cli_overrides(
+        config_overrides.parse_overrides().map_err(|e| anyhow!(e))?,
+        ConfigOverrides {
+            cwd,
+            ..Default::default()
+        },
+    )
+    .await
+    .context("failed to load config")?;
+    let codex_home = config.codex_home.as_path();
+    let cwd = config.cwd.clone();
+
+    let trusted_project = trusted_policy_project(&config);
+
+    let (policy, errors) = load_exec_policy(codex_home, &cwd, trusted_project.as_deref());
+    for err in &errors {
+        eprintln!("warning: ignoring policy file: {err:#}");
+    }
+    for dir in policy_dirs(codex_home, &cwd, trusted_project.as_deref()) {
+        println!("policy dir: {}", dir.display());
+    }
+    if trusted_project.is_none() {
+        println!("repository policy files are ignored because this project is not trusted");
+    }
+
+    let commands: Vec<(ExecCall, Vec<Redirect>)> = match parse_bash_lc_script(&command) {
+        Some(commands) => commands
+            .into_iter()
+            .map(|command| (command.exec_call, command.redirects))
+            .collect(),
+        None => match command.split_first() {
+            Some((program, args)) => vec![(
+                ExecCall {
+                    program: program.clone(),
+                    args: args.to_vec(),
+                },
+                Vec::new(),
+            )],
+            None => Vec::new(),
+        },
+    };
+
+    for (exec_call, redirects) in commands {
+        println!();
+        println!("{exec_call}");
+        let CheckExplanation { result, rule } = policy.explain(&exec_call);
+        let written = redirects.iter().find_map(Redirect::written_path);
+        match result {
+            Ok(MatchedExec::Match { exec }) => match written {
+                Some(path) => println!("  result: match (redirects output to {path})"),
+                None if exec.might_write_files() => println!("  result: match (may write files)"),
+                None => println!("  result: safe"),
+            },
+            Ok(MatchedExec::Forbidden { reason, .. }) => {
+                println!("  result: forbidden ({reason})");
+            }
+            Err(err) => println!("  result: unverified ({err:?})"),
+        }
+        match rule {
+            Some(rule) => println!("  rule: {rule}"),
+            None => println!("  rule: none"),
+        }
+    }
+
+    println!();
+    match evaluate_exec_policy(&policy, &command, &config.sandbox_policy, &cwd, &cwd) {
+        ExecPolicyVerdict::Safe => println!("Codex runs this command without asking."),
+        ExecPolicyVerdict::Forbidden { reason } => {
+            println!("Codex rejects this command: {reason}");
+        }
+        ExecPolicyVerdict::Unverified => {
+            println!("The policy cannot verify this command; the approval policy decides.");
+        }
+    }
+    Ok(())
+}
@@ -24,9 +24,19 @@ use owo_colors::OwoColorize;
 use std::path::PathBuf;
 use supports_color::Stream;
 
+mod approvals_cmd;
+mod execpolicy_cmd;
 mod mcp_cmd;
+mod session_worktree;
+mod sessions_cmd;
+mod usage_cmd;
 
+use crate::approvals_cmd::ApprovalsCli;
+use crate::execpolicy_cmd::ExecpolicyCli;
 use crate::mcp_cmd::McpCli;
+use crate::session_worktree::finish_session_worktree;
+use crate::sessions_cmd::SessionsCli;
+use crate::usage_cmd::UsageCli;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 
@@ -64,6 +74,9 @@ enum Subcommand {
     #[clap(visible_alias = "e")]
     Exec(ExecCli),
 
+    /// Review code changes non-interactively and print the findings (JSON, SARIF, GitHub annotations or markdown).
+    Review(ReviewCommand),
+
     /// Manage login.
     Login(LoginCommand),
 
@@ -76,6 +89,12 @@ enum Subcommand {
     /// [experimental] Run the Codex MCP server (stdio transport).
     McpServer,
 
+    /// Manage remembered command approvals.
+    Approvals(ApprovalsCli),
+
+    /// Check commands against the execpolicy.
+    Execpolicy(ExecpolicyCli),
+
     /// [experimental] Run the app server.
     AppServer,
 
@@ -93,6 +112,12 @@ enum Subcommand {
     /// Resume a previous interactive session (picker by default; use --last to continue the most recent).
     Resume(ResumeCommand),
 
+    /// Search recorded sessions.
+    Sessions(SessionsCli),
+
+    /// Summarize token usage and cost by day, project or model.
+    Usage(UsageCli),
+
     /// Internal: generate TypeScript protocol bindings.
     #[clap(hide = true)]
     GenerateTs(GenerateTsCommand),
@@ -130,6 +155,15 @@ struct ResumeCommand {
     config_overrides: TuiCli,
 }
 
+#[derive(Debug, Parser)]
+struct ReviewCommand {
+    #[clap(skip)]
+    config_overrides: CliConfigOverrides,
+
+    #[clap(flatten)]
+    args: codex_exec::ReviewArgs,
+}
+
 #[derive(Debug, Parser)]
 struct SandboxArgs {
     #[command(subcommand)]
@@ -235,12 +269,16 @@ fn format_exit_messages(exit_info: AppExitInfo, color_enabled: bool) -> Vec<Stri
 }
 
 /// Handle the app exit and print the results. Optionally run the update action.
-fn handle_app_exit(exit_info: AppExitInfo) -> anyhow::Result<()> {
+fn handle_app_exit(mut exit_info: AppExitInfo) -> anyhow::Result<()> {
     let update_action = exit_info.update_action;
+    let session_worktree = exit_info.session_worktree.take();
     let color_enabled = supports_color::on(Stream::Stdout).is_some();
     for line in format_exit_messages(exit_info, color_enabled) {
         println!("{line}");
     }
+    if let Some(worktree) = session_worktree {
+        finish_session_worktree(worktree)?;
+    }
     if let Some(action) = update_action {
         run_update_action(action)?;
     }
@@ -353,6 +391,18 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             );
             codex_exec::run_main(exec_cli, codex_linux_sandbox_exe).await?;
         }
+        Some(Subcommand::Review(mut review_cli)) => {
+            prepend_config_flags(
+                &mut review_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            codex_exec::run_review(
+                review_cli.args,
+                review_cli.config_overrides,
+                codex_linux_sandbox_exe,
+            )
+            .await?;
+        }
         Some(Subcommand::McpServer) => {
             codex_mcp_server::run_main(codex_linux_sandbox_exe, root_config_overrides).await?;
         }
@@ -361,6 +411,34 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             prepend_config_flags(&mut mcp_cli.config_overrides, root_config_overrides.clone());
             mcp_cli.run().await?;
         }
+        Some(Subcommand::Approvals(mut approvals_cli)) => {
+            prepend_config_flags(
+                &mut approvals_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            approvals_cli.run().await?;
+        }
+        Some(Subcommand::Execpolicy(mut execpolicy_cli)) => {
+            prepend_config_flags(
+                &mut execpolicy_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            execpolicy_cli.run().await?;
+        }
+        Some(Subcommand::Sessions(mut sessions_cli)) => {
+            prepend_config_flags(
+                &mut sessions_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            sessions_cli.run().await?;
+        }
+        Some(Subcommand::Usage(mut usage_cli)) => {
+            prepend_config_flags(
+                &mut usage_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            usage_cli.run().await?;
+        }
         Some(Subcommand::AppServer) => {
             codex_app_server::run_main(codex_linux_sandbox_exe, root_config_overrides).await?;
         }
@@ -619,6 +697,7 @@ mod tests {
                 .map(ConversationId::from_string)
                 .map(Result::unwrap),
             update_action: None,
+            session_worktree: None,
         }
     }
 
@@ -628,6 +707,7 @@ mod tests {
             token_usage: TokenUsage::default(),
             conversation_id: None,
             update_action: None,
+            session_worktree: None,
         };
         let lines = format_exit_messages(exit_info, false);
         assert!(lines.is_empty());
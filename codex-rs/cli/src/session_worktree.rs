@@ -0,0 +1,64 @@
+//! What happens to the branch of a session started with `--worktree` once the
+//! TUI exits.
+
+use std::io::BufRead;
+use std::io::IsTerminal;
+use std::io::Write;
+
+use codex_git_tooling::SessionWorktree;
+use codex_git_tooling::WorktreeExitAction;
+
+/// Commits whatever the session left uncommitted, then asks whether to merge,
+/// cherry-pick or keep the session's branch. Without a terminal to ask on,
+/// the branch is kept.
+pub(crate) fn finish_session_worktree(worktree: SessionWorktree) -> anyhow::Result<()> {
+    worktree.commit_pending_changes("Codex session changes")?;
+    let branch = worktree.branch();
+    let commits = worktree.commit_count()?;
+    if commits == 0 {
+        worktree.finish(WorktreeExitAction::Discard)?;
+        println!("The session made no changes; removed its worktree and branch {branch}.");
+        return Ok(());
+    }
+
+    let plural = if commits == 1 { "" } else { "s" };
+    println!("The session left {commits} commit{plural} on branch {branch}.");
+    let action = if std::io::stdin().is_terminal() {
+        prompt_for_action()?
+    } else {
+        WorktreeExitAction::Keep
+    };
+
+    let repo = worktree.repo_root().display();
+    match (worktree.finish(action), action) {
+        (Ok(()), WorktreeExitAction::Merge) => println!("Merged {branch} into {repo}."),
+        (Ok(()), WorktreeExitAction::CherryPick) => {
+            println!("Cherry-picked {branch} onto {repo}.")
+        }
+        (Ok(()), _) => println!("Kept branch {branch}; run `git merge {branch}` to merge it."),
+        (Err(err), WorktreeExitAction::Merge | WorktreeExitAction::CherryPick) => {
+            eprintln!("{err}");
+            println!("Left {repo} as it was and kept branch {branch}.");
+        }
+        (Err(err), _) => return Err(err.into()),
+    }
+    Ok(())
+}
+
+fn prompt_for_action() -> anyhow::Result<WorktreeExitAction> {
+    let stdin = std::io::stdin();
+    loop {
+        print!("[m]erge it, [c]herry-pick its commits, or [k]eep the branch? [k] ");
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        if stdin.lock().read_line(&mut answer)? == 0 {
+            return Ok(WorktreeExitAction::Keep);
+        }
+        match answer.trim().to_ascii_lowercase().as_str() {
+            "m" | "merge" => return Ok(WorktreeExitAction::Merge),
+            "c" | "cherry-pick" => return Ok(WorktreeExitAction::CherryPick),
+            "" | "k" | "keep" => return Ok(WorktreeExitAction::Keep),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use codex_common::CliConfigOverrides;
+use codex_core::INTERACTIVE_SESSION_SOURCES;
+use codex_core::RolloutRecorder;
+use codex_core::SearchHitKind;
+use codex_core::SessionSearchQuery;
+use codex_core::config::find_codex_home;
+
+/// Inspect recorded sessions.
+///
+/// Subcommands:
+/// - `search` — full-text search over messages, commands and patched files
+#[derive(Debug, clap::Parser)]
+pub struct SessionsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: SessionsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SessionsSubcommand {
+    /// Search recorded sessions. Every term must appear in a session; wrap
+    /// words in double quotes to match them as a phrase.
+    Search(SearchArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SearchArgs {
+    /// Only match these parts of a session (repeatable).
+    #[arg(long = "kind", value_enum, value_name = "KIND")]
+    pub kinds: Vec<KindArg>,
+
+    /// Maximum number of sessions to show.
+    #[arg(long, default_value_t = 20)]
+    pub limit: usize,
+
+    /// Include sessions from `codex exec`, the MCP server and sub-agents.
+    #[arg(long)]
+    pub all_sources: bool,
+
+    /// Output the results as JSON.
+    #[arg(long)]
+    pub json: bool,
+
+    /// Search terms.
+    #[arg(num_args = 1.., required = true)]
+    pub query: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum KindArg {
+    User,
+    Agent,
+    Command,
+    File,
+}
+
+impl From<KindArg> for SearchHitKind {
+    fn from(kind: KindArg) -> Self {
+        match kind {
+            KindArg::User => SearchHitKind::UserMessage,
+            KindArg::Agent => SearchHitKind::AgentMessage,
+            KindArg::Command => SearchHitKind::Command,
+            KindArg::File => SearchHitKind::FilePatch,
+        }
+    }
+}
+
+impl SessionsCli {
+    pub async fn run(self) -> Result<()> {
+        let SessionsCli {
+            config_overrides,
+            subcommand,
+        } = self;
+        // Validate any provided overrides even though they are not currently applied.
+        config_overrides.parse_overrides().map_err(|e| anyhow!(e))?;
+
+        let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
+        match subcommand {
+            SessionsSubcommand::Search(args) => run_search(&codex_home, args).await,
+        }
+    }
+}
+
+async fn run_search(codex_home: &std::path::Path, args: SearchArgs) -> Result<()> {
+    let SearchArgs {
+        kinds,
+        limit,
+        all_sources,
+        json,
+        query,
+    } = args;
+    let query = SessionSearchQuery {
+        text: query.join(" "),
+        kinds: kinds.into_iter().map(SearchHitKind::from).collect(),
+        limit,
+        allowed_sources: if all_sources {
+            Vec::new()
+        } else {
+            INTERACTIVE_SESSION_SOURCES.to_vec()
+        },
+    };
+    let results = RolloutRecorder::search_conversations(codex_home, query)
+        .await
+        .context("failed to search sessions")?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+        return Ok(());
+    }
+
+    if results.is_empty() {
+        println!("No matching sessions.");
+        return Ok(());
+    }
+
+    for (i, result) in results.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        let when = result
+            .updated_at
+            .as_deref()
+            .or(result.created_at.as_deref())
+            .unwrap_or("-");
+        println!("{when}  {}", result.path.display());
+        if let Some(preview) = result.preview.as_deref().and_then(|p| p.lines().next()) {
+            println!("  {preview}");
+        }
+        for hit in &result.hits {
+            println!("    {}: {}", hit.kind, hit.snippet);
+        }
+    }
+    Ok(())
+}
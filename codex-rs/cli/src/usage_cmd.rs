@@ -0,0 +1,139 @@
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use codex_common::CliConfigOverrides;
+use codex_core::UsageGroupBy;
+use codex_core::UsageSummaryRow;
+use codex_core::config::find_codex_home;
+use codex_core::summarize_usage;
+
+/// Summarize token usage and cost recorded in session rollouts.
+#[derive(Debug, clap::Parser)]
+pub struct UsageCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// How to group sessions.
+    #[arg(long = "by", value_enum, default_value_t = GroupByArg::Day)]
+    pub group_by: GroupByArg,
+
+    /// Only include sessions started on or after this UTC day (YYYY-MM-DD).
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<String>,
+
+    /// Output the summary as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum GroupByArg {
+    Day,
+    Project,
+    Model,
+}
+
+impl From<GroupByArg> for UsageGroupBy {
+    fn from(group_by: GroupByArg) -> Self {
+        match group_by {
+            GroupByArg::Day => UsageGroupBy::Day,
+            GroupByArg::Project => UsageGroupBy::Project,
+            GroupByArg::Model => UsageGroupBy::Model,
+        }
+    }
+}
+
+impl UsageCli {
+    pub async fn run(self) -> Result<()> {
+        let UsageCli {
+            config_overrides,
+            group_by,
+            since,
+            json,
+        } = self;
+        // Validate any provided overrides even though they are not currently applied.
+        config_overrides.parse_overrides().map_err(|e| anyhow!(e))?;
+        if let Some(since) = since.as_deref()
+            && !is_iso_date(since)
+        {
+            return Err(anyhow!(
+                "--since expects a date like 2025-10-01, got {since:?}"
+            ));
+        }
+
+        let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
+        let rows = summarize_usage(&codex_home, group_by.into(), since)
+            .await
+            .context("failed to read session usage")?;
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&rows)?);
+            return Ok(());
+        }
+        if rows.is_empty() {
+            println!("No recorded usage.");
+            return Ok(());
+        }
+        print_table(group_by, &rows);
+        Ok(())
+    }
+}
+
+fn is_iso_date(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+}
+
+fn print_table(group_by: GroupByArg, rows: &[UsageSummaryRow]) {
+    let header = match group_by {
+        GroupByArg::Day => "DAY",
+        GroupByArg::Project => "PROJECT",
+        GroupByArg::Model => "MODEL",
+    };
+    let key_width = rows
+        .iter()
+        .map(|row| row.key.len())
+        .chain([header.len(), "TOTAL".len()])
+        .max()
+        .unwrap_or_default();
+
+    println!(
+        "{header:<key_width$}  {:>8}  {:>12}  {:>12}  {:>10}",
+        "SESSIONS", "INPUT", "OUTPUT", "COST"
+    );
+    let mut total = UsageSummaryRow {
+        key: "TOTAL".to_string(),
+        ..Default::default()
+    };
+    for row in rows {
+        print_row(row, key_width);
+        total.sessions += row.sessions;
+        total.token_usage.add_assign(&row.token_usage);
+        total.cost_usd += row.cost_usd;
+        total.unpriced_tokens += row.unpriced_tokens;
+    }
+    // Sessions can appear in several rows when grouping by model.
+    if !matches!(group_by, GroupByArg::Model) && rows.len() > 1 {
+        print_row(&total, key_width);
+    }
+    if total.unpriced_tokens > 0 {
+        println!();
+        println!(
+            "{} tokens were used by models without a price; add them under [model_pricing] in config.toml.",
+            total.unpriced_tokens
+        );
+    }
+}
+
+fn print_row(row: &UsageSummaryRow, key_width: usize) {
+    let cost = format!("${:.2}", row.cost_usd);
+    let marker = if row.unpriced_tokens > 0 { "*" } else { "" };
+    println!(
+        "{:<key_width$}  {:>8}  {:>12}  {:>12}  {:>10}{marker}",
+        row.key, row.sessions, row.token_usage.input_tokens, row.token_usage.output_tokens, cost,
+    );
+}
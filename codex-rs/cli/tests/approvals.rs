@@ -0,0 +1,90 @@
+use std::path::Path;
+
+use anyhow::Result;
+use codex_core::approval_rules::ApprovalScope;
+use codex_core::approval_rules::CommandMatcher;
+use codex_core::approval_rules::load_approval_rules;
+use predicates::str::contains;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+
+fn codex_command(codex_home: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::cargo_bin("codex")?;
+    cmd.env("CODEX_HOME", codex_home);
+    Ok(cmd)
+}
+
+#[test]
+fn add_list_and_revoke_rule() -> Result<()> {
+    let codex_home = TempDir::new()?;
+
+    codex_command(codex_home.path())?
+        .args([
+            "approvals",
+            "add",
+            "--global",
+            "--prefix",
+            "--",
+            "cargo",
+            "test",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Added approval rule"));
+
+    let rules = load_approval_rules(codex_home.path())?;
+    assert_eq!(rules.len(), 1);
+    let rule = &rules[0];
+    assert_eq!(
+        rule.matcher,
+        CommandMatcher::Prefix {
+            command: vec!["cargo".to_string(), "test".to_string()],
+        }
+    );
+    assert_eq!(rule.scope, ApprovalScope::Global);
+
+    codex_command(codex_home.path())?
+        .args(["approvals", "list"])
+        .assert()
+        .success()
+        .stdout(contains(rule.id.as_str()))
+        .stdout(contains("cargo test"));
+
+    codex_command(codex_home.path())?
+        .args(["approvals", "revoke", rule.id.as_str()])
+        .assert()
+        .success()
+        .stdout(contains("Revoked approval rule"));
+    assert!(load_approval_rules(codex_home.path())?.is_empty());
+
+    codex_command(codex_home.path())?
+        .args(["approvals", "revoke", rule.id.as_str()])
+        .assert()
+        .success()
+        .stdout(contains("No approval rule with id"));
+
+    Ok(())
+}
+
+#[test]
+fn add_defaults_to_project_scope() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let project = TempDir::new()?;
+
+    codex_command(codex_home.path())?
+        .current_dir(project.path())
+        .args(["approvals", "add", "--", "make", "check"])
+        .assert()
+        .success();
+
+    let rules = load_approval_rules(codex_home.path())?;
+    assert_eq!(rules.len(), 1);
+    assert_eq!(
+        rules[0].scope,
+        ApprovalScope::Project {
+            path: project.path().canonicalize()?,
+        }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,541 @@
+//! A `CloudBackend` that runs tasks on this machine instead of a hosted
+//! service. Every attempt is a headless `codex exec` running in a git worktree
+//! of its own, so best-of-N attempts run side by side without touching the
+//! user's checkout.
+//!
+//! State lives on disk under the backend's root directory:
+//!
+//! ```text
+//! <root>/<task-id>/task.json
+//! <root>/<task-id>/attempt-<n>/worktree/          # git worktree of the attempt
+//! <root>/<task-id>/attempt-<n>/events.jsonl       # `codex exec --json` output
+//! <root>/<task-id>/attempt-<n>/last_message.md    # `--output-last-message`
+//! <root>/<task-id>/attempt-<n>/exit_code          # written once the process exits
+//! <root>/<task-id>/attempt-<n>/diff.patch         # diff, once the attempt is done
+//! ```
+//!
+//! Attempt state is derived from these files whenever it is read, so tasks
+//! created by `codex cloud exec` can be browsed later from `codex cloud`.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Stdio;
+
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::ApplyOutcome;
+use crate::ApplyStatus;
+use crate::AttemptStatus;
+use crate::CloudBackend;
+use crate::CloudTaskError;
+use crate::CreatedTask;
+use crate::DiffSummary;
+use crate::Result;
+use crate::TaskId;
+use crate::TaskStatus;
+use crate::TaskSummary;
+use crate::TaskText;
+use crate::TurnAttempt;
+
+const TASK_FILE: &str = "task.json";
+const EVENTS_FILE: &str = "events.jsonl";
+const STDERR_FILE: &str = "stderr.log";
+const LAST_MESSAGE_FILE: &str = "last_message.md";
+const EXIT_CODE_FILE: &str = "exit_code";
+const DIFF_FILE: &str = "diff.patch";
+
+/// Runs tasks locally; see the module docs for the on-disk layout.
+#[derive(Clone, Debug)]
+pub struct LocalClient {
+    root: PathBuf,
+    repo: PathBuf,
+    codex_bin: PathBuf,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct TaskRecord {
+    id: String,
+    title: String,
+    prompt: String,
+    environment_id: String,
+    git_ref: String,
+    qa_mode: bool,
+    repo_root: PathBuf,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    applied: bool,
+    attempts: Vec<AttemptRecord>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct AttemptRecord {
+    placement: i64,
+    worktree: PathBuf,
+    branch: String,
+    base: String,
+}
+
+impl AttemptRecord {
+    fn turn_id(&self) -> String {
+        format!("attempt-{}", self.placement)
+    }
+}
+
+impl LocalClient {
+    /// Stores tasks under `root` and runs them against the git repository
+    /// containing `repo`.
+    pub fn new(root: impl Into<PathBuf>, repo: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            repo: repo.into(),
+            codex_bin: std::env::current_exe().unwrap_or_else(|_| PathBuf::from("codex")),
+        }
+    }
+
+    /// Overrides the `codex` executable used to run attempts. Defaults to the
+    /// current executable.
+    pub fn with_codex_bin(mut self, codex_bin: impl Into<PathBuf>) -> Self {
+        self.codex_bin = codex_bin.into();
+        self
+    }
+
+    fn task_dir(&self, id: &str) -> PathBuf {
+        self.root.join(id)
+    }
+
+    fn attempt_dir(&self, id: &str, placement: i64) -> PathBuf {
+        self.task_dir(id).join(format!("attempt-{placement}"))
+    }
+
+    fn load_task(&self, id: &TaskId) -> Result<TaskRecord> {
+        let path = self.task_dir(&id.0).join(TASK_FILE);
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| CloudTaskError::Msg(format!("task {} not found: {e}", id.0)))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| CloudTaskError::Msg(format!("failed to parse {}: {e}", path.display())))
+    }
+
+    fn save_task(&self, task: &TaskRecord) -> Result<()> {
+        let dir = self.task_dir(&task.id);
+        fs::create_dir_all(&dir).map_err(io_err)?;
+        let json = serde_json::to_string_pretty(task)
+            .map_err(|e| CloudTaskError::Msg(format!("failed to serialize task: {e}")))?;
+        // Write then rename so concurrent readers never see a partial file.
+        let tmp = dir.join(format!("{TASK_FILE}.tmp"));
+        fs::write(&tmp, json).map_err(io_err)?;
+        fs::rename(&tmp, dir.join(TASK_FILE)).map_err(io_err)
+    }
+
+    fn load_tasks(&self) -> Result<Vec<TaskRecord>> {
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(io_err(e)),
+        };
+        let mut tasks = Vec::new();
+        for entry in entries.flatten() {
+            let id = TaskId(entry.file_name().to_string_lossy().into_owned());
+            if entry.path().join(TASK_FILE).is_file() {
+                tasks.push(self.load_task(&id)?);
+            }
+        }
+        Ok(tasks)
+    }
+
+    fn attempt_status(&self, task: &TaskRecord, attempt: &AttemptRecord) -> AttemptStatus {
+        let dir = self.attempt_dir(&task.id, attempt.placement);
+        if let Ok(events) = fs::read_to_string(dir.join(EVENTS_FILE)) {
+            for line in events.lines().rev() {
+                match serde_json::from_str::<serde_json::Value>(line)
+                    .ok()
+                    .and_then(|v| v.get("type").and_then(|t| t.as_str()).map(str::to_string))
+                    .as_deref()
+                {
+                    Some("turn.completed") => return AttemptStatus::Completed,
+                    Some("turn.failed") | Some("error") => return AttemptStatus::Failed,
+                    _ => {}
+                }
+            }
+        }
+        match fs::read_to_string(dir.join(EXIT_CODE_FILE)) {
+            // The process exited without finishing its turn.
+            Ok(_) => AttemptStatus::Failed,
+            Err(_) if dir.join(EVENTS_FILE).exists() => AttemptStatus::InProgress,
+            Err(_) => AttemptStatus::Pending,
+        }
+    }
+
+    /// Diff of a finished attempt, computed from its worktree the first time
+    /// it is asked for and cached afterwards.
+    fn attempt_diff(&self, task: &TaskRecord, attempt: &AttemptRecord) -> Result<Option<String>> {
+        let path = self
+            .attempt_dir(&task.id, attempt.placement)
+            .join(DIFF_FILE);
+        if let Ok(diff) = fs::read_to_string(&path) {
+            return Ok(Some(diff).filter(|d| !d.is_empty()));
+        }
+        if self.attempt_status(task, attempt) != AttemptStatus::Completed {
+            return Ok(None);
+        }
+        let diff = codex_git_tooling::worktree_diff(&attempt.worktree, &attempt.base)
+            .map_err(|e| CloudTaskError::Io(format!("failed to diff attempt: {e}")))?;
+        fs::write(&path, &diff).map_err(io_err)?;
+        Ok(Some(diff).filter(|d| !d.is_empty()))
+    }
+
+    fn attempt_messages(&self, task: &TaskRecord, attempt: &AttemptRecord) -> Vec<String> {
+        let dir = self.attempt_dir(&task.id, attempt.placement);
+        if let Ok(message) = fs::read_to_string(dir.join(LAST_MESSAGE_FILE))
+            && !message.trim().is_empty()
+        {
+            return vec![message];
+        }
+        if self.attempt_status(task, attempt) == AttemptStatus::Failed {
+            let stderr = fs::read_to_string(dir.join(STDERR_FILE)).unwrap_or_default();
+            let lines = stderr.lines().collect::<Vec<_>>();
+            let tail = lines[lines.len().saturating_sub(20)..].join("\n");
+            return vec![format!("Attempt failed.\n{tail}").trim_end().to_string()];
+        }
+        Vec::new()
+    }
+
+    fn summarize(&self, task: &TaskRecord) -> Result<TaskSummary> {
+        let statuses = task
+            .attempts
+            .iter()
+            .map(|attempt| self.attempt_status(task, attempt))
+            .collect::<Vec<_>>();
+        let status = if task.applied {
+            TaskStatus::Applied
+        } else if statuses
+            .iter()
+            .any(|s| matches!(s, AttemptStatus::Pending | AttemptStatus::InProgress))
+        {
+            TaskStatus::Pending
+        } else if statuses.contains(&AttemptStatus::Completed) {
+            TaskStatus::Ready
+        } else {
+            TaskStatus::Error
+        };
+        let summary = match task.attempts.first() {
+            Some(primary) => self
+                .attempt_diff(task, primary)?
+                .map(|diff| diff_summary(&diff))
+                .unwrap_or_default(),
+            None => DiffSummary::default(),
+        };
+        let updated_at = task
+            .attempts
+            .iter()
+            .filter_map(|attempt| {
+                let dir = self.attempt_dir(&task.id, attempt.placement);
+                fs::metadata(dir.join(EVENTS_FILE))
+                    .and_then(|m| m.modified())
+                    .ok()
+            })
+            .max()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or(task.created_at);
+        Ok(TaskSummary {
+            id: TaskId(task.id.clone()),
+            title: task.title.clone(),
+            status,
+            updated_at,
+            environment_id: Some(task.environment_id.clone()),
+            environment_label: task
+                .repo_root
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned()),
+            summary,
+            is_review: false,
+            attempt_total: Some(task.attempts.len()),
+        })
+    }
+
+    /// Creates the worktree for one attempt and starts `codex exec` in it.
+    fn spawn_attempt(&self, task: &mut TaskRecord, placement: i64) -> Result<()> {
+        let dir = self.attempt_dir(&task.id, placement);
+        fs::create_dir_all(&dir).map_err(io_err)?;
+        let branch = format!("codex/{}-{placement}", task.id);
+        // Every attempt starts from the requested ref; an empty ref means HEAD.
+        let base_rev = Some(task.git_ref.as_str()).filter(|r| !r.trim().is_empty());
+        let worktree = codex_git_tooling::create_session_worktree(
+            &task.repo_root,
+            &dir.join("worktree"),
+            &branch,
+            base_rev,
+        )
+        .map_err(|e| CloudTaskError::Io(format!("failed to create worktree: {e}")))?;
+
+        let mut command = Command::new(&self.codex_bin);
+        command
+            .arg("exec")
+            .arg("--json")
+            .arg("--cd")
+            .arg(worktree.path())
+            .arg("--output-last-message")
+            .arg(dir.join(LAST_MESSAGE_FILE));
+        if task.qa_mode {
+            // Ask mode: answer questions about the code without changing it.
+            command.args(["--sandbox", "read-only"]);
+        } else {
+            command.arg("--full-auto");
+        }
+        command
+            .arg("--")
+            .arg(&task.prompt)
+            .stdin(Stdio::null())
+            .stdout(fs::File::create(dir.join(EVENTS_FILE)).map_err(io_err)?)
+            .stderr(fs::File::create(dir.join(STDERR_FILE)).map_err(io_err)?);
+        let mut child = command.spawn().map_err(|e| {
+            CloudTaskError::Io(format!("failed to start {}: {e}", self.codex_bin.display()))
+        })?;
+
+        // Record how the process ended for as long as this process is around
+        // to see it; attempts that finish their turn are recognized from
+        // their event stream regardless.
+        let exit_code_path = dir.join(EXIT_CODE_FILE);
+        std::thread::spawn(move || {
+            if let Ok(status) = child.wait() {
+                let code = status.code().map(|c| c.to_string()).unwrap_or_default();
+                let _ = fs::write(exit_code_path, code);
+            }
+        });
+
+        task.repo_root = worktree.repo_root().to_path_buf();
+        task.attempts.push(AttemptRecord {
+            placement,
+            worktree: worktree.path().to_path_buf(),
+            branch: worktree.branch().to_string(),
+            base: worktree.base().to_string(),
+        });
+        Ok(())
+    }
+
+    fn apply(
+        &self,
+        id: TaskId,
+        diff_override: Option<String>,
+        preflight: bool,
+    ) -> Result<ApplyOutcome> {
+        let mut task = self.load_task(&id)?;
+        let diff = match diff_override {
+            Some(diff) => diff,
+            None => task
+                .attempts
+                .first()
+                .map(|primary| self.attempt_diff(&task, primary))
+                .transpose()?
+                .flatten()
+                .ok_or_else(|| {
+                    CloudTaskError::Msg(format!("No diff available for task {}", id.0))
+                })?,
+        };
+
+        let req = codex_git_apply::ApplyGitRequest {
+            cwd: task.repo_root.clone(),
+            diff,
+            revert: false,
+            preflight,
+        };
+        let r = codex_git_apply::apply_git_patch(&req)
+            .map_err(|e| CloudTaskError::Io(format!("git apply failed to run: {e}")))?;
+        let status = if r.exit_code == 0 {
+            ApplyStatus::Success
+        } else if !r.applied_paths.is_empty() || !r.conflicted_paths.is_empty() {
+            ApplyStatus::Partial
+        } else {
+            ApplyStatus::Error
+        };
+        let applied = status == ApplyStatus::Success && !preflight;
+        if applied {
+            task.applied = true;
+            self.save_task(&task)?;
+        }
+
+        let message = match (&status, preflight) {
+            (ApplyStatus::Success, true) => {
+                format!("Preflight passed for task {} (applies cleanly)", id.0)
+            }
+            (ApplyStatus::Success, false) => format!("Applied task {} locally", id.0),
+            (_, true) => format!(
+                "Preflight failed for task {} (applied={}, skipped={}, conflicts={})",
+                id.0,
+                r.applied_paths.len(),
+                r.skipped_paths.len(),
+                r.conflicted_paths.len()
+            ),
+            (_, false) => format!(
+                "Apply failed for task {} (applied={}, skipped={}, conflicts={})",
+                id.0,
+                r.applied_paths.len(),
+                r.skipped_paths.len(),
+                r.conflicted_paths.len()
+            ),
+        };
+        Ok(ApplyOutcome {
+            applied,
+            status,
+            message,
+            skipped_paths: r.skipped_paths,
+            conflict_paths: r.conflicted_paths,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl CloudBackend for LocalClient {
+    async fn list_tasks(&self, env: Option<&str>) -> Result<Vec<TaskSummary>> {
+        let mut out = Vec::new();
+        for task in self.load_tasks()? {
+            if env.is_none_or(|env| env == task.environment_id) {
+                out.push(self.summarize(&task)?);
+            }
+        }
+        out.sort_by_key(|task| std::cmp::Reverse(task.updated_at));
+        Ok(out)
+    }
+
+    async fn get_task_diff(&self, id: TaskId) -> Result<Option<String>> {
+        let task = self.load_task(&id)?;
+        match task.attempts.first() {
+            Some(primary) => self.attempt_diff(&task, primary),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_task_messages(&self, id: TaskId) -> Result<Vec<String>> {
+        let task = self.load_task(&id)?;
+        Ok(task
+            .attempts
+            .first()
+            .map(|primary| self.attempt_messages(&task, primary))
+            .unwrap_or_default())
+    }
+
+    async fn get_task_text(&self, id: TaskId) -> Result<TaskText> {
+        let task = self.load_task(&id)?;
+        let Some(primary) = task.attempts.first() else {
+            return Ok(TaskText {
+                prompt: Some(task.prompt),
+                ..TaskText::default()
+            });
+        };
+        Ok(TaskText {
+            prompt: Some(task.prompt.clone()),
+            messages: self.attempt_messages(&task, primary),
+            turn_id: Some(primary.turn_id()),
+            sibling_turn_ids: task
+                .attempts
+                .iter()
+                .skip(1)
+                .map(AttemptRecord::turn_id)
+                .collect(),
+            attempt_placement: Some(primary.placement),
+            attempt_status: self.attempt_status(&task, primary),
+        })
+    }
+
+    async fn list_sibling_attempts(
+        &self,
+        task: TaskId,
+        turn_id: String,
+    ) -> Result<Vec<TurnAttempt>> {
+        let task = self.load_task(&task)?;
+        let mut out = Vec::new();
+        for attempt in task.attempts.iter().filter(|a| a.turn_id() != turn_id) {
+            out.push(TurnAttempt {
+                turn_id: attempt.turn_id(),
+                attempt_placement: Some(attempt.placement),
+                created_at: Some(task.created_at),
+                status: self.attempt_status(&task, attempt),
+                diff: self.attempt_diff(&task, attempt)?,
+                messages: self.attempt_messages(&task, attempt),
+            });
+        }
+        Ok(out)
+    }
+
+    async fn apply_task_preflight(
+        &self,
+        id: TaskId,
+        diff_override: Option<String>,
+    ) -> Result<ApplyOutcome> {
+        self.apply(id, diff_override, true)
+    }
+
+    async fn apply_task(&self, id: TaskId, diff_override: Option<String>) -> Result<ApplyOutcome> {
+        self.apply(id, diff_override, false)
+    }
+
+    async fn create_task(
+        &self,
+        env_id: &str,
+        prompt: &str,
+        git_ref: &str,
+        qa_mode: bool,
+        best_of_n: usize,
+    ) -> Result<CreatedTask> {
+        let created_at = Utc::now();
+        let id = format!("task_local_{}", Uuid::new_v4().simple());
+        let mut task = TaskRecord {
+            id: id.clone(),
+            title: title_from_prompt(prompt),
+            prompt: prompt.to_string(),
+            environment_id: env_id.to_string(),
+            git_ref: git_ref.to_string(),
+            qa_mode,
+            // Replaced by the repository root once the first worktree exists.
+            repo_root: self.repo.clone(),
+            created_at,
+            applied: false,
+            attempts: Vec::new(),
+        };
+        for placement in 0..best_of_n.max(1) as i64 {
+            self.spawn_attempt(&mut task, placement)?;
+            // Save after every attempt so a failure part-way leaves a task
+            // that still lists the attempts already running.
+            self.save_task(&task)?;
+        }
+        Ok(CreatedTask { id: TaskId(id) })
+    }
+}
+
+fn io_err(e: std::io::Error) -> CloudTaskError {
+    CloudTaskError::Io(e.to_string())
+}
+
+/// First line of the prompt, shortened to fit the task list.
+fn title_from_prompt(prompt: &str) -> String {
+    const MAX_TITLE_CHARS: usize = 80;
+    let first_line = prompt.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    let first_line = first_line.trim();
+    if first_line.chars().count() > MAX_TITLE_CHARS {
+        let truncated: String = first_line.chars().take(MAX_TITLE_CHARS - 1).collect();
+        format!("{truncated}…")
+    } else {
+        first_line.to_string()
+    }
+}
+
+/// Counts files and changed lines in a multi-file unified diff.
+fn diff_summary(diff: &str) -> DiffSummary {
+    let mut summary = DiffSummary::default();
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            summary.files_changed += 1;
+        } else if line.starts_with("+++") || line.starts_with("---") {
+            continue;
+        } else if line.starts_with('+') {
+            summary.lines_added += 1;
+        } else if line.starts_with('-') {
+            summary.lines_removed += 1;
+        }
+    }
+    summary
+}
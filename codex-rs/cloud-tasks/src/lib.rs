@@ -28,23 +28,47 @@ struct ApplyJob {
 struct BackendContext {
     backend: Arc<dyn codex_cloud_tasks_client::CloudBackend>,
     base_url: String,
+    /// Tasks run on this machine via `LocalClient`; there are no hosted
+    /// environments or task URLs.
+    local: bool,
 }
 
+/// Environment id used for tasks created by the local backend.
+const LOCAL_ENV_ID: &str = "local";
+
 async fn init_backend(user_agent_suffix: &str) -> anyhow::Result<BackendContext> {
-    let use_mock = matches!(
-        std::env::var("CODEX_CLOUD_TASKS_MODE").ok().as_deref(),
-        Some("mock") | Some("MOCK")
-    );
+    let mode = std::env::var("CODEX_CLOUD_TASKS_MODE")
+        .ok()
+        .map(|mode| mode.to_ascii_lowercase());
     let base_url = std::env::var("CODEX_CLOUD_TASKS_BASE_URL")
         .unwrap_or_else(|_| "https://chatgpt.com/backend-api".to_string());
 
     set_user_agent_suffix(user_agent_suffix);
 
-    if use_mock {
-        return Ok(BackendContext {
-            backend: Arc::new(codex_cloud_tasks_client::MockClient),
-            base_url,
-        });
+    match mode.as_deref() {
+        Some("mock") => {
+            return Ok(BackendContext {
+                backend: Arc::new(codex_cloud_tasks_client::MockClient),
+                base_url,
+                local: false,
+            });
+        }
+        Some("local") => {
+            // Tasks run as local `codex exec` processes in worktrees of the
+            // current repository; state is kept under CODEX_HOME.
+            let root = match std::env::var("CODEX_CLOUD_TASKS_LOCAL_DIR") {
+                Ok(dir) => PathBuf::from(dir),
+                Err(_) => codex_core::config::find_codex_home()?.join("cloud-tasks"),
+            };
+            append_error_log(format!("startup: local backend root={}", root.display()));
+            let cwd = std::env::current_dir()?;
+            return Ok(BackendContext {
+                backend: Arc::new(codex_cloud_tasks_client::LocalClient::new(root, cwd)),
+                base_url,
+                local: true,
+            });
+        }
+        _ => {}
     }
 
     let ua = codex_core::default_client::get_codex_user_agent();
@@ -96,6 +120,7 @@ async fn init_backend(user_agent_suffix: &str) -> anyhow::Result<BackendContext>
     Ok(BackendContext {
         backend: Arc::new(http),
         base_url,
+        local: false,
     })
 }
 
@@ -107,7 +132,15 @@ async fn run_exec_command(args: crate::cli::ExecCommand) -> anyhow::Result<()> {
     } = args;
     let ctx = init_backend("codex_cloud_tasks_exec").await?;
     let prompt = resolve_query_input(query)?;
-    let env_id = resolve_environment_id(&ctx, &environment).await?;
+    let env_id = if ctx.local {
+        let trimmed = environment.trim();
+        if trimmed.is_empty() {
+            return Err(anyhow!("environment id must not be empty"));
+        }
+        trimmed.to_string()
+    } else {
+        resolve_environment_id(&ctx, &environment).await?
+    };
     let created = codex_cloud_tasks_client::CloudBackend::create_task(
         &*ctx.backend,
         &env_id,
@@ -117,8 +150,12 @@ async fn run_exec_command(args: crate::cli::ExecCommand) -> anyhow::Result<()> {
         attempts,
     )
     .await?;
-    let url = util::task_url(&ctx.base_url, &created.id.0);
-    println!("{url}");
+    if ctx.local {
+        println!("{}", created.id.0);
+    } else {
+        let url = util::task_url(&ctx.base_url, &created.id.0);
+        println!("{url}");
+    }
     Ok(())
 }
 
@@ -340,7 +377,7 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
         .try_init();
 
     info!("Launching Cloud Tasks list UI");
-    let BackendContext { backend, .. } = init_backend("codex_cloud_tasks_tui").await?;
+    let BackendContext { backend, local, .. } = init_backend("codex_cloud_tasks_tui").await?;
     let backend = backend;
 
     // Terminal setup
@@ -420,8 +457,29 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
             });
         });
     }
+    if local {
+        // The local backend has a single environment: the current repository.
+        let label = std::env::current_dir().ok().and_then(|cwd| {
+            cwd.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        });
+        let _ = tx.send(app::AppEvent::EnvironmentsLoaded(Ok(vec![
+            app::EnvironmentRow {
+                id: LOCAL_ENV_ID.to_string(),
+                label: label.clone(),
+                is_pinned: true,
+                repo_hints: None,
+            },
+        ])));
+        let _ = tx.send(app::AppEvent::EnvironmentAutodetected(Ok(
+            crate::env_detect::AutodetectSelection {
+                id: LOCAL_ENV_ID.to_string(),
+                label,
+            },
+        )));
+    }
     // Fetch environment list in parallel so the header can show friendly names quickly.
-    {
+    if !local {
         let tx = tx.clone();
         tokio::spawn(async move {
             let base_url = util::normalize_base_url(
@@ -436,7 +494,7 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
 
     // Try to auto-detect a likely environment id on startup and refresh if found.
     // Do this concurrently so the initial list shows quickly; on success we refetch with filter.
-    {
+    if !local {
         let tx = tx.clone();
         tokio::spawn(async move {
             let base_url = util::normalize_base_url(
@@ -657,8 +715,8 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                                         });
                                     }
                                     // Proactively fetch environments to resolve a friendly name for the header.
-                                    app.env_loading = true;
-                                    {
+                                    if !local {
+                                        app.env_loading = true;
                                         let tx = tx.clone();
                                         tokio::spawn(async move {
                                             let base_url = crate::util::normalize_base_url(
@@ -1292,7 +1350,7 @@ pub async fn run_main(cli: Cli, _codex_linux_sandbox_exe: Option<PathBuf>) -> an
                             // Environment modal key handling
                             match key.code {
                                 KeyCode::Esc => { app.env_modal = None; needs_redraw = true; }
-                                KeyCode::Char('r') | KeyCode::Char('R') => {
+                                KeyCode::Char('r') | KeyCode::Char('R') if !local => {
                                     // Trigger refresh of environments
                                     app.env_loading = true; app.env_error = None; needs_redraw = true;
                                     let _ = frame_tx.send(Instant::now() + Duration::from_millis(100));
@@ -0,0 +1,125 @@
+#![cfg(unix)]
+
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use codex_cloud_tasks_client::ApplyStatus;
+use codex_cloud_tasks_client::AttemptStatus;
+use codex_cloud_tasks_client::CloudBackend;
+use codex_cloud_tasks_client::LocalClient;
+use codex_cloud_tasks_client::TaskStatus;
+
+/// Stands in for `codex exec`: writes a file named after the attempt's
+/// worktree, records a final message and reports the turn as completed.
+const FAKE_CODEX: &str = r#"#!/bin/sh
+while [ $# -gt 0 ]; do
+  case "$1" in
+    --cd) cd "$2"; shift 2 ;;
+    --output-last-message) out="$2"; shift 2 ;;
+    *) shift ;;
+  esac
+done
+basename "$(dirname "$PWD")" > agent.txt
+echo "Added agent.txt" > "$out"
+echo '{"type":"turn.completed","usage":{"input_tokens":1,"cached_input_tokens":0,"output_tokens":1}}'
+"#;
+
+fn git(repo: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .current_dir(repo)
+        .args(args)
+        .status()
+        .expect("git command");
+    assert!(status.success(), "git command failed: {args:?}");
+}
+
+#[tokio::test]
+async fn local_backend_runs_attempts_in_worktrees_and_applies_the_result() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = tempfile::tempdir().expect("repo dir");
+    let root = tempfile::tempdir().expect("tasks dir");
+    git(repo.path(), &["init", "--initial-branch=main"]);
+    git(repo.path(), &["config", "user.name", "Tester"]);
+    git(repo.path(), &["config", "user.email", "test@example.com"]);
+    std::fs::write(repo.path().join("README.md"), "hello\n").expect("write readme");
+    git(repo.path(), &["add", "README.md"]);
+    git(repo.path(), &["commit", "-m", "init"]);
+
+    let codex_bin = root.path().join("fake-codex");
+    std::fs::write(&codex_bin, FAKE_CODEX).expect("write fake codex");
+    std::fs::set_permissions(&codex_bin, std::fs::Permissions::from_mode(0o755))
+        .expect("chmod fake codex");
+
+    let client =
+        LocalClient::new(root.path().join("tasks"), repo.path()).with_codex_bin(&codex_bin);
+    let created = client
+        .create_task("local", "Add agent.txt\nwith details", "main", false, 2)
+        .await
+        .expect("create task");
+
+    let mut tasks = Vec::new();
+    for _ in 0..100 {
+        tasks = client.list_tasks(Some("local")).await.expect("list tasks");
+        if tasks.iter().all(|t| t.status == TaskStatus::Ready) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].title, "Add agent.txt");
+    assert_eq!(tasks[0].status, TaskStatus::Ready);
+    assert_eq!(tasks[0].attempt_total, Some(2));
+    assert_eq!(tasks[0].summary.files_changed, 1);
+    assert!(
+        client
+            .list_tasks(Some("other"))
+            .await
+            .expect("list")
+            .is_empty()
+    );
+
+    let text = client
+        .get_task_text(created.id.clone())
+        .await
+        .expect("text");
+    assert_eq!(text.messages, vec!["Added agent.txt\n".to_string()]);
+    assert_eq!(text.attempt_status, AttemptStatus::Completed);
+    let turn_id = text.turn_id.expect("turn id");
+
+    let siblings = client
+        .list_sibling_attempts(created.id.clone(), turn_id)
+        .await
+        .expect("siblings");
+    assert_eq!(siblings.len(), 1);
+    let sibling_diff = siblings[0].diff.clone().expect("sibling diff");
+    assert!(sibling_diff.contains("+attempt-1"), "diff: {sibling_diff}");
+
+    let diff = client
+        .get_task_diff(created.id.clone())
+        .await
+        .expect("diff")
+        .expect("primary diff");
+    assert!(diff.contains("+attempt-0"), "diff: {diff}");
+
+    let preflight = client
+        .apply_task_preflight(created.id.clone(), None)
+        .await
+        .expect("preflight");
+    assert_eq!(preflight.status, ApplyStatus::Success);
+    assert!(!repo.path().join("agent.txt").exists());
+
+    // Apply the alternate attempt instead of the primary one.
+    let outcome = client
+        .apply_task(created.id.clone(), Some(sibling_diff))
+        .await
+        .expect("apply");
+    assert!(outcome.applied, "{outcome:?}");
+    assert_eq!(
+        std::fs::read_to_string(repo.path().join("agent.txt")).expect("read applied file"),
+        "attempt-1\n"
+    );
+    let tasks = client.list_tasks(None).await.expect("list tasks");
+    assert_eq!(tasks[0].status, TaskStatus::Applied);
+}
@@ -45,6 +45,7 @@ pub(crate) async fn apply_patch(
         turn_context.approval_policy,
         &turn_context.sandbox_policy,
         &turn_context.cwd,
+        &sess.services.executor.approval_rules(),
     ) {
         SafetyCheck::AutoApprove {
             user_explicitly_approved,
@@ -64,8 +65,16 @@ pub(crate) async fn apply_patch(
             let rx_approve = sess
                 .request_patch_approval(sub_id.to_owned(), call_id.to_owned(), &action, None, None)
                 .await;
-            match rx_approve.await.unwrap_or_default() {
-                ReviewDecision::Approved | ReviewDecision::ApprovedForSession => {
+            let decision = rx_approve.await.unwrap_or_default();
+            if decision == ReviewDecision::ApprovedForProject {
+                sess.services
+                    .executor
+                    .remember_patches_for_project(&turn_context.cwd);
+            }
+            match decision {
+                ReviewDecision::Approved
+                | ReviewDecision::ApprovedForSession
+                | ReviewDecision::ApprovedForProject => {
                     InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
                         action,
                         user_explicitly_approved_this_action: true,
@@ -0,0 +1,413 @@
+//! Persistent command approval rules.
+//!
+//! Rules live in `~/.codex/approval_rules.json` and survive across sessions,
+//! unlike the per-session approvals kept by the executor. Each rule pairs a
+//! [`CommandMatcher`] with an [`ApprovalScope`]:
+//!
+//! ````text
+//! {"rules":[{"id":"3f2a9c1e","matcher":{"type":"prefix","command":["cargo","test"]},
+//!            "scope":{"type":"project","path":"/home/me/repo"},"created_at":1718000000}]}
+//! ````
+//!
+//! A session loads the rules once when it starts; rules added from an approval
+//! prompt are written to disk and also apply to the rest of that session.
+//! Edits made from the CLI or another Codex process take effect in the next
+//! session. A project-scoped exact rule whose argv is just `apply_patch`
+//! approves patches whose paths all fall inside that project; the patch still
+//! runs in the platform sandbox.
+
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::fs::TryLockError;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Serialize;
+use tempfile::NamedTempFile;
+use wildmatch::WildMatchPattern;
+
+use crate::git_info::resolve_root_git_project_for_trust;
+use crate::safety::normalize_path;
+
+/// Filename that stores approval rules inside `~/.codex`.
+pub const APPROVAL_RULES_FILENAME: &str = "approval_rules.json";
+
+/// Lock file held while the rules file is read, modified and written back.
+/// The rules file itself is replaced by a rename, so it cannot carry the lock.
+const APPROVAL_RULES_LOCK_FILENAME: &str = "approval_rules.json.lock";
+
+/// How often, and how long apart, to retry taking the lock when contended.
+const LOCK_MAX_RETRIES: usize = 50;
+const LOCK_RETRY_SLEEP: Duration = Duration::from_millis(100);
+
+/// Argv recorded by rules that approve `apply_patch` edits inside a project.
+pub const APPLY_PATCH_RULE_COMMAND: &str = "apply_patch";
+
+/// Pattern token that matches any number of remaining arguments.
+const REST_OF_ARGS: &str = "...";
+
+/// Describes which argv vectors a rule applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CommandMatcher {
+    /// Matches exactly this argv.
+    Exact { command: Vec<String> },
+    /// Matches any argv that starts with these arguments.
+    Prefix { command: Vec<String> },
+    /// Matches argv element-wise against glob patterns (`*` and `?`). A final
+    /// `...` element matches any number of remaining arguments.
+    Pattern { pattern: Vec<String> },
+}
+
+impl CommandMatcher {
+    pub fn matches(&self, command: &[String]) -> bool {
+        match self {
+            CommandMatcher::Exact { command: expected } => {
+                !expected.is_empty() && expected.as_slice() == command
+            }
+            CommandMatcher::Prefix { command: prefix } => {
+                !prefix.is_empty() && command.starts_with(prefix)
+            }
+            CommandMatcher::Pattern { pattern } => pattern_matches(pattern, command),
+        }
+    }
+
+    /// Short, human-readable description used by the CLI and TUI listings.
+    pub fn describe(&self) -> String {
+        match self {
+            CommandMatcher::Exact { command } => shlex_join(command),
+            CommandMatcher::Prefix { command } => format!("{} …", shlex_join(command)),
+            CommandMatcher::Pattern { pattern } => format!("pattern: {}", pattern.join(" ")),
+        }
+    }
+}
+
+fn shlex_join(command: &[String]) -> String {
+    shlex::try_join(command.iter().map(String::as_str)).unwrap_or_else(|_| command.join(" "))
+}
+
+fn pattern_matches(pattern: &[String], command: &[String]) -> bool {
+    let (tokens, allow_rest) = match pattern.split_last() {
+        Some((last, rest)) if last == REST_OF_ARGS => (rest, true),
+        Some(_) => (pattern, false),
+        None => return false,
+    };
+    if tokens.is_empty() && !allow_rest {
+        return false;
+    }
+    if command.len() < tokens.len() || (!allow_rest && command.len() != tokens.len()) {
+        return false;
+    }
+    tokens
+        .iter()
+        .zip(command)
+        .all(|(token, arg)| WildMatchPattern::<'*', '?'>::new(token).matches(arg))
+}
+
+/// Where a rule applies.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ApprovalScope {
+    /// Applies regardless of the working directory.
+    Global,
+    /// Applies when the command runs in `path` or one of its subdirectories.
+    Project { path: PathBuf },
+}
+
+impl ApprovalScope {
+    /// Scope covering the project that contains `cwd`: the root of the main
+    /// git repository when there is one, otherwise `cwd` itself.
+    pub fn project_for_cwd(cwd: &Path) -> Self {
+        let path = resolve_root_git_project_for_trust(cwd).unwrap_or_else(|| cwd.to_path_buf());
+        ApprovalScope::Project { path }
+    }
+
+    pub fn applies_to(&self, cwd: &Path) -> bool {
+        match self {
+            ApprovalScope::Global => true,
+            ApprovalScope::Project { path } => {
+                // Resolve `..` first: `Path::starts_with` compares components,
+                // so `<project>/../outside` would otherwise count as inside.
+                let cwd = normalize_path(cwd);
+                if cwd.starts_with(path) {
+                    return true;
+                }
+                // Fall back to canonical paths so symlinked checkouts still match.
+                match (dunce::canonicalize(cwd), dunce::canonicalize(path)) {
+                    (Ok(cwd), Ok(path)) => cwd.starts_with(path),
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalRule {
+    /// Short identifier used to revoke the rule.
+    pub id: String,
+    pub matcher: CommandMatcher,
+    pub scope: ApprovalScope,
+    /// Creation time in seconds since the Unix epoch.
+    #[serde(default)]
+    pub created_at: u64,
+}
+
+impl ApprovalRule {
+    pub fn matches(&self, command: &[String], cwd: &Path) -> bool {
+        self.scope.applies_to(cwd) && self.matcher.matches(command)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ApprovalRulesFile {
+    #[serde(default)]
+    rules: Vec<ApprovalRule>,
+}
+
+pub fn approval_rules_path(codex_home: &Path) -> PathBuf {
+    codex_home.join(APPROVAL_RULES_FILENAME)
+}
+
+/// Loads all persisted rules. A missing file yields an empty list.
+pub fn load_approval_rules(codex_home: &Path) -> std::io::Result<Vec<ApprovalRule>> {
+    let contents = match std::fs::read_to_string(approval_rules_path(codex_home)) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let file: ApprovalRulesFile = serde_json::from_str(&contents)
+        .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+    Ok(file.rules)
+}
+
+/// Returns the first persisted rule that approves `command` when run in `cwd`.
+pub fn find_matching_rule<'a>(
+    rules: &'a [ApprovalRule],
+    command: &[String],
+    cwd: &Path,
+) -> Option<&'a ApprovalRule> {
+    rules.iter().find(|rule| rule.matches(command, cwd))
+}
+
+/// Persists a new rule, or returns the existing one when an identical
+/// matcher/scope pair is already stored.
+pub fn add_approval_rule(
+    codex_home: &Path,
+    matcher: CommandMatcher,
+    scope: ApprovalScope,
+) -> std::io::Result<ApprovalRule> {
+    let _lock = lock_approval_rules(codex_home)?;
+    let mut rules = load_approval_rules(codex_home)?;
+    if let Some(existing) = rules
+        .iter()
+        .find(|rule| rule.matcher == matcher && rule.scope == scope)
+    {
+        return Ok(existing.clone());
+    }
+
+    let created_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let rule = ApprovalRule {
+        id: new_rule_id(&rules),
+        matcher,
+        scope,
+        created_at,
+    };
+    rules.push(rule.clone());
+    write_approval_rules(codex_home, rules)?;
+    Ok(rule)
+}
+
+/// Removes the rule with the given id. Returns the removed rule, if any.
+pub fn remove_approval_rule(codex_home: &Path, id: &str) -> std::io::Result<Option<ApprovalRule>> {
+    let _lock = lock_approval_rules(codex_home)?;
+    let mut rules = load_approval_rules(codex_home)?;
+    let Some(index) = rules.iter().position(|rule| rule.id == id) else {
+        return Ok(None);
+    };
+    let removed = rules.remove(index);
+    write_approval_rules(codex_home, rules)?;
+    Ok(Some(removed))
+}
+
+/// Takes an exclusive advisory lock that serializes read-modify-write cycles
+/// on the rules file across processes. The lock is released when the returned
+/// file is dropped.
+fn lock_approval_rules(codex_home: &Path) -> std::io::Result<File> {
+    std::fs::create_dir_all(codex_home)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(codex_home.join(APPROVAL_RULES_LOCK_FILENAME))?;
+    // Retry for a while rather than blocking indefinitely when contended.
+    for _ in 0..LOCK_MAX_RETRIES {
+        match file.try_lock() {
+            Ok(()) => return Ok(file),
+            Err(TryLockError::WouldBlock) => std::thread::sleep(LOCK_RETRY_SLEEP),
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Err(std::io::Error::new(
+        ErrorKind::WouldBlock,
+        "could not acquire exclusive lock on approval rules after multiple attempts",
+    ))
+}
+
+fn new_rule_id(existing: &[ApprovalRule]) -> String {
+    loop {
+        let id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+        if existing.iter().all(|rule| rule.id != id) {
+            return id;
+        }
+    }
+}
+
+fn write_approval_rules(codex_home: &Path, rules: Vec<ApprovalRule>) -> std::io::Result<()> {
+    std::fs::create_dir_all(codex_home)?;
+    let json = serde_json::to_string_pretty(&ApprovalRulesFile { rules })
+        .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+    let tmp_file = NamedTempFile::new_in(codex_home)?;
+    std::fs::write(tmp_file.path(), json)?;
+    tmp_file.persist(approval_rules_path(codex_home))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn exact_and_prefix_matchers() {
+        let exact = CommandMatcher::Exact {
+            command: argv(&["cargo", "test"]),
+        };
+        assert!(exact.matches(&argv(&["cargo", "test"])));
+        assert!(!exact.matches(&argv(&["cargo", "test", "-p", "foo"])));
+
+        let prefix = CommandMatcher::Prefix {
+            command: argv(&["cargo", "test"]),
+        };
+        assert!(prefix.matches(&argv(&["cargo", "test", "-p", "foo"])));
+        assert!(!prefix.matches(&argv(&["cargo", "build"])));
+        assert!(!CommandMatcher::Prefix { command: vec![] }.matches(&argv(&["rm", "-rf", "/"])));
+    }
+
+    #[test]
+    fn pattern_matcher_supports_globs_and_rest() {
+        let pattern = CommandMatcher::Pattern {
+            pattern: argv(&["cargo", "test", "-p", "codex-*"]),
+        };
+        assert!(pattern.matches(&argv(&["cargo", "test", "-p", "codex-core"])));
+        assert!(!pattern.matches(&argv(&["cargo", "test", "-p", "other"])));
+        assert!(!pattern.matches(&argv(&["cargo", "test", "-p", "codex-core", "--", "x"])));
+
+        let rest = CommandMatcher::Pattern {
+            pattern: argv(&["npm", "run", "..."]),
+        };
+        assert!(rest.matches(&argv(&["npm", "run"])));
+        assert!(rest.matches(&argv(&["npm", "run", "lint", "--fix"])));
+        assert!(!rest.matches(&argv(&["npm", "install"])));
+    }
+
+    #[test]
+    fn project_scope_applies_to_subdirectories_only() {
+        let tmp = TempDir::new().unwrap();
+        let project = tmp.path().join("project");
+        let nested = project.join("crates").join("foo");
+        std::fs::create_dir_all(&nested).unwrap();
+        let other = tmp.path().join("other");
+        std::fs::create_dir_all(&other).unwrap();
+
+        let scope = ApprovalScope::Project {
+            path: project.clone(),
+        };
+        assert!(scope.applies_to(&project));
+        assert!(scope.applies_to(&nested));
+        assert!(!scope.applies_to(&other));
+        assert!(!scope.applies_to(&nested.join("../../../other")));
+        assert!(ApprovalScope::Global.applies_to(&other));
+    }
+
+    #[test]
+    fn add_find_and_remove_round_trip() {
+        let codex_home = TempDir::new().unwrap();
+        assert_eq!(load_approval_rules(codex_home.path()).unwrap(), vec![]);
+
+        let project = codex_home.path().join("repo");
+        std::fs::create_dir_all(&project).unwrap();
+        let rule = add_approval_rule(
+            codex_home.path(),
+            CommandMatcher::Prefix {
+                command: argv(&["cargo", "test"]),
+            },
+            ApprovalScope::Project {
+                path: project.clone(),
+            },
+        )
+        .unwrap();
+
+        // Adding the same rule again does not create a duplicate.
+        let again =
+            add_approval_rule(codex_home.path(), rule.matcher.clone(), rule.scope.clone()).unwrap();
+        assert_eq!(again, rule);
+
+        let rules = load_approval_rules(codex_home.path()).unwrap();
+        assert_eq!(rules, vec![rule.clone()]);
+        assert_eq!(
+            find_matching_rule(&rules, &argv(&["cargo", "test", "-p", "foo"]), &project),
+            Some(&rule)
+        );
+        assert_eq!(
+            find_matching_rule(&rules, &argv(&["cargo", "test"]), codex_home.path()),
+            None
+        );
+
+        assert_eq!(
+            remove_approval_rule(codex_home.path(), &rule.id).unwrap(),
+            Some(rule)
+        );
+        assert_eq!(
+            remove_approval_rule(codex_home.path(), "missing").unwrap(),
+            None
+        );
+        assert_eq!(load_approval_rules(codex_home.path()).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn concurrent_adds_are_all_persisted() {
+        let codex_home = TempDir::new().unwrap();
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let codex_home = codex_home.path().to_path_buf();
+                std::thread::spawn(move || {
+                    add_approval_rule(
+                        &codex_home,
+                        CommandMatcher::Exact {
+                            command: argv(&["echo", &i.to_string()]),
+                        },
+                        ApprovalScope::Global,
+                    )
+                    .unwrap()
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(load_approval_rules(codex_home.path()).unwrap().len(), 8);
+    }
+}
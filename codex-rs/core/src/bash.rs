@@ -1,3 +1,5 @@
+use codex_execpolicy::ScriptCommand;
+use codex_execpolicy::parse_shell_script;
 use tree_sitter::Node;
 use tree_sitter::Parser;
 use tree_sitter::Tree;
@@ -103,6 +105,21 @@ pub fn parse_bash_lc_plain_commands(command: &[String]) -> Option<Vec<Vec<String
     try_parse_word_only_commands_sequence(&tree, script)
 }
 
+/// Returns the commands within a `bash -lc "..."` invocation as split by the
+/// execpolicy script parser, which also understands subshells and
+/// redirections. Returns `None` if the script cannot be checked statically.
+pub fn parse_bash_lc_script(command: &[String]) -> Option<Vec<ScriptCommand>> {
+    let [bash, flag, script] = command else {
+        return None;
+    };
+
+    if bash != "bash" || flag != "-lc" {
+        return None;
+    }
+
+    parse_shell_script(script).ok()
+}
+
 fn parse_plain_command_from_node(cmd: tree_sitter::Node, src: &str) -> Option<Vec<String>> {
     if cmd.kind() != "command" {
         return None;
@@ -25,6 +25,7 @@ use tracing::debug;
 use tracing::trace;
 use tracing::warn;
 
+use crate::anthropic_messages::stream_anthropic_messages;
 use crate::chat_completions::AggregateStreamExt;
 use crate::chat_completions::stream_chat_completions;
 use crate::client_common::Prompt;
@@ -42,6 +43,8 @@ use crate::flags::CODEX_RS_SSE_FIXTURE;
 use crate::model_family::ModelFamily;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
+use crate::ollama_chat::cached_context_length;
+use crate::ollama_chat::stream_ollama_chat;
 use crate::openai_model_info::get_model_info;
 use crate::openai_tools::create_tools_json_for_responses_api;
 use crate::protocol::RateLimitSnapshot;
@@ -112,6 +115,13 @@ impl ModelClient {
         self.config
             .model_context_window
             .or_else(|| get_model_info(&self.config.model_family).map(|info| info.context_window))
+            .or_else(|| match self.provider.wire_api {
+                // Known once the first request has probed the model.
+                WireApi::Ollama => {
+                    cached_context_length(&self.provider, &self.config.model_family.slug)
+                }
+                WireApi::Responses | WireApi::Chat | WireApi::Anthropic => None,
+            })
     }
 
     pub fn get_auto_compact_token_limit(&self) -> Option<i64> {
@@ -120,8 +130,8 @@ impl ModelClient {
         })
     }
 
-    /// Dispatches to either the Responses or Chat implementation depending on
-    /// the provider config.  Public callers always invoke `stream()` – the
+    /// Dispatches to the Responses, Chat, Anthropic Messages or Ollama chat
+    /// implementation depending on the provider config.  Public callers always invoke `stream()` – the
     /// specialised helpers are private to avoid accidental misuse.
     pub async fn stream(&self, prompt: &Prompt) -> Result<ResponseStream> {
         self.stream_with_task_kind(prompt, TaskKind::Regular).await
@@ -170,6 +180,29 @@ impl ModelClient {
 
                 Ok(ResponseStream { rx_event: rx })
             }
+            WireApi::Anthropic => {
+                stream_anthropic_messages(
+                    prompt,
+                    &self.config.model_family,
+                    self.config.model_max_output_tokens,
+                    self.effort,
+                    &self.client,
+                    &self.provider,
+                    &self.otel_event_manager,
+                )
+                .await
+            }
+            WireApi::Ollama => {
+                stream_ollama_chat(
+                    prompt,
+                    &self.config.model_family,
+                    self.config.model_context_window,
+                    &self.client,
+                    &self.provider,
+                    &self.otel_event_manager,
+                )
+                .await
+            }
         }
     }
 
@@ -466,6 +499,10 @@ impl ModelClient {
     pub fn get_auth_manager(&self) -> Option<Arc<AuthManager>> {
         self.auth_manager.clone()
     }
+
+    pub(crate) fn get_config(&self) -> Arc<Config> {
+        Arc::clone(&self.config)
+    }
 }
 
 enum StreamAttemptError {
@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::fmt::Debug;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
@@ -8,6 +9,8 @@ use crate::AuthManager;
 use crate::client_common::REVIEW_PROMPT;
 use crate::event_mapping::map_response_item_to_event_messages;
 use crate::function_tool::FunctionCallError;
+use crate::hooks::HookOutcome;
+use crate::hooks::HookRunner;
 use crate::review_format::format_review_findings_block;
 use crate::terminal;
 use crate::user_notification::UserNotifier;
@@ -43,6 +46,7 @@ use crate::apply_patch::convert_apply_patch_to_protocol;
 use crate::client::ModelClient;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
+use crate::command_safety::is_safe_command::is_known_safe_command;
 use crate::config::Config;
 use crate::config_types::ShellEnvironmentPolicy;
 use crate::conversation_history::ConversationHistory;
@@ -55,16 +59,22 @@ use crate::exec::StreamOutput;
 use crate::exec_command::ExecCommandParams;
 use crate::exec_command::ExecSessionManager;
 use crate::exec_command::WriteStdinParams;
+use crate::exec_policy::trusted_policy_project;
 use crate::executor::Executor;
 use crate::executor::ExecutorConfig;
 use crate::executor::normalize_exec_result;
 use crate::mcp::auth::compute_auth_statuses;
+use crate::mcp::references::has_mcp_references;
+use crate::mcp::references::resolve_mcp_references;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::model_family::ModelFamily;
 use crate::model_family::find_family_for_model;
 use crate::openai_model_info::get_model_info;
 use crate::openai_tools::ToolsConfig;
 use crate::openai_tools::ToolsConfigParams;
 use crate::parse_command::parse_command;
+use crate::project_doc::ScopedProjectDoc;
+use crate::project_doc::ScopedProjectDocs;
 use crate::project_doc::get_user_instructions;
 use crate::protocol::AgentMessageDeltaEvent;
 use crate::protocol::AgentReasoningDeltaEvent;
@@ -79,8 +89,11 @@ use crate::protocol::EventMsg;
 use crate::protocol::ExecApprovalRequestEvent;
 use crate::protocol::ExecCommandBeginEvent;
 use crate::protocol::ExecCommandEndEvent;
+use crate::protocol::FileChange;
 use crate::protocol::InputItem;
 use crate::protocol::ListCustomPromptsResponseEvent;
+use crate::protocol::McpListPromptsResponseEvent;
+use crate::protocol::McpListResourcesResponseEvent;
 use crate::protocol::Op;
 use crate::protocol::PatchApplyBeginEvent;
 use crate::protocol::PatchApplyEndEvent;
@@ -95,8 +108,10 @@ use crate::protocol::TokenCountEvent;
 use crate::protocol::TokenUsage;
 use crate::protocol::TurnDiffEvent;
 use crate::protocol::WebSearchBeginEvent;
+use crate::review_target::resolve_review_request;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
+use crate::rollout::usage::latest_session_usage;
 use crate::shell;
 use crate::state::ActiveTurn;
 use crate::state::SessionServices;
@@ -104,6 +119,7 @@ use crate::state::TaskKind;
 use crate::tasks::CompactTask;
 use crate::tasks::RegularTask;
 use crate::tasks::ReviewTask;
+use crate::token_estimate;
 use crate::tools::ToolRouter;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::format_exec_output_str;
@@ -125,6 +141,7 @@ use codex_protocol::protocol::InitialHistory;
 
 pub mod compact;
 use self::compact::build_compacted_history;
+use self::compact::collect_pinned_items;
 use self::compact::collect_user_messages;
 
 /// The high-level interface to the Codex system.
@@ -146,6 +163,10 @@ pub struct CodexSpawnOk {
 pub(crate) const INITIAL_SUBMIT_ID: &str = "";
 pub(crate) const SUBMISSION_CHANNEL_CAPACITY: usize = 64;
 
+/// Share of the context window an estimated request may fill before the
+/// history is compacted ahead of sending it; the rest is left for output.
+const PROACTIVE_AUTO_COMPACT_WINDOW_PERCENT: u64 = 90;
+
 impl Codex {
     /// Spawn a new [`Codex`] and initialize the session.
     pub async fn spawn(
@@ -399,7 +420,15 @@ impl Session {
         })?;
         let rollout_path = rollout_recorder.rollout_path.clone();
         // Create the mutable state for the Session.
-        let state = SessionState::new();
+        let mut state = SessionState::new();
+        match ScopedProjectDocs::new(&config) {
+            Ok(scoped_project_docs) => state.scoped_project_docs = scoped_project_docs,
+            Err(e) => error!("error trying to find project doc scopes: {e:#}"),
+        }
+        // Load the tokenizer vocabulary in the background so the first
+        // request of the session does not wait for it.
+        let tokenizer_model = config.model.clone();
+        tokio::task::spawn_blocking(move || token_estimate::tokenizer_for_model(&tokenizer_model));
 
         // Handle MCP manager result and record any startup failures.
         let (mcp_connection_manager, failed_clients) = match mcp_res {
@@ -451,7 +480,7 @@ impl Session {
             terminal::user_agent(),
         );
 
-        otel_event_manager.conversation_starts(
+        let session_span = otel_event_manager.conversation_starts(
             config.model_provider.name.as_str(),
             config.model_reasoning_effort,
             config.model_reasoning_summary,
@@ -480,7 +509,8 @@ impl Session {
             tools_config: ToolsConfig::new(&ToolsConfigParams {
                 model_family: &config.model_family,
                 features: &config.features,
-            }),
+            })
+            .with_enabled_tools(config.enabled_tools.clone()),
             user_instructions,
             base_instructions,
             approval_policy,
@@ -495,14 +525,22 @@ impl Session {
             session_manager: ExecSessionManager::default(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: notify,
+            hooks: HookRunner::new(config.hooks.clone(), conversation_id.to_string()),
             rollout: Mutex::new(Some(rollout_recorder)),
             user_shell: default_shell,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
-            executor: Executor::new(ExecutorConfig::new(
-                turn_context.sandbox_policy.clone(),
-                turn_context.cwd.clone(),
-                config.codex_linux_sandbox_exe.clone(),
-            )),
+            model_pricing: config.model_pricing.clone(),
+            session_budget_usd: config.session_budget_usd,
+            executor: Executor::new(
+                ExecutorConfig::new(
+                    turn_context.sandbox_policy.clone(),
+                    turn_context.cwd.clone(),
+                    config.codex_linux_sandbox_exe.clone(),
+                ),
+                config.codex_home.clone(),
+                trusted_policy_project(&config),
+            ),
+            session_span: session_span.end_on_drop(),
         };
 
         let sess = Arc::new(Session {
@@ -536,6 +574,9 @@ impl Session {
         for event in events {
             sess.send_event(event).await;
         }
+        sess.services
+            .hooks
+            .session_start(&turn_context.cwd, &config.model);
 
         Ok((sess, turn_context))
     }
@@ -566,6 +607,12 @@ impl Session {
                 let rollout_items = conversation_history.get_rollout_items();
                 let persist = matches!(conversation_history, InitialHistory::Forked(_));
 
+                if let InitialHistory::Resumed(_) = &conversation_history
+                    && let Some(usage) = latest_session_usage(&rollout_items)
+                {
+                    self.state.lock().await.restore_usage(usage);
+                }
+
                 // Always add response items to conversation history
                 let reconstructed_history =
                     self.reconstruct_history_from_rollout(turn_context, &rollout_items);
@@ -605,21 +652,8 @@ impl Session {
         reason: Option<String>,
     ) -> ReviewDecision {
         // Add the tx_approve callback to the map before sending the request.
-        let (tx_approve, rx_approve) = oneshot::channel();
         let event_id = sub_id.clone();
-        let prev_entry = {
-            let mut active = self.active_turn.lock().await;
-            match active.as_mut() {
-                Some(at) => {
-                    let mut ts = at.turn_state.lock().await;
-                    ts.insert_pending_approval(sub_id, tx_approve)
-                }
-                None => None,
-            }
-        };
-        if prev_entry.is_some() {
-            warn!("Overwriting existing pending approval for sub_id: {event_id}");
-        }
+        let rx_approve = self.register_pending_approval(sub_id).await;
 
         let parsed_cmd = parse_command(&command);
         let event = Event {
@@ -645,6 +679,36 @@ impl Session {
         grant_root: Option<PathBuf>,
     ) -> oneshot::Receiver<ReviewDecision> {
         // Add the tx_approve callback to the map before sending the request.
+        let event_id = sub_id.clone();
+        let rx_approve = self.register_pending_approval(sub_id).await;
+
+        let event = Event {
+            id: event_id,
+            msg: EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
+                call_id,
+                changes: convert_apply_patch_to_protocol(action),
+                reason,
+                grant_root,
+            }),
+        };
+        self.send_event(event).await;
+        rx_approve
+    }
+
+    /// Re-emits an approval request raised by a child conversation (see the
+    /// `spawn_agent` tool) under `sub_id` and awaits the user's decision.
+    pub(crate) async fn request_forwarded_approval(
+        &self,
+        sub_id: String,
+        msg: EventMsg,
+    ) -> ReviewDecision {
+        let event_id = sub_id.clone();
+        let rx_approve = self.register_pending_approval(sub_id).await;
+        self.send_event(Event { id: event_id, msg }).await;
+        rx_approve.await.unwrap_or_default()
+    }
+
+    async fn register_pending_approval(&self, sub_id: String) -> oneshot::Receiver<ReviewDecision> {
         let (tx_approve, rx_approve) = oneshot::channel();
         let event_id = sub_id.clone();
         let prev_entry = {
@@ -660,17 +724,6 @@ impl Session {
         if prev_entry.is_some() {
             warn!("Overwriting existing pending approval for sub_id: {event_id}");
         }
-
-        let event = Event {
-            id: event_id,
-            msg: EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
-                call_id,
-                changes: convert_apply_patch_to_protocol(action),
-                reason,
-                grant_root,
-            }),
-        };
-        self.send_event(event).await;
         rx_approve
     }
 
@@ -716,8 +769,10 @@ impl Session {
                 RolloutItem::Compacted(compacted) => {
                     let snapshot = history.contents();
                     let user_messages = collect_user_messages(&snapshot);
+                    let pinned_items = collect_pinned_items(&snapshot);
                     let rebuilt = build_compacted_history(
                         self.build_initial_context(turn_context),
+                        &pinned_items,
                         &user_messages,
                         &compacted.message,
                     );
@@ -735,6 +790,20 @@ impl Session {
         state.record_items(items.iter());
     }
 
+    /// Note that the agent read or edited `paths` so that project docs scoped
+    /// to their directories are injected before the next model request.
+    pub(crate) async fn on_paths_touched(&self, paths: &[PathBuf]) {
+        let mut state = self.state.lock().await;
+        for path in paths {
+            state.scoped_project_docs.on_path_touched(path);
+        }
+    }
+
+    async fn take_scoped_project_docs(&self) -> Vec<ScopedProjectDoc> {
+        let mut state = self.state.lock().await;
+        state.scoped_project_docs.take_pending()
+    }
+
     async fn replace_history(&self, items: Vec<ResponseItem>) {
         let mut state = self.state.lock().await;
         state.replace_history(items);
@@ -780,6 +849,31 @@ impl Session {
         state.history_snapshot()
     }
 
+    /// Writes the cumulative usage of the session to the rollout if it
+    /// changed since the last write.
+    pub(crate) async fn persist_session_usage(&self) {
+        let Some(usage) = self.state.lock().await.take_unpersisted_usage() else {
+            return;
+        };
+        let recorder = {
+            let guard = self.services.rollout.lock().await;
+            guard.clone()
+        };
+        if let Some(rec) = recorder
+            && let Err(e) = rec.record_usage(usage).await
+        {
+            error!("failed to record session usage: {e:#}");
+        }
+    }
+
+    /// Returns `(budget, spent)` when the session has spent more than its
+    /// configured budget.
+    async fn exceeded_session_budget(&self) -> Option<(f64, f64)> {
+        let budget = self.services.session_budget_usd?;
+        let spent = self.state.lock().await.usage.cost_usd();
+        (spent > budget).then_some((budget, spent))
+    }
+
     async fn update_token_usage_info(
         &self,
         sub_id: &str,
@@ -793,6 +887,11 @@ impl Session {
                     token_usage,
                     turn_context.client.get_model_context_window(),
                 );
+                state.add_model_usage(
+                    &turn_context.client.get_model(),
+                    token_usage,
+                    &self.services.model_pricing,
+                );
             }
         }
         self.send_token_count_event(sub_id).await;
@@ -806,14 +905,26 @@ impl Session {
         self.send_token_count_event(sub_id).await;
     }
 
+    /// Estimates the size of `prompt` and remembers the breakdown for the
+    /// next `TokenCount` event.
+    async fn estimate_prompt_tokens(&self, prompt: &Prompt, model_family: &ModelFamily) -> u64 {
+        let mut state = self.state.lock().await;
+        state.estimate_prompt_tokens(prompt, model_family)
+    }
+
     async fn send_token_count_event(&self, sub_id: &str) {
-        let (info, rate_limits) = {
+        let (info, rate_limits, context_breakdown) = {
             let state = self.state.lock().await;
-            state.token_info_and_rate_limits()
+            let (info, rate_limits) = state.token_info_and_rate_limits();
+            (info, rate_limits, state.context_breakdown.clone())
         };
         let event = Event {
             id: sub_id.to_string(),
-            msg: EventMsg::TokenCount(TokenCountEvent { info, rate_limits }),
+            msg: EventMsg::TokenCount(TokenCountEvent {
+                info,
+                rate_limits,
+                context_breakdown,
+            }),
         };
         self.send_event(event).await;
     }
@@ -874,6 +985,17 @@ impl Session {
                     let mut tracker = turn_diff_tracker.lock().await;
                     tracker.on_patch_begin(&changes);
                 }
+                let touched_paths: Vec<PathBuf> = changes
+                    .iter()
+                    .flat_map(|(path, change)| match change {
+                        FileChange::Update {
+                            move_path: Some(dest),
+                            ..
+                        } => vec![path.clone(), dest.clone()],
+                        _ => vec![path.clone()],
+                    })
+                    .collect();
+                self.on_paths_touched(&touched_paths).await;
 
                 EventMsg::PatchApplyBegin(PatchApplyBeginEvent {
                     call_id,
@@ -881,12 +1003,20 @@ impl Session {
                     changes,
                 })
             }
-            None => EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
-                call_id,
-                command: command_for_display.clone(),
-                cwd,
-                parsed_cmd: parse_command(&command_for_display),
-            }),
+            None => {
+                // Read-only commands cannot change the tree, so skip the snapshot.
+                if !is_known_safe_command(&command_for_display) {
+                    TurnDiffTracker::on_exec_begin(&turn_diff_tracker, &call_id, &cwd).await;
+                }
+                self.on_paths_touched(std::slice::from_ref(&cwd)).await;
+
+                EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+                    call_id,
+                    command: command_for_display.clone(),
+                    cwd,
+                    parsed_cmd: parse_command(&command_for_display),
+                })
+            }
         };
         let event = Event {
             id: sub_id.to_string(),
@@ -942,21 +1072,30 @@ impl Session {
         };
         self.send_event(event).await;
 
-        // If this is an apply_patch, after we emit the end patch, emit a second event
-        // with the full turn diff if there is one.
-        if is_apply_patch {
-            let unified_diff = {
-                let mut tracker = turn_diff_tracker.lock().await;
-                tracker.get_unified_diff()
+        // If this is an apply_patch, or a shell command that changed files, after we emit the
+        // end event, emit a second event with the full turn diff if there is one.
+        let changed_paths = if is_apply_patch {
+            Vec::new()
+        } else {
+            TurnDiffTracker::on_exec_end(&turn_diff_tracker, call_id).await
+        };
+        // Computing the diff reads every tracked file, so keep it off the async runtime.
+        let unified_diff = if is_apply_patch || !changed_paths.is_empty() {
+            let mut tracker = Arc::clone(&turn_diff_tracker).lock_owned().await;
+            tokio::task::spawn_blocking(move || tracker.get_unified_diff())
+                .await
+                .ok()
+        } else {
+            None
+        };
+        self.on_paths_touched(&changed_paths).await;
+        if let Some(Ok(Some(unified_diff))) = unified_diff {
+            let msg = EventMsg::TurnDiff(TurnDiffEvent { unified_diff });
+            let event = Event {
+                id: sub_id.into(),
+                msg,
             };
-            if let Ok(Some(unified_diff)) = unified_diff {
-                let msg = EventMsg::TurnDiff(TurnDiffEvent { unified_diff });
-                let event = Event {
-                    id: sub_id.into(),
-                    msg,
-                };
-                self.send_event(event).await;
-            }
+            self.send_event(event).await;
         }
     }
     /// Runs the exec tool call and emits events for the begin and end of the
@@ -1046,6 +1185,10 @@ impl Session {
         }
     }
 
+    pub async fn has_active_turn(&self) -> bool {
+        self.active_turn.lock().await.is_some()
+    }
+
     pub async fn get_pending_input(&self) -> Vec<ResponseInputItem> {
         let mut active = self.active_turn.lock().await;
         match active.as_mut() {
@@ -1223,7 +1366,8 @@ async fn submission_loop(
                 let tools_config = ToolsConfig::new(&ToolsConfigParams {
                     model_family: &effective_family,
                     features: &config.features,
-                });
+                })
+                .with_enabled_tools(config.enabled_tools.clone());
 
                 let new_turn_context = TurnContext {
                     client,
@@ -1258,12 +1402,7 @@ async fn submission_loop(
                     .client
                     .get_otel_event_manager()
                     .user_prompt(&items);
-                // attempt to inject input into current task
-                if let Err(items) = sess.inject_input(items).await {
-                    // no current task, spawn a new one
-                    sess.spawn_task(Arc::clone(&turn_context), sub.id, items, RegularTask)
-                        .await;
-                }
+                submit_user_input(Arc::clone(&sess), Arc::clone(&turn_context), sub.id, items);
             }
             Op::UserTurn {
                 items,
@@ -1279,8 +1418,10 @@ async fn submission_loop(
                     .client
                     .get_otel_event_manager()
                     .user_prompt(&items);
-                // attempt to inject input into current task
-                if let Err(items) = sess.inject_input(items).await {
+                // a running task keeps its own turn context; just queue the input for it
+                if sess.has_active_turn().await {
+                    submit_user_input(Arc::clone(&sess), Arc::clone(&turn_context), sub.id, items);
+                } else {
                     // Derive a fresh TurnContext for this turn using the provided overrides.
                     let provider = turn_context.client.get_provider();
                     let auth_manager = turn_context.client.get_auth_manager();
@@ -1320,7 +1461,8 @@ async fn submission_loop(
                         tools_config: ToolsConfig::new(&ToolsConfigParams {
                             model_family: &model_family,
                             features: &config.features,
-                        }),
+                        })
+                        .with_enabled_tools(config.enabled_tools.clone()),
                         user_instructions: turn_context.user_instructions.clone(),
                         base_instructions: turn_context.base_instructions.clone(),
                         approval_policy,
@@ -1353,9 +1495,8 @@ async fn submission_loop(
                     // Install the new persistent context for subsequent tasks/turns.
                     turn_context = Arc::new(fresh_turn_context);
 
-                    // no current task, spawn a new one with the per-turn context
-                    sess.spawn_task(Arc::clone(&turn_context), sub.id, items, RegularTask)
-                        .await;
+                    // no current task, start a new one with the per-turn context
+                    submit_user_input(Arc::clone(&sess), Arc::clone(&turn_context), sub.id, items);
                 }
             }
             Op::ExecApproval { id, decision } => match decision {
@@ -1435,6 +1576,47 @@ async fn submission_loop(
                 };
                 sess.send_event(event).await;
             }
+            Op::ListMcpResources => {
+                let sess_clone = Arc::clone(&sess);
+                let sub_id = sub.id.clone();
+
+                // Resources are fetched from the servers on every request, so
+                // answer from a background task to keep the loop responsive.
+                tokio::spawn(async move {
+                    let manager = &sess_clone.services.mcp_connection_manager;
+                    let (resources, resource_templates) = tokio::join!(
+                        manager.list_all_resources(),
+                        manager.list_all_resource_templates()
+                    );
+                    let event = Event {
+                        id: sub_id,
+                        msg: EventMsg::McpListResourcesResponse(McpListResourcesResponseEvent {
+                            resources,
+                            resource_templates,
+                        }),
+                    };
+                    sess_clone.send_event(event).await;
+                });
+            }
+            Op::ListMcpPrompts => {
+                let sess_clone = Arc::clone(&sess);
+                let sub_id = sub.id.clone();
+
+                tokio::spawn(async move {
+                    let prompts = sess_clone
+                        .services
+                        .mcp_connection_manager
+                        .list_all_prompts()
+                        .await;
+                    let event = Event {
+                        id: sub_id,
+                        msg: EventMsg::McpListPromptsResponse(McpListPromptsResponseEvent {
+                            prompts,
+                        }),
+                    };
+                    sess_clone.send_event(event).await;
+                });
+            }
             Op::ListCustomPrompts => {
                 let sub_id = sub.id.clone();
 
@@ -1453,11 +1635,11 @@ async fn submission_loop(
                 };
                 sess.send_event(event).await;
             }
-            Op::Compact => {
+            Op::Compact { instructions } => {
                 // Attempt to inject input into current task
                 if let Err(items) = sess
                     .inject_input(vec![InputItem::Text {
-                        text: compact::SUMMARIZATION_PROMPT.to_string(),
+                        text: compact::summarization_prompt(instructions.as_deref()),
                     }])
                     .await
                 {
@@ -1465,9 +1647,42 @@ async fn submission_loop(
                         .await;
                 }
             }
+            Op::Pin { text } => {
+                let text = match text.filter(|text| !text.trim().is_empty()) {
+                    Some(text) => Some(text),
+                    None => get_last_assistant_message_from_turn(&sess.history_snapshot().await),
+                };
+                match text {
+                    Some(text) => {
+                        // Pin after the current request if a task is running so
+                        // the pinned content does not precede its source.
+                        if let Err(items) = sess.inject_input(compact::pinned_input(&text)).await {
+                            let item = ResponseItem::from(ResponseInputItem::from(items));
+                            sess.record_conversation_items(std::slice::from_ref(&item))
+                                .await;
+                        }
+                        sess.notify_background_event(
+                            &sub.id,
+                            "Pinned; compaction will keep it verbatim.",
+                        )
+                        .await;
+                    }
+                    None => {
+                        let event = Event {
+                            id: sub.id.clone(),
+                            msg: EventMsg::Error(ErrorEvent {
+                                message: "Nothing to pin: there is no response yet.".to_string(),
+                            }),
+                        };
+                        sess.send_event(event).await;
+                    }
+                }
+            }
             Op::Shutdown => {
                 sess.abort_all_tasks(TurnAbortReason::Interrupted).await;
+                sess.persist_session_usage().await;
                 info!("Shutting down Codex instance");
+                sess.services.session_span.end();
 
                 // Gracefully flush and shutdown rollout recorder on session end so tests
                 // that inspect the rollout file do not race with the background writer.
@@ -1540,6 +1755,87 @@ async fn submission_loop(
     debug!("Agent loop exited");
 }
 
+/// Resolves `items` in a detached task so slow hooks or MCP reads don't hold
+/// up the submission loop (and with it interrupts and approvals), then injects
+/// them into the running task or, if there is none, spawns a new one.
+fn submit_user_input(
+    sess: Arc<Session>,
+    turn_context: Arc<TurnContext>,
+    sub_id: String,
+    items: Vec<InputItem>,
+) {
+    tokio::spawn(async move {
+        let cwd = turn_context.cwd.clone();
+        let Some(items) = resolve_user_input(&sess, &cwd, &sub_id, items).await else {
+            return;
+        };
+        if let Err(items) = sess.inject_input(items).await {
+            sess.spawn_task(turn_context, sub_id, items, RegularTask)
+                .await;
+        }
+    });
+}
+
+/// Expands MCP resource and prompt references in `items` and runs the
+/// `user_prompt_submit` hooks. If a reference cannot be resolved or a hook
+/// blocks the prompt, the error is reported and `None` is returned, so that
+/// the turn is not started.
+async fn resolve_user_input(
+    sess: &Session,
+    cwd: &Path,
+    sub_id: &str,
+    items: Vec<InputItem>,
+) -> Option<Vec<InputItem>> {
+    let mut items = if has_mcp_references(&items) {
+        match resolve_mcp_references(&sess.services.mcp_connection_manager, items).await {
+            Ok(items) => items,
+            Err(e) => {
+                send_user_input_error(sess, sub_id, format!("{e:#}")).await;
+                return None;
+            }
+        }
+    } else {
+        items
+    };
+
+    let prompt = items
+        .iter()
+        .filter_map(|item| match item {
+            InputItem::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    match sess
+        .services
+        .hooks
+        .user_prompt_submit(cwd, sub_id, &prompt)
+        .await
+    {
+        HookOutcome::Continue => {}
+        HookOutcome::Modified {
+            additional_context, ..
+        } => items.extend(
+            additional_context
+                .into_iter()
+                .map(|text| InputItem::Text { text }),
+        ),
+        HookOutcome::Block { reason } => {
+            send_user_input_error(sess, sub_id, format!("Prompt blocked by hook: {reason}")).await;
+            return None;
+        }
+    }
+    Some(items)
+}
+
+async fn send_user_input_error(sess: &Session, sub_id: &str, message: String) {
+    let event = Event {
+        id: sub_id.to_string(),
+        msg: EventMsg::Error(ErrorEvent { message }),
+    };
+    sess.send_event(event).await;
+}
+
 /// Spawn a review thread using the given prompt.
 async fn spawn_review_thread(
     sess: Arc<Session>,
@@ -1557,13 +1853,30 @@ async fn spawn_review_thread(
     review_features.disable(crate::features::Feature::WebSearchRequest);
     review_features.disable(crate::features::Feature::ViewImageTool);
     review_features.disable(crate::features::Feature::StreamableShell);
+    review_features.disable(crate::features::Feature::SpawnAgent);
     let tools_config = ToolsConfig::new(&ToolsConfigParams {
         model_family: &review_model_family,
         features: &review_features,
     });
 
+    let mut review_request = review_request;
+    let resolved = match resolve_review_request(&parent_turn_context.cwd, &review_request).await {
+        Ok(resolved) => resolved,
+        Err(message) => {
+            sess.send_event(Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: format!("Cannot start review: {message}"),
+                }),
+            })
+            .await;
+            return;
+        }
+    };
+    review_request.user_facing_hint = resolved.user_facing_hint;
+
     let base_instructions = REVIEW_PROMPT.to_string();
-    let review_prompt = review_request.prompt.clone();
+    let review_prompt = resolved.prompt;
     let provider = parent_turn_context.client.get_provider();
     let auth_manager = parent_turn_context.client.get_auth_manager();
     let model_family = review_model_family.clone();
@@ -1728,12 +2041,30 @@ pub(crate) async fn run_task(
                 })
             })
             .collect();
+        let (router, prompt) = build_turn_prompt(&sess, &turn_context, turn_input);
+        if !is_review_mode {
+            let estimated_tokens = sess
+                .estimate_prompt_tokens(&prompt, &turn_context.client.get_model_family())
+                .await;
+
+            // Compact before sending a request that would overflow the context
+            // window instead of waiting for the provider to reject it.
+            if !auto_compact_recently_attempted
+                && let Some(context_window) = turn_context.client.get_model_context_window()
+                && estimated_tokens >= context_window * PROACTIVE_AUTO_COMPACT_WINDOW_PERCENT / 100
+            {
+                auto_compact_recently_attempted = true;
+                compact::run_inline_auto_compact_task(sess.clone(), turn_context.clone()).await;
+                continue;
+            }
+        }
         match run_turn(
             Arc::clone(&sess),
             Arc::clone(&turn_context),
             Arc::clone(&turn_diff_tracker),
             sub_id.clone(),
-            turn_input,
+            router,
+            prompt,
             task_kind,
         )
         .await
@@ -1846,6 +2177,17 @@ pub(crate) async fn run_task(
                     }
                 }
 
+                // Instructions from nested project docs become relevant once
+                // the agent has touched files they apply to.
+                if !is_review_mode {
+                    items_to_record_in_conversation_history.extend(
+                        sess.take_scoped_project_docs()
+                            .await
+                            .into_iter()
+                            .map(ResponseItem::from),
+                    );
+                }
+
                 // Only attempt to take the lock if there is something to record.
                 if !items_to_record_in_conversation_history.is_empty() {
                     if is_review_mode {
@@ -1892,6 +2234,27 @@ pub(crate) async fn run_task(
                             input_messages: turn_input_messages,
                             last_assistant_message: last_agent_message.clone(),
                         });
+                    sess.services.hooks.turn_complete(
+                        &turn_context.cwd,
+                        &sub_id,
+                        last_agent_message.as_deref(),
+                    );
+                    break;
+                }
+
+                // The model wants to continue, e.g. with the output of a tool
+                // call. Stop here instead if the session is over budget; the
+                // next user message picks up where the agent left off.
+                if let Some((budget, spent)) = sess.exceeded_session_budget().await {
+                    let event = Event {
+                        id: sub_id.clone(),
+                        msg: EventMsg::BackgroundEvent(BackgroundEventEvent {
+                            message: format!(
+                                "Paused: this session has cost ${spent:.2}, over its budget of ${budget:.2} (`session_budget_usd`). Each message you send lets the agent take one more step."
+                            ),
+                        }),
+                    };
+                    sess.send_event(event).await;
                     break;
                 }
                 continue;
@@ -1955,14 +2318,11 @@ fn parse_review_output_event(text: &str) -> ReviewOutputEvent {
     }
 }
 
-async fn run_turn(
-    sess: Arc<Session>,
-    turn_context: Arc<TurnContext>,
-    turn_diff_tracker: SharedTurnDiffTracker,
-    sub_id: String,
+fn build_turn_prompt(
+    sess: &Session,
+    turn_context: &TurnContext,
     input: Vec<ResponseItem>,
-    task_kind: TaskKind,
-) -> CodexResult<TurnRunResult> {
+) -> (Arc<ToolRouter>, Prompt) {
     let mcp_tools = sess.services.mcp_connection_manager.list_all_tools();
     let router = Arc::new(ToolRouter::from_config(
         &turn_context.tools_config,
@@ -1981,19 +2341,34 @@ async fn run_turn(
         base_instructions_override: turn_context.base_instructions.clone(),
         output_schema: turn_context.final_output_json_schema.clone(),
     };
+    (router, prompt)
+}
 
+async fn run_turn(
+    sess: Arc<Session>,
+    turn_context: Arc<TurnContext>,
+    turn_diff_tracker: SharedTurnDiffTracker,
+    sub_id: String,
+    router: Arc<ToolRouter>,
+    prompt: Prompt,
+    task_kind: TaskKind,
+) -> CodexResult<TurnRunResult> {
+    let otel_event_manager = turn_context.client.get_otel_event_manager();
     let mut retries = 0;
     loop {
-        match try_run_turn(
-            Arc::clone(&router),
-            Arc::clone(&sess),
-            Arc::clone(&turn_context),
-            Arc::clone(&turn_diff_tracker),
-            &sub_id,
-            &prompt,
-            task_kind,
-        )
-        .await
+        match otel_event_manager
+            .log_model_request(retries, || {
+                try_run_turn(
+                    Arc::clone(&router),
+                    Arc::clone(&sess),
+                    Arc::clone(&turn_context),
+                    Arc::clone(&turn_diff_tracker),
+                    &sub_id,
+                    &prompt,
+                    task_kind,
+                )
+            })
+            .await
         {
             Ok(output) => return Ok(output),
             Err(CodexErr::Interrupted) => return Err(CodexErr::Interrupted),
@@ -2252,6 +2627,12 @@ async fn try_run_turn(
                 response_id: _,
                 token_usage,
             } => {
+                if let Some(usage) = &token_usage {
+                    turn_context
+                        .client
+                        .get_otel_event_manager()
+                        .token_usage(usage);
+                }
                 sess.update_token_usage_info(sub_id, turn_context.as_ref(), token_usage.as_ref())
                     .await;
 
@@ -2491,6 +2872,7 @@ mod tests {
     use crate::tools::handle_container_exec_with_params;
     use crate::turn_diff_tracker::TurnDiffTracker;
     use codex_app_server_protocol::AuthMode;
+    use codex_otel::otel_event_manager::OtelSpanGuard;
     use codex_protocol::models::ContentItem;
     use codex_protocol::models::ResponseItem;
 
@@ -2794,14 +3176,22 @@ mod tests {
             session_manager: ExecSessionManager::default(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: UserNotifier::default(),
+            hooks: HookRunner::default(),
             rollout: Mutex::new(None),
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
-            executor: Executor::new(ExecutorConfig::new(
-                turn_context.sandbox_policy.clone(),
-                turn_context.cwd.clone(),
+            model_pricing: config.model_pricing.clone(),
+            session_budget_usd: config.session_budget_usd,
+            executor: Executor::new(
+                ExecutorConfig::new(
+                    turn_context.sandbox_policy.clone(),
+                    turn_context.cwd.clone(),
+                    None,
+                ),
+                config.codex_home.clone(),
                 None,
-            )),
+            ),
+            session_span: OtelSpanGuard::default(),
         };
         let session = Session {
             conversation_id,
@@ -2862,14 +3252,18 @@ mod tests {
             session_manager: ExecSessionManager::default(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: UserNotifier::default(),
+            hooks: HookRunner::default(),
             rollout: Mutex::new(None),
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
-            executor: Executor::new(ExecutorConfig::new(
-                config.sandbox_policy.clone(),
-                config.cwd.clone(),
+            model_pricing: config.model_pricing.clone(),
+            session_budget_usd: config.session_budget_usd,
+            executor: Executor::new(
+                ExecutorConfig::new(config.sandbox_policy.clone(), config.cwd.clone(), None),
+                config.codex_home.clone(),
                 None,
-            )),
+            ),
+            session_span: OtelSpanGuard::default(),
         };
         let session = Arc::new(Session {
             conversation_id,
@@ -3059,6 +3453,7 @@ mod tests {
         let user_messages1 = collect_user_messages(&snapshot1);
         let rebuilt1 = build_compacted_history(
             session.build_initial_context(turn_context),
+            &[],
             &user_messages1,
             summary1,
         );
@@ -3092,6 +3487,7 @@ mod tests {
         let user_messages2 = collect_user_messages(&snapshot2);
         let rebuilt2 = build_compacted_history(
             session.build_initial_context(turn_context),
+            &[],
             &user_messages2,
             summary2,
         );
@@ -14,9 +14,12 @@ use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::InputItem;
 use crate::protocol::InputMessageKind;
+use crate::protocol::PINNED_CONTEXT_CLOSE_TAG;
+use crate::protocol::PINNED_CONTEXT_OPEN_TAG;
 use crate::protocol::TaskStartedEvent;
 use crate::protocol::TurnContextItem;
 use crate::state::TaskKind;
+use crate::token_estimate;
 use crate::truncate::truncate_middle;
 use crate::util::backoff;
 use askama::Template;
@@ -24,6 +27,7 @@ use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::RolloutItem;
+use codex_utils_tokenizer::EncodingKind;
 use futures::prelude::*;
 
 pub const SUMMARIZATION_PROMPT: &str = include_str!("../../templates/compact/prompt.md");
@@ -36,6 +40,24 @@ struct HistoryBridgeTemplate<'a> {
     summary_text: &'a str,
 }
 
+/// Returns the summarization prompt, extended with the user's guidance on
+/// what the summary should focus on.
+pub(crate) fn summarization_prompt(instructions: Option<&str>) -> String {
+    match instructions.map(str::trim).filter(|text| !text.is_empty()) {
+        Some(instructions) => format!(
+            "{SUMMARIZATION_PROMPT}\n\nThe user gave these instructions for the note; follow them when deciding what to keep:\n{instructions}"
+        ),
+        None => SUMMARIZATION_PROMPT.to_string(),
+    }
+}
+
+/// Wraps `text` so that compaction carries it over verbatim.
+pub(crate) fn pinned_input(text: &str) -> Vec<InputItem> {
+    vec![InputItem::Text {
+        text: format!("{PINNED_CONTEXT_OPEN_TAG}\n{text}\n{PINNED_CONTEXT_CLOSE_TAG}"),
+    }]
+}
+
 pub(crate) async fn run_inline_auto_compact_task(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
@@ -159,8 +181,14 @@ async fn run_compact_task_inner(
     let history_snapshot = sess.history_snapshot().await;
     let summary_text = get_last_assistant_message_from_turn(&history_snapshot).unwrap_or_default();
     let user_messages = collect_user_messages(&history_snapshot);
+    let pinned_items = collect_pinned_items(&history_snapshot);
     let initial_context = sess.build_initial_context(turn_context.as_ref());
-    let new_history = build_compacted_history(initial_context, &user_messages, &summary_text);
+    let new_history = build_compacted_history(
+        initial_context,
+        &pinned_items,
+        &user_messages,
+        &summary_text,
+    );
     sess.replace_history(new_history).await;
 
     let rollout_item = RolloutItem::Compacted(CompactedItem {
@@ -209,30 +237,67 @@ pub(crate) fn collect_user_messages(items: &[ResponseItem]) -> Vec<String> {
         .collect()
 }
 
+/// Returns true for context blocks added by the harness rather than typed by
+/// the user, including pinned content.
 pub fn is_session_prefix_message(text: &str) -> bool {
     matches!(
         InputMessageKind::from(("user", text)),
-        InputMessageKind::UserInstructions | InputMessageKind::EnvironmentContext
+        InputMessageKind::UserInstructions
+            | InputMessageKind::EnvironmentContext
+            | InputMessageKind::PinnedContext
     )
 }
 
+/// Returns the pinned messages in `items`, in the order they were pinned.
+pub(crate) fn collect_pinned_items(items: &[ResponseItem]) -> Vec<ResponseItem> {
+    items
+        .iter()
+        .filter(|item| match item {
+            ResponseItem::Message { role, content, .. } if role == "user" => {
+                content_items_to_text(content).is_some_and(|text| {
+                    matches!(
+                        InputMessageKind::from(("user", &text)),
+                        InputMessageKind::PinnedContext
+                    )
+                })
+            }
+            _ => false,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Builds the history that replaces the conversation after compaction: the
+/// initial context, pinned items verbatim, then a bridge message carrying the
+/// prior user messages and the summary.
 pub(crate) fn build_compacted_history(
     initial_context: Vec<ResponseItem>,
+    pinned_items: &[ResponseItem],
     user_messages: &[String],
     summary_text: &str,
 ) -> Vec<ResponseItem> {
     let mut history = initial_context;
+    history.extend_from_slice(pinned_items);
     let mut user_messages_text = if user_messages.is_empty() {
         "(none)".to_string()
     } else {
         user_messages.join("\n\n")
     };
     // Truncate the concatenated prior user messages so the bridge message
-    // stays well under the context window (approx. 4 bytes/token).
-    let max_bytes = COMPACT_USER_MESSAGE_MAX_TOKENS * 4;
-    if user_messages_text.len() > max_bytes {
-        user_messages_text = truncate_middle(&user_messages_text, max_bytes).0;
-    }
+    // stays well under the context window. The history may be rebuilt without
+    // knowing the model, so count with the encoding current models use.
+    user_messages_text = match token_estimate::tokenizer(EncodingKind::O200kBase) {
+        Some(tokenizer) => {
+            tokenizer
+                .truncate_middle(&user_messages_text, COMPACT_USER_MESSAGE_MAX_TOKENS)
+                .0
+        }
+        None => {
+            // Approximate 4 bytes/token.
+            let max_bytes = COMPACT_USER_MESSAGE_MAX_TOKENS * 4;
+            truncate_middle(&user_messages_text, max_bytes).0
+        }
+    };
     let summary_text = if summary_text.is_empty() {
         "(no summary available)".to_string()
     } else {
@@ -390,9 +455,11 @@ mod tests {
     fn build_compacted_history_truncates_overlong_user_messages() {
         // Prepare a very large prior user message so the aggregated
         // `user_messages_text` exceeds the truncation threshold used by
-        // `build_compacted_history` (80k bytes).
-        let big = "X".repeat(200_000);
-        let history = build_compacted_history(Vec::new(), std::slice::from_ref(&big), "SUMMARY");
+        // `build_compacted_history` (20k tokens).
+        // Each repetition is a single token.
+        let big = "word ".repeat(50_000);
+        let history =
+            build_compacted_history(Vec::new(), &[], std::slice::from_ref(&big), "SUMMARY");
 
         // Expect exactly one bridge message added to history (plus any initial context we provided, which is none).
         assert_eq!(history.len(), 1);
@@ -419,4 +486,45 @@ mod tests {
             "bridge should include the provided summary text"
         );
     }
+
+    #[test]
+    fn build_compacted_history_keeps_pinned_items_verbatim() {
+        let pinned_text = match pinned_input("spec: keep the API stable").as_slice() {
+            [InputItem::Text { text }] => text.clone(),
+            other => panic!("unexpected pinned input: {other:?}"),
+        };
+        let pinned = ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText { text: pinned_text }],
+        };
+        let items = vec![
+            ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![ContentItem::InputText {
+                    text: "write the spec".to_string(),
+                }],
+            },
+            pinned.clone(),
+        ];
+
+        let user_messages = collect_user_messages(&items);
+        let pinned_items = collect_pinned_items(&items);
+        let history = build_compacted_history(Vec::new(), &pinned_items, &user_messages, "SUMMARY");
+
+        assert_eq!(vec!["write the spec".to_string()], user_messages);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0], pinned);
+    }
+
+    #[test]
+    fn summarization_prompt_appends_instructions() {
+        assert_eq!(summarization_prompt(None), SUMMARIZATION_PROMPT);
+        assert_eq!(summarization_prompt(Some("  ")), SUMMARIZATION_PROMPT);
+
+        let prompt = summarization_prompt(Some("focus on the parser"));
+        assert!(prompt.starts_with(SUMMARIZATION_PROMPT));
+        assert!(prompt.ends_with("\nfocus on the parser"));
+    }
 }
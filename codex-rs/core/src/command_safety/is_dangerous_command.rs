@@ -1,4 +1,4 @@
-use crate::bash::parse_bash_lc_plain_commands;
+use crate::bash::parse_bash_lc_script;
 
 pub fn command_might_be_dangerous(command: &[String]) -> bool {
     if is_dangerous_to_call_with_exec(command) {
@@ -6,10 +6,10 @@ pub fn command_might_be_dangerous(command: &[String]) -> bool {
     }
 
     // Support `bash -lc "<script>"` where the any part of the script might contain a dangerous command.
-    if let Some(all_commands) = parse_bash_lc_plain_commands(command)
+    if let Some(all_commands) = parse_bash_lc_script(command)
         && all_commands
             .iter()
-            .any(|cmd| is_dangerous_to_call_with_exec(cmd))
+            .any(|cmd| is_dangerous_to_call_with_exec(&cmd.exec_call.to_argv()))
     {
         return true;
     }
@@ -57,6 +57,15 @@ mod tests {
         ])));
     }
 
+    #[test]
+    fn bash_git_reset_in_subshell_is_dangerous() {
+        assert!(command_might_be_dangerous(&vec_str(&[
+            "bash",
+            "-lc",
+            "ls && (git reset --hard) 2>/dev/null"
+        ])));
+    }
+
     #[test]
     fn git_status_is_not_dangerous() {
         assert!(!command_might_be_dangerous(&vec_str(&["git", "status"])));
@@ -1,4 +1,4 @@
-use crate::bash::parse_bash_lc_plain_commands;
+use crate::bash::parse_bash_lc_script;
 
 pub fn is_known_safe_command(command: &[String]) -> bool {
     #[cfg(target_os = "windows")]
@@ -13,17 +13,20 @@ pub fn is_known_safe_command(command: &[String]) -> bool {
         return true;
     }
 
-    // Support `bash -lc "..."` where the script consists solely of one or
-    // more "plain" commands (only bare words / quoted strings) combined with
-    // a conservative allow‑list of shell operators that themselves do not
-    // introduce side effects ( "&&", "||", ";", and "|" ). If every
-    // individual command in the script is itself a known‑safe command, then
-    // the composite expression is considered safe.
-    if let Some(all_commands) = parse_bash_lc_plain_commands(command)
+    // Support `bash -lc "..."` where execpolicy can split the script into
+    // the individual commands it runs: literal words combined with "&&",
+    // "||", ";", "|", and subshells, optionally redirected. If every
+    // individual command in the script is itself a known‑safe command and no
+    // redirection writes to a file, then the composite expression is
+    // considered safe.
+    if let Some(all_commands) = parse_bash_lc_script(command)
         && !all_commands.is_empty()
-        && all_commands
-            .iter()
-            .all(|cmd| is_safe_to_call_with_exec(cmd))
+        && all_commands.iter().all(|cmd| {
+            cmd.redirects
+                .iter()
+                .all(|redirect| redirect.written_path().is_none())
+                && is_safe_to_call_with_exec(&cmd.exec_call.to_argv())
+        })
     {
         return true;
     }
@@ -306,6 +309,26 @@ mod tests {
         ])));
     }
 
+    #[test]
+    fn bash_lc_safe_examples_with_subshells_and_redirections() {
+        assert!(is_known_safe_command(&vec_str(&["bash", "-lc", "(ls)"])));
+        assert!(is_known_safe_command(&vec_str(&[
+            "bash",
+            "-lc",
+            "ls || (pwd && echo hi)"
+        ])));
+        assert!(is_known_safe_command(&vec_str(&[
+            "bash",
+            "-lc",
+            "grep -R foo . 2>/dev/null | head -n 5"
+        ])));
+        assert!(is_known_safe_command(&vec_str(&[
+            "bash",
+            "-lc",
+            "rg foo 2>&1 | wc -l"
+        ])));
+    }
+
     #[test]
     fn bash_lc_unsafe_examples() {
         assert!(
@@ -328,14 +351,10 @@ mod tests {
             "Sequence containing unsafe command must be rejected"
         );
 
-        // Disallowed because of parentheses / subshell.
+        // Disallowed because of an unsafe command inside a subshell.
         assert!(
-            !is_known_safe_command(&vec_str(&["bash", "-lc", "(ls)"])),
-            "Parentheses (subshell) are not provably safe with the current parser"
-        );
-        assert!(
-            !is_known_safe_command(&vec_str(&["bash", "-lc", "ls || (pwd && echo hi)"])),
-            "Nested parentheses are not provably safe with the current parser"
+            !is_known_safe_command(&vec_str(&["bash", "-lc", "ls || (pwd && rm -rf /)"])),
+            "Subshell containing unsafe command must be rejected"
         );
 
         // Disallowed redirection.
@@ -343,5 +362,13 @@ mod tests {
             !is_known_safe_command(&vec_str(&["bash", "-lc", "ls > out.txt"])),
             "> redirection should be rejected"
         );
+        assert!(
+            !is_known_safe_command(&vec_str(&["bash", "-lc", "(ls; pwd) >> out.txt"])),
+            "Redirecting a subshell into a file should be rejected"
+        );
+        assert!(
+            !is_known_safe_command(&vec_str(&["bash", "-lc", "cat $HOME/.ssh/id_rsa"])),
+            "Expansions cannot be checked statically"
+        );
     }
 }
@@ -5,8 +5,10 @@ use crate::config_loader::merge_toml_values;
 use crate::config_profile::ConfigProfile;
 use crate::config_types::DEFAULT_OTEL_ENVIRONMENT;
 use crate::config_types::History;
+use crate::config_types::Hooks;
 use crate::config_types::McpServerConfig;
 use crate::config_types::McpServerTransportConfig;
+use crate::config_types::ModelPricing;
 use crate::config_types::Notifications;
 use crate::config_types::OtelConfig;
 use crate::config_types::OtelConfigToml;
@@ -28,6 +30,7 @@ use crate::model_family::find_family_for_model;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::built_in_model_providers;
 use crate::openai_model_info::get_model_info;
+use crate::pricing::model_pricing_table;
 use crate::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
 use crate::project_doc::LOCAL_PROJECT_DOC_FILENAME;
 use crate::protocol::AskForApproval;
@@ -101,6 +104,10 @@ pub struct Config {
 
     pub sandbox_policy: SandboxPolicy,
 
+    /// `[sandbox_workspace_write]` settings from config.toml, kept so the
+    /// sandbox of a running session can be switched to `workspace-write`.
+    pub sandbox_workspace_write: Option<SandboxWorkspaceWrite>,
+
     /// True if the user passed in an override or set a value in config.toml
     /// for either of approval_policy or sandbox_mode.
     pub did_user_set_custom_approval_policy_or_sandbox_mode: bool,
@@ -144,6 +151,21 @@ pub struct Config {
     /// If unset the feature is disabled.
     pub notify: Option<Vec<String>>,
 
+    /// Lifecycle hooks configured under `[hooks]`.
+    pub hooks: Hooks,
+
+    /// Prices per model used to compute the cost of a session: the built-in
+    /// prices with `[model_pricing]` from `config.toml` applied on top.
+    pub model_pricing: HashMap<String, ModelPricing>,
+
+    /// When the cost of a session exceeds this many US dollars, the agent
+    /// pauses before sending further requests and waits for the user.
+    pub session_budget_usd: Option<f64>,
+
+    /// Run the session in a fresh `git worktree` on a new branch instead of
+    /// the user's working tree.
+    pub worktree: bool,
+
     /// TUI notifications preference. When set, the TUI will send OSC 9 notifications on approvals
     /// and turn completions when not focused.
     pub tui_notifications: Notifications,
@@ -232,6 +254,11 @@ pub struct Config {
     /// Centralized feature flags; source of truth for feature gating.
     pub features: Features,
 
+    /// When set, only the tools with these names are offered to the model.
+    /// This cannot be set in the config file: it is used to restrict the
+    /// tools of child conversations started by `spawn_agent`.
+    pub enabled_tools: Option<Vec<String>>,
+
     /// The active profile name used to derive this `Config` (if any).
     pub active_profile: Option<String>,
 
@@ -747,6 +774,20 @@ pub struct ConfigToml {
     #[serde(default)]
     pub notify: Option<Vec<String>>,
 
+    /// Commands to run at points in the agent lifecycle.
+    #[serde(default)]
+    pub hooks: Option<Hooks>,
+
+    /// Prices of models in US dollars per million tokens, keyed by model.
+    #[serde(default)]
+    pub model_pricing: HashMap<String, ModelPricing>,
+
+    /// Cost in US dollars after which a session pauses.
+    pub session_budget_usd: Option<f64>,
+
+    /// Run sessions in their own git worktree and branch.
+    pub worktree: Option<bool>,
+
     /// System instructions.
     pub instructions: Option<String>,
 
@@ -896,6 +937,34 @@ impl From<ToolsToml> for Tools {
     }
 }
 
+/// Builds the [`SandboxPolicy`] for `mode`, applying the
+/// `[sandbox_workspace_write]` settings when the mode is `workspace-write`.
+fn sandbox_policy_for_mode(
+    mode: SandboxMode,
+    workspace_write: Option<&SandboxWorkspaceWrite>,
+) -> SandboxPolicy {
+    match mode {
+        SandboxMode::ReadOnly => SandboxPolicy::new_read_only_policy(),
+        SandboxMode::WorkspaceWrite => match workspace_write {
+            Some(SandboxWorkspaceWrite {
+                writable_roots,
+                network_access,
+                network_allowlist,
+                exclude_tmpdir_env_var,
+                exclude_slash_tmp,
+            }) => SandboxPolicy::WorkspaceWrite {
+                writable_roots: writable_roots.clone(),
+                network_access: *network_access,
+                network_allowlist: network_allowlist.clone(),
+                exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
+                exclude_slash_tmp: *exclude_slash_tmp,
+            },
+            None => SandboxPolicy::new_workspace_write_policy(),
+        },
+        SandboxMode::DangerFullAccess => SandboxPolicy::DangerFullAccess,
+    }
+}
+
 impl ConfigToml {
     /// Derive the effective sandbox policy from the configuration.
     fn derive_sandbox_policy(
@@ -916,24 +985,7 @@ impl ConfigToml {
                 })
             })
             .unwrap_or_default();
-        match resolved_sandbox_mode {
-            SandboxMode::ReadOnly => SandboxPolicy::new_read_only_policy(),
-            SandboxMode::WorkspaceWrite => match self.sandbox_workspace_write.as_ref() {
-                Some(SandboxWorkspaceWrite {
-                    writable_roots,
-                    network_access,
-                    exclude_tmpdir_env_var,
-                    exclude_slash_tmp,
-                }) => SandboxPolicy::WorkspaceWrite {
-                    writable_roots: writable_roots.clone(),
-                    network_access: *network_access,
-                    exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
-                    exclude_slash_tmp: *exclude_slash_tmp,
-                },
-                None => SandboxPolicy::new_workspace_write_policy(),
-            },
-            SandboxMode::DangerFullAccess => SandboxPolicy::DangerFullAccess,
-        }
+        sandbox_policy_for_mode(resolved_sandbox_mode, self.sandbox_workspace_write.as_ref())
     }
 
     /// Resolves the cwd to an existing project, or returns None if ConfigToml
@@ -997,6 +1049,7 @@ pub struct ConfigOverrides {
     pub include_view_image_tool: Option<bool>,
     pub show_raw_agent_reasoning: Option<bool>,
     pub tools_web_search_request: Option<bool>,
+    pub worktree: Option<bool>,
 }
 
 impl Config {
@@ -1025,6 +1078,7 @@ impl Config {
             include_view_image_tool: include_view_image_tool_override,
             show_raw_agent_reasoning,
             tools_web_search_request: override_tools_web_search_request,
+            worktree: worktree_override,
         } = overrides;
 
         let active_profile_name = config_profile_key
@@ -1190,9 +1244,14 @@ impl Config {
             cwd: resolved_cwd,
             approval_policy,
             sandbox_policy,
+            sandbox_workspace_write: cfg.sandbox_workspace_write,
             did_user_set_custom_approval_policy_or_sandbox_mode,
             shell_environment_policy,
             notify: cfg.notify,
+            hooks: cfg.hooks.unwrap_or_default(),
+            model_pricing: model_pricing_table(cfg.model_pricing),
+            session_budget_usd: cfg.session_budget_usd,
+            worktree: worktree_override.or(cfg.worktree).unwrap_or(false),
             user_instructions,
             base_instructions,
             mcp_servers: cfg.mcp_servers,
@@ -1244,6 +1303,7 @@ impl Config {
             use_experimental_use_rmcp_client,
             include_view_image_tool: include_view_image_tool_flag,
             features,
+            enabled_tools: None,
             active_profile: active_profile_name,
             active_project,
             windows_wsl_setup_acknowledged: cfg.windows_wsl_setup_acknowledged.unwrap_or(false),
@@ -1260,16 +1320,30 @@ impl Config {
                     .environment
                     .unwrap_or(DEFAULT_OTEL_ENVIRONMENT.to_string());
                 let exporter = t.exporter.unwrap_or(OtelExporterKind::None);
+                let trace_exporter = t
+                    .trace_exporter
+                    .unwrap_or_else(|| exporter.for_signal("traces"));
+                let metrics_exporter = t
+                    .metrics_exporter
+                    .unwrap_or_else(|| exporter.for_signal("metrics"));
                 OtelConfig {
                     log_user_prompt,
                     environment,
                     exporter,
+                    trace_exporter,
+                    metrics_exporter,
                 }
             },
         };
         Ok(config)
     }
 
+    /// The sandbox policy for `mode`, honoring the `[sandbox_workspace_write]`
+    /// settings this config was loaded with.
+    pub fn sandbox_policy_for_mode(&self, mode: SandboxMode) -> SandboxPolicy {
+        sandbox_policy_for_mode(mode, self.sandbox_workspace_write.as_ref())
+    }
+
     fn load_instructions(codex_dir: Option<&Path>) -> Option<String> {
         let base = codex_dir?;
         for candidate in [LOCAL_PROJECT_DOC_FILENAME, DEFAULT_PROJECT_DOC_FILENAME] {
@@ -1375,6 +1449,7 @@ pub fn log_dir(cfg: &Config) -> std::io::Result<PathBuf> {
 mod tests {
     use crate::config_types::HistoryPersistence;
     use crate::config_types::Notifications;
+    use crate::config_types::OtelHttpProtocol;
     use crate::features::Feature;
 
     use super::*;
@@ -1468,6 +1543,7 @@ sandbox_mode = "workspace-write"
 writable_roots = [
     "/my/workspace",
 ]
+network_allowlist = ["crates.internal.example:443"]
 exclude_tmpdir_env_var = true
 exclude_slash_tmp = true
 "#;
@@ -1479,6 +1555,7 @@ exclude_slash_tmp = true
             SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![PathBuf::from("/my/workspace")],
                 network_access: false,
+                network_allowlist: vec!["crates.internal.example:443".to_string()],
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
             },
@@ -1507,6 +1584,7 @@ trust_level = "trusted"
             SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![PathBuf::from("/my/workspace")],
                 network_access: false,
+                network_allowlist: vec![],
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
             },
@@ -1515,6 +1593,38 @@ trust_level = "trusted"
         );
     }
 
+    #[test]
+    fn workspace_write_settings_survive_a_read_only_sandbox() -> std::io::Result<()> {
+        let cfg = r#"
+sandbox_mode = "read-only"
+
+[sandbox_workspace_write]
+writable_roots = ["/my/workspace"]
+network_access = true
+"#;
+        let parsed =
+            toml::from_str::<ConfigToml>(cfg).expect("TOML deserialization should succeed");
+        let temp_dir = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            parsed,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )?;
+
+        assert_eq!(config.sandbox_policy, SandboxPolicy::ReadOnly);
+        assert_eq!(
+            config.sandbox_policy_for_mode(SandboxMode::WorkspaceWrite),
+            SandboxPolicy::WorkspaceWrite {
+                writable_roots: vec![PathBuf::from("/my/workspace")],
+                network_access: true,
+                network_allowlist: vec![],
+                exclude_tmpdir_env_var: false,
+                exclude_slash_tmp: false,
+            }
+        );
+        Ok(())
+    }
+
     #[test]
     fn approve_all_feature_forces_on_request_policy() -> std::io::Result<()> {
         let cfg = r#"
@@ -1535,6 +1645,37 @@ approve_all = true
         Ok(())
     }
 
+    #[test]
+    fn otel_signal_exporters_default_to_the_log_exporter() -> std::io::Result<()> {
+        let cfg = r#"
+[otel]
+exporter = { otlp-http = { endpoint = "https://otel.example.com/v1/logs", protocol = "json", headers = {} } }
+metrics_exporter = { otlp-file = {} }
+"#;
+        let parsed = toml::from_str::<ConfigToml>(cfg)
+            .expect("TOML deserialization should succeed for otel exporters");
+        let temp_dir = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            parsed,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.otel.trace_exporter,
+            OtelExporterKind::OtlpHttp {
+                endpoint: "https://otel.example.com/v1/traces".to_string(),
+                headers: HashMap::new(),
+                protocol: OtelHttpProtocol::Json,
+            }
+        );
+        assert_eq!(
+            config.otel.metrics_exporter,
+            OtelExporterKind::OtlpFile { path: None }
+        );
+        Ok(())
+    }
+
     #[test]
     fn config_defaults_to_auto_oauth_store_mode() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -2298,10 +2439,15 @@ model_verbosity = "high"
                 model_provider: fixture.openai_provider.clone(),
                 approval_policy: AskForApproval::Never,
                 sandbox_policy: SandboxPolicy::new_read_only_policy(),
+                sandbox_workspace_write: None,
                 did_user_set_custom_approval_policy_or_sandbox_mode: true,
                 shell_environment_policy: ShellEnvironmentPolicy::default(),
                 user_instructions: None,
                 notify: None,
+                hooks: Hooks::default(),
+                model_pricing: model_pricing_table(HashMap::new()),
+                session_budget_usd: None,
+                worktree: false,
                 cwd: fixture.cwd(),
                 mcp_servers: HashMap::new(),
                 mcp_oauth_credentials_store_mode: Default::default(),
@@ -2327,6 +2473,7 @@ model_verbosity = "high"
                 use_experimental_use_rmcp_client: false,
                 include_view_image_tool: true,
                 features: Features::with_defaults(),
+                enabled_tools: None,
                 active_profile: Some("o3".to_string()),
                 active_project: ProjectConfig { trust_level: None },
                 windows_wsl_setup_acknowledged: false,
@@ -2364,10 +2511,15 @@ model_verbosity = "high"
             model_provider: fixture.openai_chat_completions_provider.clone(),
             approval_policy: AskForApproval::UnlessTrusted,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
+            sandbox_workspace_write: None,
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: Hooks::default(),
+            model_pricing: model_pricing_table(HashMap::new()),
+            session_budget_usd: None,
+            worktree: false,
             cwd: fixture.cwd(),
             mcp_servers: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
@@ -2393,6 +2545,7 @@ model_verbosity = "high"
             use_experimental_use_rmcp_client: false,
             include_view_image_tool: true,
             features: Features::with_defaults(),
+            enabled_tools: None,
             active_profile: Some("gpt3".to_string()),
             active_project: ProjectConfig { trust_level: None },
             windows_wsl_setup_acknowledged: false,
@@ -2445,10 +2598,15 @@ model_verbosity = "high"
             model_provider: fixture.openai_provider.clone(),
             approval_policy: AskForApproval::OnFailure,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
+            sandbox_workspace_write: None,
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: Hooks::default(),
+            model_pricing: model_pricing_table(HashMap::new()),
+            session_budget_usd: None,
+            worktree: false,
             cwd: fixture.cwd(),
             mcp_servers: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
@@ -2474,6 +2632,7 @@ model_verbosity = "high"
             use_experimental_use_rmcp_client: false,
             include_view_image_tool: true,
             features: Features::with_defaults(),
+            enabled_tools: None,
             active_profile: Some("zdr".to_string()),
             active_project: ProjectConfig { trust_level: None },
             windows_wsl_setup_acknowledged: false,
@@ -2512,10 +2671,15 @@ model_verbosity = "high"
             model_provider: fixture.openai_provider.clone(),
             approval_policy: AskForApproval::OnFailure,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
+            sandbox_workspace_write: None,
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: Hooks::default(),
+            model_pricing: model_pricing_table(HashMap::new()),
+            session_budget_usd: None,
+            worktree: false,
             cwd: fixture.cwd(),
             mcp_servers: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
@@ -2541,6 +2705,7 @@ model_verbosity = "high"
             use_experimental_use_rmcp_client: false,
             include_view_image_tool: true,
             features: Features::with_defaults(),
+            enabled_tools: None,
             active_profile: Some("gpt5".to_string()),
             active_project: ProjectConfig { trust_level: None },
             windows_wsl_setup_acknowledged: false,
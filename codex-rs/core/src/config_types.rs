@@ -221,6 +221,60 @@ impl UriBasedFileOpener {
     }
 }
 
+/// Commands run at points in the agent lifecycle, configured under `[hooks]`.
+/// Each event accepts a list of hooks that run in order.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Hooks {
+    /// Runs before a tool call; can block the call or replace its input.
+    #[serde(default)]
+    pub pre_tool_use: Vec<HookCommand>,
+
+    /// Runs after a tool call; can add context to the output sent to the model.
+    #[serde(default)]
+    pub post_tool_use: Vec<HookCommand>,
+
+    /// Runs when the user submits a prompt; can block it or add context.
+    #[serde(default)]
+    pub user_prompt_submit: Vec<HookCommand>,
+
+    /// Runs in the background after the agent finishes a turn.
+    #[serde(default)]
+    pub turn_complete: Vec<HookCommand>,
+
+    /// Runs in the background once the session is configured.
+    #[serde(default)]
+    pub session_start: Vec<HookCommand>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct HookCommand {
+    /// Program and arguments; the event is written to stdin as JSON.
+    pub command: Vec<String>,
+
+    /// Tool names this hook applies to, separated by `|`. Only used by the
+    /// tool hooks; when unset the hook runs for every tool.
+    #[serde(default)]
+    pub matcher: Option<String>,
+
+    /// Maximum time to wait for the command. Defaults to 60 seconds.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// Price of a model in US dollars per million tokens, configured under
+/// `[model_pricing."<model>"]`.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    pub input: f64,
+
+    /// Price of cached input tokens. Defaults to `input`.
+    #[serde(default)]
+    pub cached_input: Option<f64>,
+
+    /// Price of output tokens, including reasoning tokens.
+    pub output: f64,
+}
+
 /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct History {
@@ -267,6 +321,35 @@ pub enum OtelExporterKind {
         endpoint: String,
         headers: HashMap<String, String>,
     },
+    /// Write OTLP/JSON lines to files in `path`, which defaults to
+    /// `$CODEX_HOME/otel`.
+    OtlpFile {
+        #[serde(default)]
+        path: Option<PathBuf>,
+    },
+}
+
+impl OtelExporterKind {
+    /// The exporter to use for another signal when only the log exporter is
+    /// configured. OTLP/HTTP endpoints name the signal in their path, so a
+    /// trailing `/v1/logs` is swapped for the signal's own path.
+    pub(crate) fn for_signal(&self, signal: &str) -> Self {
+        match self {
+            OtelExporterKind::OtlpHttp {
+                endpoint,
+                headers,
+                protocol,
+            } => OtelExporterKind::OtlpHttp {
+                endpoint: match endpoint.strip_suffix("/v1/logs") {
+                    Some(base) => format!("{base}/v1/{signal}"),
+                    None => endpoint.clone(),
+                },
+                headers: headers.clone(),
+                protocol: protocol.clone(),
+            },
+            other => other.clone(),
+        }
+    }
 }
 
 /// OTEL settings loaded from config.toml. Fields are optional so we can apply defaults.
@@ -278,8 +361,14 @@ pub struct OtelConfigToml {
     /// Mark traces with environment (dev, staging, prod, test). Defaults to dev.
     pub environment: Option<String>,
 
-    /// Exporter to use. Defaults to `otlp-file`.
+    /// Exporter to use for log events. Defaults to `none`.
     pub exporter: Option<OtelExporterKind>,
+
+    /// Exporter to use for spans. Defaults to `exporter`.
+    pub trace_exporter: Option<OtelExporterKind>,
+
+    /// Exporter to use for metrics. Defaults to `exporter`.
+    pub metrics_exporter: Option<OtelExporterKind>,
 }
 
 /// Effective OTEL settings after defaults are applied.
@@ -288,6 +377,8 @@ pub struct OtelConfig {
     pub log_user_prompt: bool,
     pub environment: String,
     pub exporter: OtelExporterKind,
+    pub trace_exporter: OtelExporterKind,
+    pub metrics_exporter: OtelExporterKind,
 }
 
 impl Default for OtelConfig {
@@ -296,6 +387,8 @@ impl Default for OtelConfig {
             log_user_prompt: false,
             environment: DEFAULT_OTEL_ENVIRONMENT.to_owned(),
             exporter: OtelExporterKind::None,
+            trace_exporter: OtelExporterKind::None,
+            metrics_exporter: OtelExporterKind::None,
         }
     }
 }
@@ -328,6 +421,10 @@ pub struct SandboxWorkspaceWrite {
     pub writable_roots: Vec<PathBuf>,
     #[serde(default)]
     pub network_access: bool,
+    /// Hosts (`host`, `host:port`, `*.domain`) reachable from the sandbox
+    /// when `network_access` is `false`. Enforced on Linux only.
+    #[serde(default)]
+    pub network_allowlist: Vec<String>,
     #[serde(default)]
     pub exclude_tmpdir_env_var: bool,
     #[serde(default)]
@@ -339,6 +436,7 @@ impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings
         Self {
             writable_roots: sandbox_workspace_write.writable_roots,
             network_access: Some(sandbox_workspace_write.network_access),
+            network_allowlist: sandbox_workspace_write.network_allowlist,
             exclude_tmpdir_env_var: Some(sandbox_workspace_write.exclude_tmpdir_env_var),
             exclude_slash_tmp: Some(sandbox_workspace_write.exclude_slash_tmp),
         }
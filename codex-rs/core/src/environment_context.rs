@@ -18,6 +18,8 @@ use std::path::PathBuf;
 #[strum(serialize_all = "kebab-case")]
 pub enum NetworkAccess {
     Restricted,
+    /// Only the hosts listed in `network_allowlist` are reachable.
+    Allowlisted,
     Enabled,
 }
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -27,6 +29,7 @@ pub(crate) struct EnvironmentContext {
     pub approval_policy: Option<AskForApproval>,
     pub sandbox_mode: Option<SandboxMode>,
     pub network_access: Option<NetworkAccess>,
+    pub network_allowlist: Option<Vec<String>>,
     pub writable_roots: Option<Vec<PathBuf>>,
     pub shell: Option<Shell>,
 }
@@ -38,6 +41,10 @@ impl EnvironmentContext {
         sandbox_policy: Option<SandboxPolicy>,
         shell: Option<Shell>,
     ) -> Self {
+        let network_allowlist = sandbox_policy
+            .as_ref()
+            .map(enforced_network_allowlist)
+            .filter(|allowlist| !allowlist.is_empty());
         Self {
             cwd,
             approval_policy,
@@ -53,12 +60,15 @@ impl EnvironmentContext {
                 Some(SandboxPolicy::WorkspaceWrite { network_access, .. }) => {
                     if network_access {
                         Some(NetworkAccess::Enabled)
+                    } else if network_allowlist.is_some() {
+                        Some(NetworkAccess::Allowlisted)
                     } else {
                         Some(NetworkAccess::Restricted)
                     }
                 }
                 None => None,
             },
+            network_allowlist,
             writable_roots: match sandbox_policy {
                 Some(SandboxPolicy::WorkspaceWrite { writable_roots, .. }) => {
                     if writable_roots.is_empty() {
@@ -82,6 +92,7 @@ impl EnvironmentContext {
             approval_policy,
             sandbox_mode,
             network_access,
+            network_allowlist,
             writable_roots,
             // should compare all fields except shell
             shell: _,
@@ -91,10 +102,21 @@ impl EnvironmentContext {
             && self.approval_policy == *approval_policy
             && self.sandbox_mode == *sandbox_mode
             && self.network_access == *network_access
+            && self.network_allowlist == *network_allowlist
             && self.writable_roots == *writable_roots
     }
 }
 
+/// The network allowlist is only honored by the Linux sandbox; elsewhere the
+/// network stays disabled, so do not advertise hosts the model cannot reach.
+fn enforced_network_allowlist(sandbox_policy: &SandboxPolicy) -> Vec<String> {
+    if cfg!(target_os = "linux") {
+        sandbox_policy.network_allowlist().to_vec()
+    } else {
+        Vec::new()
+    }
+}
+
 impl From<&TurnContext> for EnvironmentContext {
     fn from(turn_context: &TurnContext) -> Self {
         Self::new(
@@ -119,6 +141,7 @@ impl EnvironmentContext {
     ///   <sandbox_mode>...</sandbox_mode>
     ///   <writable_roots>...</writable_roots>
     ///   <network_access>...</network_access>
+    ///   <network_allowlist>...</network_allowlist>
     ///   <shell>...</shell>
     /// </environment_context>
     /// ```
@@ -140,6 +163,13 @@ impl EnvironmentContext {
                 "  <network_access>{network_access}</network_access>"
             ));
         }
+        if let Some(network_allowlist) = self.network_allowlist {
+            lines.push("  <network_allowlist>".to_string());
+            for host in network_allowlist {
+                lines.push(format!("    <host>{host}</host>"));
+            }
+            lines.push("  </network_allowlist>".to_string());
+        }
         if let Some(writable_roots) = self.writable_roots {
             lines.push("  <writable_roots>".to_string());
             for writable_root in writable_roots {
@@ -184,6 +214,7 @@ mod tests {
         SandboxPolicy::WorkspaceWrite {
             writable_roots: writable_roots.into_iter().map(PathBuf::from).collect(),
             network_access,
+            network_allowlist: vec![],
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
         }
@@ -212,6 +243,39 @@ mod tests {
         assert_eq!(context.serialize_to_xml(), expected);
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn serialize_network_allowlist_environment_context() {
+        let context = EnvironmentContext::new(
+            Some(PathBuf::from("/repo")),
+            Some(AskForApproval::OnRequest),
+            Some(SandboxPolicy::WorkspaceWrite {
+                writable_roots: vec![],
+                network_access: false,
+                network_allowlist: vec![
+                    "crates.internal.example:443".to_string(),
+                    "*.pypi.internal.example".to_string(),
+                ],
+                exclude_tmpdir_env_var: false,
+                exclude_slash_tmp: false,
+            }),
+            None,
+        );
+
+        let expected = r#"<environment_context>
+  <cwd>/repo</cwd>
+  <approval_policy>on-request</approval_policy>
+  <sandbox_mode>workspace-write</sandbox_mode>
+  <network_access>allowlisted</network_access>
+  <network_allowlist>
+    <host>crates.internal.example:443</host>
+    <host>*.pypi.internal.example</host>
+  </network_allowlist>
+</environment_context>"#;
+
+        assert_eq!(context.serialize_to_xml(), expected);
+    }
+
     #[test]
     fn serialize_read_only_environment_context() {
         let context = EnvironmentContext::new(
@@ -0,0 +1,385 @@
+//! The execpolicy consulted before asking the user to approve a command: the
+//! built-in `default.policy` merged with the `*.policy` files in
+//! `$CODEX_HOME/policy` and, for trusted projects, `<repo>/.codex/policy`.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use codex_execpolicy::ExecCall;
+use codex_execpolicy::MatchedExec;
+use codex_execpolicy::Policy;
+use codex_execpolicy::Redirect;
+use codex_execpolicy::get_default_policy;
+use codex_execpolicy::load_policy_file;
+use codex_execpolicy::policy_files_in_dir;
+
+use crate::bash::parse_bash_lc_script;
+use crate::config::Config;
+use crate::git_info::get_git_repo_root;
+use crate::git_info::resolve_root_git_project_for_trust;
+use crate::protocol::SandboxPolicy;
+use crate::safety::normalize_path;
+
+/// Name of the policy directory in `$CODEX_HOME` and in a repository's
+/// `.codex` directory.
+pub const POLICY_DIR_NAME: &str = "policy";
+
+/// The project whose `.codex/policy` files may be loaded: the repository
+/// containing `config.cwd`, if the user has marked it as trusted. A policy
+/// can let commands run without a sandbox, so a freshly cloned repository
+/// must not be able to grant that to its own scripts.
+pub fn trusted_policy_project(config: &Config) -> Option<PathBuf> {
+    if !config.active_project.is_trusted() {
+        return None;
+    }
+    resolve_root_git_project_for_trust(&config.cwd)
+}
+
+/// Directories searched for policy files when running commands in `cwd`, in
+/// increasing order of precedence. The repository's own policy directory is
+/// only included when the repository is `trusted_project`.
+pub fn policy_dirs(codex_home: &Path, cwd: &Path, trusted_project: Option<&Path>) -> Vec<PathBuf> {
+    let mut dirs = vec![codex_home.join(POLICY_DIR_NAME)];
+    if let Some(repo_root) = get_git_repo_root(cwd)
+        && let Some(trusted_project) = trusted_project
+        && resolve_root_git_project_for_trust(cwd).as_deref() == Some(trusted_project)
+    {
+        dirs.push(repo_root.join(".codex").join(POLICY_DIR_NAME));
+    }
+    dirs
+}
+
+/// Loads the built-in policy merged with every policy file that applies to
+/// `cwd`. Rules from later files take precedence. A file that fails to load
+/// (including one whose `should_match`/`should_not_match` examples do not
+/// hold) is skipped, and its error is returned alongside the policy.
+pub fn load_exec_policy(
+    codex_home: &Path,
+    cwd: &Path,
+    trusted_project: Option<&Path>,
+) -> (Policy, Vec<anyhow::Error>) {
+    #[expect(clippy::expect_used)]
+    let mut policy = get_default_policy().expect("default.policy is verified by tests");
+    let mut errors = Vec::new();
+    // Merge in reverse so that the files with the highest precedence are
+    // tried first.
+    for dir in policy_dirs(codex_home, cwd, trusted_project).iter().rev() {
+        let files = match policy_files_in_dir(dir) {
+            Ok(files) => files,
+            Err(err) => {
+                errors.push(anyhow::anyhow!("failed to read {}: {err}", dir.display()));
+                continue;
+            }
+        };
+        for file in files.iter().rev() {
+            match load_policy_file(file) {
+                Ok(file_policy) => policy.merge(file_policy),
+                Err(err) => errors.push(err),
+            }
+        }
+    }
+    (policy, errors)
+}
+
+/// What the execpolicy says about a command the model wants to run.
+#[derive(Debug, PartialEq)]
+pub enum ExecPolicyVerdict {
+    /// Every command matched a rule and none of them write files.
+    Safe,
+    /// A rule forbids the command.
+    Forbidden { reason: String },
+    /// The policy cannot vouch for the command; fall back to the approval
+    /// policy.
+    Unverified,
+}
+
+/// Checks `command` against `policy`. For `bash -lc` scripts, each command in
+/// the script is checked and redirections into files make the script unsafe.
+///
+/// Under a `workspace-write` sandbox, a redirection whose target resolves
+/// outside the writable roots (computed for `sandbox_cwd`) makes the command
+/// forbidden. Relative targets are resolved against `cwd`, the directory the
+/// command runs in, up to the first `cd` in the script.
+pub fn evaluate_exec_policy(
+    policy: &Policy,
+    command: &[String],
+    sandbox_policy: &SandboxPolicy,
+    sandbox_cwd: &Path,
+    cwd: &Path,
+) -> ExecPolicyVerdict {
+    let writable_roots = match sandbox_policy {
+        SandboxPolicy::WorkspaceWrite { .. } => {
+            Some(sandbox_policy.get_writable_roots_with_cwd(sandbox_cwd))
+        }
+        SandboxPolicy::ReadOnly | SandboxPolicy::DangerFullAccess => None,
+    };
+
+    let exec_calls = match parse_bash_lc_script(command) {
+        Some(commands) => {
+            let mut cwd_known = true;
+            let mut exec_calls = Vec::with_capacity(commands.len());
+            for command in commands {
+                let mut redirect_writes = false;
+                for target in command.redirects.iter().filter_map(Redirect::written_path) {
+                    redirect_writes = true;
+                    let target = Path::new(target);
+                    if let Some(roots) = &writable_roots
+                        && (cwd_known || target.is_absolute())
+                    {
+                        let resolved = normalize_path(&cwd.join(target));
+                        if !roots.iter().any(|root| root.is_path_writable(&resolved)) {
+                            return ExecPolicyVerdict::Forbidden {
+                                reason: format!(
+                                    "redirects output to {}, outside the writable roots",
+                                    target.display()
+                                ),
+                            };
+                        }
+                    }
+                }
+                cwd_known &= command.exec_call.program != "cd";
+                exec_calls.push((command.exec_call, redirect_writes));
+            }
+            exec_calls
+        }
+        None => match command.split_first() {
+            Some((program, args)) => vec![(
+                ExecCall {
+                    program: program.clone(),
+                    args: args.to_vec(),
+                },
+                false,
+            )],
+            None => Vec::new(),
+        },
+    };
+
+    let mut safe = !exec_calls.is_empty();
+    for (exec_call, redirect_writes) in exec_calls {
+        match policy.check(&exec_call) {
+            Ok(MatchedExec::Forbidden { reason, .. }) => {
+                return ExecPolicyVerdict::Forbidden { reason };
+            }
+            Ok(MatchedExec::Match { exec }) => {
+                safe &= !redirect_writes && !exec.might_write_files();
+            }
+            Err(_) => safe = false,
+        }
+    }
+    if safe {
+        ExecPolicyVerdict::Safe
+    } else {
+        ExecPolicyVerdict::Unverified
+    }
+}
+
+/// Loads the merged policy once per repository so policy files are not
+/// re-read for every command.
+#[derive(Clone)]
+pub(crate) struct ExecPolicyCache {
+    codex_home: PathBuf,
+    trusted_project: Option<PathBuf>,
+    policies: Arc<Mutex<HashMap<Option<PathBuf>, Arc<Policy>>>>,
+}
+
+impl ExecPolicyCache {
+    pub(crate) fn new(codex_home: PathBuf, trusted_project: Option<PathBuf>) -> Self {
+        Self {
+            codex_home,
+            trusted_project,
+            policies: Arc::default(),
+        }
+    }
+
+    pub(crate) fn policy_for(&self, cwd: &Path) -> Arc<Policy> {
+        let repo_root = get_git_repo_root(cwd);
+        if let Ok(policies) = self.policies.lock()
+            && let Some(policy) = policies.get(&repo_root)
+        {
+            return Arc::clone(policy);
+        }
+
+        let (policy, errors) =
+            load_exec_policy(&self.codex_home, cwd, self.trusted_project.as_deref());
+        for err in errors {
+            tracing::warn!("ignoring execpolicy file: {err:#}");
+        }
+        let policy = Arc::new(policy);
+        if let Ok(mut policies) = self.policies.lock() {
+            policies.insert(repo_root, Arc::clone(&policy));
+        }
+        policy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn vec_str(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    fn evaluate_read_only(policy: &Policy, command: &[&str]) -> ExecPolicyVerdict {
+        let cwd = Path::new("/");
+        evaluate_exec_policy(
+            policy,
+            &vec_str(command),
+            &SandboxPolicy::ReadOnly,
+            cwd,
+            cwd,
+        )
+    }
+
+    #[test]
+    fn user_policy_files_are_merged_and_validated() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cwd = TempDir::new()?;
+        let policy_dir = codex_home.path().join(POLICY_DIR_NAME);
+        std::fs::create_dir_all(&policy_dir)?;
+        std::fs::write(
+            policy_dir.join("team.policy"),
+            r#"
+define_program(program="./x.py", args=["check"], should_match=[["check"]])
+define_program(program="deploy", forbidden="deploys go through CI")
+"#,
+        )?;
+        std::fs::write(
+            policy_dir.join("broken.policy"),
+            r#"define_program(program="ls", args=[], should_match=[["-z"]])"#,
+        )?;
+
+        let (policy, errors) = load_exec_policy(codex_home.path(), cwd.path(), None);
+        assert_eq!(1, errors.len());
+        assert!(format!("{:#}", errors[0]).contains("broken.policy"));
+
+        assert_eq!(
+            ExecPolicyVerdict::Safe,
+            evaluate_read_only(&policy, &["./x.py", "check"])
+        );
+        assert_eq!(
+            ExecPolicyVerdict::Forbidden {
+                reason: "deploys go through CI".to_string()
+            },
+            evaluate_read_only(&policy, &["bash", "-lc", "ls && deploy"])
+        );
+        assert_eq!(
+            ExecPolicyVerdict::Unverified,
+            evaluate_read_only(&policy, &["./x.py", "fix"])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn repo_policy_files_require_a_trusted_project() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        let repo = TempDir::new()?;
+        let status = std::process::Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(repo.path())
+            .status()?;
+        assert!(status.success());
+        let policy_dir = repo.path().join(".codex").join(POLICY_DIR_NAME);
+        std::fs::create_dir_all(&policy_dir)?;
+        std::fs::write(
+            policy_dir.join("repo.policy"),
+            r#"define_program(program="./evil.sh", args=[])"#,
+        )?;
+
+        let (policy, _) = load_exec_policy(codex_home.path(), repo.path(), None);
+        assert_eq!(
+            ExecPolicyVerdict::Unverified,
+            evaluate_read_only(&policy, &["./evil.sh"])
+        );
+
+        let other = TempDir::new()?;
+        let (policy, _) = load_exec_policy(codex_home.path(), repo.path(), Some(other.path()));
+        assert_eq!(
+            ExecPolicyVerdict::Unverified,
+            evaluate_read_only(&policy, &["./evil.sh"])
+        );
+
+        let trusted_project = resolve_root_git_project_for_trust(repo.path());
+        let (policy, errors) =
+            load_exec_policy(codex_home.path(), repo.path(), trusted_project.as_deref());
+        assert!(errors.is_empty());
+        assert_eq!(
+            ExecPolicyVerdict::Safe,
+            evaluate_read_only(&policy, &["./evil.sh"])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn scripts_that_write_files_are_not_safe() {
+        let policy = get_default_policy().expect("load default policy");
+        assert_eq!(
+            ExecPolicyVerdict::Safe,
+            evaluate_read_only(&policy, &["bash", "-lc", "ls 2>/dev/null && pwd"])
+        );
+        assert_eq!(
+            ExecPolicyVerdict::Unverified,
+            evaluate_read_only(&policy, &["bash", "-lc", "ls > files.txt"])
+        );
+        assert_eq!(
+            ExecPolicyVerdict::Unverified,
+            evaluate_read_only(&policy, &["cp", "a", "b"])
+        );
+    }
+
+    #[test]
+    fn redirects_outside_writable_roots_are_forbidden() -> anyhow::Result<()> {
+        let policy = get_default_policy().expect("load default policy");
+        let tmp = TempDir::new()?;
+        let cwd = tmp.path().join("repo");
+        std::fs::create_dir_all(cwd.join("sub"))?;
+        let sandbox_policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: false,
+            network_allowlist: vec![],
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        };
+        let evaluate = |script: &str| {
+            evaluate_exec_policy(
+                &policy,
+                &vec_str(&["bash", "-lc", script]),
+                &sandbox_policy,
+                &cwd,
+                &cwd,
+            )
+        };
+
+        assert_eq!(
+            ExecPolicyVerdict::Forbidden {
+                reason: "redirects output to /etc/passwd, outside the writable roots".to_string()
+            },
+            evaluate("echo x > /etc/passwd")
+        );
+        assert_eq!(
+            ExecPolicyVerdict::Forbidden {
+                reason: "redirects output to ../outside, outside the writable roots".to_string()
+            },
+            evaluate("ls > ../outside")
+        );
+        assert_eq!(
+            ExecPolicyVerdict::Unverified,
+            evaluate("ls > sub/files.txt")
+        );
+        // After a `cd`, relative targets can no longer be resolved statically.
+        assert_eq!(
+            ExecPolicyVerdict::Unverified,
+            evaluate("cd sub && ls > ../files.txt")
+        );
+        assert_eq!(
+            ExecPolicyVerdict::Safe,
+            evaluate("ls 2>/dev/null && ls >/dev/stderr")
+        );
+        Ok(())
+    }
+}
@@ -1,15 +1,44 @@
 use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
 
+use crate::approval_rules::APPLY_PATCH_RULE_COMMAND;
+use crate::approval_rules::ApprovalRule;
+use crate::approval_rules::ApprovalScope;
+use crate::approval_rules::CommandMatcher;
+use crate::approval_rules::add_approval_rule;
+use crate::approval_rules::load_approval_rules;
+
 #[derive(Clone, Debug, Default)]
 /// Thread-safe store of user approvals so repeated commands can reuse
-/// previously granted trust.
+/// previously granted trust. Session approvals live in memory; when a
+/// `codex_home` is configured, persisted approval rules are loaded once when
+/// the cache is created and rules added during the session are kept alongside
+/// them.
 pub(crate) struct ApprovalCache {
     inner: Arc<Mutex<HashSet<Vec<String>>>>,
+    rules: Arc<Mutex<Vec<ApprovalRule>>>,
+    codex_home: Option<PathBuf>,
 }
 
 impl ApprovalCache {
+    pub(crate) fn new(codex_home: PathBuf) -> Self {
+        let rules = match load_approval_rules(&codex_home) {
+            Ok(rules) => rules,
+            Err(err) => {
+                tracing::warn!("failed to load approval rules: {err}");
+                Vec::new()
+            }
+        };
+        Self {
+            inner: Arc::default(),
+            rules: Arc::new(Mutex::new(rules)),
+            codex_home: Some(codex_home),
+        }
+    }
+
     pub(crate) fn insert(&self, command: Vec<String>) {
         if command.is_empty() {
             return;
@@ -22,11 +51,62 @@ impl ApprovalCache {
     pub(crate) fn snapshot(&self) -> HashSet<Vec<String>> {
         self.inner.lock().map(|g| g.clone()).unwrap_or_default()
     }
+
+    /// Persisted approval rules known to this session.
+    pub(crate) fn rules(&self) -> Vec<ApprovalRule> {
+        self.rules.lock().map(|g| g.clone()).unwrap_or_default()
+    }
+
+    /// Approves `command` for this session and persists an exact-match rule
+    /// scoped to the project containing `cwd`.
+    pub(crate) fn remember_for_project(&self, command: Vec<String>, cwd: &Path) {
+        if command.is_empty() {
+            return;
+        }
+        self.persist_rule(
+            CommandMatcher::Exact {
+                command: command.clone(),
+            },
+            cwd,
+        );
+        self.insert(command);
+    }
+
+    /// Persists a rule approving `apply_patch` edits that stay inside the
+    /// project containing `cwd`.
+    pub(crate) fn remember_patches_for_project(&self, cwd: &Path) {
+        self.persist_rule(
+            CommandMatcher::Exact {
+                command: vec![APPLY_PATCH_RULE_COMMAND.to_string()],
+            },
+            cwd,
+        );
+    }
+
+    fn persist_rule(&self, matcher: CommandMatcher, cwd: &Path) {
+        let scope = ApprovalScope::project_for_cwd(cwd);
+        let rule = match &self.codex_home {
+            Some(codex_home) => match add_approval_rule(codex_home, matcher, scope) {
+                Ok(rule) => rule,
+                Err(err) => {
+                    tracing::warn!("failed to persist approval rule: {err}");
+                    return;
+                }
+            },
+            None => return,
+        };
+        if let Ok(mut rules) = self.rules.lock()
+            && !rules.contains(&rule)
+        {
+            rules.push(rule);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::approval_rules::find_matching_rule;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -48,4 +128,23 @@ mod tests {
         let snap2 = cache.snapshot();
         assert_eq!(snap1, snap2);
     }
+
+    #[test]
+    fn remember_for_project_persists_rule_for_new_sessions() {
+        let codex_home = tempfile::tempdir().unwrap();
+        let project = tempfile::tempdir().unwrap();
+        let cmd = vec!["cargo".to_string(), "test".to_string()];
+
+        let cache = ApprovalCache::new(codex_home.path().to_path_buf());
+        cache.remember_for_project(cmd.clone(), project.path());
+        assert!(cache.snapshot().contains(&cmd));
+        assert_eq!(cache.rules().len(), 1);
+
+        // A fresh cache (i.e. a new session) picks the rule up from disk, but
+        // only for commands run inside the project.
+        let fresh = ApprovalCache::new(codex_home.path().to_path_buf());
+        let rules = fresh.rules();
+        assert!(find_matching_rule(&rules, &cmd, project.path()).is_some());
+        assert!(find_matching_rule(&rules, &cmd, codex_home.path()).is_none());
+    }
 }
@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::RwLock;
@@ -6,6 +7,7 @@ use std::time::Duration;
 use super::backends::ExecutionMode;
 use super::backends::backend_for_mode;
 use super::cache::ApprovalCache;
+use crate::approval_rules::ApprovalRule;
 use crate::codex::Session;
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
@@ -16,6 +18,7 @@ use crate::exec::SandboxType;
 use crate::exec::StdoutStream;
 use crate::exec::StreamOutput;
 use crate::exec::process_exec_tool_call;
+use crate::exec_policy::ExecPolicyCache;
 use crate::executor::errors::ExecError;
 use crate::executor::sandbox::select_sandbox;
 use crate::function_tool::FunctionCallError;
@@ -51,17 +54,36 @@ impl ExecutorConfig {
 /// execution for tool calls requested by the model.
 pub(crate) struct Executor {
     approval_cache: ApprovalCache,
+    exec_policy: ExecPolicyCache,
     config: Arc<RwLock<ExecutorConfig>>,
 }
 
 impl Executor {
-    pub(crate) fn new(config: ExecutorConfig) -> Self {
+    /// `trusted_policy_project` is the project whose `.codex/policy` files
+    /// may be loaded; see [`crate::exec_policy::trusted_policy_project`].
+    pub(crate) fn new(
+        config: ExecutorConfig,
+        codex_home: PathBuf,
+        trusted_policy_project: Option<PathBuf>,
+    ) -> Self {
         Self {
-            approval_cache: ApprovalCache::default(),
+            approval_cache: ApprovalCache::new(codex_home.clone()),
+            exec_policy: ExecPolicyCache::new(codex_home, trusted_policy_project),
             config: Arc::new(RwLock::new(config)),
         }
     }
 
+    /// Persisted approval rules loaded for this session.
+    pub(crate) fn approval_rules(&self) -> Vec<ApprovalRule> {
+        self.approval_cache.rules()
+    }
+
+    /// Persists a rule approving future patches inside the project that
+    /// contains `cwd`.
+    pub(crate) fn remember_patches_for_project(&self, cwd: &Path) {
+        self.approval_cache.remember_patches_for_project(cwd);
+    }
+
     /// Updates the sandbox policy and working directory used for future
     /// executions without recreating the executor.
     pub(crate) fn update_environment(&self, sandbox_policy: SandboxPolicy, sandbox_cwd: PathBuf) {
@@ -105,10 +127,15 @@ impl Executor {
             .map_err(ExecError::from)?;
 
         // Step 3: Decide sandbox placement, prompting for approval when needed.
+        let approved_snapshot = self.approval_cache.snapshot();
+        let approval_rules = self.approval_cache.rules();
+        let exec_policy = self.exec_policy.policy_for(&request.params.cwd);
         let sandbox_decision = select_sandbox(
             &request,
             approval_policy,
-            self.approval_cache.snapshot(),
+            approved_snapshot,
+            &approval_rules,
+            &exec_policy,
             &config,
             session,
             &context.sub_id,
@@ -116,7 +143,10 @@ impl Executor {
             &context.otel_event_manager,
         )
         .await?;
-        if sandbox_decision.record_session_approval {
+        if sandbox_decision.record_project_approval {
+            self.approval_cache
+                .remember_for_project(request.approval_command.clone(), &request.params.cwd);
+        } else if sandbox_decision.record_session_approval {
             self.approval_cache.insert(request.approval_command.clone());
         }
 
@@ -126,6 +156,7 @@ impl Executor {
                 request.params.clone(),
                 sandbox_decision.initial_sandbox,
                 &config,
+                context,
                 stdout_stream.clone(),
             )
             .await;
@@ -190,9 +221,16 @@ impl Executor {
             ToolDecisionSource::User,
         );
         match decision {
-            ReviewDecision::Approved | ReviewDecision::ApprovedForSession => {
+            ReviewDecision::Approved
+            | ReviewDecision::ApprovedForSession
+            | ReviewDecision::ApprovedForProject => {
                 if matches!(decision, ReviewDecision::ApprovedForSession) {
                     self.approval_cache.insert(request.approval_command.clone());
+                } else if matches!(decision, ReviewDecision::ApprovedForProject) {
+                    self.approval_cache.remember_for_project(
+                        request.approval_command.clone(),
+                        &request.params.cwd,
+                    );
                 }
                 session
                     .notify_background_event(&context.sub_id, "retrying command without sandbox")
@@ -203,6 +241,7 @@ impl Executor {
                         request.params.clone(),
                         SandboxType::None,
                         config,
+                        context,
                         stdout_stream,
                     )
                     .await?;
@@ -220,9 +259,10 @@ impl Executor {
         params: ExecParams,
         sandbox: SandboxType,
         config: &ExecutorConfig,
+        context: &ExecCommandContext,
         stdout_stream: Option<StdoutStream>,
     ) -> Result<ExecToolCallOutput, CodexErr> {
-        process_exec_tool_call(
+        let result = process_exec_tool_call(
             params,
             sandbox,
             &config.sandbox_policy,
@@ -230,7 +270,18 @@ impl Executor {
             &config.codex_exe,
             stdout_stream,
         )
-        .await
+        .await;
+        let exit_code = match &result {
+            Ok(output) => Some(output.exit_code),
+            Err(CodexErr::Sandbox(
+                SandboxErr::Denied { output } | SandboxErr::Timeout { output },
+            )) => Some(output.exit_code),
+            Err(_) => None,
+        };
+        context
+            .otel_event_manager
+            .record_exec(sandbox_name(sandbox), exit_code);
+        result
     }
 }
 
@@ -253,6 +304,14 @@ fn maybe_translate_shell_command(
     params
 }
 
+fn sandbox_name(sandbox: SandboxType) -> &'static str {
+    match sandbox {
+        SandboxType::None => "none",
+        SandboxType::MacosSeatbelt => "seatbelt",
+        SandboxType::LinuxSeccomp => "seccomp",
+    }
+}
+
 fn sandbox_failure_message(error: SandboxErr) -> String {
     let codex_error = CodexErr::Sandbox(error);
     let friendly = get_error_message_ui(&codex_error);
@@ -267,6 +326,17 @@ pub(crate) struct ExecutionRequest {
     pub use_shell_profile: bool,
 }
 
+impl ExecutionRequest {
+    /// The argv that approval checks and rules are evaluated against.
+    pub(crate) fn command_for_safety(&self) -> &[String] {
+        if self.approval_command.is_empty() {
+            &self.params.command
+        } else {
+            &self.approval_command
+        }
+    }
+}
+
 pub(crate) struct NormalizedExecOutput<'a> {
     borrowed: Option<&'a ExecToolCallOutput>,
     synthetic: Option<ExecToolCallOutput>,
@@ -1,4 +1,5 @@
 use crate::apply_patch::ApplyPatchExec;
+use crate::approval_rules::ApprovalRule;
 use crate::codex::Session;
 use crate::exec::SandboxType;
 use crate::executor::ExecutionMode;
@@ -8,6 +9,7 @@ use crate::executor::errors::ExecError;
 use crate::safety::SafetyCheck;
 use crate::safety::assess_command_safety;
 use crate::safety::assess_patch_safety;
+use codex_execpolicy::Policy;
 use codex_otel::otel_event_manager::OtelEventManager;
 use codex_otel::otel_event_manager::ToolDecisionSource;
 use codex_protocol::protocol::AskForApproval;
@@ -20,6 +22,7 @@ pub(crate) struct SandboxDecision {
     pub(crate) initial_sandbox: SandboxType,
     pub(crate) escalate_on_failure: bool,
     pub(crate) record_session_approval: bool,
+    pub(crate) record_project_approval: bool,
 }
 
 impl SandboxDecision {
@@ -28,6 +31,7 @@ impl SandboxDecision {
             initial_sandbox: sandbox,
             escalate_on_failure,
             record_session_approval: false,
+            record_project_approval: false,
         }
     }
 
@@ -36,6 +40,14 @@ impl SandboxDecision {
             initial_sandbox: SandboxType::None,
             escalate_on_failure: false,
             record_session_approval,
+            record_project_approval: false,
+        }
+    }
+
+    fn user_override_for_project() -> Self {
+        Self {
+            record_project_approval: true,
+            ..Self::user_override(true)
         }
     }
 }
@@ -57,6 +69,8 @@ pub async fn select_sandbox(
     request: &ExecutionRequest,
     approval_policy: AskForApproval,
     approval_cache: HashSet<Vec<String>>,
+    approval_rules: &[ApprovalRule],
+    exec_policy: &Policy,
     config: &ExecutorConfig,
     session: &Session,
     sub_id: &str,
@@ -69,6 +83,8 @@ pub async fn select_sandbox(
                 request,
                 approval_policy,
                 approval_cache,
+                approval_rules,
+                exec_policy,
                 config,
                 session,
                 sub_id,
@@ -78,7 +94,7 @@ pub async fn select_sandbox(
             .await
         }
         ExecutionMode::ApplyPatch(exec) => {
-            select_apply_patch_sandbox(exec, approval_policy, config)
+            select_apply_patch_sandbox(exec, approval_policy, approval_rules, config)
         }
     }
 }
@@ -88,23 +104,23 @@ async fn select_shell_sandbox(
     request: &ExecutionRequest,
     approval_policy: AskForApproval,
     approved_snapshot: HashSet<Vec<String>>,
+    approval_rules: &[ApprovalRule],
+    exec_policy: &Policy,
     config: &ExecutorConfig,
     session: &Session,
     sub_id: &str,
     call_id: &str,
     otel_event_manager: &OtelEventManager,
 ) -> Result<SandboxDecision, ExecError> {
-    let command_for_safety = if request.approval_command.is_empty() {
-        request.params.command.clone()
-    } else {
-        request.approval_command.clone()
-    };
-
     let safety = assess_command_safety(
-        &command_for_safety,
+        request.command_for_safety(),
         approval_policy,
         &config.sandbox_policy,
+        &config.sandbox_cwd,
         &approved_snapshot,
+        approval_rules,
+        &request.params.cwd,
+        exec_policy,
         request.params.with_escalated_permissions.unwrap_or(false),
     );
 
@@ -148,6 +164,9 @@ async fn select_shell_sandbox(
             match decision {
                 ReviewDecision::Approved => Ok(SandboxDecision::user_override(false)),
                 ReviewDecision::ApprovedForSession => Ok(SandboxDecision::user_override(true)),
+                ReviewDecision::ApprovedForProject => {
+                    Ok(SandboxDecision::user_override_for_project())
+                }
                 ReviewDecision::Denied | ReviewDecision::Abort => {
                     Err(ExecError::rejection("exec command rejected by user"))
                 }
@@ -162,6 +181,7 @@ async fn select_shell_sandbox(
 fn select_apply_patch_sandbox(
     exec: &ApplyPatchExec,
     approval_policy: AskForApproval,
+    approval_rules: &[ApprovalRule],
     config: &ExecutorConfig,
 ) -> Result<SandboxDecision, ExecError> {
     if exec.user_explicitly_approved_this_action {
@@ -173,6 +193,7 @@ fn select_apply_patch_sandbox(
         approval_policy,
         &config.sandbox_policy,
         &config.sandbox_cwd,
+        approval_rules,
     ) {
         SafetyCheck::AutoApprove { sandbox_type, .. } => Ok(SandboxDecision::auto(
             sandbox_type,
@@ -195,6 +216,7 @@ mod tests {
     use crate::function_tool::FunctionCallError;
     use crate::protocol::SandboxPolicy;
     use codex_apply_patch::ApplyPatchAction;
+    use codex_execpolicy::get_default_policy;
     use pretty_assertions::assert_eq;
 
     #[tokio::test]
@@ -227,6 +249,8 @@ mod tests {
             &request,
             AskForApproval::OnRequest,
             Default::default(),
+            &[],
+            &get_default_policy().expect("load default policy"),
             &cfg,
             &session,
             "sub",
@@ -270,6 +294,8 @@ mod tests {
             &request,
             AskForApproval::OnRequest,
             Default::default(),
+            &[],
+            &get_default_policy().expect("load default policy"),
             &cfg,
             &session,
             "sub",
@@ -314,6 +340,8 @@ mod tests {
             &request,
             AskForApproval::UnlessTrusted,
             Default::default(),
+            &[],
+            &get_default_policy().expect("load default policy"),
             &cfg,
             &session,
             "sub",
@@ -353,6 +381,8 @@ mod tests {
             &request,
             AskForApproval::OnRequest,
             Default::default(),
+            &[],
+            &get_default_policy().expect("load default policy"),
             &cfg,
             &session,
             "sub",
@@ -390,6 +420,8 @@ mod tests {
             &request,
             AskForApproval::OnFailure,
             Default::default(),
+            &[],
+            &get_default_policy().expect("load default policy"),
             &cfg,
             &session,
             "sub",
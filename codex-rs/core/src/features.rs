@@ -43,6 +43,10 @@ pub enum Feature {
     WebSearchRequest,
     /// Automatically approve all approval requests from the harness.
     ApproveAll,
+    /// Include the spawn_agent tool for delegating work to child conversations.
+    SpawnAgent,
+    /// Include the pin_context tool for keeping content verbatim across compactions.
+    PinTool,
 }
 
 impl Feature {
@@ -255,4 +259,16 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::Experimental,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::SpawnAgent,
+        key: "spawn_agent",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::PinTool,
+        key: "pin_tool",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
 ];
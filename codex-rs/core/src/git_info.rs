@@ -3,6 +3,8 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use codex_app_server_protocol::GitSha;
+use codex_git_tooling::CreateGhostCommitOptions;
+use codex_git_tooling::create_ghost_commit;
 use codex_protocol::protocol::GitInfo;
 use futures::future::join_all;
 use serde::Deserialize;
@@ -513,6 +515,101 @@ async fn diff_against_sha(cwd: &Path, sha: &GitSha) -> Option<String> {
     Some(diff)
 }
 
+/// Resolves `rev` (a branch, tag or abbreviated sha) to a full commit sha.
+pub async fn resolve_commit(cwd: &Path, rev: &str) -> Option<String> {
+    let spec = format!("{rev}^{{commit}}");
+    let out = run_git_command_with_timeout(
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            "--end-of-options",
+            &spec,
+        ],
+        cwd,
+    )
+    .await?;
+    if !out.status.success() {
+        return None;
+    }
+    String::from_utf8(out.stdout)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|sha| !sha.is_empty())
+}
+
+/// Returns the sha where HEAD diverged from `branch`, preferring the
+/// branch's upstream so that unpushed local commits on it are not missed.
+pub async fn merge_base_with_head(cwd: &Path, branch: &str) -> Option<String> {
+    let upstream = format!("{branch}@{{upstream}}");
+    let base = match resolve_commit(cwd, &upstream).await {
+        Some(sha) => sha,
+        None => resolve_commit(cwd, branch).await?,
+    };
+    let out = run_git_command_with_timeout(&["merge-base", "HEAD", &base], cwd).await?;
+    if !out.status.success() {
+        return None;
+    }
+    String::from_utf8(out.stdout)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|sha| !sha.is_empty())
+}
+
+/// Returns the diff of the working tree, including untracked files, against
+/// `sha`.
+pub async fn diff_working_tree_against(cwd: &Path, sha: &str) -> Option<String> {
+    diff_against_sha(cwd, &GitSha::new(sha)).await
+}
+
+/// Records the working tree under `cwd`, including uncommitted and untracked
+/// files, as an unreferenced commit and returns its sha. Neither the index nor
+/// any ref is touched, so the snapshot can be taken while the user works.
+pub async fn snapshot_working_tree(cwd: &Path) -> Option<String> {
+    let cwd = cwd.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        create_ghost_commit(&CreateGhostCommitOptions::new(&cwd))
+            .ok()
+            .map(|commit| commit.id().to_string())
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Returns the diff between two revisions.
+pub async fn diff_between(cwd: &Path, from: &str, to: &str) -> Option<String> {
+    let output = run_git_command_with_timeout(
+        &["diff", "--no-textconv", "--no-ext-diff", from, to, "--"],
+        cwd,
+    )
+    .await?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+/// Returns the message and diff of a single commit.
+pub async fn commit_diff(cwd: &Path, sha: &str) -> Option<String> {
+    let output = run_git_command_with_timeout(
+        &[
+            "show",
+            "--no-textconv",
+            "--no-ext-diff",
+            "--format=commit %H%n%n%B",
+            sha,
+            "--",
+        ],
+        cwd,
+    )
+    .await?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
 /// Resolve the path that should be used for trust checks. Similar to
 /// `[get_git_repo_root]`, but resolves to the root of the main
 /// repository. Handles worktrees.
@@ -0,0 +1,455 @@
+//! Lifecycle hooks configured under `[hooks]`.
+//!
+//! A hook is an external command. Codex writes a JSON object describing the
+//! event to its stdin, e.g.
+//!
+//! ```json
+//! {"hook_event":"pre_tool_use","session_id":"…","turn_id":"…","cwd":"/repo","tool_name":"shell","call_id":"…","tool_input":{"command":["ls"]}}
+//! ```
+//!
+//! and interprets the result as follows:
+//!
+//! - Exit status 0: the call proceeds. Stdout may contain a JSON reply
+//!   ([`HookReply`]) that blocks the call, replaces the tool input, or adds
+//!   context for the model.
+//! - Exit status 2: the call is blocked; stderr is reported as the reason.
+//! - Anything else (including timeouts): the failure is logged and ignored.
+//!
+//! `turn_complete` and `session_start` hooks run in the background and their
+//! replies are ignored.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::warn;
+
+use crate::config_types::HookCommand;
+use crate::config_types::Hooks;
+
+const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Exit status a hook uses to block the call it was invoked for.
+const BLOCK_EXIT_CODE: i32 = 2;
+
+/// JSON a hook may print on stdout.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+struct HookReply {
+    decision: Option<HookDecision>,
+    reason: Option<String>,
+    /// Replacement tool input (`pre_tool_use` only).
+    tool_input: Option<Value>,
+    /// Text appended to the tool output or prompt.
+    additional_context: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum HookDecision {
+    Allow,
+    Block,
+}
+
+#[derive(Debug, PartialEq)]
+enum HookResponse {
+    Continue(HookReply),
+    Block(String),
+}
+
+/// Result of running the hooks for an event that can be blocked.
+#[derive(Debug, PartialEq)]
+pub(crate) enum HookOutcome {
+    Continue,
+    /// Continue, with changes requested by the hooks.
+    Modified {
+        tool_input: Option<Value>,
+        additional_context: Vec<String>,
+    },
+    Block {
+        reason: String,
+    },
+}
+
+/// Runs the configured hooks for a session.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HookRunner {
+    hooks: Arc<Hooks>,
+    session_id: String,
+}
+
+impl HookRunner {
+    pub(crate) fn new(hooks: Hooks, session_id: String) -> Self {
+        Self {
+            hooks: Arc::new(hooks),
+            session_id,
+        }
+    }
+
+    pub(crate) fn has_tool_hooks(&self) -> bool {
+        !self.hooks.pre_tool_use.is_empty() || !self.hooks.post_tool_use.is_empty()
+    }
+
+    pub(crate) async fn pre_tool_use(
+        &self,
+        cwd: &Path,
+        turn_id: &str,
+        tool_name: &str,
+        call_id: &str,
+        tool_input: Value,
+    ) -> HookOutcome {
+        let hooks = matching(&self.hooks.pre_tool_use, tool_name);
+        let details = json!({
+            "tool_name": tool_name,
+            "call_id": call_id,
+            "tool_input": tool_input,
+        });
+        self.run_blocking("pre_tool_use", &hooks, cwd, turn_id, details)
+            .await
+    }
+
+    /// Returns context the hooks asked to append to the tool output.
+    pub(crate) async fn post_tool_use(
+        &self,
+        cwd: &Path,
+        turn_id: &str,
+        tool_name: &str,
+        call_id: &str,
+        tool_input: Value,
+        tool_output: Value,
+    ) -> Vec<String> {
+        let hooks = matching(&self.hooks.post_tool_use, tool_name);
+        let details = json!({
+            "tool_name": tool_name,
+            "call_id": call_id,
+            "tool_input": tool_input,
+            "tool_output": tool_output,
+        });
+        match self
+            .run_blocking("post_tool_use", &hooks, cwd, turn_id, details)
+            .await
+        {
+            HookOutcome::Modified {
+                additional_context, ..
+            } => additional_context,
+            HookOutcome::Continue | HookOutcome::Block { .. } => Vec::new(),
+        }
+    }
+
+    pub(crate) async fn user_prompt_submit(
+        &self,
+        cwd: &Path,
+        turn_id: &str,
+        prompt: &str,
+    ) -> HookOutcome {
+        let hooks: Vec<&HookCommand> = self.hooks.user_prompt_submit.iter().collect();
+        let details = json!({ "prompt": prompt });
+        self.run_blocking("user_prompt_submit", &hooks, cwd, turn_id, details)
+            .await
+    }
+
+    pub(crate) fn turn_complete(
+        &self,
+        cwd: &Path,
+        turn_id: &str,
+        last_assistant_message: Option<&str>,
+    ) {
+        let details = json!({ "last_assistant_message": last_assistant_message });
+        self.spawn_background(
+            "turn_complete",
+            self.hooks.turn_complete.clone(),
+            cwd,
+            turn_id,
+            details,
+        );
+    }
+
+    pub(crate) fn session_start(&self, cwd: &Path, model: &str) {
+        let details = json!({ "model": model });
+        self.spawn_background(
+            "session_start",
+            self.hooks.session_start.clone(),
+            cwd,
+            "",
+            details,
+        );
+    }
+
+    /// Runs `hooks` in order, feeding replaced tool input to later hooks.
+    /// Stops at the first hook that blocks.
+    async fn run_blocking(
+        &self,
+        event: &str,
+        hooks: &[&HookCommand],
+        cwd: &Path,
+        turn_id: &str,
+        mut details: Value,
+    ) -> HookOutcome {
+        let mut replaced_input: Option<Value> = None;
+        let mut additional_context: Vec<String> = Vec::new();
+        for hook in hooks {
+            let input = self.input(event, cwd, turn_id, &details);
+            let reply = match run_hook(hook, cwd, &input).await {
+                HookResponse::Block(reason) => return HookOutcome::Block { reason },
+                HookResponse::Continue(reply) => reply,
+            };
+            if let Some(tool_input) = reply.tool_input {
+                details["tool_input"] = tool_input.clone();
+                replaced_input = Some(tool_input);
+            }
+            additional_context.extend(reply.additional_context);
+        }
+        if replaced_input.is_none() && additional_context.is_empty() {
+            HookOutcome::Continue
+        } else {
+            HookOutcome::Modified {
+                tool_input: replaced_input,
+                additional_context,
+            }
+        }
+    }
+
+    fn spawn_background(
+        &self,
+        event: &'static str,
+        hooks: Vec<HookCommand>,
+        cwd: &Path,
+        turn_id: &str,
+        details: Value,
+    ) {
+        if hooks.is_empty() {
+            return;
+        }
+        let input = self.input(event, cwd, turn_id, &details);
+        let cwd = cwd.to_path_buf();
+        tokio::spawn(async move {
+            for hook in &hooks {
+                run_hook(hook, &cwd, &input).await;
+            }
+        });
+    }
+
+    fn input(&self, event: &str, cwd: &Path, turn_id: &str, details: &Value) -> Value {
+        let mut input = json!({
+            "hook_event": event,
+            "session_id": self.session_id,
+            "turn_id": turn_id,
+            "cwd": cwd,
+        });
+        if let (Some(input), Some(details)) = (input.as_object_mut(), details.as_object()) {
+            input.extend(details.clone());
+        }
+        input
+    }
+}
+
+fn matching<'a>(hooks: &'a [HookCommand], tool_name: &str) -> Vec<&'a HookCommand> {
+    hooks
+        .iter()
+        .filter(|hook| match &hook.matcher {
+            None => true,
+            Some(matcher) => matcher
+                .split('|')
+                .map(str::trim)
+                .any(|name| name == "*" || name == tool_name),
+        })
+        .collect()
+}
+
+async fn run_hook(hook: &HookCommand, cwd: &Path, input: &Value) -> HookResponse {
+    let continue_default = HookResponse::Continue(HookReply::default());
+    let Some((program, args)) = hook.command.split_first() else {
+        return continue_default;
+    };
+
+    let mut child = match Command::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            warn!("failed to spawn hook `{program}`: {e}");
+            return continue_default;
+        }
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let payload = input.to_string();
+        // A hook that does not read its input closes the pipe early; that is fine.
+        let _ = stdin.write_all(payload.as_bytes()).await;
+    }
+
+    let timeout = hook
+        .timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_HOOK_TIMEOUT);
+    let output = match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => {
+            warn!("hook `{program}` failed: {e}");
+            return continue_default;
+        }
+        Err(_) => {
+            warn!("hook `{program}` timed out after {timeout:?}");
+            return continue_default;
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match output.status.code() {
+        Some(0) => {
+            let reply = if stdout.trim().is_empty() {
+                HookReply::default()
+            } else {
+                match serde_json::from_str::<HookReply>(stdout.trim()) {
+                    Ok(reply) => reply,
+                    Err(e) => {
+                        warn!("ignoring invalid reply from hook `{program}`: {e}");
+                        HookReply::default()
+                    }
+                }
+            };
+            match reply.decision {
+                Some(HookDecision::Block) => HookResponse::Block(
+                    reply
+                        .reason
+                        .unwrap_or_else(|| format!("blocked by hook `{program}`")),
+                ),
+                Some(HookDecision::Allow) | None => HookResponse::Continue(reply),
+            }
+        }
+        Some(BLOCK_EXIT_CODE) => {
+            let reason = stderr.trim();
+            HookResponse::Block(if reason.is_empty() {
+                format!("blocked by hook `{program}`")
+            } else {
+                reason.to_string()
+            })
+        }
+        _ => {
+            warn!(
+                "hook `{program}` exited with {}: {}",
+                output.status,
+                stderr.trim()
+            );
+            continue_default
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn sh(script: &str) -> HookCommand {
+        HookCommand {
+            command: vec!["/bin/sh".to_string(), "-c".to_string(), script.to_string()],
+            matcher: None,
+            timeout_ms: None,
+        }
+    }
+
+    fn runner(hooks: Hooks) -> HookRunner {
+        HookRunner::new(hooks, "session-1".to_string())
+    }
+
+    #[tokio::test]
+    async fn exit_code_two_blocks_with_stderr_reason() {
+        let cwd = TempDir::new().unwrap();
+        let runner = runner(Hooks {
+            pre_tool_use: vec![sh("echo 'generated file' >&2; exit 2")],
+            ..Default::default()
+        });
+
+        let outcome = runner
+            .pre_tool_use(cwd.path(), "turn-1", "apply_patch", "call-1", json!({}))
+            .await;
+
+        assert_eq!(
+            outcome,
+            HookOutcome::Block {
+                reason: "generated file".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn replies_replace_input_for_later_hooks_and_collect_context() {
+        let cwd = TempDir::new().unwrap();
+        let runner = runner(Hooks {
+            pre_tool_use: vec![
+                sh(r#"echo '{"tool_input": {"command": ["ls", "-a"]}}'"#),
+                // Sees the replaced input on stdin.
+                sh(r#"grep -q '"-a"' && echo '{"additional_context": "listed hidden files"}'"#),
+            ],
+            ..Default::default()
+        });
+
+        let outcome = runner
+            .pre_tool_use(
+                cwd.path(),
+                "turn-1",
+                "shell",
+                "call-1",
+                json!({"command": ["ls"]}),
+            )
+            .await;
+
+        assert_eq!(
+            outcome,
+            HookOutcome::Modified {
+                tool_input: Some(json!({"command": ["ls", "-a"]})),
+                additional_context: vec!["listed hidden files".to_string()],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn matcher_limits_hooks_to_named_tools_and_failures_are_ignored() {
+        let cwd = TempDir::new().unwrap();
+        let mut formatter = sh(r#"echo '{"additional_context": "formatted"}'"#);
+        formatter.matcher = Some("apply_patch | write_file".to_string());
+        let runner = runner(Hooks {
+            post_tool_use: vec![sh("exit 1"), formatter],
+            ..Default::default()
+        });
+
+        let for_patch = runner
+            .post_tool_use(
+                cwd.path(),
+                "turn-1",
+                "apply_patch",
+                "call-1",
+                json!("*** Begin Patch"),
+                json!({"content": "Done!"}),
+            )
+            .await;
+        let for_shell = runner
+            .post_tool_use(
+                cwd.path(),
+                "turn-1",
+                "shell",
+                "call-2",
+                json!({"command": ["ls"]}),
+                json!({"content": ""}),
+            )
+            .await;
+
+        assert_eq!(for_patch, vec!["formatted".to_string()]);
+        assert_eq!(for_shell, Vec::<String>::new());
+    }
+}
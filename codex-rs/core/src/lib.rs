@@ -5,7 +5,9 @@
 // the TUI or the tracing stack).
 #![deny(clippy::print_stdout, clippy::print_stderr)]
 
+mod anthropic_messages;
 mod apply_patch;
+pub mod approval_rules;
 pub mod auth;
 pub mod bash;
 mod chat_completions;
@@ -28,10 +30,12 @@ pub mod error;
 pub mod exec;
 mod exec_command;
 pub mod exec_env;
+pub mod exec_policy;
 pub mod executor;
 pub mod features;
 mod flags;
 pub mod git_info;
+mod hooks;
 pub mod landlock;
 pub mod mcp;
 mod mcp_connection_manager;
@@ -39,6 +43,7 @@ mod mcp_tool_call;
 mod message_history;
 mod model_provider_info;
 pub mod parse_command;
+mod token_estimate;
 mod truncate;
 mod unified_exec;
 mod user_instructions;
@@ -50,6 +55,7 @@ pub use model_provider_info::create_oss_provider_with_base_url;
 mod conversation_manager;
 mod event_mapping;
 pub mod review_format;
+mod review_target;
 pub use codex_protocol::protocol::InitialHistory;
 pub use conversation_manager::ConversationManager;
 pub use conversation_manager::NewConversation;
@@ -58,8 +64,11 @@ pub use auth::AuthManager;
 pub use auth::CodexAuth;
 pub mod default_client;
 pub mod model_family;
+mod ollama_chat;
+pub use ollama_chat::OllamaModelCapabilities;
 mod openai_model_info;
 mod openai_tools;
+pub mod pricing;
 pub mod project_doc;
 mod rollout;
 pub(crate) mod safety;
@@ -69,6 +78,7 @@ pub mod spawn;
 pub mod terminal;
 mod tools;
 pub mod turn_diff_tracker;
+pub mod worktree;
 pub use rollout::ARCHIVED_SESSIONS_SUBDIR;
 pub use rollout::INTERACTIVE_SESSION_SOURCES;
 pub use rollout::RolloutRecorder;
@@ -78,6 +88,13 @@ pub use rollout::find_conversation_path_by_id_str;
 pub use rollout::list::ConversationItem;
 pub use rollout::list::ConversationsPage;
 pub use rollout::list::Cursor;
+pub use rollout::search::SearchHit;
+pub use rollout::search::SearchHitKind;
+pub use rollout::search::SessionSearchQuery;
+pub use rollout::search::SessionSearchResult;
+pub use rollout::usage::UsageGroupBy;
+pub use rollout::usage::UsageSummaryRow;
+pub use rollout::usage::summarize_usage;
 mod function_tool;
 mod state;
 mod tasks;
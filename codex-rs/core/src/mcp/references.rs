@@ -0,0 +1,228 @@
+//! Resolution of MCP resource and prompt references in user input.
+//!
+//! Clients may submit [`InputItem::McpResource`] and [`InputItem::McpPrompt`]
+//! items. Before a turn starts, these are replaced with the content fetched
+//! from the corresponding server so the model only ever sees text and images.
+//! Resolution happens off the submission loop, and each reference is bounded
+//! by [`REFERENCE_TIMEOUT`] so a stalled server fails the prompt quickly
+//! instead of leaving it pending for the full tool timeout.
+
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use mcp_types::BlobResourceContents;
+use mcp_types::ContentBlock;
+use mcp_types::EmbeddedResourceResource;
+use mcp_types::GetPromptResult;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use mcp_types::TextResourceContents;
+
+use crate::mcp_connection_manager::McpConnectionManager;
+use crate::protocol::InputItem;
+
+/// How long a single resource read or prompt render may take.
+const REFERENCE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Returns `true` if any item must be fetched from an MCP server.
+pub(crate) fn has_mcp_references(items: &[InputItem]) -> bool {
+    items.iter().any(|item| {
+        matches!(
+            item,
+            InputItem::McpResource { .. } | InputItem::McpPrompt { .. }
+        )
+    })
+}
+
+/// Replaces every MCP resource and prompt reference in `items` with the
+/// content returned by its server. Fails on the first reference that cannot
+/// be resolved.
+pub(crate) async fn resolve_mcp_references(
+    manager: &McpConnectionManager,
+    items: Vec<InputItem>,
+) -> Result<Vec<InputItem>> {
+    let mut resolved = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            InputItem::McpResource { server, uri } => {
+                let what = format!("resource read for `{server}:{uri}`");
+                let result = with_timeout(&what, manager.read_resource(&server, &uri)).await?;
+                resolved.extend(resource_to_input_items(&server, result));
+            }
+            InputItem::McpPrompt {
+                server,
+                name,
+                arguments,
+            } => {
+                let what = format!("prompt request for `{server}/{name}`");
+                let result =
+                    with_timeout(&what, manager.get_prompt(&server, &name, arguments)).await?;
+                resolved.extend(prompt_to_input_items(&server, result));
+            }
+            other => resolved.push(other),
+        }
+    }
+    Ok(resolved)
+}
+
+async fn with_timeout<T>(what: &str, fut: impl Future<Output = Result<T>>) -> Result<T> {
+    tokio::time::timeout(REFERENCE_TIMEOUT, fut)
+        .await
+        .with_context(|| format!("{what} timed out after {REFERENCE_TIMEOUT:?}"))?
+}
+
+fn resource_to_input_items(server: &str, result: ReadResourceResult) -> Vec<InputItem> {
+    result
+        .contents
+        .into_iter()
+        .map(|contents| match contents {
+            ReadResourceResultContents::TextResourceContents(text) => {
+                text_resource_item(server, text)
+            }
+            ReadResourceResultContents::BlobResourceContents(blob) => {
+                blob_resource_item(server, blob)
+            }
+        })
+        .collect()
+}
+
+/// Every message returned by `prompts/get` is forwarded as user input,
+/// regardless of its role.
+fn prompt_to_input_items(server: &str, result: GetPromptResult) -> Vec<InputItem> {
+    result
+        .messages
+        .into_iter()
+        .filter_map(|message| match message.content {
+            ContentBlock::TextContent(content) => Some(InputItem::Text { text: content.text }),
+            ContentBlock::ImageContent(image) => Some(InputItem::Image {
+                image_url: format!("data:{};base64,{}", image.mime_type, image.data),
+            }),
+            ContentBlock::EmbeddedResource(embedded) => Some(match embedded.resource {
+                EmbeddedResourceResource::TextResourceContents(text) => {
+                    text_resource_item(server, text)
+                }
+                EmbeddedResourceResource::BlobResourceContents(blob) => {
+                    blob_resource_item(server, blob)
+                }
+            }),
+            ContentBlock::ResourceLink(link) => Some(InputItem::Text {
+                text: format!(
+                    "Resource `{}` ({}) on MCP server `{server}`",
+                    link.name, link.uri
+                ),
+            }),
+            ContentBlock::AudioContent(_) => None,
+        })
+        .collect()
+}
+
+fn text_resource_item(server: &str, contents: TextResourceContents) -> InputItem {
+    let TextResourceContents { text, uri, .. } = contents;
+    InputItem::Text {
+        text: format!("<mcp_resource server=\"{server}\" uri=\"{uri}\">\n{text}\n</mcp_resource>"),
+    }
+}
+
+/// Images are attached as-is; other binary content cannot be shown to the
+/// model, so only a placeholder is included.
+fn blob_resource_item(server: &str, contents: BlobResourceContents) -> InputItem {
+    let BlobResourceContents {
+        blob,
+        mime_type,
+        uri,
+    } = contents;
+    match mime_type {
+        Some(mime_type) if mime_type.starts_with("image/") => InputItem::Image {
+            image_url: format!("data:{mime_type};base64,{blob}"),
+        },
+        mime_type => {
+            let mime_type = mime_type.as_deref().unwrap_or("application/octet-stream");
+            InputItem::Text {
+                text: format!(
+                    "<mcp_resource server=\"{server}\" uri=\"{uri}\">\n[binary content of type {mime_type} omitted]\n</mcp_resource>"
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::PromptMessage;
+    use mcp_types::Role;
+    use mcp_types::TextContent;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn resource_contents_become_text_and_images() {
+        let result = ReadResourceResult {
+            contents: vec![
+                ReadResourceResultContents::TextResourceContents(TextResourceContents {
+                    mime_type: Some("text/markdown".to_string()),
+                    text: "# Schema".to_string(),
+                    uri: "docs://schema".to_string(),
+                }),
+                ReadResourceResultContents::BlobResourceContents(BlobResourceContents {
+                    blob: "aGVsbG8=".to_string(),
+                    mime_type: Some("image/png".to_string()),
+                    uri: "docs://diagram".to_string(),
+                }),
+                ReadResourceResultContents::BlobResourceContents(BlobResourceContents {
+                    blob: "aGVsbG8=".to_string(),
+                    mime_type: None,
+                    uri: "docs://archive".to_string(),
+                }),
+            ],
+        };
+
+        assert_eq!(
+            resource_to_input_items("docs", result),
+            vec![
+                InputItem::Text {
+                    text: "<mcp_resource server=\"docs\" uri=\"docs://schema\">\n# Schema\n</mcp_resource>"
+                        .to_string(),
+                },
+                InputItem::Image {
+                    image_url: "data:image/png;base64,aGVsbG8=".to_string(),
+                },
+                InputItem::Text {
+                    text: "<mcp_resource server=\"docs\" uri=\"docs://archive\">\n[binary content of type application/octet-stream omitted]\n</mcp_resource>"
+                        .to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn prompt_messages_become_input_items() {
+        let text_message = |role, text: &str| PromptMessage {
+            content: ContentBlock::TextContent(TextContent {
+                annotations: None,
+                text: text.to_string(),
+                r#type: "text".to_string(),
+            }),
+            role,
+        };
+        let result = GetPromptResult {
+            description: None,
+            messages: vec![
+                text_message(Role::User, "Review this schema."),
+                text_message(Role::Assistant, "Which part?"),
+            ],
+        };
+
+        assert_eq!(
+            prompt_to_input_items("docs", result),
+            vec![
+                InputItem::Text {
+                    text: "Review this schema.".to_string(),
+                },
+                InputItem::Text {
+                    text: "Which part?".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -4,7 +4,8 @@
 //! configured server (keyed by the *server name*). It offers convenience
 //! helpers to query the available tools across *all* servers and returns them
 //! in a single aggregated map using the fully-qualified tool name
-//! `"<server><MCP_TOOL_NAME_DELIMITER><tool>"` as the key.
+//! `"<server><MCP_TOOL_NAME_DELIMITER><tool>"` as the key. Resources and
+//! prompts are queried on demand and keyed by server name.
 
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -20,7 +21,16 @@ use codex_mcp_client::McpClient;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
 use codex_rmcp_client::RmcpClient;
 use mcp_types::ClientCapabilities;
+use mcp_types::GetPromptResult;
 use mcp_types::Implementation;
+use mcp_types::ListPromptsRequestParams;
+use mcp_types::ListResourceTemplatesRequestParams;
+use mcp_types::ListResourcesRequestParams;
+use mcp_types::Prompt;
+use mcp_types::ReadResourceResult;
+use mcp_types::Resource;
+use mcp_types::ResourceTemplate;
+use mcp_types::ServerCapabilities;
 use mcp_types::Tool;
 
 use serde_json::json;
@@ -91,6 +101,8 @@ struct ToolInfo {
 
 struct ManagedClient {
     client: McpClientAdapter,
+    /// Capabilities the server advertised during initialization.
+    capabilities: ServerCapabilities,
     startup_timeout: Duration,
     tool_timeout: Option<Duration>,
 }
@@ -109,15 +121,15 @@ impl McpClientAdapter {
         env: Option<HashMap<String, String>>,
         params: mcp_types::InitializeRequestParams,
         startup_timeout: Duration,
-    ) -> Result<Self> {
+    ) -> Result<(Self, ServerCapabilities)> {
         if use_rmcp_client {
             let client = Arc::new(RmcpClient::new_stdio_client(program, args, env).await?);
-            client.initialize(params, Some(startup_timeout)).await?;
-            Ok(McpClientAdapter::Rmcp(client))
+            let result = client.initialize(params, Some(startup_timeout)).await?;
+            Ok((McpClientAdapter::Rmcp(client), result.capabilities))
         } else {
             let client = Arc::new(McpClient::new_stdio_client(program, args, env).await?);
-            client.initialize(params, Some(startup_timeout)).await?;
-            Ok(McpClientAdapter::Legacy(client))
+            let result = client.initialize(params, Some(startup_timeout)).await?;
+            Ok((McpClientAdapter::Legacy(client), result.capabilities))
         }
     }
 
@@ -128,13 +140,13 @@ impl McpClientAdapter {
         params: mcp_types::InitializeRequestParams,
         startup_timeout: Duration,
         store_mode: OAuthCredentialsStoreMode,
-    ) -> Result<Self> {
+    ) -> Result<(Self, ServerCapabilities)> {
         let client = Arc::new(
             RmcpClient::new_streamable_http_client(&server_name, &url, bearer_token, store_mode)
                 .await?,
         );
-        client.initialize(params, Some(startup_timeout)).await?;
-        Ok(McpClientAdapter::Rmcp(client))
+        let result = client.initialize(params, Some(startup_timeout)).await?;
+        Ok((McpClientAdapter::Rmcp(client), result.capabilities))
     }
 
     async fn list_tools(
@@ -159,6 +171,136 @@ impl McpClientAdapter {
             McpClientAdapter::Rmcp(client) => client.call_tool(name, arguments, timeout).await,
         }
     }
+
+    async fn list_resources(
+        &self,
+        params: Option<ListResourcesRequestParams>,
+        timeout: Option<Duration>,
+    ) -> Result<mcp_types::ListResourcesResult> {
+        match self {
+            McpClientAdapter::Legacy(client) => client.list_resources(params, timeout).await,
+            McpClientAdapter::Rmcp(client) => client.list_resources(params, timeout).await,
+        }
+    }
+
+    async fn read_resource(
+        &self,
+        uri: String,
+        timeout: Option<Duration>,
+    ) -> Result<ReadResourceResult> {
+        match self {
+            McpClientAdapter::Legacy(client) => client.read_resource(uri, timeout).await,
+            McpClientAdapter::Rmcp(client) => client.read_resource(uri, timeout).await,
+        }
+    }
+
+    async fn list_resource_templates(
+        &self,
+        params: Option<ListResourceTemplatesRequestParams>,
+        timeout: Option<Duration>,
+    ) -> Result<mcp_types::ListResourceTemplatesResult> {
+        match self {
+            McpClientAdapter::Legacy(client) => {
+                client.list_resource_templates(params, timeout).await
+            }
+            McpClientAdapter::Rmcp(client) => client.list_resource_templates(params, timeout).await,
+        }
+    }
+
+    async fn list_prompts(
+        &self,
+        params: Option<ListPromptsRequestParams>,
+        timeout: Option<Duration>,
+    ) -> Result<mcp_types::ListPromptsResult> {
+        match self {
+            McpClientAdapter::Legacy(client) => client.list_prompts(params, timeout).await,
+            McpClientAdapter::Rmcp(client) => client.list_prompts(params, timeout).await,
+        }
+    }
+
+    async fn get_prompt(
+        &self,
+        name: String,
+        arguments: Option<serde_json::Value>,
+        timeout: Option<Duration>,
+    ) -> Result<GetPromptResult> {
+        match self {
+            McpClientAdapter::Legacy(client) => client.get_prompt(name, arguments, timeout).await,
+            McpClientAdapter::Rmcp(client) => client.get_prompt(name, arguments, timeout).await,
+        }
+    }
+
+    /// Lists every resource exposed by the server, following pagination.
+    async fn list_all_resources(self, timeout: Option<Duration>) -> Result<Vec<Resource>> {
+        let mut resources = Vec::new();
+        let mut cursor = None;
+        let mut seen_cursors = HashSet::new();
+        loop {
+            let params = cursor.map(|cursor| ListResourcesRequestParams {
+                cursor: Some(cursor),
+            });
+            let result = self.list_resources(params, timeout).await?;
+            resources.extend(result.resources);
+            cursor = next_page_cursor(&mut seen_cursors, result.next_cursor);
+            if cursor.is_none() {
+                return Ok(resources);
+            }
+        }
+    }
+
+    /// Lists every resource template exposed by the server, following
+    /// pagination.
+    async fn list_all_resource_templates(
+        self,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<ResourceTemplate>> {
+        let mut templates = Vec::new();
+        let mut cursor = None;
+        let mut seen_cursors = HashSet::new();
+        loop {
+            let params = cursor.map(|cursor| ListResourceTemplatesRequestParams {
+                cursor: Some(cursor),
+            });
+            let result = self.list_resource_templates(params, timeout).await?;
+            templates.extend(result.resource_templates);
+            cursor = next_page_cursor(&mut seen_cursors, result.next_cursor);
+            if cursor.is_none() {
+                return Ok(templates);
+            }
+        }
+    }
+
+    /// Lists every prompt exposed by the server, following pagination.
+    async fn list_all_prompts(self, timeout: Option<Duration>) -> Result<Vec<Prompt>> {
+        let mut prompts = Vec::new();
+        let mut cursor = None;
+        let mut seen_cursors = HashSet::new();
+        loop {
+            let params = cursor.map(|cursor| ListPromptsRequestParams {
+                cursor: Some(cursor),
+            });
+            let result = self.list_prompts(params, timeout).await?;
+            prompts.extend(result.prompts);
+            cursor = next_page_cursor(&mut seen_cursors, result.next_cursor);
+            if cursor.is_none() {
+                return Ok(prompts);
+            }
+        }
+    }
+}
+
+/// Returns the cursor for the next page, or `None` when pagination is done.
+///
+/// A server that hands back a cursor it already returned would otherwise keep
+/// the caller paging forever, so a repeated cursor also ends pagination.
+fn next_page_cursor(seen: &mut HashSet<String>, next: Option<String>) -> Option<String> {
+    let next = next?;
+    if seen.insert(next.clone()) {
+        Some(next)
+    } else {
+        warn!("MCP server repeated pagination cursor `{next}`; stopping");
+        None
+    }
 }
 
 /// A thin wrapper around a set of running [`McpClient`] instances.
@@ -271,7 +413,7 @@ impl McpConnectionManager {
                         .await
                     }
                 }
-                .map(|c| (c, startup_timeout));
+                .map(|(c, capabilities)| (c, capabilities, startup_timeout));
 
                 ((server_name, tool_timeout), client)
             });
@@ -289,11 +431,12 @@ impl McpConnectionManager {
             };
 
             match client_res {
-                Ok((client, startup_timeout)) => {
+                Ok((client, capabilities, startup_timeout)) => {
                     clients.insert(
                         server_name,
                         ManagedClient {
                             client,
+                            capabilities,
                             startup_timeout,
                             tool_timeout: Some(tool_timeout),
                         },
@@ -352,6 +495,115 @@ impl McpConnectionManager {
             .get(tool_name)
             .map(|tool| (tool.server_name.clone(), tool.tool_name.clone()))
     }
+
+    /// Returns the resources exposed by every server that advertises the
+    /// `resources` capability, keyed by server name.
+    pub async fn list_all_resources(&self) -> HashMap<String, Vec<Resource>> {
+        self.query_servers(
+            "resources",
+            |capabilities| capabilities.resources.is_some(),
+            McpClientAdapter::list_all_resources,
+        )
+        .await
+    }
+
+    /// Returns the resource templates exposed by every server that advertises
+    /// the `resources` capability, keyed by server name.
+    pub async fn list_all_resource_templates(&self) -> HashMap<String, Vec<ResourceTemplate>> {
+        self.query_servers(
+            "resource templates",
+            |capabilities| capabilities.resources.is_some(),
+            McpClientAdapter::list_all_resource_templates,
+        )
+        .await
+    }
+
+    /// Returns the prompts exposed by every server that advertises the
+    /// `prompts` capability, keyed by server name.
+    pub async fn list_all_prompts(&self) -> HashMap<String, Vec<Prompt>> {
+        self.query_servers(
+            "prompts",
+            |capabilities| capabilities.prompts.is_some(),
+            McpClientAdapter::list_all_prompts,
+        )
+        .await
+    }
+
+    /// Read the resource identified by `uri` from `server`.
+    pub async fn read_resource(&self, server: &str, uri: &str) -> Result<ReadResourceResult> {
+        let managed = self
+            .clients
+            .get(server)
+            .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))?;
+        let client = managed.client.clone();
+        let timeout = managed.tool_timeout;
+
+        client
+            .read_resource(uri.to_string(), timeout)
+            .await
+            .with_context(|| format!("resource read failed for `{server}:{uri}`"))
+    }
+
+    /// Render the prompt `name` exposed by `server` with the given arguments.
+    pub async fn get_prompt(
+        &self,
+        server: &str,
+        name: &str,
+        arguments: Option<HashMap<String, String>>,
+    ) -> Result<GetPromptResult> {
+        let managed = self
+            .clients
+            .get(server)
+            .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))?;
+        let client = managed.client.clone();
+        let timeout = managed.tool_timeout;
+        let arguments = arguments.map(|arguments| json!(arguments));
+
+        client
+            .get_prompt(name.to_string(), arguments, timeout)
+            .await
+            .with_context(|| format!("prompt request failed for `{server}/{name}`"))
+    }
+
+    /// Run `list` concurrently against every server whose capabilities pass
+    /// `supported`. Servers that fail are logged and omitted from the result.
+    async fn query_servers<T, F, Fut>(
+        &self,
+        what: &'static str,
+        supported: fn(&ServerCapabilities) -> bool,
+        list: F,
+    ) -> HashMap<String, Vec<T>>
+    where
+        T: Send + 'static,
+        F: Fn(McpClientAdapter, Option<Duration>) -> Fut,
+        Fut: Future<Output = Result<Vec<T>>> + Send + 'static,
+    {
+        let mut join_set = JoinSet::new();
+        for (server_name, managed_client) in &self.clients {
+            if !supported(&managed_client.capabilities) {
+                continue;
+            }
+            let server_name = server_name.clone();
+            let fut = list(managed_client.client.clone(), managed_client.tool_timeout);
+            join_set.spawn(async move { (server_name, fut.await) });
+        }
+
+        let mut aggregated = HashMap::with_capacity(join_set.len());
+        while let Some(join_res) = join_set.join_next().await {
+            match join_res {
+                Ok((server_name, Ok(items))) => {
+                    aggregated.insert(server_name, items);
+                }
+                Ok((server_name, Err(e))) => {
+                    warn!("Failed to list {what} for MCP server '{server_name}': {e:#}");
+                }
+                Err(e) => {
+                    warn!("Task panic when listing {what} for MCP server: {e:#}");
+                }
+            }
+        }
+        aggregated
+    }
 }
 
 fn resolve_bearer_token(
@@ -466,6 +718,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn next_page_cursor_stops_on_repeated_cursor() {
+        let mut seen = HashSet::new();
+        assert_eq!(
+            next_page_cursor(&mut seen, Some("a".to_string())),
+            Some("a".to_string())
+        );
+        assert_eq!(
+            next_page_cursor(&mut seen, Some("b".to_string())),
+            Some("b".to_string())
+        );
+        assert_eq!(next_page_cursor(&mut seen, Some("a".to_string())), None);
+        assert_eq!(next_page_cursor(&mut seen, None), None);
+    }
+
     #[test]
     fn test_qualify_tools_short_non_duplicated_names() {
         let tools = vec![
@@ -6,6 +6,9 @@
 //!      key. These override or extend the defaults at runtime.
 
 use crate::CodexAuth;
+use crate::anthropic_messages::ANTHROPIC_API_VERSION;
+use crate::ollama_chat::DEFAULT_OLLAMA_HOST_ROOT;
+use crate::ollama_chat::ollama_host_root;
 use codex_app_server_protocol::AuthMode;
 use serde::Deserialize;
 use serde::Serialize;
@@ -37,6 +40,12 @@ pub enum WireApi {
     /// Regular Chat Completions compatible with `/v1/chat/completions`.
     #[default]
     Chat,
+
+    /// The Anthropic Messages API exposed at `/v1/messages`.
+    Anthropic,
+
+    /// Ollama's native chat API exposed at `/api/chat`.
+    Ollama,
 }
 
 /// Serializable representation of a provider definition.
@@ -92,7 +101,8 @@ impl ModelProviderInfo {
     /// Construct a `POST` RequestBuilder for the given URL using the provided
     /// reqwest Client applying:
     ///   • provider-specific headers (static + env based)
-    ///   • Bearer auth header when an API key is available.
+    ///   • Bearer auth header when an API key is available (`x-api-key` plus
+    ///     `anthropic-version` for the Anthropic Messages API).
     ///   • Auth token for OAuth.
     ///
     /// If the provider declares an `env_key` but the variable is missing/empty, returns an [`Err`] identical to the
@@ -101,6 +111,19 @@ impl ModelProviderInfo {
         &'a self,
         client: &'a reqwest::Client,
         auth: &Option<CodexAuth>,
+    ) -> crate::error::Result<reqwest::RequestBuilder> {
+        self.create_request_builder_with_url(client, auth, |auth| self.get_full_url(auth))
+            .await
+    }
+
+    /// Same as [`Self::create_request_builder`], but posts to the URL `url`
+    /// returns for the effective auth, for provider endpoints other than the
+    /// model call itself.
+    pub(crate) async fn create_request_builder_with_url(
+        &self,
+        client: &reqwest::Client,
+        auth: &Option<CodexAuth>,
+        url: impl FnOnce(&Option<CodexAuth>) -> String,
     ) -> crate::error::Result<reqwest::RequestBuilder> {
         let effective_auth = match self.api_key() {
             Ok(Some(key)) => Some(CodexAuth::from_api_key(&key)),
@@ -114,17 +137,31 @@ impl ModelProviderInfo {
             }
         };
 
-        let url = self.get_full_url(&effective_auth);
-
-        let mut builder = client.post(url);
+        let mut builder = client.post(url(&effective_auth));
 
         if let Some(auth) = effective_auth.as_ref() {
-            builder = builder.bearer_auth(auth.get_token().await?);
+            let token = auth.get_token().await?;
+            builder = match self.wire_api {
+                WireApi::Anthropic => builder.header("x-api-key", token),
+                WireApi::Responses | WireApi::Chat | WireApi::Ollama => builder.bearer_auth(token),
+            };
+        }
+
+        if self.wire_api == WireApi::Anthropic && !self.has_http_header("anthropic-version") {
+            builder = builder.header("anthropic-version", ANTHROPIC_API_VERSION);
         }
 
         Ok(self.apply_http_headers(builder))
     }
 
+    fn has_http_header(&self, name: &str) -> bool {
+        self.http_headers
+            .iter()
+            .flat_map(HashMap::keys)
+            .chain(self.env_http_headers.iter().flat_map(HashMap::keys))
+            .any(|key| key.eq_ignore_ascii_case(name))
+    }
+
     fn get_query_string(&self) -> String {
         self.query_params
             .as_ref()
@@ -139,7 +176,11 @@ impl ModelProviderInfo {
     }
 
     pub(crate) fn get_full_url(&self, auth: &Option<CodexAuth>) -> String {
-        let default_base_url = if matches!(
+        let default_base_url = if self.wire_api == WireApi::Anthropic {
+            "https://api.anthropic.com/v1"
+        } else if self.wire_api == WireApi::Ollama {
+            DEFAULT_OLLAMA_HOST_ROOT
+        } else if matches!(
             auth,
             Some(CodexAuth {
                 mode: AuthMode::ChatGPT,
@@ -159,6 +200,11 @@ impl ModelProviderInfo {
         match self.wire_api {
             WireApi::Responses => format!("{base_url}/responses{query_string}"),
             WireApi::Chat => format!("{base_url}/chat/completions{query_string}"),
+            WireApi::Anthropic => format!("{base_url}/messages{query_string}"),
+            WireApi::Ollama => format!(
+                "{}/api/chat{query_string}",
+                ollama_host_root(Some(&base_url))
+            ),
         }
     }
 
@@ -324,7 +370,13 @@ pub fn create_oss_provider() -> ModelProviderInfo {
         ),
     };
 
-    create_oss_provider_with_base_url(&codex_oss_base_url)
+    let mut provider = create_oss_provider_with_base_url(&codex_oss_base_url);
+    // Ollama's native API is opt-in: the OpenAI-compatible endpoint also works
+    // with other local servers that speak the same protocol.
+    if std::env::var("CODEX_OSS_WIRE_API").is_ok_and(|v| v.trim().eq_ignore_ascii_case("ollama")) {
+        provider.wire_api = WireApi::Ollama;
+    }
+    provider
 }
 
 pub fn create_oss_provider_with_base_url(base_url: &str) -> ModelProviderInfo {
@@ -447,6 +499,56 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
         assert_eq!(expected_provider, provider);
     }
 
+    #[test]
+    fn test_deserialize_anthropic_model_provider_toml() {
+        let anthropic_provider_toml = r#"
+name = "Anthropic"
+env_key = "ANTHROPIC_API_KEY"
+wire_api = "anthropic"
+        "#;
+        let expected_provider = ModelProviderInfo {
+            name: "Anthropic".into(),
+            base_url: None,
+            env_key: Some("ANTHROPIC_API_KEY".into()),
+            env_key_instructions: None,
+            wire_api: WireApi::Anthropic,
+            query_params: None,
+            http_headers: None,
+            env_http_headers: None,
+            request_max_retries: None,
+            stream_max_retries: None,
+            stream_idle_timeout_ms: None,
+            requires_openai_auth: false,
+        };
+
+        let provider: ModelProviderInfo = toml::from_str(anthropic_provider_toml).unwrap();
+        assert_eq!(expected_provider, provider);
+        assert_eq!(
+            "https://api.anthropic.com/v1/messages",
+            provider.get_full_url(&None)
+        );
+    }
+
+    #[test]
+    fn test_deserialize_ollama_model_provider_toml() {
+        let ollama_provider_toml = r#"
+name = "Ollama"
+base_url = "http://gpu-box:11434/v1"
+wire_api = "ollama"
+        "#;
+
+        let provider: ModelProviderInfo = toml::from_str(ollama_provider_toml).unwrap();
+        assert_eq!(WireApi::Ollama, provider.wire_api);
+        assert_eq!(
+            "http://gpu-box:11434/api/chat",
+            provider.get_full_url(&None)
+        );
+        assert_eq!(
+            "http://localhost:11434/v1/chat/completions",
+            create_oss_provider_with_base_url("http://localhost:11434/v1").get_full_url(&None)
+        );
+    }
+
     #[test]
     fn detects_azure_responses_base_urls() {
         fn provider_for(base_url: &str) -> ModelProviderInfo {
@@ -0,0 +1,722 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::ModelProviderInfo;
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::error::RetryLimitReachedError;
+use crate::error::UnexpectedResponseError;
+use crate::model_family::ModelFamily;
+use crate::openai_tools::create_tools_json_for_chat_completions_api;
+use crate::protocol::TokenUsage;
+use crate::util::backoff;
+use bytes::Bytes;
+use bytes::BytesMut;
+use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ReasoningItemContent;
+use codex_protocol::models::ResponseItem;
+use futures::Stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tracing::debug;
+use tracing::trace;
+use tracing::warn;
+
+/// Default Ollama host used when a provider with `wire_api = "ollama"` does
+/// not set `base_url`.
+pub(crate) const DEFAULT_OLLAMA_HOST_ROOT: &str = "http://localhost:11434";
+
+/// What a local model supports, as reported by Ollama's `/api/show`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OllamaModelCapabilities {
+    /// Whether the model accepts `tools` in `/api/chat`.
+    pub tools: bool,
+    /// Whether the model can emit a separate `thinking` stream.
+    pub thinking: bool,
+    /// Whether the model accepts images.
+    pub vision: bool,
+    /// The trained context length (`<arch>.context_length` in `model_info`).
+    pub context_length: Option<u64>,
+}
+
+impl OllamaModelCapabilities {
+    /// Parses the body of a `POST /api/show` response. Servers that predate the
+    /// `capabilities` field are assumed to support tools so that requests are
+    /// not silently stripped of them.
+    pub fn from_show_response(value: &Value) -> Self {
+        let capabilities = value.get("capabilities").and_then(Value::as_array);
+        let has = |name: &str| {
+            capabilities.is_none_or(|caps| caps.iter().any(|c| c.as_str() == Some(name)))
+        };
+        let context_length = value
+            .get("model_info")
+            .and_then(Value::as_object)
+            .and_then(|info| {
+                info.iter()
+                    .find(|(key, _)| key.ends_with(".context_length"))
+                    .and_then(|(_, v)| v.as_u64())
+            });
+        Self {
+            tools: has("tools"),
+            thinking: capabilities.is_some() && has("thinking"),
+            vision: capabilities.is_some() && has("vision"),
+            context_length,
+        }
+    }
+}
+
+/// Converts a provider `base_url` into the native Ollama host root, dropping an
+/// OpenAI-compatible `/v1` suffix if present.
+pub(crate) fn ollama_host_root(base_url: Option<&str>) -> String {
+    let trimmed = base_url
+        .unwrap_or(DEFAULT_OLLAMA_HOST_ROOT)
+        .trim_end_matches('/');
+    trimmed
+        .strip_suffix("/v1")
+        .unwrap_or(trimmed)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Capabilities probed so far, keyed by host root and model name. Probing is
+/// cheap but adds a round trip, so a successful probe happens once per model
+/// per process.
+fn capabilities_cache() -> &'static Mutex<HashMap<(String, String), OllamaModelCapabilities>> {
+    static CACHE: OnceLock<Mutex<HashMap<(String, String), OllamaModelCapabilities>>> =
+        OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Returns the context length probed for `model`, if it has been probed.
+pub(crate) fn cached_context_length(provider: &ModelProviderInfo, model: &str) -> Option<u64> {
+    let key = (
+        ollama_host_root(provider.base_url.as_deref()),
+        model.to_string(),
+    );
+    capabilities_cache()
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(&key).and_then(|caps| caps.context_length))
+}
+
+async fn probe_capabilities(
+    client: &reqwest::Client,
+    provider: &ModelProviderInfo,
+    model: &str,
+) -> OllamaModelCapabilities {
+    let host_root = ollama_host_root(provider.base_url.as_deref());
+    let key = (host_root.clone(), model.to_string());
+    if let Some(caps) = capabilities_cache()
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(&key).cloned())
+    {
+        return caps;
+    }
+
+    let request = provider
+        .create_request_builder_with_url(client, &None, |_| format!("{host_root}/api/show"))
+        .await;
+    let response = match request {
+        Ok(request) => request.json(&json!({"model": model})).send().await,
+        Err(e) => {
+            warn!("failed to build /api/show request for {model}: {e}");
+            return OllamaModelCapabilities::from_show_response(&Value::Null);
+        }
+    };
+    // Failures fall back to the defaults for this request only, so that the
+    // probe is retried once the server is reachable again.
+    let body = match response {
+        Ok(resp) if resp.status().is_success() => match resp.json::<Value>().await {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("failed to parse /api/show response for {model}: {e}");
+                return OllamaModelCapabilities::from_show_response(&Value::Null);
+            }
+        },
+        Ok(resp) => {
+            warn!("/api/show for {model} returned HTTP {}", resp.status());
+            return OllamaModelCapabilities::from_show_response(&Value::Null);
+        }
+        Err(e) => {
+            warn!("failed to probe capabilities for {model}: {e}");
+            return OllamaModelCapabilities::from_show_response(&Value::Null);
+        }
+    };
+    let caps = OllamaModelCapabilities::from_show_response(&body);
+
+    if !caps.tools {
+        warn!("{model} does not support tool calling; requests will be sent without tools");
+    }
+    if let Ok(mut cache) = capabilities_cache().lock() {
+        cache.insert(key, caps.clone());
+    }
+    caps
+}
+
+/// Picks `options.num_ctx`. Ollama otherwise falls back to a small default
+/// window and silently truncates the prompt, which drops the instructions and
+/// tool definitions Codex sends first.
+fn num_ctx(caps: &OllamaModelCapabilities, model_context_window: Option<u64>) -> Option<u64> {
+    match (model_context_window, caps.context_length) {
+        (Some(configured), Some(trained)) => Some(configured.min(trained)),
+        (configured, trained) => configured.or(trained),
+    }
+}
+
+/// Implementation for Ollama's native `/api/chat` endpoint.
+pub(crate) async fn stream_ollama_chat(
+    prompt: &Prompt,
+    model_family: &ModelFamily,
+    model_context_window: Option<u64>,
+    client: &reqwest::Client,
+    provider: &ModelProviderInfo,
+    otel_event_manager: &OtelEventManager,
+) -> Result<ResponseStream> {
+    let model = model_family.slug.as_str();
+    let caps = probe_capabilities(client, provider, model).await;
+
+    let mut messages = vec![json!({
+        "role": "system",
+        "content": prompt.get_full_instructions(model_family),
+    })];
+    messages.extend(build_messages(&prompt.get_formatted_input()));
+
+    let mut payload = json!({
+        "model": model,
+        "messages": messages,
+        "stream": true,
+    });
+    if let Some(obj) = payload.as_object_mut() {
+        let tools_json = create_tools_json_for_chat_completions_api(&prompt.tools)?;
+        if caps.tools && !tools_json.is_empty() {
+            obj.insert("tools".to_string(), json!(tools_json));
+        }
+        if let Some(num_ctx) = num_ctx(&caps, model_context_window) {
+            obj.insert("options".to_string(), json!({"num_ctx": num_ctx}));
+        }
+        if let Some(schema) = &prompt.output_schema {
+            obj.insert("format".to_string(), schema.clone());
+        }
+    }
+
+    debug!(
+        "POST to {}: {}",
+        provider.get_full_url(&None),
+        serde_json::to_string_pretty(&payload).unwrap_or_default()
+    );
+
+    let mut attempt = 0;
+    let max_retries = provider.request_max_retries();
+    loop {
+        attempt += 1;
+
+        let req_builder = provider.create_request_builder(client, &None).await?;
+
+        let res = otel_event_manager
+            .log_request(attempt, || req_builder.json(&payload).send())
+            .await;
+
+        match res {
+            Ok(resp) if resp.status().is_success() => {
+                let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+                let stream = resp.bytes_stream().map_err(CodexErr::Reqwest);
+                tokio::spawn(process_ollama_ndjson(
+                    stream,
+                    tx_event,
+                    provider.stream_idle_timeout(),
+                    otel_event_manager.clone(),
+                ));
+                return Ok(ResponseStream { rx_event });
+            }
+            Ok(res) => {
+                let status = res.status();
+                if !(status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()) {
+                    let body = (res.text().await).unwrap_or_default();
+                    return Err(CodexErr::UnexpectedStatus(UnexpectedResponseError {
+                        status,
+                        body,
+                        request_id: None,
+                    }));
+                }
+
+                if attempt > max_retries {
+                    return Err(CodexErr::RetryLimit(RetryLimitReachedError {
+                        status,
+                        request_id: None,
+                    }));
+                }
+
+                tokio::time::sleep(backoff(attempt)).await;
+            }
+            Err(e) => {
+                if attempt > max_retries {
+                    return Err(e.into());
+                }
+                tokio::time::sleep(backoff(attempt)).await;
+            }
+        }
+    }
+}
+
+/// Converts the conversation history into `/api/chat` messages. Tool calls are
+/// attached to the preceding assistant message and tool results are sent as
+/// `tool` messages naming the function they answer, since Ollama has no call
+/// ids.
+fn build_messages(input: &[ResponseItem]) -> Vec<Value> {
+    let mut messages: Vec<Value> = Vec::new();
+    let mut tool_names: HashMap<&str, &str> = HashMap::new();
+
+    for item in input {
+        match item {
+            ResponseItem::Message { role, content, .. } => {
+                let mut text = String::new();
+                let mut images = Vec::new();
+                for c in content {
+                    match c {
+                        ContentItem::InputText { text: t }
+                        | ContentItem::OutputText { text: t } => {
+                            text.push_str(t);
+                        }
+                        ContentItem::InputImage { image_url } => {
+                            // Ollama only accepts inline base64 images.
+                            if let Some((_, data)) = image_url
+                                .strip_prefix("data:")
+                                .and_then(|rest| rest.split_once(";base64,"))
+                            {
+                                images.push(data.to_string());
+                            }
+                        }
+                    }
+                }
+                let role = match role.as_str() {
+                    "assistant" => "assistant",
+                    "system" | "developer" => "system",
+                    _ => "user",
+                };
+                let mut message = json!({"role": role, "content": text});
+                if !images.is_empty()
+                    && let Some(obj) = message.as_object_mut()
+                {
+                    obj.insert("images".to_string(), json!(images));
+                }
+                messages.push(message);
+            }
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                tool_names.insert(call_id, name);
+                let arguments = serde_json::from_str::<Value>(arguments)
+                    .ok()
+                    .filter(Value::is_object)
+                    .unwrap_or_else(|| json!({}));
+                push_tool_call(&mut messages, name, arguments);
+            }
+            ResponseItem::CustomToolCall {
+                call_id,
+                name,
+                input,
+                ..
+            } => {
+                tool_names.insert(call_id, name);
+                push_tool_call(&mut messages, name, json!({"input": input}));
+            }
+            ResponseItem::LocalShellCall {
+                id,
+                call_id,
+                action,
+                ..
+            } => {
+                if let Some(call_id) = call_id.as_ref().or(id.as_ref()) {
+                    tool_names.insert(call_id, "local_shell");
+                }
+                push_tool_call(&mut messages, "local_shell", json!(action));
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                messages.push(tool_message(&tool_names, call_id, &output.content));
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                messages.push(tool_message(&tool_names, call_id, output));
+            }
+            ResponseItem::Reasoning { .. }
+            | ResponseItem::WebSearchCall { .. }
+            | ResponseItem::Other => {
+                // Omit these items from the conversation history.
+                continue;
+            }
+        }
+    }
+
+    messages
+}
+
+fn push_tool_call(messages: &mut Vec<Value>, name: &str, arguments: Value) {
+    let call = json!({"function": {"name": name, "arguments": arguments}});
+    if let Some(last) = messages.last_mut()
+        && last.get("role").and_then(Value::as_str) == Some("assistant")
+        && let Some(obj) = last.as_object_mut()
+    {
+        if let Some(Value::Array(calls)) = obj.get_mut("tool_calls") {
+            calls.push(call);
+        } else {
+            obj.insert("tool_calls".to_string(), json!([call]));
+        }
+        return;
+    }
+    messages.push(json!({"role": "assistant", "content": "", "tool_calls": [call]}));
+}
+
+fn tool_message(tool_names: &HashMap<&str, &str>, call_id: &str, content: &str) -> Value {
+    let mut message = json!({"role": "tool", "content": content});
+    if let Some(name) = tool_names.get(call_id)
+        && let Some(obj) = message.as_object_mut()
+    {
+        obj.insert("tool_name".to_string(), json!(name));
+    }
+    message
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChunk {
+    #[serde(default)]
+    message: Option<ChunkMessage>,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    prompt_eval_count: Option<u64>,
+    #[serde(default)]
+    eval_count: Option<u64>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkMessage {
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    thinking: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<ChunkToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkToolCall {
+    function: ChunkFunction,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkFunction {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+/// Processor for the newline-delimited JSON stream returned by `/api/chat`.
+/// Text and thinking are streamed as deltas; tool calls arrive whole and are
+/// forwarded as function calls with freshly minted call ids.
+async fn process_ollama_ndjson<S>(
+    stream: S,
+    tx_event: mpsc::Sender<Result<ResponseEvent>>,
+    idle_timeout: Duration,
+    otel_event_manager: OtelEventManager,
+) where
+    S: Stream<Item = Result<Bytes>> + Unpin,
+{
+    let mut stream = stream;
+    let mut buf = BytesMut::new();
+    let mut created = false;
+    let mut text = String::new();
+    let mut thinking = String::new();
+    let mut tool_calls = Vec::new();
+
+    loop {
+        let start = std::time::Instant::now();
+        let response = timeout(idle_timeout, stream.next()).await;
+        let duration = start.elapsed();
+
+        let error = match response {
+            Ok(Some(Ok(bytes))) => {
+                buf.extend_from_slice(&bytes);
+                None
+            }
+            Ok(Some(Err(e))) => Some(e),
+            Ok(None) => Some(CodexErr::Stream("stream closed before done".into(), None)),
+            Err(_) => Some(CodexErr::Stream(
+                "idle timeout waiting for Ollama".into(),
+                None,
+            )),
+        };
+        if let Some(error) = error {
+            otel_event_manager.sse_event_failed(None, duration, &error);
+            let _ = tx_event.send(Err(error)).await;
+            return;
+        }
+
+        while let Some(pos) = buf.iter().position(|b| *b == b'\n') {
+            let line = buf.split_to(pos + 1);
+            let Ok(line) = std::str::from_utf8(&line) else {
+                continue;
+            };
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let chunk: ChatChunk = match serde_json::from_str(line) {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    debug!("failed to parse Ollama chat chunk: {e}, data: {line}");
+                    continue;
+                }
+            };
+            trace!("ollama_chat received chunk: {chunk:?}");
+
+            if let Some(error) = chunk.error {
+                otel_event_manager.sse_event_failed(None, duration, &error);
+                let _ = tx_event.send(Err(CodexErr::Stream(error, None))).await;
+                return;
+            }
+            if !created {
+                created = true;
+                let _ = tx_event.send(Ok(ResponseEvent::Created)).await;
+            }
+
+            if let Some(message) = chunk.message {
+                if let Some(delta) = message.thinking.filter(|t| !t.is_empty()) {
+                    thinking.push_str(&delta);
+                    let _ = tx_event
+                        .send(Ok(ResponseEvent::ReasoningContentDelta(delta)))
+                        .await;
+                }
+                if !message.content.is_empty() {
+                    text.push_str(&message.content);
+                    let _ = tx_event
+                        .send(Ok(ResponseEvent::OutputTextDelta(message.content)))
+                        .await;
+                }
+                tool_calls.extend(message.tool_calls);
+            }
+
+            if chunk.done {
+                for item in final_items(
+                    std::mem::take(&mut thinking),
+                    std::mem::take(&mut text),
+                    std::mem::take(&mut tool_calls),
+                ) {
+                    let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
+                }
+                let input_tokens = chunk.prompt_eval_count.unwrap_or(0);
+                let output_tokens = chunk.eval_count.unwrap_or(0);
+                otel_event_manager.sse_event_completed(input_tokens, output_tokens, None, None, 0);
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id: chunk.created_at.unwrap_or_default(),
+                        token_usage: Some(TokenUsage {
+                            input_tokens,
+                            cached_input_tokens: 0,
+                            output_tokens,
+                            reasoning_output_tokens: 0,
+                            total_tokens: input_tokens + output_tokens,
+                        }),
+                    }))
+                    .await;
+                return;
+            }
+        }
+    }
+}
+
+/// Builds the items emitted at the end of a response, in the order the model
+/// produced them: reasoning, then the assistant message, then tool calls.
+fn final_items(
+    thinking: String,
+    text: String,
+    tool_calls: Vec<ChunkToolCall>,
+) -> Vec<ResponseItem> {
+    let mut items = Vec::new();
+    if !thinking.is_empty() {
+        items.push(ResponseItem::Reasoning {
+            id: String::new(),
+            summary: Vec::new(),
+            content: Some(vec![ReasoningItemContent::ReasoningText { text: thinking }]),
+            encrypted_content: None,
+        });
+    }
+    if !text.is_empty() {
+        items.push(ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText { text }],
+        });
+    }
+    for ChunkToolCall {
+        function: ChunkFunction { name, arguments },
+    } in tool_calls
+    {
+        // Some models return arguments as a JSON-encoded string.
+        let arguments = match arguments {
+            Value::String(s) => s,
+            Value::Null => "{}".to_string(),
+            other => other.to_string(),
+        };
+        items.push(ResponseItem::FunctionCall {
+            id: None,
+            name,
+            arguments,
+            call_id: format!("call_{}", uuid::Uuid::new_v4().simple()),
+        });
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn capabilities_from_show_response() {
+        let body = json!({
+            "capabilities": ["completion", "tools", "thinking"],
+            "model_info": {
+                "general.architecture": "gptoss",
+                "gptoss.context_length": 131072,
+            },
+        });
+        assert_eq!(
+            OllamaModelCapabilities::from_show_response(&body),
+            OllamaModelCapabilities {
+                tools: true,
+                thinking: true,
+                vision: false,
+                context_length: Some(131_072),
+            }
+        );
+
+        let no_tools = json!({"capabilities": ["completion"], "model_info": {}});
+        assert!(!OllamaModelCapabilities::from_show_response(&no_tools).tools);
+
+        // Older servers do not report capabilities at all.
+        let legacy = OllamaModelCapabilities::from_show_response(&json!({}));
+        assert!(legacy.tools);
+        assert_eq!(legacy.context_length, None);
+    }
+
+    #[test]
+    fn num_ctx_caps_configured_window_at_trained_length() {
+        let caps = OllamaModelCapabilities {
+            context_length: Some(8_192),
+            ..Default::default()
+        };
+        assert_eq!(num_ctx(&caps, None), Some(8_192));
+        assert_eq!(num_ctx(&caps, Some(4_096)), Some(4_096));
+        assert_eq!(num_ctx(&caps, Some(32_768)), Some(8_192));
+        assert_eq!(num_ctx(&OllamaModelCapabilities::default(), None), None);
+    }
+
+    #[test]
+    fn host_root_strips_openai_compat_suffix() {
+        assert_eq!(
+            ollama_host_root(Some("http://localhost:11434/v1/")),
+            "http://localhost:11434"
+        );
+        assert_eq!(
+            ollama_host_root(Some("http://gpu-box:11434")),
+            "http://gpu-box:11434"
+        );
+        assert_eq!(ollama_host_root(None), DEFAULT_OLLAMA_HOST_ROOT);
+    }
+
+    #[test]
+    fn build_messages_attaches_tool_calls_and_names_results() {
+        let input = vec![
+            ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![ContentItem::InputText {
+                    text: "list files".to_string(),
+                }],
+            },
+            ResponseItem::Message {
+                id: None,
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText {
+                    text: "Listing.".to_string(),
+                }],
+            },
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["ls"]}"#.to_string(),
+                call_id: "call_1".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "call_1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "Cargo.toml".to_string(),
+                    success: Some(true),
+                },
+            },
+        ];
+
+        assert_eq!(
+            build_messages(&input),
+            vec![
+                json!({"role": "user", "content": "list files"}),
+                json!({
+                    "role": "assistant",
+                    "content": "Listing.",
+                    "tool_calls": [{"function": {"name": "shell", "arguments": {"command": ["ls"]}}}],
+                }),
+                json!({"role": "tool", "content": "Cargo.toml", "tool_name": "shell"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn final_items_orders_reasoning_text_and_tool_calls() {
+        let items = final_items(
+            "thinking".to_string(),
+            String::new(),
+            vec![ChunkToolCall {
+                function: ChunkFunction {
+                    name: "shell".to_string(),
+                    arguments: json!({"command": ["pwd"]}),
+                },
+            }],
+        );
+        assert_eq!(items.len(), 2);
+        assert!(matches!(items[0], ResponseItem::Reasoning { .. }));
+        match &items[1] {
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                assert_eq!(name, "shell");
+                assert_eq!(arguments, r#"{"command":["pwd"]}"#);
+                assert!(call_id.starts_with("call_"));
+            }
+            other => panic!("expected function call, got {other:?}"),
+        }
+    }
+}
@@ -15,7 +15,19 @@ pub fn build_provider(
     config: &Config,
     service_version: &str,
 ) -> Result<Option<OtelProvider>, Box<dyn Error>> {
-    let exporter = match &config.otel.exporter {
+    OtelProvider::from(&OtelSettings {
+        service_name: originator().value.to_owned(),
+        service_version: service_version.to_string(),
+        codex_home: config.codex_home.clone(),
+        environment: config.otel.environment.to_string(),
+        exporter: to_otel_exporter(config, &config.otel.exporter),
+        trace_exporter: to_otel_exporter(config, &config.otel.trace_exporter),
+        metrics_exporter: to_otel_exporter(config, &config.otel.metrics_exporter),
+    })
+}
+
+fn to_otel_exporter(config: &Config, kind: &Kind) -> OtelExporter {
+    match kind {
         Kind::None => OtelExporter::None,
         Kind::OtlpHttp {
             endpoint,
@@ -43,15 +55,12 @@ pub fn build_provider(
                 .map(|(k, v)| (k.clone(), v.clone()))
                 .collect(),
         },
-    };
-
-    OtelProvider::from(&OtelSettings {
-        service_name: originator().value.to_owned(),
-        service_version: service_version.to_string(),
-        codex_home: config.codex_home.clone(),
-        environment: config.otel.environment.to_string(),
-        exporter,
-    })
+        Kind::OtlpFile { path } => OtelExporter::OtlpFile {
+            path: path
+                .clone()
+                .unwrap_or_else(|| config.codex_home.join("otel")),
+        },
+    }
 }
 
 /// Filter predicate for exporting only Codex-owned events via OTEL.
@@ -0,0 +1,149 @@
+//! Model prices, used to turn token usage into cost.
+
+use std::collections::HashMap;
+
+use crate::config_types::ModelPricing;
+use crate::protocol::SessionUsage;
+use crate::protocol::TokenUsage;
+
+/// Prices used when `[model_pricing]` does not list a model, in US dollars
+/// per million tokens: (model, input, cached input, output).
+const BUILT_IN_MODEL_PRICING: &[(&str, f64, f64, f64)] = &[
+    ("gpt-5", 1.25, 0.125, 10.0),
+    ("gpt-5-codex", 1.25, 0.125, 10.0),
+    ("gpt-5-mini", 0.25, 0.025, 2.0),
+    ("gpt-5-nano", 0.05, 0.005, 0.4),
+    ("gpt-4.1", 2.0, 0.5, 8.0),
+    ("o3", 2.0, 0.5, 8.0),
+    ("o4-mini", 1.1, 0.275, 4.4),
+    ("codex-mini-latest", 1.5, 0.375, 6.0),
+];
+
+/// The built-in prices with the entries from `config.toml` applied on top.
+pub(crate) fn model_pricing_table(
+    configured: HashMap<String, ModelPricing>,
+) -> HashMap<String, ModelPricing> {
+    let mut table: HashMap<String, ModelPricing> = BUILT_IN_MODEL_PRICING
+        .iter()
+        .map(|&(model, input, cached_input, output)| {
+            (
+                model.to_string(),
+                ModelPricing {
+                    input,
+                    cached_input: Some(cached_input),
+                    output,
+                },
+            )
+        })
+        .collect();
+    table.extend(configured);
+    table
+}
+
+/// Looks up the price of `model`. Dated snapshots such as `gpt-5-2025-08-07`
+/// use the price of the model they are a snapshot of.
+pub fn price_for<'a>(
+    table: &'a HashMap<String, ModelPricing>,
+    model: &str,
+) -> Option<&'a ModelPricing> {
+    if let Some(pricing) = table.get(model) {
+        return Some(pricing);
+    }
+    table
+        .iter()
+        .filter(|(name, _)| {
+            model
+                .strip_prefix(name.as_str())
+                .and_then(|rest| rest.strip_prefix('-'))
+                .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        })
+        .max_by_key(|(name, _)| name.len())
+        .map(|(_, pricing)| pricing)
+}
+
+/// Cost of `usage` in US dollars.
+pub fn cost_usd(pricing: &ModelPricing, usage: &TokenUsage) -> f64 {
+    let cached_input = pricing.cached_input.unwrap_or(pricing.input);
+    (usage.non_cached_input() as f64 * pricing.input
+        + usage.cached_input() as f64 * cached_input
+        + usage.output_tokens as f64 * pricing.output)
+        / 1_000_000.0
+}
+
+/// Adds the usage of one request to `model` to the session totals.
+pub(crate) fn add_usage(
+    session_usage: &mut SessionUsage,
+    table: &HashMap<String, ModelPricing>,
+    model: &str,
+    usage: &TokenUsage,
+) {
+    let entry = session_usage.models.entry(model.to_string()).or_default();
+    entry.token_usage.add_assign(usage);
+    if let Some(pricing) = price_for(table, model) {
+        *entry.cost_usd.get_or_insert(0.0) += cost_usd(pricing, usage);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn usage(input: u64, cached: u64, output: u64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: input,
+            cached_input_tokens: cached,
+            output_tokens: output,
+            reasoning_output_tokens: 0,
+            total_tokens: input + output,
+        }
+    }
+
+    #[test]
+    fn configured_prices_override_built_in_ones() {
+        let table = model_pricing_table(HashMap::from([(
+            "gpt-5".to_string(),
+            ModelPricing {
+                input: 2.0,
+                cached_input: None,
+                output: 4.0,
+            },
+        )]));
+
+        let pricing = price_for(&table, "gpt-5").expect("gpt-5 price");
+        assert_eq!(cost_usd(pricing, &usage(1_000_000, 500_000, 250_000)), 3.0);
+        assert_eq!(price_for(&table, "gpt-5-2025-08-07"), Some(pricing));
+        assert_eq!(
+            price_for(&table, "gpt-5-mini-2025-08-07").map(|p| p.input),
+            Some(0.25)
+        );
+        assert_eq!(price_for(&table, "o3-pro"), None);
+    }
+
+    #[test]
+    fn add_usage_tracks_models_without_a_price() {
+        let table = model_pricing_table(HashMap::new());
+        let mut session_usage = SessionUsage::default();
+
+        add_usage(
+            &mut session_usage,
+            &table,
+            "gpt-5",
+            &usage(1_000_000, 0, 100_000),
+        );
+        add_usage(&mut session_usage, &table, "gpt-5", &usage(0, 0, 100_000));
+        add_usage(
+            &mut session_usage,
+            &table,
+            "my-local-model",
+            &usage(10, 0, 10),
+        );
+
+        assert_eq!(session_usage.cost_usd(), 3.25);
+        assert_eq!(
+            session_usage.models["gpt-5"].token_usage.output_tokens,
+            200_000
+        );
+        assert_eq!(session_usage.models["my-local-model"].cost_usd, None);
+    }
+}
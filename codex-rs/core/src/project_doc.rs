@@ -12,12 +12,24 @@
 //!     current working directory (inclusive) and concatenate their contents in
 //!     that order.
 //! 3.  We do **not** walk past the Git root.
+//!
+//! Docs in other directories of the repository, such as nested packages below
+//! the current working directory, are not part of the session instructions.
+//! [`ScopedProjectDocs`] loads them lazily, the first time the agent reads or
+//! edits a file in their directory, and each one gets its own
+//! `project_doc_max_bytes` budget.
 
 use crate::config::Config;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
 use dunce::canonicalize as normalize_path;
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::Path;
 use std::path::PathBuf;
 use tokio::io::AsyncReadExt;
 use tracing::error;
+use tracing::warn;
 
 /// Default filename scanned for project-level docs.
 pub const DEFAULT_PROJECT_DOC_FILENAME: &str = "AGENTS.md";
@@ -111,6 +123,33 @@ pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String
 /// directory (inclusive). Symlinks are allowed. When `project_doc_max_bytes`
 /// is zero, returns an empty list.
 pub fn discover_project_doc_paths(config: &Config) -> std::io::Result<Vec<PathBuf>> {
+    let mut found: Vec<PathBuf> = Vec::new();
+    let candidate_filenames = candidate_filenames(config);
+    for d in project_doc_search_dirs(config)? {
+        for name in &candidate_filenames {
+            let candidate = d.join(name);
+            match std::fs::symlink_metadata(&candidate) {
+                Ok(md) => {
+                    let ft = md.file_type();
+                    // Allow regular files and symlinks; opening will later fail for dangling links.
+                    if ft.is_file() || ft.is_symlink() {
+                        found.push(candidate);
+                        break;
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+/// Directories searched for session-level docs, ordered from the repository
+/// root (or the current working directory outside a repository) down to the
+/// current working directory.
+fn project_doc_search_dirs(config: &Config) -> std::io::Result<Vec<PathBuf>> {
     let mut dir = config.cwd.clone();
     if let Ok(canon) = normalize_path(&dir) {
         dir = canon;
@@ -137,7 +176,7 @@ pub fn discover_project_doc_paths(config: &Config) -> std::io::Result<Vec<PathBu
         cursor = parent.to_path_buf();
     }
 
-    let search_dirs: Vec<PathBuf> = if let Some(root) = git_root {
+    Ok(if let Some(root) = git_root {
         let mut dirs: Vec<PathBuf> = Vec::new();
         let mut saw_root = false;
         for p in chain.iter().rev() {
@@ -153,29 +192,168 @@ pub fn discover_project_doc_paths(config: &Config) -> std::io::Result<Vec<PathBu
         dirs
     } else {
         vec![config.cwd.clone()]
-    };
+    })
+}
 
-    let mut found: Vec<PathBuf> = Vec::new();
-    let candidate_filenames = candidate_filenames(config);
-    for d in search_dirs {
-        for name in &candidate_filenames {
-            let candidate = d.join(name);
-            match std::fs::symlink_metadata(&candidate) {
-                Ok(md) => {
-                    let ft = md.file_type();
-                    // Allow regular files and symlinks; opening will later fail for dangling links.
-                    if ft.is_file() || ft.is_symlink() {
-                        found.push(candidate);
-                        break;
-                    }
-                }
+/// A project doc from a directory outside the session-level search path.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ScopedProjectDoc {
+    /// Directory whose files the instructions apply to.
+    pub(crate) dir: PathBuf,
+    pub(crate) path: PathBuf,
+    pub(crate) contents: String,
+}
+
+impl From<ScopedProjectDoc> for ResponseItem {
+    fn from(doc: ScopedProjectDoc) -> Self {
+        ResponseItem::Message {
+            id: None,
+            role: "developer".to_string(),
+            content: vec![ContentItem::InputText {
+                text: format!(
+                    "Instructions from `{}` apply to files under `{}`:\n\n{}",
+                    doc.path.display(),
+                    doc.dir.display(),
+                    doc.contents
+                ),
+            }],
+        }
+    }
+}
+
+/// Lazily discovers project docs in directories of the repository as the agent
+/// touches files there.
+///
+/// Directories searched at session start are never loaded again, and every
+/// other directory is loaded at most once until [`Self::reset`] is called.
+#[derive(Debug, Default)]
+pub(crate) struct ScopedProjectDocs {
+    /// Repository root (or working directory outside a repository); files
+    /// outside of it never pull in docs.
+    root: Option<PathBuf>,
+    candidate_filenames: Vec<String>,
+    max_bytes: usize,
+    session_dirs: Vec<PathBuf>,
+    seen_dirs: HashSet<PathBuf>,
+    pending: Vec<ScopedProjectDoc>,
+}
+
+impl ScopedProjectDocs {
+    /// Disabled when `project_doc_max_bytes` is zero.
+    pub(crate) fn new(config: &Config) -> std::io::Result<Self> {
+        if config.project_doc_max_bytes == 0 {
+            return Ok(Self::default());
+        }
+        let session_dirs: Vec<PathBuf> = project_doc_search_dirs(config)?
+            .into_iter()
+            .map(|dir| normalize_path(&dir).unwrap_or(dir))
+            .collect();
+        Ok(Self {
+            root: session_dirs.first().cloned(),
+            candidate_filenames: candidate_filenames(config)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            max_bytes: config.project_doc_max_bytes,
+            seen_dirs: session_dirs.iter().cloned().collect(),
+            session_dirs,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Queue the docs that apply to `path` and have not been loaded yet,
+    /// outermost directory first.
+    pub(crate) fn on_path_touched(&mut self, path: &Path) {
+        let Some(root) = self.root.clone() else {
+            return;
+        };
+        let path = normalize_touched_path(path);
+        let dir = if path.is_dir() {
+            path.as_path()
+        } else {
+            match path.parent() {
+                Some(parent) => parent,
+                None => return,
+            }
+        };
+        let Ok(rel) = dir.strip_prefix(&root) else {
+            return;
+        };
+
+        let mut cursor = root;
+        for component in rel.components() {
+            cursor.push(component);
+            if self.seen_dirs.insert(cursor.clone())
+                && let Some(doc) = self.read_doc(&cursor)
+            {
+                self.pending.push(doc);
+            }
+        }
+    }
+
+    /// Docs discovered since the last call, in discovery order.
+    pub(crate) fn take_pending(&mut self) -> Vec<ScopedProjectDoc> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Forget which docs were injected, e.g. after the history that contained
+    /// them was replaced.
+    pub(crate) fn reset(&mut self) {
+        self.seen_dirs = self.session_dirs.iter().cloned().collect();
+        self.pending.clear();
+    }
+
+    fn read_doc(&self, dir: &Path) -> Option<ScopedProjectDoc> {
+        for name in &self.candidate_filenames {
+            let path = dir.join(name);
+            let file = match std::fs::File::open(&path) {
+                Ok(file) => file,
                 Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
-                Err(e) => return Err(e),
+                Err(e) => {
+                    warn!("failed to open project doc `{}`: {e}", path.display());
+                    return None;
+                }
+            };
+
+            let size = file.metadata().map(|md| md.len()).unwrap_or_default();
+            let mut data: Vec<u8> = Vec::new();
+            if let Err(e) = file.take(self.max_bytes as u64).read_to_end(&mut data) {
+                warn!("failed to read project doc `{}`: {e}", path.display());
+                return None;
             }
+            if size > self.max_bytes as u64 {
+                warn!(
+                    "Project doc `{}` exceeds its budget ({} bytes) - truncating.",
+                    path.display(),
+                    self.max_bytes,
+                );
+            }
+
+            let contents = String::from_utf8_lossy(&data).to_string();
+            if contents.trim().is_empty() {
+                return None;
+            }
+            return Some(ScopedProjectDoc {
+                dir: dir.to_path_buf(),
+                path,
+                contents,
+            });
         }
+        None
     }
+}
 
-    Ok(found)
+/// Canonicalize `path`, or its parent when the file does not exist (yet).
+fn normalize_touched_path(path: &Path) -> PathBuf {
+    if let Ok(path) = normalize_path(path) {
+        return path;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => normalize_path(parent)
+            .map(|parent| parent.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
 }
 
 fn candidate_filenames<'a>(config: &'a Config) -> Vec<&'a str> {
@@ -447,4 +625,49 @@ mod tests {
                 .eq(DEFAULT_PROJECT_DOC_FILENAME)
         );
     }
+
+    /// Nested docs are loaded once, outermost first, when a file below them is
+    /// touched, and each gets the full byte budget.
+    #[test]
+    fn scoped_docs_are_loaded_lazily_per_directory() {
+        let repo = tempfile::tempdir().expect("tempdir");
+        std::fs::write(
+            repo.path().join(".git"),
+            "gitdir: /path/to/actual/git/dir\n",
+        )
+        .unwrap();
+        fs::write(repo.path().join("AGENTS.md"), "root doc").unwrap();
+        let pkg = repo.path().join("packages/web");
+        std::fs::create_dir_all(pkg.join("src")).unwrap();
+        fs::write(repo.path().join("packages/AGENTS.md"), "packages doc").unwrap();
+        fs::write(pkg.join("AGENTS.md"), "web doc that is long").unwrap();
+
+        let mut scoped = ScopedProjectDocs::new(&make_config(&repo, 8, None)).expect("scopes");
+        scoped.on_path_touched(&pkg.join("src/new_file.ts"));
+        scoped.on_path_touched(&pkg.join("package.json"));
+
+        let root = normalize_path(repo.path()).unwrap();
+        let docs: Vec<(PathBuf, String)> = scoped
+            .take_pending()
+            .into_iter()
+            .map(|doc| (doc.dir, doc.contents))
+            .collect();
+        assert_eq!(
+            docs,
+            vec![
+                (root.join("packages"), "packages".to_string()),
+                (root.join("packages/web"), "web doc ".to_string()),
+            ]
+        );
+
+        // The session-level doc and already injected docs are not repeated.
+        scoped.on_path_touched(&repo.path().join("README.md"));
+        scoped.on_path_touched(&pkg.join("src/new_file.ts"));
+        assert_eq!(scoped.take_pending(), Vec::new());
+
+        // After the history is replaced, docs are injected again.
+        scoped.reset();
+        scoped.on_path_touched(&pkg.join("package.json"));
+        assert_eq!(scoped.take_pending().len(), 2);
+    }
 }
@@ -1,4 +1,14 @@
+use std::path::Path;
+
+use serde_json::Value;
+use serde_json::json;
+
+use crate::protocol::ReviewExportFormat;
 use crate::protocol::ReviewFinding;
+use crate::protocol::ReviewOutputEvent;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_RULE_ID: &str = "codex-review";
 
 // Note: We keep this module UI-agnostic. It returns plain strings that
 // higher layers (e.g., TUI) may style as needed.
@@ -53,3 +63,273 @@ pub fn format_review_findings_block(
 
     lines.join("\n")
 }
+
+/// Render a review in `format`. File paths under `root` are written relative
+/// to it.
+pub fn export_review(
+    output: &ReviewOutputEvent,
+    format: ReviewExportFormat,
+    root: &Path,
+) -> serde_json::Result<String> {
+    Ok(match format {
+        ReviewExportFormat::Json => serde_json::to_string_pretty(output)?,
+        ReviewExportFormat::Sarif => {
+            serde_json::to_string_pretty(&review_output_to_sarif(output, root))?
+        }
+        ReviewExportFormat::Github => review_output_to_github_annotations(output, root),
+        ReviewExportFormat::Markdown => review_output_to_markdown(output, root),
+    })
+}
+
+/// Path of the finding relative to `root` with forward slashes, or the
+/// absolute path when it lies outside `root`.
+fn relative_path(item: &ReviewFinding, root: &Path) -> String {
+    let path = &item.code_location.absolute_file_path;
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Convert a review into a SARIF 2.1.0 log with one result per finding.
+///
+/// File paths under `root` are reported relative to it so that code scanning
+/// tools can match them to files in the repository.
+pub fn review_output_to_sarif(output: &ReviewOutputEvent, root: &Path) -> Value {
+    let results: Vec<Value> = output
+        .findings
+        .iter()
+        .map(|finding| {
+            let location = &finding.code_location;
+            let uri = relative_path(finding, root);
+            json!({
+                "ruleId": SARIF_RULE_ID,
+                "level": sarif_level(finding.priority),
+                "message": { "text": format!("{}\n\n{}", finding.title, finding.body) },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri },
+                        "region": {
+                            "startLine": location.line_range.start.max(1),
+                            "endLine": location.line_range.end.max(location.line_range.start).max(1),
+                        },
+                    },
+                }],
+                "properties": {
+                    "priority": finding.priority,
+                    "confidence": finding.confidence_score,
+                },
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "codex",
+                    "informationUri": "https://github.com/openai/codex",
+                    "rules": [{
+                        "id": SARIF_RULE_ID,
+                        "shortDescription": { "text": "Issue found by Codex review" },
+                    }],
+                },
+            },
+            "results": results,
+            "properties": {
+                "overall_correctness": output.overall_correctness,
+                "overall_explanation": output.overall_explanation,
+                "overall_confidence_score": output.overall_confidence_score,
+            },
+        }],
+    })
+}
+
+/// P0 and P1 findings are errors, P2 warnings, anything else notes.
+fn sarif_level(priority: i32) -> &'static str {
+    match priority {
+        0 | 1 => "error",
+        2 => "warning",
+        _ => "note",
+    }
+}
+
+/// Convert a review into GitHub Actions workflow commands, one
+/// `::error`/`::warning`/`::notice` line per finding, so that the findings
+/// show up as annotations on the pull request.
+pub fn review_output_to_github_annotations(output: &ReviewOutputEvent, root: &Path) -> String {
+    let mut out = String::new();
+    for item in &output.findings {
+        let level = match sarif_level(item.priority) {
+            "note" => "notice",
+            level => level,
+        };
+        let range = &item.code_location.line_range;
+        let start = range.start.max(1);
+        let end = range.end.max(start);
+        out.push_str(&format!(
+            "::{level} file={},line={start},endLine={end},title={}::{}\n",
+            escape_workflow_property(&relative_path(item, root)),
+            escape_workflow_property(&item.title),
+            escape_workflow_data(&item.body),
+        ));
+    }
+    out
+}
+
+/// Escaping for the message of a workflow command.
+fn escape_workflow_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escaping for `key=value` properties of a workflow command.
+fn escape_workflow_property(value: &str) -> String {
+    escape_workflow_data(value)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+/// Convert a review into a markdown report: the overall verdict followed by
+/// the findings in order.
+pub fn review_output_to_markdown(output: &ReviewOutputEvent, root: &Path) -> String {
+    let mut lines: Vec<String> = vec!["## Codex review".to_string(), String::new()];
+
+    let verdict = output.overall_correctness.trim();
+    if !verdict.is_empty() {
+        lines.push(format!(
+            "**Verdict:** {verdict} (confidence {:.2})",
+            output.overall_confidence_score
+        ));
+        lines.push(String::new());
+    }
+    let explanation = output.overall_explanation.trim();
+    if !explanation.is_empty() {
+        lines.push(explanation.to_string());
+        lines.push(String::new());
+    }
+
+    if output.findings.is_empty() {
+        lines.push("No findings.".to_string());
+    } else {
+        lines.push("### Findings".to_string());
+        for (idx, item) in output.findings.iter().enumerate() {
+            let range = &item.code_location.line_range;
+            lines.push(String::new());
+            lines.push(format!(
+                "{}. **{}** — `{}:{}-{}` (confidence {:.2})",
+                idx + 1,
+                item.title,
+                relative_path(item, root),
+                range.start,
+                range.end,
+                item.confidence_score
+            ));
+            for body_line in item.body.lines() {
+                if body_line.is_empty() {
+                    lines.push(String::new());
+                } else {
+                    lines.push(format!("   {body_line}"));
+                }
+            }
+        }
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ReviewCodeLocation;
+    use crate::protocol::ReviewLineRange;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn sample_output() -> ReviewOutputEvent {
+        ReviewOutputEvent {
+            findings: vec![ReviewFinding {
+                title: "[P1] Off by one".to_string(),
+                body: "The loop skips the last item.".to_string(),
+                confidence_score: 0.8,
+                priority: 1,
+                code_location: ReviewCodeLocation {
+                    absolute_file_path: PathBuf::from("/repo/src/lib.rs"),
+                    line_range: ReviewLineRange { start: 10, end: 12 },
+                },
+            }],
+            overall_correctness: "patch is incorrect".to_string(),
+            overall_explanation: "The new loop drops data.".to_string(),
+            overall_confidence_score: 0.7,
+        }
+    }
+
+    #[test]
+    fn sarif_reports_findings_relative_to_root() {
+        let output = sample_output();
+
+        let sarif = review_output_to_sarif(&output, Path::new("/repo"));
+        let result = &sarif["runs"][0]["results"][0];
+
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(result["level"], "error");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"],
+            json!({
+                "artifactLocation": { "uri": "src/lib.rs" },
+                "region": { "startLine": 10, "endLine": 12 },
+            })
+        );
+    }
+
+    #[test]
+    fn github_annotations_escape_messages_and_properties() {
+        let mut output = sample_output();
+        output.findings[0].title = "[P1] Off by one, again: 100%".to_string();
+        output.findings[0].body = "First line.\nSecond line.".to_string();
+        output.findings.push(ReviewFinding {
+            title: "[P3] Typo".to_string(),
+            body: "Spelling.".to_string(),
+            confidence_score: 0.5,
+            priority: 3,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: PathBuf::from("/elsewhere/README.md"),
+                line_range: ReviewLineRange { start: 0, end: 0 },
+            },
+        });
+
+        let annotations = review_output_to_github_annotations(&output, Path::new("/repo"));
+
+        assert_eq!(
+            annotations,
+            "::error file=src/lib.rs,line=10,endLine=12,title=[P1] Off by one%2C again%3A 100%25::First line.%0ASecond line.\n\
+             ::notice file=/elsewhere/README.md,line=1,endLine=1,title=[P3] Typo::Spelling.\n"
+        );
+    }
+
+    #[test]
+    fn markdown_report_lists_verdict_and_findings() {
+        let markdown = review_output_to_markdown(&sample_output(), Path::new("/repo"));
+
+        assert_eq!(
+            markdown,
+            "## Codex review
+
+**Verdict:** patch is incorrect (confidence 0.70)
+
+The new loop drops data.
+
+### Findings
+
+1. **[P1] Off by one** — `src/lib.rs:10-12` (confidence 0.80)
+   The loop skips the last item.
+"
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! Resolution of structured review targets into review prompts.
+//!
+//! Clients describe what to review with a [`ReviewTarget`]; the session turns
+//! it into a concrete diff using git before the review thread starts, so the
+//! reviewer does not have to work out which commands to run.
+
+use std::path::Path;
+
+use crate::git_info;
+use crate::protocol::ReviewRequest;
+use crate::protocol::ReviewTarget;
+
+/// Diffs up to this size are included in the prompt; larger ones are left for
+/// the reviewer to inspect with the command shown instead.
+const MAX_INLINE_DIFF_BYTES: usize = 96 * 1024;
+
+/// A review target turned into the first message of the review thread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ResolvedReview {
+    pub(crate) prompt: String,
+    pub(crate) user_facing_hint: String,
+}
+
+/// Resolves `request` against the repository at `cwd`. Requests without a
+/// target are passed through unchanged.
+pub(crate) async fn resolve_review_request(
+    cwd: &Path,
+    request: &ReviewRequest,
+) -> Result<ResolvedReview, String> {
+    let Some(target) = &request.target else {
+        return Ok(ResolvedReview {
+            prompt: request.prompt.clone(),
+            user_facing_hint: request.user_facing_hint.clone(),
+        });
+    };
+
+    let resolved = resolve_target(cwd, target).await?;
+    let mut prompt = format!(
+        "Review the {}. Provide prioritized, actionable findings.",
+        resolved.description
+    );
+    let instructions = request.prompt.trim();
+    if !instructions.is_empty() {
+        prompt.push_str(&format!("\n\nAdditional instructions:\n{instructions}"));
+    }
+    prompt.push_str("\n\n");
+    if resolved.diff.len() <= MAX_INLINE_DIFF_BYTES {
+        prompt.push_str(&format!(
+            "The diff, from `{}`:\n\n```diff\n{}\n```",
+            resolved.command,
+            resolved.diff.trim_end()
+        ));
+    } else {
+        prompt.push_str(&format!(
+            "The diff is too large to include here ({} bytes); run `{}` to inspect it.",
+            resolved.diff.len(),
+            resolved.command
+        ));
+    }
+
+    let user_facing_hint = if request.user_facing_hint.trim().is_empty() {
+        resolved.hint
+    } else {
+        request.user_facing_hint.clone()
+    };
+    Ok(ResolvedReview {
+        prompt,
+        user_facing_hint,
+    })
+}
+
+struct ResolvedTarget {
+    /// Completes "Review the ...".
+    description: String,
+    hint: String,
+    /// Shell command that reproduces `diff`.
+    command: String,
+    diff: String,
+}
+
+async fn resolve_target(cwd: &Path, target: &ReviewTarget) -> Result<ResolvedTarget, String> {
+    let resolved = match target {
+        ReviewTarget::UncommittedChanges => {
+            let head = git_info::resolve_commit(cwd, "HEAD")
+                .await
+                .ok_or_else(|| format!("{} is not a git repository", cwd.display()))?;
+            let diff = git_info::diff_working_tree_against(cwd, &head)
+                .await
+                .ok_or("failed to diff the working tree")?;
+            ResolvedTarget {
+                description: "current code changes (staged, unstaged, and untracked files)"
+                    .to_string(),
+                hint: "current changes".to_string(),
+                command: "git diff HEAD".to_string(),
+                diff,
+            }
+        }
+        ReviewTarget::BaseBranch { branch } => {
+            let base = git_info::merge_base_with_head(cwd, branch)
+                .await
+                .ok_or_else(|| format!("no merge base between HEAD and branch '{branch}'"))?;
+            let diff = git_info::diff_working_tree_against(cwd, &base)
+                .await
+                .ok_or_else(|| format!("failed to diff against branch '{branch}'"))?;
+            ResolvedTarget {
+                description: format!(
+                    "code changes that would be merged into the '{branch}' branch (diffed against merge base {})",
+                    short_sha(&base)
+                ),
+                hint: format!("changes against '{branch}'"),
+                command: format!("git diff {base}"),
+                diff,
+            }
+        }
+        ReviewTarget::Commit { sha } => {
+            let full = git_info::resolve_commit(cwd, sha)
+                .await
+                .ok_or_else(|| format!("unknown commit '{sha}'"))?;
+            let diff = git_info::commit_diff(cwd, &full)
+                .await
+                .ok_or_else(|| format!("failed to read commit '{sha}'"))?;
+            ResolvedTarget {
+                description: format!("code changes introduced by commit {full}"),
+                hint: format!("commit {}", short_sha(&full)),
+                command: format!("git show {full}"),
+                diff,
+            }
+        }
+        ReviewTarget::CommitRange { from, to } => {
+            let from_sha = git_info::resolve_commit(cwd, from)
+                .await
+                .ok_or_else(|| format!("unknown revision '{from}'"))?;
+            let to_sha = git_info::resolve_commit(cwd, to)
+                .await
+                .ok_or_else(|| format!("unknown revision '{to}'"))?;
+            let diff = git_info::diff_between(cwd, &from_sha, &to_sha)
+                .await
+                .ok_or_else(|| format!("failed to diff {from}..{to}"))?;
+            ResolvedTarget {
+                description: format!("code changes between {from} and {to}"),
+                hint: format!("{from}..{to}"),
+                command: format!("git diff {from_sha} {to_sha}"),
+                diff,
+            }
+        }
+        ReviewTarget::PatchFile { path } => {
+            let path = cwd.join(path);
+            let diff = tokio::fs::read_to_string(&path)
+                .await
+                .map_err(|err| format!("failed to read patch {}: {err}", path.display()))?;
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string());
+            ResolvedTarget {
+                description: format!("code changes in the patch {}", path.display()),
+                hint: format!("patch {name}"),
+                command: format!("cat {}", path.display()),
+                diff,
+            }
+        }
+    };
+
+    if resolved.diff.trim().is_empty() {
+        return Err(format!("there are no {} to review", resolved.hint));
+    }
+    Ok(resolved)
+}
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(cwd: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(cwd)
+            .status()
+            .expect("run git");
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    fn init_repo() -> TempDir {
+        let dir = TempDir::new().expect("tempdir");
+        let cwd = dir.path();
+        git(cwd, &["init", "-q", "-b", "main"]);
+        git(cwd, &["config", "user.email", "test@example.com"]);
+        git(cwd, &["config", "user.name", "Test"]);
+        std::fs::write(cwd.join("lib.rs"), "fn a() {}\n").expect("write");
+        git(cwd, &["add", "."]);
+        git(cwd, &["commit", "-q", "-m", "initial"]);
+        dir
+    }
+
+    fn request(target: ReviewTarget) -> ReviewRequest {
+        ReviewRequest {
+            prompt: String::new(),
+            user_facing_hint: String::new(),
+            target: Some(target),
+        }
+    }
+
+    #[tokio::test]
+    async fn resolves_base_branch_against_merge_base() {
+        let repo = init_repo();
+        let cwd = repo.path();
+        git(cwd, &["checkout", "-q", "-b", "feature"]);
+        std::fs::write(cwd.join("lib.rs"), "fn a() {}\nfn b() {}\n").expect("write");
+        git(cwd, &["commit", "-q", "-am", "add b"]);
+
+        let resolved = resolve_review_request(
+            cwd,
+            &request(ReviewTarget::BaseBranch {
+                branch: "main".to_string(),
+            }),
+        )
+        .await
+        .expect("resolve");
+
+        assert_eq!(resolved.user_facing_hint, "changes against 'main'");
+        assert!(
+            resolved.prompt.contains("+fn b() {}"),
+            "{}",
+            resolved.prompt
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_empty_and_unknown_targets() {
+        let repo = init_repo();
+        let cwd = repo.path();
+
+        let empty = resolve_review_request(cwd, &request(ReviewTarget::UncommittedChanges)).await;
+        let unknown = resolve_review_request(
+            cwd,
+            &request(ReviewTarget::Commit {
+                sha: "does-not-exist".to_string(),
+            }),
+        )
+        .await;
+
+        assert_eq!(
+            empty,
+            Err("there are no current changes to review".to_string())
+        );
+        assert_eq!(unknown, Err("unknown commit 'does-not-exist'".to_string()));
+    }
+}
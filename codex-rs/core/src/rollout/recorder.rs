@@ -11,6 +11,7 @@ use serde_json::Value;
 use time::OffsetDateTime;
 use time::format_description::FormatItem;
 use time::macros::format_description;
+use tokio::io::AsyncBufReadExt;
 use tokio::io::AsyncWriteExt;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::mpsc::{self};
@@ -23,6 +24,9 @@ use super::list::ConversationsPage;
 use super::list::Cursor;
 use super::list::get_conversations;
 use super::policy::is_persisted_response_item;
+use super::search::SessionSearchQuery;
+use super::search::SessionSearchResult;
+use super::search::search_sessions;
 use crate::config::Config;
 use crate::default_client::originator;
 use crate::git_info::collect_git_info;
@@ -33,6 +37,7 @@ use codex_protocol::protocol::RolloutLine;
 use codex_protocol::protocol::SessionMeta;
 use codex_protocol::protocol::SessionMetaLine;
 use codex_protocol::protocol::SessionSource;
+use codex_protocol::protocol::SessionUsage;
 
 /// Records all [`ResponseItem`]s for a session and flushes them to disk after
 /// every update.
@@ -63,6 +68,8 @@ pub enum RolloutRecorderParams {
 
 enum RolloutCmd {
     AddItems(Vec<RolloutItem>),
+    /// Append the session's `SessionMeta` again with its cumulative usage.
+    RecordUsage(SessionUsage),
     /// Ensure all prior writes are processed; respond when flushed.
     Flush {
         ack: oneshot::Sender<()>,
@@ -101,11 +108,20 @@ impl RolloutRecorder {
         get_conversations(codex_home, page_size, cursor, allowed_sources).await
     }
 
+    /// Full-text search across all recorded conversations under the provided
+    /// Codex home directory, updating the search index first.
+    pub async fn search_conversations(
+        codex_home: &Path,
+        query: SessionSearchQuery,
+    ) -> std::io::Result<Vec<SessionSearchResult>> {
+        search_sessions(codex_home, query).await
+    }
+
     /// Attempt to create a new [`RolloutRecorder`]. If the sessions directory
     /// cannot be created or the rollout file cannot be opened we return the
     /// error so the caller can decide whether to disable persistence.
     pub async fn new(config: &Config, params: RolloutRecorderParams) -> std::io::Result<Self> {
-        let (file, rollout_path, meta) = match params {
+        let (file, rollout_path, meta, resumed_meta) = match params {
             RolloutRecorderParams::Create {
                 conversation_id,
                 instructions,
@@ -137,17 +153,23 @@ impl RolloutRecorder {
                         cli_version: env!("CARGO_PKG_VERSION").to_string(),
                         instructions,
                         source,
+                        usage: None,
                     }),
+                    None,
+                )
+            }
+            RolloutRecorderParams::Resume { path } => {
+                let resumed_meta = read_session_meta_line(&path).await?;
+                (
+                    tokio::fs::OpenOptions::new()
+                        .append(true)
+                        .open(&path)
+                        .await?,
+                    path,
+                    None,
+                    resumed_meta,
                 )
             }
-            RolloutRecorderParams::Resume { path } => (
-                tokio::fs::OpenOptions::new()
-                    .append(true)
-                    .open(&path)
-                    .await?,
-                path,
-                None,
-            ),
         };
 
         // Clone the cwd for the spawned task to collect git info asynchronously
@@ -161,7 +183,7 @@ impl RolloutRecorder {
         // Spawn a Tokio task that owns the file handle and performs async
         // writes. Using `tokio::fs::File` keeps everything on the async I/O
         // driver instead of blocking the runtime.
-        tokio::task::spawn(rollout_writer(file, rx, meta, cwd));
+        tokio::task::spawn(rollout_writer(file, rx, meta, resumed_meta, cwd));
 
         Ok(Self { tx, rollout_path })
     }
@@ -185,6 +207,15 @@ impl RolloutRecorder {
             .map_err(|e| IoError::other(format!("failed to queue rollout items: {e}")))
     }
 
+    /// Record the cumulative usage of the session by appending its
+    /// `SessionMeta` with `usage` set.
+    pub(crate) async fn record_usage(&self, usage: SessionUsage) -> std::io::Result<()> {
+        self.tx
+            .send(RolloutCmd::RecordUsage(usage))
+            .await
+            .map_err(|e| IoError::other(format!("failed to queue session usage: {e}")))
+    }
+
     /// Flush all queued writes and wait until they are committed by the writer task.
     pub async fn flush(&self) -> std::io::Result<()> {
         let (tx, rx) = oneshot::channel();
@@ -339,10 +370,27 @@ fn create_log_file(
     })
 }
 
+/// Reads the first `SessionMeta` of an existing rollout.
+async fn read_session_meta_line(path: &Path) -> std::io::Result<Option<SessionMetaLine>> {
+    let file = tokio::fs::File::open(path).await?;
+    let mut lines = tokio::io::BufReader::new(file).lines();
+    while let Some(line) = lines.next_line().await? {
+        if let Ok(RolloutLine {
+            item: RolloutItem::SessionMeta(session_meta_line),
+            ..
+        }) = serde_json::from_str::<RolloutLine>(&line)
+        {
+            return Ok(Some(session_meta_line));
+        }
+    }
+    Ok(None)
+}
+
 async fn rollout_writer(
     file: tokio::fs::File,
     mut rx: mpsc::Receiver<RolloutCmd>,
     mut meta: Option<SessionMeta>,
+    mut session_meta_line: Option<SessionMetaLine>,
     cwd: std::path::PathBuf,
 ) -> std::io::Result<()> {
     let mut writer = JsonlWriter { file };
@@ -350,15 +398,16 @@ async fn rollout_writer(
     // If we have a meta, collect git info asynchronously and write meta first
     if let Some(session_meta) = meta.take() {
         let git_info = collect_git_info(&cwd).await;
-        let session_meta_line = SessionMetaLine {
+        let line = SessionMetaLine {
             meta: session_meta,
             git: git_info,
         };
 
         // Write the SessionMeta as the first item in the file, wrapped in a rollout line
         writer
-            .write_rollout_item(RolloutItem::SessionMeta(session_meta_line))
+            .write_rollout_item(RolloutItem::SessionMeta(line.clone()))
             .await?;
+        session_meta_line = Some(line);
     }
 
     // Process rollout commands
@@ -371,6 +420,14 @@ async fn rollout_writer(
                     }
                 }
             }
+            RolloutCmd::RecordUsage(usage) => {
+                if let Some(line) = session_meta_line.as_mut() {
+                    line.meta.usage = Some(usage);
+                    writer
+                        .write_rollout_item(RolloutItem::SessionMeta(line.clone()))
+                        .await?;
+                }
+            }
             RolloutCmd::Flush { ack } => {
                 // Ensure underlying file is flushed and then ack.
                 if let Err(e) = writer.file.flush().await {
@@ -0,0 +1,759 @@
+//! Full-text search across recorded sessions.
+//!
+//! Searching reads from an index stored at `$CODEX_HOME/session_index.json`
+//! that holds, per rollout file, the user messages, agent messages, commands
+//! and patched file paths extracted from it. Rollouts are append-only, so the
+//! index remembers how many bytes of each file it has consumed and only
+//! parses what was appended since the previous search.
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::ConversationId;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::InputMessageKind;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SessionSource;
+use codex_protocol::protocol::SessionUsage;
+use codex_protocol::protocol::USER_MESSAGE_BEGIN;
+use serde::Deserialize;
+use serde::Serialize;
+use tempfile::NamedTempFile;
+
+use super::SESSIONS_SUBDIR;
+
+pub const SEARCH_INDEX_FILENAME: &str = "session_index.json";
+
+/// Bumped whenever the extracted entries change so stale indexes are rebuilt.
+const INDEX_VERSION: u32 = 2;
+/// Maximum number of hits reported per session.
+const MAX_HITS_PER_SESSION: usize = 3;
+/// Bytes of context shown on each side of a match in a snippet.
+const SNIPPET_CONTEXT: usize = 60;
+
+/// What part of a session a search hit was found in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchHitKind {
+    UserMessage,
+    AgentMessage,
+    Command,
+    FilePatch,
+}
+
+impl fmt::Display for SearchHitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            SearchHitKind::UserMessage => "user",
+            SearchHitKind::AgentMessage => "agent",
+            SearchHitKind::Command => "command",
+            SearchHitKind::FilePatch => "file",
+        };
+        f.write_str(label)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionSearchQuery {
+    /// Whitespace-separated terms, all of which must appear in a session.
+    /// Double quotes group words into a single phrase. Matching is
+    /// case-insensitive.
+    pub text: String,
+    /// Restricts matching to these kinds of entries; empty means all.
+    pub kinds: Vec<SearchHitKind>,
+    /// Maximum number of sessions returned.
+    pub limit: usize,
+    /// Only sessions started from these sources are returned; empty means all.
+    pub allowed_sources: Vec<SessionSource>,
+}
+
+/// A session matching a [`SessionSearchQuery`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionSearchResult {
+    /// Absolute path to the rollout file.
+    pub path: PathBuf,
+    pub conversation_id: Option<ConversationId>,
+    /// RFC3339 timestamp string for when the session was created, if available.
+    pub created_at: Option<String>,
+    /// RFC3339 timestamp string for the last recorded line, if available.
+    pub updated_at: Option<String>,
+    pub cwd: Option<PathBuf>,
+    /// The first user message of the session.
+    pub preview: Option<String>,
+    /// The best matching entries, most relevant first.
+    pub hits: Vec<SearchHit>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchHit {
+    pub kind: SearchHitKind,
+    /// Single-line excerpt around the first matching term.
+    pub snippet: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SearchIndex {
+    version: u32,
+    sessions: BTreeMap<PathBuf, IndexedSession>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IndexedSession {
+    /// Number of bytes of the rollout file reflected in `entries`. Always ends
+    /// on a line boundary.
+    indexed_bytes: u64,
+    conversation_id: Option<ConversationId>,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+    cwd: Option<PathBuf>,
+    source: Option<SessionSource>,
+    preview: Option<String>,
+    entries: Vec<IndexedEntry>,
+    /// The latest cumulative usage recorded by the session.
+    #[serde(default)]
+    usage: Option<SessionUsage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct IndexedEntry {
+    kind: SearchHitKind,
+    text: String,
+}
+
+/// Brings the index up to date with the rollouts under `codex_home` and
+/// returns the sessions matching `query`, best matches first.
+pub async fn search_sessions(
+    codex_home: &Path,
+    query: SessionSearchQuery,
+) -> io::Result<Vec<SessionSearchResult>> {
+    let codex_home = codex_home.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let index = update_index(&codex_home)?;
+        Ok(run_query(&index, &query))
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// Usage recorded by one session, as kept in the index.
+pub(super) struct RecordedUsage {
+    pub(super) created_at: Option<String>,
+    pub(super) cwd: Option<PathBuf>,
+    pub(super) usage: SessionUsage,
+}
+
+/// Brings the index up to date and returns the usage of every session that
+/// recorded one.
+pub(super) fn recorded_usage(codex_home: &Path) -> io::Result<Vec<RecordedUsage>> {
+    let index = update_index(codex_home)?;
+    Ok(index
+        .sessions
+        .into_values()
+        .filter_map(|session| {
+            Some(RecordedUsage {
+                usage: session.usage?,
+                created_at: session.created_at,
+                cwd: session.cwd,
+            })
+        })
+        .collect())
+}
+
+fn index_path(codex_home: &Path) -> PathBuf {
+    codex_home.join(SEARCH_INDEX_FILENAME)
+}
+
+fn load_index(codex_home: &Path) -> SearchIndex {
+    let Ok(contents) = fs::read_to_string(index_path(codex_home)) else {
+        return SearchIndex::default();
+    };
+    match serde_json::from_str::<SearchIndex>(&contents) {
+        Ok(index) if index.version == INDEX_VERSION => index,
+        // Unreadable or outdated indexes are rebuilt from scratch.
+        _ => SearchIndex::default(),
+    }
+}
+
+fn save_index(codex_home: &Path, index: &SearchIndex) -> io::Result<()> {
+    fs::create_dir_all(codex_home)?;
+    let json =
+        serde_json::to_string(index).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+    let tmp_file = NamedTempFile::new_in(codex_home)?;
+    fs::write(tmp_file.path(), json)?;
+    tmp_file.persist(index_path(codex_home))?;
+    Ok(())
+}
+
+fn update_index(codex_home: &Path) -> io::Result<SearchIndex> {
+    let mut index = load_index(codex_home);
+    let mut changed = index.version != INDEX_VERSION;
+    index.version = INDEX_VERSION;
+
+    let mut rollouts = Vec::new();
+    collect_rollout_files(&codex_home.join(SESSIONS_SUBDIR), &mut rollouts)?;
+
+    let live: HashSet<&PathBuf> = rollouts.iter().collect();
+    let before = index.sessions.len();
+    index.sessions.retain(|path, _| live.contains(path));
+    changed |= index.sessions.len() != before;
+
+    for path in &rollouts {
+        let Ok(metadata) = fs::metadata(path) else {
+            continue;
+        };
+        let session = index.sessions.entry(path.clone()).or_default();
+        let len = metadata.len();
+        if len == session.indexed_bytes {
+            continue;
+        }
+        if len < session.indexed_bytes {
+            // The file was rewritten rather than appended to.
+            *session = IndexedSession::default();
+        }
+        match index_appended_lines(path, session) {
+            Ok(()) => changed = true,
+            Err(e) => tracing::warn!("failed to index {}: {e}", path.display()),
+        }
+    }
+
+    if changed {
+        save_index(codex_home, &index)?;
+    }
+    Ok(index)
+}
+
+/// Recursively collects `rollout-*.jsonl` files below `dir`.
+fn collect_rollout_files(dir: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_rollout_files(&path, out)?;
+        } else if file_type.is_file()
+            && let Some(name) = path.file_name().and_then(|n| n.to_str())
+            && name.starts_with("rollout-")
+            && name.ends_with(".jsonl")
+        {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Parses the complete lines appended to `path` since the last update. A
+/// trailing partial line is left for the next update.
+fn index_appended_lines(path: &Path, session: &mut IndexedSession) -> io::Result<()> {
+    let mut file = fs::File::open(path)?;
+    file.seek(SeekFrom::Start(session.indexed_bytes))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+    let Some(last_newline) = buf.iter().rposition(|&b| b == b'\n') else {
+        return Ok(());
+    };
+    let complete = &buf[..=last_newline];
+    for line in String::from_utf8_lossy(complete).lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Ok(rollout_line) = serde_json::from_str::<RolloutLine>(trimmed) {
+            index_rollout_line(session, rollout_line);
+        }
+    }
+    session.indexed_bytes += complete.len() as u64;
+    Ok(())
+}
+
+fn index_rollout_line(session: &mut IndexedSession, line: RolloutLine) {
+    let RolloutLine { timestamp, item } = line;
+    session.updated_at = Some(timestamp.clone());
+    match item {
+        RolloutItem::SessionMeta(meta_line) => {
+            let meta = meta_line.meta;
+            // Forked rollouts also contain the meta lines of their parent; the
+            // first one describes this session.
+            if session.conversation_id.is_some_and(|id| id != meta.id) {
+                return;
+            }
+            if meta.usage.is_some() {
+                session.usage = meta.usage;
+            }
+            session.conversation_id = Some(meta.id);
+            session.cwd = Some(meta.cwd);
+            session.source = Some(meta.source);
+            if session.created_at.is_none() {
+                session.created_at = Some(if meta.timestamp.is_empty() {
+                    timestamp
+                } else {
+                    meta.timestamp
+                });
+            }
+        }
+        RolloutItem::EventMsg(EventMsg::UserMessage(event)) => {
+            if !matches!(event.kind, None | Some(InputMessageKind::Plain)) {
+                return;
+            }
+            let message = match event.message.find(USER_MESSAGE_BEGIN) {
+                Some(idx) => event.message[idx + USER_MESSAGE_BEGIN.len()..].trim(),
+                None => event.message.trim(),
+            };
+            if message.is_empty() {
+                return;
+            }
+            if session.preview.is_none() {
+                session.preview = Some(message.to_string());
+            }
+            push_entry(session, SearchHitKind::UserMessage, message.to_string());
+        }
+        RolloutItem::EventMsg(EventMsg::AgentMessage(event)) => {
+            push_entry(session, SearchHitKind::AgentMessage, event.message);
+        }
+        RolloutItem::ResponseItem(item) => index_response_item(session, item),
+        RolloutItem::EventMsg(_) | RolloutItem::TurnContext(_) | RolloutItem::Compacted(_) => {}
+    }
+}
+
+fn index_response_item(session: &mut IndexedSession, item: ResponseItem) {
+    match item {
+        ResponseItem::LocalShellCall {
+            action: LocalShellAction::Exec(exec),
+            ..
+        } => push_command(session, &exec.command),
+        ResponseItem::FunctionCall {
+            name, arguments, ..
+        } => {
+            let Ok(args) = serde_json::from_str::<serde_json::Value>(&arguments) else {
+                return;
+            };
+            if name == "apply_patch" {
+                if let Some(patch) = args.get("input").and_then(|v| v.as_str()) {
+                    push_patched_files(session, patch);
+                }
+                return;
+            }
+            if let Some(command) = args
+                .get("command")
+                .or_else(|| args.get("input"))
+                .and_then(|v| v.as_array())
+            {
+                let command: Vec<String> = command
+                    .iter()
+                    .filter_map(|arg| arg.as_str().map(str::to_string))
+                    .collect();
+                push_command(session, &command);
+            } else if let Some(cmd) = args.get("cmd").and_then(|v| v.as_str()) {
+                push_command(session, &[cmd.to_string()]);
+            }
+        }
+        ResponseItem::CustomToolCall { name, input, .. } if name == "apply_patch" => {
+            push_patched_files(session, &input);
+        }
+        _ => {}
+    }
+}
+
+fn push_command(session: &mut IndexedSession, command: &[String]) {
+    let text = match command {
+        [shell, flag, script] if (flag == "-lc" || flag == "-c") && shell.ends_with("sh") => {
+            script.clone()
+        }
+        _ => shlex::try_join(command.iter().map(String::as_str))
+            .unwrap_or_else(|_| command.join(" ")),
+    };
+    if text.is_empty() {
+        return;
+    }
+    // `apply_patch` is also invoked through the shell with a heredoc.
+    push_patched_files(session, &text);
+    push_entry(session, SearchHitKind::Command, text);
+}
+
+fn push_patched_files(session: &mut IndexedSession, patch: &str) {
+    const FILE_MARKERS: &[&str] = &[
+        "*** Add File: ",
+        "*** Update File: ",
+        "*** Delete File: ",
+        "*** Move to: ",
+    ];
+    for line in patch.lines() {
+        let line = line.trim();
+        if let Some(path) = FILE_MARKERS
+            .iter()
+            .find_map(|marker| line.strip_prefix(marker))
+        {
+            let entry = IndexedEntry {
+                kind: SearchHitKind::FilePatch,
+                text: path.trim().to_string(),
+            };
+            if !session.entries.contains(&entry) {
+                session.entries.push(entry);
+            }
+        }
+    }
+}
+
+fn push_entry(session: &mut IndexedSession, kind: SearchHitKind, text: String) {
+    if !text.trim().is_empty() {
+        session.entries.push(IndexedEntry { kind, text });
+    }
+}
+
+fn run_query(index: &SearchIndex, query: &SessionSearchQuery) -> Vec<SessionSearchResult> {
+    let terms = parse_terms(&query.text);
+    if terms.is_empty() || query.limit == 0 {
+        return Vec::new();
+    }
+
+    let mut scored: Vec<(usize, SessionSearchResult)> = Vec::new();
+    for (path, session) in &index.sessions {
+        if !query.allowed_sources.is_empty()
+            && !session
+                .source
+                .as_ref()
+                .is_some_and(|source| query.allowed_sources.contains(source))
+        {
+            continue;
+        }
+
+        let mut covered = vec![false; terms.len()];
+        let mut matches: Vec<(usize, &IndexedEntry, &str)> = Vec::new();
+        for entry in &session.entries {
+            if !query.kinds.is_empty() && !query.kinds.contains(&entry.kind) {
+                continue;
+            }
+            let lower = entry.text.to_lowercase();
+            let mut matched = 0;
+            let mut first_term = None;
+            for (i, term) in terms.iter().enumerate() {
+                if lower.contains(term.as_str()) {
+                    covered[i] = true;
+                    matched += 1;
+                    first_term.get_or_insert(term.as_str());
+                }
+            }
+            if let Some(term) = first_term {
+                matches.push((matched, entry, term));
+            }
+        }
+        if !covered.iter().all(|c| *c) {
+            continue;
+        }
+
+        let score = matches.iter().map(|(matched, _, _)| matched).sum();
+        // Stable sort keeps entries with equal relevance in session order.
+        matches.sort_by(|a, b| b.0.cmp(&a.0));
+        let hits = matches
+            .into_iter()
+            .take(MAX_HITS_PER_SESSION)
+            .map(|(_, entry, term)| SearchHit {
+                kind: entry.kind,
+                snippet: snippet(&entry.text, term),
+            })
+            .collect();
+        scored.push((
+            score,
+            SessionSearchResult {
+                path: path.clone(),
+                conversation_id: session.conversation_id,
+                created_at: session.created_at.clone(),
+                updated_at: session.updated_at.clone(),
+                cwd: session.cwd.clone(),
+                preview: session.preview.clone(),
+                hits,
+            },
+        ));
+    }
+
+    scored.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then_with(|| b.updated_at.cmp(&a.updated_at))
+    });
+    scored
+        .into_iter()
+        .take(query.limit)
+        .map(|(_, result)| result)
+        .collect()
+}
+
+/// Splits a query into lowercase terms; double-quoted text is kept as one
+/// phrase.
+fn parse_terms(query: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for (i, part) in query.split('"').enumerate() {
+        if i % 2 == 1 {
+            let phrase = part.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !phrase.is_empty() {
+                terms.push(phrase.to_lowercase());
+            }
+        } else {
+            terms.extend(part.split_whitespace().map(str::to_lowercase));
+        }
+    }
+    terms
+}
+
+/// Returns a single-line excerpt of `text` around the first occurrence of
+/// the (lowercase) `term`.
+fn snippet(text: &str, term: &str) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let lower = collapsed.to_lowercase();
+    // Lowercasing may change byte offsets for some scripts; fall back to the
+    // start of the text in that case.
+    let pos = if lower.len() == collapsed.len() {
+        lower.find(term).unwrap_or(0)
+    } else {
+        0
+    };
+
+    let mut start = pos.saturating_sub(SNIPPET_CONTEXT);
+    while !collapsed.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (pos + term.len() + SNIPPET_CONTEXT).min(collapsed.len());
+    while !collapsed.is_char_boundary(end) {
+        end += 1;
+    }
+
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    out.push_str(collapsed[start..end].trim());
+    if end < collapsed.len() {
+        out.push('…');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn write_rollout(codex_home: &Path, name: &str, lines: &[serde_json::Value]) -> PathBuf {
+        let dir = codex_home.join(SESSIONS_SUBDIR).join("2025/01/03");
+        fs::create_dir_all(&dir).expect("create sessions dir");
+        let path = dir.join(name);
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .expect("open rollout");
+        for line in lines {
+            writeln!(file, "{line}").expect("write rollout line");
+        }
+        path
+    }
+
+    fn meta_line(id: &str, ts: &str) -> serde_json::Value {
+        json!({
+            "timestamp": ts,
+            "type": "session_meta",
+            "payload": {
+                "id": id,
+                "timestamp": ts,
+                "cwd": "/repo",
+                "originator": "test",
+                "cli_version": "0.0.0",
+                "instructions": null,
+                "source": "cli",
+            }
+        })
+    }
+
+    fn event_line(ts: &str, payload: serde_json::Value) -> serde_json::Value {
+        json!({ "timestamp": ts, "type": "event_msg", "payload": payload })
+    }
+
+    fn response_line(ts: &str, payload: serde_json::Value) -> serde_json::Value {
+        json!({ "timestamp": ts, "type": "response_item", "payload": payload })
+    }
+
+    fn query(text: &str) -> SessionSearchQuery {
+        SessionSearchQuery {
+            text: text.to_string(),
+            limit: 10,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn finds_messages_commands_and_patched_files() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let ts = "2025-01-03T12:00:00Z";
+        let migration = write_rollout(
+            home.path(),
+            "rollout-2025-01-03T12-00-00-00000000-0000-0000-0000-000000000001.jsonl",
+            &[
+                meta_line("00000000-0000-0000-0000-000000000001", ts),
+                event_line(
+                    ts,
+                    json!({"type": "user_message", "message": "fix the flaky migration test", "kind": "plain"}),
+                ),
+                response_line(
+                    ts,
+                    json!({
+                        "type": "function_call",
+                        "name": "shell",
+                        "arguments": "{\"command\":[\"cargo\",\"test\",\"-p\",\"db\"]}",
+                        "call_id": "c1",
+                    }),
+                ),
+                response_line(
+                    ts,
+                    json!({
+                        "type": "custom_tool_call",
+                        "name": "apply_patch",
+                        "input": "*** Begin Patch\n*** Update File: db/src/migrate.rs\n@@\n-a\n+b\n*** End Patch",
+                        "call_id": "c2",
+                    }),
+                ),
+                event_line(
+                    ts,
+                    json!({"type": "agent_message", "message": "The migration test no longer races."}),
+                ),
+            ],
+        );
+        write_rollout(
+            home.path(),
+            "rollout-2025-01-03T13-00-00-00000000-0000-0000-0000-000000000002.jsonl",
+            &[
+                meta_line(
+                    "00000000-0000-0000-0000-000000000002",
+                    "2025-01-03T13:00:00Z",
+                ),
+                event_line(
+                    "2025-01-03T13:00:00Z",
+                    json!({"type": "user_message", "message": "write docs", "kind": "plain"}),
+                ),
+            ],
+        );
+
+        let results = search_sessions(home.path(), query("Flaky migration")).await?;
+        assert_eq!(results.len(), 1);
+        let result = &results[0];
+        assert_eq!(result.path, migration);
+        assert_eq!(
+            result.preview.as_deref(),
+            Some("fix the flaky migration test")
+        );
+        assert_eq!(
+            result.hits,
+            vec![
+                SearchHit {
+                    kind: SearchHitKind::UserMessage,
+                    snippet: "fix the flaky migration test".to_string(),
+                },
+                SearchHit {
+                    kind: SearchHitKind::AgentMessage,
+                    snippet: "The migration test no longer races.".to_string(),
+                },
+            ]
+        );
+
+        let commands = search_sessions(
+            home.path(),
+            SessionSearchQuery {
+                kinds: vec![SearchHitKind::Command, SearchHitKind::FilePatch],
+                ..query("migrate.rs")
+            },
+        )
+        .await?;
+        assert_eq!(
+            commands[0].hits,
+            vec![SearchHit {
+                kind: SearchHitKind::FilePatch,
+                snippet: "db/src/migrate.rs".to_string(),
+            }]
+        );
+        assert!(
+            search_sessions(
+                home.path(),
+                SessionSearchQuery {
+                    kinds: vec![SearchHitKind::Command],
+                    ..query("flaky")
+                },
+            )
+            .await?
+            .is_empty()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn index_picks_up_appended_lines() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let ts = "2025-01-03T12:00:00Z";
+        let name = "rollout-2025-01-03T12-00-00-00000000-0000-0000-0000-000000000001.jsonl";
+        write_rollout(
+            home.path(),
+            name,
+            &[meta_line("00000000-0000-0000-0000-000000000001", ts)],
+        );
+        assert!(
+            search_sessions(home.path(), query("\"cargo fmt\""))
+                .await?
+                .is_empty()
+        );
+        assert!(home.path().join(SEARCH_INDEX_FILENAME).exists());
+
+        write_rollout(
+            home.path(),
+            name,
+            &[response_line(
+                "2025-01-03T12:05:00Z",
+                json!({
+                    "type": "local_shell_call",
+                    "call_id": "c1",
+                    "status": "completed",
+                    "action": {"type": "exec", "command": ["bash", "-lc", "cargo fmt --all"]},
+                }),
+            )],
+        );
+        let results = search_sessions(home.path(), query("\"cargo fmt\"")).await?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].updated_at.as_deref(),
+            Some("2025-01-03T12:05:00Z")
+        );
+        assert_eq!(
+            results[0].hits,
+            vec![SearchHit {
+                kind: SearchHitKind::Command,
+                snippet: "cargo fmt --all".to_string(),
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn snippet_centers_on_match() {
+        let text = format!("{} needle {}", "a ".repeat(100), "b ".repeat(100));
+        let snippet = snippet(&text, "needle");
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(snippet.contains("needle"));
+        assert!(snippet.len() < 2 * SNIPPET_CONTEXT + 20);
+    }
+}
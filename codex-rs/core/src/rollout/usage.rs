@@ -0,0 +1,246 @@
+//! Spend summaries built from the usage that sessions record in their
+//! rollouts.
+//!
+//! A session appends its `SessionMeta` with the cumulative `usage` after
+//! every task, so the latest such line with the session's own id holds its
+//! total. The search index keeps that total per rollout, which lets
+//! summaries avoid re-reading every file.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::io;
+use std::path::Path;
+
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::SessionUsage;
+use codex_protocol::protocol::TokenUsage;
+use serde::Serialize;
+
+use super::search::RecordedUsage;
+use super::search::recorded_usage;
+
+/// How [`summarize_usage`] groups sessions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageGroupBy {
+    /// The UTC day the session started.
+    Day,
+    /// The working directory of the session.
+    Project,
+    Model,
+}
+
+/// Usage of the sessions in one group.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UsageSummaryRow {
+    /// The day (`YYYY-MM-DD`), project directory or model.
+    pub key: String,
+    pub sessions: usize,
+    pub token_usage: TokenUsage,
+    /// Cost in US dollars of the tokens whose price is known.
+    pub cost_usd: f64,
+    /// Tokens used by models without a known price.
+    pub unpriced_tokens: u64,
+}
+
+/// Summarizes the usage recorded under `codex_home`, optionally only for
+/// sessions started on or after the UTC day `since` (`YYYY-MM-DD`). Days are
+/// listed in order; projects and models by cost, highest first.
+pub async fn summarize_usage(
+    codex_home: &Path,
+    group_by: UsageGroupBy,
+    since: Option<String>,
+) -> io::Result<Vec<UsageSummaryRow>> {
+    let codex_home = codex_home.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let sessions = recorded_usage(&codex_home)?;
+        Ok(summarize(sessions, group_by, since.as_deref()))
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// The cumulative usage recorded in a session's rollout items, if any.
+pub(crate) fn latest_session_usage(items: &[RolloutItem]) -> Option<SessionUsage> {
+    let id = items.iter().find_map(|item| match item {
+        RolloutItem::SessionMeta(line) => Some(line.meta.id),
+        _ => None,
+    })?;
+    items.iter().rev().find_map(|item| match item {
+        RolloutItem::SessionMeta(line) if line.meta.id == id => line.meta.usage.clone(),
+        _ => None,
+    })
+}
+
+fn summarize(
+    sessions: Vec<RecordedUsage>,
+    group_by: UsageGroupBy,
+    since: Option<&str>,
+) -> Vec<UsageSummaryRow> {
+    let mut rows: BTreeMap<String, UsageSummaryRow> = BTreeMap::new();
+    for session in sessions {
+        let day = session
+            .created_at
+            .as_deref()
+            .and_then(|ts| ts.get(..10))
+            .unwrap_or("unknown")
+            .to_string();
+        if since.is_some_and(|since| day.as_str() < since) {
+            continue;
+        }
+        let project = session
+            .cwd
+            .as_ref()
+            .map(|cwd| cwd.display().to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let mut keys = BTreeSet::new();
+        for (model, usage) in &session.usage.models {
+            let key = match group_by {
+                UsageGroupBy::Day => day.clone(),
+                UsageGroupBy::Project => project.clone(),
+                UsageGroupBy::Model => model.clone(),
+            };
+            let row = rows.entry(key.clone()).or_insert_with(|| UsageSummaryRow {
+                key: key.clone(),
+                ..Default::default()
+            });
+            row.token_usage.add_assign(&usage.token_usage);
+            match usage.cost_usd {
+                Some(cost) => row.cost_usd += cost,
+                None => row.unpriced_tokens += usage.token_usage.total_tokens,
+            }
+            keys.insert(key);
+        }
+        for key in keys {
+            if let Some(row) = rows.get_mut(&key) {
+                row.sessions += 1;
+            }
+        }
+    }
+
+    let mut rows: Vec<UsageSummaryRow> = rows.into_values().collect();
+    if group_by != UsageGroupBy::Day {
+        rows.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::ConversationId;
+    use codex_protocol::protocol::ModelUsage;
+    use codex_protocol::protocol::SessionMeta;
+    use codex_protocol::protocol::SessionMetaLine;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn usage(models: &[(&str, u64, Option<f64>)]) -> SessionUsage {
+        SessionUsage {
+            models: models
+                .iter()
+                .map(|&(model, tokens, cost_usd)| {
+                    (
+                        model.to_string(),
+                        ModelUsage {
+                            token_usage: TokenUsage {
+                                total_tokens: tokens,
+                                ..Default::default()
+                            },
+                            cost_usd,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    fn session(created_at: &str, cwd: &str, usage: SessionUsage) -> RecordedUsage {
+        RecordedUsage {
+            created_at: Some(created_at.to_string()),
+            cwd: Some(PathBuf::from(cwd)),
+            usage,
+        }
+    }
+
+    fn sessions() -> Vec<RecordedUsage> {
+        vec![
+            session(
+                "2025-10-01T09:00:00.000Z",
+                "/repo/a",
+                usage(&[("gpt-5", 100, Some(1.0)), ("local", 50, None)]),
+            ),
+            session(
+                "2025-10-01T18:00:00.000Z",
+                "/repo/b",
+                usage(&[("gpt-5", 200, Some(2.5))]),
+            ),
+            session(
+                "2025-10-02T09:00:00.000Z",
+                "/repo/a",
+                usage(&[("gpt-5-mini", 10, Some(0.25))]),
+            ),
+        ]
+    }
+
+    fn keys_and_costs(rows: &[UsageSummaryRow]) -> Vec<(&str, usize, f64, u64)> {
+        rows.iter()
+            .map(|row| {
+                (
+                    row.key.as_str(),
+                    row.sessions,
+                    row.cost_usd,
+                    row.unpriced_tokens,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn groups_sessions_by_day_project_and_model() {
+        assert_eq!(
+            keys_and_costs(&summarize(sessions(), UsageGroupBy::Day, None)),
+            vec![("2025-10-01", 2, 3.5, 50), ("2025-10-02", 1, 0.25, 0)]
+        );
+        assert_eq!(
+            keys_and_costs(&summarize(sessions(), UsageGroupBy::Project, None)),
+            vec![("/repo/b", 1, 2.5, 0), ("/repo/a", 2, 1.25, 50)]
+        );
+        assert_eq!(
+            keys_and_costs(&summarize(
+                sessions(),
+                UsageGroupBy::Model,
+                Some("2025-10-02")
+            )),
+            vec![("gpt-5-mini", 1, 0.25, 0)]
+        );
+    }
+
+    #[test]
+    fn latest_usage_ignores_meta_lines_of_a_forked_parent() {
+        let meta = |id: ConversationId, usage: Option<SessionUsage>| {
+            RolloutItem::SessionMeta(SessionMetaLine {
+                meta: SessionMeta {
+                    id,
+                    usage,
+                    ..Default::default()
+                },
+                git: None,
+            })
+        };
+        let own = ConversationId::new();
+        let parent = ConversationId::new();
+        let items = vec![
+            meta(own, None),
+            meta(parent, Some(usage(&[("gpt-5", 1, Some(1.0))]))),
+            meta(own, Some(usage(&[("gpt-5", 2, Some(2.0))]))),
+            meta(parent, Some(usage(&[("gpt-5", 3, Some(3.0))]))),
+        ];
+
+        assert_eq!(
+            latest_session_usage(&items),
+            Some(usage(&[("gpt-5", 2, Some(2.0))]))
+        );
+        assert_eq!(latest_session_usage(&items[..2]), None);
+    }
+}
@@ -5,11 +5,19 @@ use std::path::PathBuf;
 
 use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::ApplyPatchFileChange;
+use codex_execpolicy::Policy;
 
+use crate::approval_rules::APPLY_PATCH_RULE_COMMAND;
+use crate::approval_rules::ApprovalRule;
+use crate::approval_rules::ApprovalScope;
+use crate::approval_rules::CommandMatcher;
+use crate::approval_rules::find_matching_rule;
 use crate::exec::SandboxType;
 
 use crate::command_safety::is_dangerous_command::command_might_be_dangerous;
 use crate::command_safety::is_safe_command::is_known_safe_command;
+use crate::exec_policy::ExecPolicyVerdict;
+use crate::exec_policy::evaluate_exec_policy;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
 
@@ -30,6 +38,7 @@ pub fn assess_patch_safety(
     policy: AskForApproval,
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
+    approval_rules: &[ApprovalRule],
 ) -> SafetyCheck {
     if action.is_empty() {
         return SafetyCheck::Reject {
@@ -37,6 +46,29 @@ pub fn assess_patch_safety(
         };
     }
 
+    // The user approved edits to this project in an earlier prompt ("don't
+    // ask again for this project"); honor that as long as every path the
+    // patch touches stays inside the approved project. The patch still runs
+    // in the platform sandbox: the rule vouches for the paths, not for
+    // lifting isolation.
+    if is_patch_approved_by_rule(action, approval_rules, cwd) {
+        match get_platform_sandbox() {
+            Some(sandbox_type) => {
+                return SafetyCheck::AutoApprove {
+                    sandbox_type,
+                    user_explicitly_approved: true,
+                };
+            }
+            None if sandbox_policy == &SandboxPolicy::DangerFullAccess => {
+                return SafetyCheck::AutoApprove {
+                    sandbox_type: SandboxType::None,
+                    user_explicitly_approved: true,
+                };
+            }
+            None => {}
+        }
+    }
+
     match policy {
         AskForApproval::OnFailure | AskForApproval::Never | AskForApproval::OnRequest => {
             // Continue to see if this can be auto-approved.
@@ -83,23 +115,81 @@ pub fn assess_patch_safety(
     }
 }
 
+/// Only project-scoped rules for exactly `apply_patch` count: a global rule,
+/// or a prefix or pattern that happens to match `apply_patch`, was never an
+/// approval to edit files anywhere on disk.
+fn is_patch_approved_by_rule(
+    action: &ApplyPatchAction,
+    approval_rules: &[ApprovalRule],
+    cwd: &Path,
+) -> bool {
+    let command = [APPLY_PATCH_RULE_COMMAND.to_string()];
+    approval_rules.iter().any(|rule| {
+        let is_project_patch_rule = match (&rule.matcher, &rule.scope) {
+            (CommandMatcher::Exact { command: expected }, ApprovalScope::Project { .. }) => {
+                expected.as_slice() == command.as_slice()
+            }
+            _ => false,
+        };
+        is_project_patch_rule
+            && rule.scope.applies_to(cwd)
+            && action.changes().iter().all(|(path, change)| {
+                let move_path = match change {
+                    ApplyPatchFileChange::Update { move_path, .. } => move_path.as_ref(),
+                    ApplyPatchFileChange::Add { .. } | ApplyPatchFileChange::Delete { .. } => None,
+                };
+                // Refuse `..` outright rather than normalizing it away: a
+                // symlinked directory inside the project can make
+                // `link/../file` resolve outside of it. Absolute paths are
+                // kept by `join` and must fall inside the project as well.
+                std::iter::once(path).chain(move_path).all(|path| {
+                    !path
+                        .components()
+                        .any(|component| component == Component::ParentDir)
+                        && rule.scope.applies_to(&cwd.join(path))
+                })
+            })
+    })
+}
+
 /// For a command to be run _without_ a sandbox, one of the following must be
 /// true:
 ///
-/// - the user has explicitly approved the command
-/// - the command is on the "known safe" list
+/// - the user has explicitly approved the command, for this session or via a
+///   persisted approval rule that applies in `cwd`
+/// - the command is on the "known safe" list, or `exec_policy` matches it
+///   and it does not write files
 /// - `DangerFullAccess` was specified and `UnlessTrusted` was not
+///
+/// A command forbidden by `exec_policy`, or one that redirects output outside
+/// the writable roots of a `workspace-write` sandbox, is always rejected.
+#[allow(clippy::too_many_arguments)]
 pub fn assess_command_safety(
     command: &[String],
     approval_policy: AskForApproval,
     sandbox_policy: &SandboxPolicy,
+    sandbox_cwd: &Path,
     approved: &HashSet<Vec<String>>,
+    approval_rules: &[ApprovalRule],
+    cwd: &Path,
+    exec_policy: &Policy,
     with_escalated_permissions: bool,
 ) -> SafetyCheck {
+    let exec_policy_verdict =
+        evaluate_exec_policy(exec_policy, command, sandbox_policy, sandbox_cwd, cwd);
+    if let ExecPolicyVerdict::Forbidden { reason } = exec_policy_verdict {
+        return SafetyCheck::Reject {
+            reason: format!("forbidden by execpolicy: {reason}"),
+        };
+    }
+
+    let user_explicitly_approved =
+        approved.contains(command) || find_matching_rule(approval_rules, command, cwd).is_some();
+
     // Some commands look dangerous. Even if they are run inside a sandbox,
     // unless the user has explicitly approved them, we should ask,
     // or reject if the approval_policy tells us not to ask.
-    if command_might_be_dangerous(command) && !approved.contains(command) {
+    if command_might_be_dangerous(command) && !user_explicitly_approved {
         if approval_policy == AskForApproval::Never {
             return SafetyCheck::Reject {
                 reason: "dangerous command detected; rejected by user approval settings"
@@ -124,8 +214,10 @@ pub fn assess_command_safety(
     // `approved.contains(command)` is `true`, the user may have approved it for
     // the session _because_ they know it needs to run outside a sandbox.
 
-    if is_known_safe_command(command) || approved.contains(command) {
-        let user_explicitly_approved = approved.contains(command);
+    if is_known_safe_command(command)
+        || exec_policy_verdict == ExecPolicyVerdict::Safe
+        || user_explicitly_approved
+    {
         return SafetyCheck::AutoApprove {
             sandbox_type: SandboxType::None,
             user_explicitly_approved,
@@ -211,6 +303,22 @@ pub fn get_platform_sandbox() -> Option<SandboxType> {
     }
 }
 
+/// Normalize a path by removing `.` and resolving `..` without touching the
+/// filesystem (works even if the file does not exist).
+pub(crate) fn normalize_path(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for comp in path.components() {
+        match comp {
+            Component::ParentDir => {
+                out.pop();
+            }
+            Component::CurDir => { /* skip */ }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
 fn is_write_patch_constrained_to_writable_paths(
     action: &ApplyPatchAction,
     sandbox_policy: &SandboxPolicy,
@@ -227,22 +335,6 @@ fn is_write_patch_constrained_to_writable_paths(
         SandboxPolicy::WorkspaceWrite { .. } => sandbox_policy.get_writable_roots_with_cwd(cwd),
     };
 
-    // Normalize a path by removing `.` and resolving `..` without touching the
-    // filesystem (works even if the file does not exist).
-    fn normalize(path: &Path) -> Option<PathBuf> {
-        let mut out = PathBuf::new();
-        for comp in path.components() {
-            match comp {
-                Component::ParentDir => {
-                    out.pop();
-                }
-                Component::CurDir => { /* skip */ }
-                other => out.push(other.as_os_str()),
-            }
-        }
-        Some(out)
-    }
-
     // Determine whether `path` is inside **any** writable root. Both `path`
     // and roots are converted to absolute, normalized forms before the
     // prefix check.
@@ -252,10 +344,7 @@ fn is_write_patch_constrained_to_writable_paths(
         } else {
             cwd.join(p)
         };
-        let abs = match normalize(&abs) {
-            Some(v) => v,
-            None => return false,
-        };
+        let abs = normalize_path(&abs);
 
         writable_roots
             .iter()
@@ -288,6 +377,8 @@ fn is_write_patch_constrained_to_writable_paths(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use codex_execpolicy::PolicyParser;
+    use codex_execpolicy::get_default_policy;
     use tempfile::TempDir;
 
     #[test]
@@ -309,6 +400,7 @@ mod tests {
         let policy_workspace_only = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![],
             network_access: false,
+            network_allowlist: vec![],
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
         };
@@ -330,6 +422,7 @@ mod tests {
         let policy_with_parent = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![parent],
             network_access: false,
+            network_allowlist: vec![],
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
         };
@@ -340,6 +433,144 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn project_patch_rule_approves_patches_inside_the_project() {
+        let tmp = TempDir::new().unwrap();
+        let project = tmp.path().join("project");
+        std::fs::create_dir_all(&project).unwrap();
+        let rules = vec![ApprovalRule {
+            id: "patches".to_string(),
+            matcher: crate::approval_rules::CommandMatcher::Exact {
+                command: vec![APPLY_PATCH_RULE_COMMAND.to_string()],
+            },
+            scope: crate::approval_rules::ApprovalScope::Project {
+                path: project.clone(),
+            },
+            created_at: 0,
+        }];
+
+        let inside = ApplyPatchAction::new_add_for_test(&project.join("a.txt"), "".to_string());
+        let expected = match get_platform_sandbox() {
+            Some(sandbox_type) => SafetyCheck::AutoApprove {
+                sandbox_type,
+                user_explicitly_approved: true,
+            },
+            None => SafetyCheck::AskUser,
+        };
+        assert_eq!(
+            assess_patch_safety(
+                &inside,
+                AskForApproval::UnlessTrusted,
+                &SandboxPolicy::ReadOnly,
+                &project,
+                &rules,
+            ),
+            expected
+        );
+
+        let outside = ApplyPatchAction::new_add_for_test(&tmp.path().join("b.txt"), "".to_string());
+        assert_eq!(
+            assess_patch_safety(
+                &outside,
+                AskForApproval::UnlessTrusted,
+                &SandboxPolicy::ReadOnly,
+                &project,
+                &rules,
+            ),
+            SafetyCheck::AskUser
+        );
+    }
+
+    #[test]
+    fn only_project_scoped_exact_patch_rules_approve_patches() {
+        let tmp = TempDir::new().unwrap();
+        let project = tmp.path().join("project");
+        std::fs::create_dir_all(&project).unwrap();
+        let patch_command = vec![APPLY_PATCH_RULE_COMMAND.to_string()];
+        let project_scope = ApprovalScope::Project {
+            path: project.clone(),
+        };
+        let rules_that_do_not_approve = [
+            (
+                CommandMatcher::Exact {
+                    command: patch_command.clone(),
+                },
+                ApprovalScope::Global,
+            ),
+            (
+                CommandMatcher::Prefix {
+                    command: patch_command.clone(),
+                },
+                project_scope.clone(),
+            ),
+            (
+                CommandMatcher::Pattern {
+                    pattern: vec!["*".to_string()],
+                },
+                project_scope,
+            ),
+        ];
+
+        for (matcher, scope) in rules_that_do_not_approve {
+            let rules = vec![ApprovalRule {
+                id: "patches".to_string(),
+                matcher: matcher.clone(),
+                scope: scope.clone(),
+                created_at: 0,
+            }];
+            for path in [project.join("a.txt"), PathBuf::from("/etc/passwd")] {
+                let action = ApplyPatchAction::new_add_for_test(&path, "".to_string());
+                assert_eq!(
+                    assess_patch_safety(
+                        &action,
+                        AskForApproval::UnlessTrusted,
+                        &SandboxPolicy::ReadOnly,
+                        &project,
+                        &rules,
+                    ),
+                    SafetyCheck::AskUser,
+                    "{matcher:?} in {scope:?} must not approve {path:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn project_patch_rule_does_not_approve_parent_dir_paths() {
+        let tmp = TempDir::new().unwrap();
+        let project = tmp.path().join("project");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        let rules = vec![ApprovalRule {
+            id: "patches".to_string(),
+            matcher: crate::approval_rules::CommandMatcher::Exact {
+                command: vec![APPLY_PATCH_RULE_COMMAND.to_string()],
+            },
+            scope: crate::approval_rules::ApprovalScope::Project {
+                path: project.clone(),
+            },
+            created_at: 0,
+        }];
+
+        for path in [
+            project.join("../../.bashrc"),
+            project.join("src/../../outside.txt"),
+            project.join("src/../inside.txt"),
+        ] {
+            let action = ApplyPatchAction::new_add_for_test(&path, "".to_string());
+            assert_eq!(
+                assess_patch_safety(
+                    &action,
+                    AskForApproval::UnlessTrusted,
+                    &SandboxPolicy::ReadOnly,
+                    &project,
+                    &rules,
+                ),
+                SafetyCheck::AskUser,
+                "{path:?} must not be approved by the project rule"
+            );
+        }
+    }
+
     #[test]
     fn test_request_escalated_privileges() {
         // Should not be a trusted command
@@ -353,7 +584,11 @@ mod tests {
             &command,
             approval_policy,
             &sandbox_policy,
+            Path::new("."),
             &approved,
+            &[],
+            Path::new("."),
+            &get_default_policy().expect("load default policy"),
             request_escalated_privileges,
         );
 
@@ -373,7 +608,11 @@ mod tests {
             &command,
             approval_policy,
             &sandbox_policy,
+            Path::new("."),
             &approved,
+            &[],
+            Path::new("."),
+            &get_default_policy().expect("load default policy"),
             request_escalated_privileges,
         );
 
@@ -398,7 +637,11 @@ mod tests {
             &command,
             approval_policy,
             &sandbox_policy,
+            Path::new("."),
             &approved,
+            &[],
+            Path::new("."),
+            &get_default_policy().expect("load default policy"),
             request_escalated_privileges,
         );
 
@@ -423,7 +666,11 @@ mod tests {
             &command,
             approval_policy,
             &sandbox_policy,
+            Path::new("."),
             &approved,
+            &[],
+            Path::new("."),
+            &get_default_policy().expect("load default policy"),
             request_escalated_privileges,
         );
 
@@ -436,4 +683,50 @@ mod tests {
         };
         assert_eq!(safety_check, expected);
     }
+
+    #[test]
+    fn exec_policy_rules_decide_before_approval_policy() {
+        let mut exec_policy = get_default_policy().expect("load default policy");
+        exec_policy.merge(
+            PolicyParser::new(
+                "team.policy",
+                r#"
+define_program(program="./x.py", args=["check"])
+define_program(program="deploy", forbidden="deploys go through CI")
+"#,
+            )
+            .parse()
+            .expect("parse team policy"),
+        );
+        let approved: HashSet<Vec<String>> = HashSet::new();
+        let assess = |command: &[&str]| {
+            let command: Vec<String> = command.iter().map(ToString::to_string).collect();
+            assess_command_safety(
+                &command,
+                AskForApproval::UnlessTrusted,
+                &SandboxPolicy::ReadOnly,
+                Path::new("."),
+                &approved,
+                &[],
+                Path::new("."),
+                &exec_policy,
+                false,
+            )
+        };
+
+        assert_eq!(
+            assess(&["./x.py", "check"]),
+            SafetyCheck::AutoApprove {
+                sandbox_type: SandboxType::None,
+                user_explicitly_approved: false,
+            }
+        );
+        assert_eq!(
+            assess(&["bash", "-lc", "./x.py check && deploy"]),
+            SafetyCheck::Reject {
+                reason: "forbidden by execpolicy: deploys go through CI".to_string(),
+            }
+        );
+        assert_eq!(assess(&["./x.py", "fix"]), SafetyCheck::AskUser);
+    }
 }
@@ -1,9 +1,14 @@
+use std::collections::HashMap;
+
 use crate::RolloutRecorder;
+use crate::config_types::ModelPricing;
 use crate::exec_command::ExecSessionManager;
 use crate::executor::Executor;
+use crate::hooks::HookRunner;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_notification::UserNotifier;
+use codex_otel::otel_event_manager::OtelSpanGuard;
 use tokio::sync::Mutex;
 
 pub(crate) struct SessionServices {
@@ -11,8 +16,13 @@ pub(crate) struct SessionServices {
     pub(crate) session_manager: ExecSessionManager,
     pub(crate) unified_exec_manager: UnifiedExecSessionManager,
     pub(crate) notifier: UserNotifier,
+    pub(crate) hooks: HookRunner,
     pub(crate) rollout: Mutex<Option<RolloutRecorder>>,
     pub(crate) user_shell: crate::shell::Shell,
     pub(crate) show_raw_agent_reasoning: bool,
+    pub(crate) model_pricing: HashMap<String, ModelPricing>,
+    pub(crate) session_budget_usd: Option<f64>,
     pub(crate) executor: Executor,
+    /// Ends the session span when the session is dropped, even without `Op::Shutdown`.
+    pub(crate) session_span: OtelSpanGuard,
 }
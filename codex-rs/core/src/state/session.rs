@@ -1,11 +1,21 @@
 //! Session-wide mutable state.
 
+use std::collections::HashMap;
+
 use codex_protocol::models::ResponseItem;
 
+use crate::client_common::Prompt;
+use crate::config_types::ModelPricing;
 use crate::conversation_history::ConversationHistory;
+use crate::model_family::ModelFamily;
+use crate::pricing::add_usage;
+use crate::project_doc::ScopedProjectDocs;
+use crate::protocol::ContextBreakdown;
 use crate::protocol::RateLimitSnapshot;
+use crate::protocol::SessionUsage;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
+use crate::token_estimate::TokenEstimator;
 
 /// Persistent, session-scoped state previously stored directly on `Session`.
 #[derive(Default)]
@@ -13,6 +23,15 @@ pub(crate) struct SessionState {
     pub(crate) history: ConversationHistory,
     pub(crate) token_info: Option<TokenUsageInfo>,
     pub(crate) latest_rate_limits: Option<RateLimitSnapshot>,
+    /// Estimate of the most recent request sent to the model.
+    pub(crate) context_breakdown: Option<ContextBreakdown>,
+    token_estimator: TokenEstimator,
+    pub(crate) scoped_project_docs: ScopedProjectDocs,
+    /// Tokens and cost per model since the session started, including
+    /// earlier runs of a resumed session.
+    pub(crate) usage: SessionUsage,
+    /// The usage last written to the rollout.
+    persisted_usage: Option<SessionUsage>,
 }
 
 impl SessionState {
@@ -39,6 +58,8 @@ impl SessionState {
 
     pub(crate) fn replace_history(&mut self, items: Vec<ResponseItem>) {
         self.history.replace(items);
+        // Scoped docs injected into the old history are gone; allow them again.
+        self.scoped_project_docs.reset();
     }
 
     // Token/rate limit helpers
@@ -54,6 +75,31 @@ impl SessionState {
         );
     }
 
+    pub(crate) fn add_model_usage(
+        &mut self,
+        model: &str,
+        usage: &TokenUsage,
+        pricing: &HashMap<String, ModelPricing>,
+    ) {
+        add_usage(&mut self.usage, pricing, model, usage);
+    }
+
+    /// Continues the usage of a resumed session from what its rollout recorded.
+    pub(crate) fn restore_usage(&mut self, usage: SessionUsage) {
+        self.persisted_usage = Some(usage.clone());
+        self.usage = usage;
+    }
+
+    /// Returns the usage if it changed since it was last written to the
+    /// rollout, marking it as written.
+    pub(crate) fn take_unpersisted_usage(&mut self) -> Option<SessionUsage> {
+        if self.usage.is_empty() || self.persisted_usage.as_ref() == Some(&self.usage) {
+            return None;
+        }
+        self.persisted_usage = Some(self.usage.clone());
+        Some(self.usage.clone())
+    }
+
     pub(crate) fn set_rate_limits(&mut self, snapshot: RateLimitSnapshot) {
         self.latest_rate_limits = Some(snapshot);
     }
@@ -64,6 +110,19 @@ impl SessionState {
         (self.token_info.clone(), self.latest_rate_limits.clone())
     }
 
+    /// Estimates the tokens `prompt` will consume, remembering the breakdown
+    /// for status reporting. Returns the estimated total.
+    pub(crate) fn estimate_prompt_tokens(
+        &mut self,
+        prompt: &Prompt,
+        model_family: &ModelFamily,
+    ) -> u64 {
+        let breakdown = self.token_estimator.estimate_prompt(prompt, model_family);
+        let total = breakdown.total();
+        self.context_breakdown = Some(breakdown);
+        total
+    }
+
     pub(crate) fn set_token_usage_full(&mut self, context_window: u64) {
         match &mut self.token_info {
             Some(info) => info.fill_to_context_window(context_window),
@@ -74,14 +74,22 @@ impl Session {
             let ctx = Arc::clone(&turn_context);
             let task_for_run = Arc::clone(&task);
             let sub_clone = sub_id.clone();
-            tokio::spawn(async move {
+            let turn_span = turn_context
+                .client
+                .get_otel_event_manager()
+                .turn_span(&self.services.session_span, &sub_id);
+            let task_span = turn_span.clone();
+            tokio::spawn(task_span.instrument(async move {
+                // Dropped with the future if the task is aborted.
+                let turn_span = turn_span.end_on_drop();
                 let last_agent_message = task_for_run
                     .run(Arc::clone(&session_ctx), ctx, sub_clone.clone(), input)
                     .await;
+                drop(turn_span);
                 // Emit completion uniformly from spawn site so all tasks share the same lifecycle.
                 let sess = session_ctx.clone_session();
                 sess.on_task_finished(sub_clone, last_agent_message).await;
-            })
+            }))
             .abort_handle()
         };
 
@@ -111,6 +119,7 @@ impl Session {
             *active = None;
         }
         drop(active);
+        self.persist_session_usage().await;
         let event = Event {
             id: sub_id,
             msg: EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }),
@@ -0,0 +1,251 @@
+//! Local token estimates for model requests.
+//!
+//! Providers only report token usage once a response completes. Estimating
+//! the prompt with the model's tokenizer lets the session notice that the
+//! history is about to overflow the context window before sending it, and
+//! lets clients show what is consuming the context.
+
+use std::collections::HashMap;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::OnceLock;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ReasoningItemContent;
+use codex_protocol::models::ReasoningItemReasoningSummary;
+use codex_protocol::models::ResponseItem;
+use codex_utils_tokenizer::EncodingKind;
+use codex_utils_tokenizer::Tokenizer;
+use tracing::warn;
+
+use crate::client_common::Prompt;
+use crate::model_family::ModelFamily;
+use crate::protocol::ContextBreakdown;
+use crate::tools::spec::create_tools_json_for_responses_api;
+
+/// Images are billed by tile, which cannot be derived from a data URL without
+/// decoding it; this is the cost of a 1024x1024 image at high detail.
+const IMAGE_TOKEN_ESTIMATE: u64 = 765;
+
+/// Tokens spent on the framing of each input item (role, separators).
+const ITEM_OVERHEAD_TOKENS: u64 = 4;
+
+/// Shorter texts are cheaper to tokenize than to look up.
+const MIN_CACHED_BYTES: usize = 256;
+
+/// Bound on remembered counts; the cache starts over once it is reached.
+const MAX_CACHED_COUNTS: usize = 16_384;
+
+/// Returns the shared tokenizer for `model`. See [`tokenizer`].
+pub(crate) fn tokenizer_for_model(model: &str) -> Option<&'static Tokenizer> {
+    tokenizer(EncodingKind::for_model(model))
+}
+
+/// Returns the shared tokenizer for `kind`, loading its vocabulary on first
+/// use. Returns `None` if the vocabulary fails to load, in which case callers
+/// fall back to a byte-based estimate.
+pub(crate) fn tokenizer(kind: EncodingKind) -> Option<&'static Tokenizer> {
+    static O200K_BASE: OnceLock<Option<Tokenizer>> = OnceLock::new();
+    static CL100K_BASE: OnceLock<Option<Tokenizer>> = OnceLock::new();
+
+    let cell = match kind {
+        EncodingKind::O200kBase => &O200K_BASE,
+        EncodingKind::Cl100kBase => &CL100K_BASE,
+    };
+    cell.get_or_init(|| {
+        Tokenizer::new(kind)
+            .inspect_err(|err| warn!("falling back to byte-based token estimates: {err}"))
+            .ok()
+    })
+    .as_ref()
+}
+
+/// Counts the tokens in `text`, estimating 4 bytes per token when no
+/// tokenizer is available.
+fn count_tokens(tokenizer: Option<&Tokenizer>, text: &str) -> u64 {
+    match tokenizer {
+        Some(tokenizer) => tokenizer.count(text),
+        None => (text.len() as u64).div_ceil(4),
+    }
+}
+
+/// Estimates prompt sizes for a session.
+///
+/// Instructions, tool schemas and history items repeat in every request of a
+/// session, so token counts are remembered by content and only text that has
+/// not been seen before is tokenized.
+#[derive(Debug, Default)]
+pub(crate) struct TokenEstimator {
+    counts: HashMap<(Option<EncodingKind>, u64), u64>,
+}
+
+impl TokenEstimator {
+    /// Estimates the tokens `prompt` will consume when sent to `model_family`.
+    pub(crate) fn estimate_prompt(
+        &mut self,
+        prompt: &Prompt,
+        model_family: &ModelFamily,
+    ) -> ContextBreakdown {
+        let tokenizer = tokenizer_for_model(&model_family.slug);
+
+        let tools = create_tools_json_for_responses_api(&prompt.tools)
+            .ok()
+            .and_then(|tools| serde_json::to_string(&tools).ok())
+            .unwrap_or_default();
+        let mut breakdown = ContextBreakdown {
+            instructions: self.count(tokenizer, &prompt.get_full_instructions(model_family)),
+            tools: self.count(tokenizer, &tools),
+            ..Default::default()
+        };
+
+        for item in &prompt.input {
+            match item {
+                ResponseItem::Message { role, content, .. } => {
+                    let mut tokens = ITEM_OVERHEAD_TOKENS;
+                    for content in content {
+                        tokens += match content {
+                            ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+                                self.count(tokenizer, text)
+                            }
+                            ContentItem::InputImage { .. } => IMAGE_TOKEN_ESTIMATE,
+                        };
+                    }
+                    if role == "assistant" {
+                        breakdown.assistant_messages += tokens;
+                    } else {
+                        breakdown.user_messages += tokens;
+                    }
+                }
+                ResponseItem::Reasoning {
+                    summary,
+                    content,
+                    encrypted_content,
+                    ..
+                } => {
+                    let mut tokens = ITEM_OVERHEAD_TOKENS;
+                    for ReasoningItemReasoningSummary::SummaryText { text } in summary {
+                        tokens += self.count(tokenizer, text);
+                    }
+                    for content in content.iter().flatten() {
+                        let (ReasoningItemContent::ReasoningText { text }
+                        | ReasoningItemContent::Text { text }) = content;
+                        tokens += self.count(tokenizer, text);
+                    }
+                    // Encrypted reasoning is opaque; only its size is known.
+                    if let Some(encrypted) = encrypted_content {
+                        tokens += count_tokens(None, encrypted);
+                    }
+                    breakdown.reasoning += tokens;
+                }
+                ResponseItem::LocalShellCall { action, .. } => {
+                    let action = serde_json::to_string(action).unwrap_or_default();
+                    breakdown.tool_calls += ITEM_OVERHEAD_TOKENS + self.count(tokenizer, &action);
+                }
+                ResponseItem::FunctionCall {
+                    name, arguments, ..
+                } => {
+                    breakdown.tool_calls += ITEM_OVERHEAD_TOKENS
+                        + self.count(tokenizer, name)
+                        + self.count(tokenizer, arguments);
+                }
+                ResponseItem::FunctionCallOutput { output, .. } => {
+                    breakdown.tool_calls +=
+                        ITEM_OVERHEAD_TOKENS + self.count(tokenizer, &output.content);
+                }
+                ResponseItem::CustomToolCall { name, input, .. } => {
+                    breakdown.tool_calls += ITEM_OVERHEAD_TOKENS
+                        + self.count(tokenizer, name)
+                        + self.count(tokenizer, input);
+                }
+                ResponseItem::CustomToolCallOutput { output, .. } => {
+                    breakdown.tool_calls += ITEM_OVERHEAD_TOKENS + self.count(tokenizer, output);
+                }
+                ResponseItem::WebSearchCall { action, .. } => {
+                    let action = serde_json::to_string(action).unwrap_or_default();
+                    breakdown.tool_calls += ITEM_OVERHEAD_TOKENS + self.count(tokenizer, &action);
+                }
+                ResponseItem::Other => {}
+            }
+        }
+
+        breakdown
+    }
+
+    fn count(&mut self, tokenizer: Option<&Tokenizer>, text: &str) -> u64 {
+        if text.len() < MIN_CACHED_BYTES {
+            return count_tokens(tokenizer, text);
+        }
+
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        let key = (tokenizer.map(Tokenizer::kind), hasher.finish());
+        if let Some(tokens) = self.counts.get(&key) {
+            return *tokens;
+        }
+        if self.counts.len() >= MAX_CACHED_COUNTS {
+            self.counts.clear();
+        }
+        let tokens = count_tokens(tokenizer, text);
+        self.counts.insert(key, tokens);
+        tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_family::find_family_for_model;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    fn message(role: &str, text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn estimate_groups_items_by_kind() {
+        let model_family = find_family_for_model("gpt-5").expect("known model");
+        let prompt = Prompt {
+            input: vec![
+                message("user", "hello world"),
+                message("assistant", "hello world"),
+                ResponseItem::FunctionCall {
+                    id: None,
+                    name: "shell".to_string(),
+                    arguments: "{}".to_string(),
+                    call_id: "call-1".to_string(),
+                },
+                ResponseItem::FunctionCallOutput {
+                    call_id: "call-1".to_string(),
+                    output: FunctionCallOutputPayload {
+                        content: "hello world".to_string(),
+                        success: Some(true),
+                    },
+                },
+            ],
+            base_instructions_override: Some("hello world".to_string()),
+            ..Default::default()
+        };
+
+        let breakdown = TokenEstimator::default().estimate_prompt(&prompt, &model_family);
+
+        assert_eq!(
+            breakdown,
+            ContextBreakdown {
+                instructions: 2,
+                tools: 1,
+                user_messages: ITEM_OVERHEAD_TOKENS + 2,
+                assistant_messages: ITEM_OVERHEAD_TOKENS + 2,
+                reasoning: 0,
+                tool_calls: 2 * ITEM_OVERHEAD_TOKENS + 1 + 1 + 2,
+            }
+        );
+    }
+}
@@ -11,6 +11,8 @@ use codex_protocol::models::ShellToolCallParams;
 use codex_protocol::protocol::FileChange;
 use codex_utils_string::take_bytes_at_char_boundary;
 use mcp_types::CallToolResult;
+use serde_json::Value;
+use serde_json::json;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -61,6 +63,56 @@ impl ToolPayload {
             ToolPayload::Mcp { raw_arguments, .. } => Cow::Borrowed(raw_arguments),
         }
     }
+
+    /// The tool input as shown to hooks: JSON arguments are parsed, freeform
+    /// input is passed as a string.
+    pub(crate) fn hook_input(&self) -> Value {
+        let parse = |arguments: &str| {
+            serde_json::from_str(arguments).unwrap_or_else(|_| Value::String(arguments.to_string()))
+        };
+        match self {
+            ToolPayload::Function { arguments } | ToolPayload::UnifiedExec { arguments } => {
+                parse(arguments)
+            }
+            ToolPayload::Custom { input } => Value::String(input.clone()),
+            ToolPayload::LocalShell { params } => json!({
+                "command": params.command,
+                "workdir": params.workdir,
+                "timeout_ms": params.timeout_ms,
+            }),
+            ToolPayload::Mcp { raw_arguments, .. } => parse(raw_arguments),
+        }
+    }
+
+    /// Replace the tool input with one returned by a hook, in the format of
+    /// [`Self::hook_input`].
+    pub(crate) fn with_hook_input(self, input: Value) -> Result<Self, String> {
+        let arguments = || match &input {
+            Value::String(arguments) => arguments.clone(),
+            other => other.to_string(),
+        };
+        Ok(match self {
+            ToolPayload::Function { .. } => ToolPayload::Function {
+                arguments: arguments(),
+            },
+            ToolPayload::UnifiedExec { .. } => ToolPayload::UnifiedExec {
+                arguments: arguments(),
+            },
+            ToolPayload::Custom { .. } => match input {
+                Value::String(input) => ToolPayload::Custom { input },
+                _ => return Err("tool_input must be a string for this tool".to_string()),
+            },
+            ToolPayload::LocalShell { .. } => ToolPayload::LocalShell {
+                params: serde_json::from_value(input)
+                    .map_err(|e| format!("invalid tool_input: {e}"))?,
+            },
+            ToolPayload::Mcp { server, tool, .. } => ToolPayload::Mcp {
+                server,
+                tool,
+                raw_arguments: arguments(),
+            },
+        })
+    }
 }
 
 #[derive(Clone)]
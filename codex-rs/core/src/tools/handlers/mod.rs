@@ -3,9 +3,11 @@ mod exec_stream;
 mod grep_files;
 mod list_dir;
 mod mcp;
+mod pin;
 mod plan;
 mod read_file;
 mod shell;
+mod spawn_agent;
 mod test_sync;
 mod unified_exec;
 mod view_image;
@@ -17,9 +19,13 @@ pub use exec_stream::ExecStreamHandler;
 pub use grep_files::GrepFilesHandler;
 pub use list_dir::ListDirHandler;
 pub use mcp::McpHandler;
+pub use pin::PIN_CONTEXT_TOOL_NAME;
+pub use pin::PinContextHandler;
 pub use plan::PlanHandler;
 pub use read_file::ReadFileHandler;
 pub use shell::ShellHandler;
+pub use spawn_agent::SPAWN_AGENT_TOOL_NAME;
+pub use spawn_agent::SpawnAgentHandler;
 pub use test_sync::TestSyncHandler;
 pub use unified_exec::UnifiedExecHandler;
 pub use view_image::ViewImageHandler;
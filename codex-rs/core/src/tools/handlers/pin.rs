@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use codex_protocol::models::ResponseItem;
+use serde::Deserialize;
+
+use crate::codex::compact;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub const PIN_CONTEXT_TOOL_NAME: &str = "pin_context";
+
+pub struct PinContextHandler;
+
+#[derive(Deserialize)]
+struct PinContextArgs {
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    call_id: Option<String>,
+}
+
+#[async_trait]
+impl ToolHandler for PinContextHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session, payload, ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "pin_context handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: PinContextArgs = serde_json::from_str(&arguments).map_err(|e| {
+            FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e}"))
+        })?;
+
+        let text = match (args.text, args.call_id) {
+            (Some(text), None) if !text.trim().is_empty() => text,
+            (None, Some(call_id)) => {
+                let history = session.history_snapshot().await;
+                find_tool_output(&history, &call_id).ok_or_else(|| {
+                    FunctionCallError::RespondToModel(format!(
+                        "no earlier tool output with call_id `{call_id}`"
+                    ))
+                })?
+            }
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "provide either non-empty `text` or a `call_id`".to_string(),
+                ));
+            }
+        };
+
+        // The pin is recorded after this call's output so it reads in order.
+        session
+            .inject_input(compact::pinned_input(&text))
+            .await
+            .map_err(|_| {
+                FunctionCallError::RespondToModel(
+                    "unable to pin content (no active task)".to_string(),
+                )
+            })?;
+
+        Ok(ToolOutput::Function {
+            content: "pinned; compaction will keep it verbatim".to_string(),
+            success: Some(true),
+        })
+    }
+}
+
+/// Returns the output recorded for the tool call `call_id`.
+fn find_tool_output(history: &[ResponseItem], call_id: &str) -> Option<String> {
+    history.iter().rev().find_map(|item| match item {
+        ResponseItem::FunctionCallOutput {
+            call_id: id,
+            output,
+        } if id == call_id => Some(output.content.clone()),
+        ResponseItem::CustomToolCallOutput {
+            call_id: id,
+            output,
+        } if id == call_id => Some(output.clone()),
+        _ => None,
+    })
+}
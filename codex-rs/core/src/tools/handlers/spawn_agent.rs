@@ -0,0 +1,423 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use codex_protocol::config_types::SandboxMode;
+use codex_protocol::protocol::InitialHistory;
+use codex_protocol::protocol::SessionSource;
+use serde::Deserialize;
+
+use crate::codex::Codex;
+use crate::codex::CodexSpawnOk;
+use crate::codex::Session;
+use crate::features::Feature;
+use crate::function_tool::FunctionCallError;
+use crate::protocol::ErrorEvent;
+use crate::protocol::Event;
+use crate::protocol::EventMsg;
+use crate::protocol::InputItem;
+use crate::protocol::Op;
+use crate::protocol::SandboxPolicy;
+use crate::protocol::SubAgentBeginEvent;
+use crate::protocol::SubAgentEndEvent;
+use crate::protocol::SubAgentEvent;
+use crate::protocol::TaskCompleteEvent;
+use crate::protocol::WritableRoot;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub const SPAWN_AGENT_TOOL_NAME: &str = "spawn_agent";
+
+pub struct SpawnAgentHandler;
+
+#[derive(Deserialize)]
+struct SpawnAgentArgs {
+    prompt: String,
+    #[serde(default)]
+    instructions: Option<String>,
+    #[serde(default)]
+    cwd: Option<String>,
+    #[serde(default)]
+    sandbox: Option<SandboxMode>,
+    #[serde(default)]
+    tools: Option<Vec<String>>,
+}
+
+#[async_trait]
+impl ToolHandler for SpawnAgentHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            sub_id,
+            call_id,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "spawn_agent handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: SpawnAgentArgs = serde_json::from_str(&arguments).map_err(|e| {
+            FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e}"))
+        })?;
+
+        let cwd = turn.resolve_path(args.cwd);
+        if !cwd.is_dir() {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "cwd `{}` is not a directory",
+                cwd.display()
+            )));
+        }
+        let sandbox_policy = child_sandbox_policy(&turn.sandbox_policy, args.sandbox)?;
+        check_child_cwd(&turn.sandbox_policy, &turn.cwd, &sandbox_policy, &cwd)?;
+        let auth_manager = turn.client.get_auth_manager().ok_or_else(|| {
+            FunctionCallError::RespondToModel(
+                "spawn_agent is unavailable in this session".to_string(),
+            )
+        })?;
+
+        // The child starts from the configuration of the current turn.
+        let mut config = (*turn.client.get_config()).clone();
+        config.cwd = cwd.clone();
+        config.approval_policy = turn.approval_policy;
+        config.sandbox_policy = sandbox_policy;
+        config.model_reasoning_effort = turn.client.get_reasoning_effort();
+        config.model_reasoning_summary = turn.client.get_reasoning_summary();
+        if let Some(instructions) = args.instructions {
+            config.user_instructions = Some(instructions);
+        }
+        if let Some(tools) = args.tools {
+            config.enabled_tools = Some(match config.enabled_tools.take() {
+                Some(allowed) => tools.into_iter().filter(|t| allowed.contains(t)).collect(),
+                None => tools,
+            });
+        }
+        // Sub-agents cannot spawn further sub-agents.
+        config.features.disable(Feature::SpawnAgent);
+
+        let CodexSpawnOk {
+            codex,
+            conversation_id,
+        } = Codex::spawn(
+            config,
+            auth_manager,
+            InitialHistory::New,
+            SessionSource::SubAgent,
+        )
+        .await
+        .map_err(|e| {
+            FunctionCallError::RespondToModel(format!("failed to start sub-agent: {e}"))
+        })?;
+        let child = ChildConversation {
+            codex: Arc::new(codex),
+        };
+        // The first event is always `SessionConfigured`.
+        child.codex.next_event().await.map_err(|e| {
+            FunctionCallError::RespondToModel(format!("failed to start sub-agent: {e}"))
+        })?;
+
+        session
+            .send_event(Event {
+                id: sub_id.clone(),
+                msg: EventMsg::SubAgentBegin(SubAgentBeginEvent {
+                    call_id: call_id.clone(),
+                    conversation_id,
+                    prompt: args.prompt.clone(),
+                    cwd,
+                }),
+            })
+            .await;
+
+        let outcome = match child
+            .codex
+            .submit(Op::UserInput {
+                items: vec![InputItem::Text { text: args.prompt }],
+            })
+            .await
+        {
+            Ok(_) => run_child(&session, &sub_id, &call_id, &child.codex).await,
+            Err(e) => ChildOutcome {
+                last_agent_message: None,
+                error: Some(e.to_string()),
+            },
+        };
+        drop(child);
+
+        let success = outcome.error.is_none();
+        session
+            .send_event(Event {
+                id: sub_id,
+                msg: EventMsg::SubAgentEnd(SubAgentEndEvent {
+                    call_id,
+                    conversation_id,
+                    last_agent_message: outcome.last_agent_message.clone(),
+                    success,
+                }),
+            })
+            .await;
+
+        let content = match (outcome.error, outcome.last_agent_message) {
+            (None, Some(message)) => message,
+            (None, None) => "The sub-agent finished without a final message.".to_string(),
+            (Some(error), Some(message)) => format!("The sub-agent failed: {error}\n\n{message}"),
+            (Some(error), None) => format!("The sub-agent failed: {error}"),
+        };
+        Ok(ToolOutput::Function {
+            content,
+            success: Some(success),
+        })
+    }
+}
+
+/// Resolves the sandbox for the child; it may be as restrictive as the
+/// parent's or more, never less.
+fn child_sandbox_policy(
+    parent: &SandboxPolicy,
+    requested: Option<SandboxMode>,
+) -> Result<SandboxPolicy, FunctionCallError> {
+    match (requested, parent) {
+        (None, _) => Ok(parent.clone()),
+        (Some(SandboxMode::ReadOnly), _) => Ok(SandboxPolicy::new_read_only_policy()),
+        (Some(SandboxMode::WorkspaceWrite), SandboxPolicy::WorkspaceWrite { .. })
+        | (Some(SandboxMode::DangerFullAccess), SandboxPolicy::DangerFullAccess) => {
+            Ok(parent.clone())
+        }
+        (Some(SandboxMode::WorkspaceWrite), SandboxPolicy::DangerFullAccess) => {
+            Ok(SandboxPolicy::new_workspace_write_policy())
+        }
+        (Some(requested), _) => Err(FunctionCallError::RespondToModel(format!(
+            "sandbox `{requested}` is less restrictive than the current sandbox"
+        ))),
+    }
+}
+
+/// A `workspace-write` child can write to its own cwd, so that cwd must be
+/// somewhere the parent could already write to.
+fn check_child_cwd(
+    parent: &SandboxPolicy,
+    parent_cwd: &Path,
+    child: &SandboxPolicy,
+    cwd: &Path,
+) -> Result<(), FunctionCallError> {
+    if !matches!(child, SandboxPolicy::WorkspaceWrite { .. }) || parent.has_full_disk_write_access()
+    {
+        return Ok(());
+    }
+
+    // Compare canonical paths so `..` components and symlinks cannot point
+    // the child outside of the parent's writable roots.
+    let canonicalize =
+        |path: &Path| dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let cwd = canonicalize(cwd);
+    let writable = parent
+        .get_writable_roots_with_cwd(parent_cwd)
+        .into_iter()
+        .any(|root| {
+            WritableRoot {
+                root: canonicalize(&root.root),
+                read_only_subpaths: root
+                    .read_only_subpaths
+                    .iter()
+                    .map(|path| canonicalize(path))
+                    .collect(),
+            }
+            .is_path_writable(&cwd)
+        });
+    if writable {
+        Ok(())
+    } else {
+        Err(FunctionCallError::RespondToModel(format!(
+            "cwd `{}` is outside the writable roots of the current sandbox",
+            cwd.display()
+        )))
+    }
+}
+
+struct ChildOutcome {
+    last_agent_message: Option<String>,
+    error: Option<String>,
+}
+
+/// Drives the child conversation until its task completes, forwarding its
+/// events nested under `call_id` and routing its approval requests through
+/// the parent session.
+async fn run_child(session: &Session, sub_id: &str, call_id: &str, codex: &Codex) -> ChildOutcome {
+    let mut error = None;
+    loop {
+        let event = match codex.next_event().await {
+            Ok(event) => event,
+            Err(e) => {
+                return ChildOutcome {
+                    last_agent_message: None,
+                    error: Some(e.to_string()),
+                };
+            }
+        };
+
+        let approval_op = match event.msg {
+            msg @ EventMsg::ExecApprovalRequest(_) => Some(Op::ExecApproval {
+                id: event.id,
+                decision: session
+                    .request_forwarded_approval(sub_id.to_string(), msg)
+                    .await,
+            }),
+            msg @ EventMsg::ApplyPatchApprovalRequest(_) => Some(Op::PatchApproval {
+                id: event.id,
+                decision: session
+                    .request_forwarded_approval(sub_id.to_string(), msg)
+                    .await,
+            }),
+            EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
+                return ChildOutcome {
+                    last_agent_message,
+                    error,
+                };
+            }
+            EventMsg::TurnAborted(_) | EventMsg::ShutdownComplete => {
+                return ChildOutcome {
+                    last_agent_message: None,
+                    error: Some("the sub-agent was interrupted".to_string()),
+                };
+            }
+            msg => {
+                if let EventMsg::Error(ErrorEvent { message }) = &msg {
+                    error = Some(message.clone());
+                }
+                session
+                    .send_event(Event {
+                        id: sub_id.to_string(),
+                        msg: EventMsg::SubAgentEvent(SubAgentEvent {
+                            call_id: call_id.to_string(),
+                            msg: Box::new(msg),
+                        }),
+                    })
+                    .await;
+                None
+            }
+        };
+
+        if let Some(op) = approval_op
+            && let Err(e) = codex.submit(op).await
+        {
+            return ChildOutcome {
+                last_agent_message: None,
+                error: Some(e.to_string()),
+            };
+        }
+    }
+}
+
+/// Shuts the child conversation down once the tool call finishes or the
+/// parent turn is aborted.
+struct ChildConversation {
+    codex: Arc<Codex>,
+}
+
+impl Drop for ChildConversation {
+    fn drop(&mut self) {
+        let codex = Arc::clone(&self.codex);
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                let _ = codex.submit(Op::Interrupt).await;
+                let _ = codex.submit(Op::Shutdown).await;
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn child_sandbox_cannot_be_less_restrictive() {
+        let workspace_write = SandboxPolicy::new_workspace_write_policy();
+
+        assert_eq!(
+            child_sandbox_policy(&workspace_write, None),
+            Ok(workspace_write.clone())
+        );
+        assert_eq!(
+            child_sandbox_policy(&workspace_write, Some(SandboxMode::ReadOnly)),
+            Ok(SandboxPolicy::new_read_only_policy())
+        );
+        assert_eq!(
+            child_sandbox_policy(
+                &SandboxPolicy::DangerFullAccess,
+                Some(SandboxMode::WorkspaceWrite)
+            ),
+            Ok(workspace_write.clone())
+        );
+        assert_eq!(
+            child_sandbox_policy(&workspace_write, Some(SandboxMode::DangerFullAccess)),
+            Err(FunctionCallError::RespondToModel(
+                "sandbox `danger-full-access` is less restrictive than the current sandbox"
+                    .to_string()
+            ))
+        );
+        assert!(
+            child_sandbox_policy(
+                &SandboxPolicy::new_read_only_policy(),
+                Some(SandboxMode::WorkspaceWrite)
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn child_cwd_must_be_writable_by_parent() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let project = tmp.path().join("project");
+        let nested = project.join("crates");
+        let other = tmp.path().join("other");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(&other).unwrap();
+        let workspace_write = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: false,
+            network_allowlist: vec![],
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        };
+
+        assert_eq!(
+            check_child_cwd(&workspace_write, &project, &workspace_write, &nested),
+            Ok(())
+        );
+        assert!(check_child_cwd(&workspace_write, &project, &workspace_write, &other).is_err());
+        assert!(
+            check_child_cwd(
+                &workspace_write,
+                &project,
+                &workspace_write,
+                &project.join("../other"),
+            )
+            .is_err()
+        );
+        // A read-only child cannot write anywhere, so its cwd is unrestricted.
+        assert_eq!(
+            check_child_cwd(
+                &workspace_write,
+                &project,
+                &SandboxPolicy::new_read_only_policy(),
+                &other,
+            ),
+            Ok(())
+        );
+    }
+}
@@ -11,6 +11,7 @@ use crate::function_tool::FunctionCallError;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::router::ToolCall;
 use crate::tools::router::ToolRouter;
+use codex_otel::otel_event_manager::OtelSpan;
 use codex_protocol::models::ResponseInputItem;
 
 pub(crate) struct ToolCallRuntime {
@@ -54,7 +55,7 @@ impl ToolCallRuntime {
         let lock = Arc::clone(&self.parallel_execution);
 
         let handle: AbortOnDropHandle<Result<ResponseInputItem, FunctionCallError>> =
-            AbortOnDropHandle::new(tokio::spawn(async move {
+            AbortOnDropHandle::new(tokio::spawn(OtelSpan::current().instrument(async move {
                 let _guard = if supports_parallel {
                     Either::Left(lock.read().await)
                 } else {
@@ -64,7 +65,7 @@ impl ToolCallRuntime {
                 router
                     .dispatch_tool_call(session, turn, tracker, sub_id, call)
                     .await
-            }));
+            })));
 
         async move {
             match handle.await {
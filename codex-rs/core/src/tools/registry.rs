@@ -188,6 +188,12 @@ impl ToolRegistryBuilder {
         }
     }
 
+    /// Drops every tool spec and handler whose name does not satisfy `keep`.
+    pub fn retain_tools(&mut self, keep: impl Fn(&str) -> bool) {
+        self.specs.retain(|configured| keep(configured.spec.name()));
+        self.handlers.retain(|name, _| keep(name));
+    }
+
     // TODO(jif) for dynamic tools.
     // pub fn register_many<I>(&mut self, names: I, handler: Arc<dyn ToolHandler>)
     // where
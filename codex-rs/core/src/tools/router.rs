@@ -5,6 +5,7 @@ use crate::client_common::tools::ToolSpec;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::function_tool::FunctionCallError;
+use crate::hooks::HookOutcome;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolPayload;
@@ -16,6 +17,10 @@ use codex_protocol::models::LocalShellAction;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::models::ShellToolCallParams;
+use mcp_types::ContentBlock;
+use mcp_types::TextContent;
+use serde_json::Value;
+use serde_json::json;
 
 #[derive(Clone)]
 pub struct ToolCall {
@@ -140,11 +145,62 @@ impl ToolRouter {
         let ToolCall {
             tool_name,
             call_id,
-            payload,
+            mut payload,
         } = call;
         let payload_outputs_custom = matches!(payload, ToolPayload::Custom { .. });
         let failure_call_id = call_id.clone();
 
+        let hooks = session.services.hooks.clone();
+        let run_hooks = hooks.has_tool_hooks();
+        let mut hook_context = Vec::new();
+        if run_hooks {
+            match hooks
+                .pre_tool_use(
+                    &turn.cwd,
+                    &sub_id,
+                    &tool_name,
+                    &call_id,
+                    payload.hook_input(),
+                )
+                .await
+            {
+                HookOutcome::Continue => {}
+                HookOutcome::Modified {
+                    tool_input,
+                    additional_context,
+                } => {
+                    if let Some(tool_input) = tool_input {
+                        payload = match payload.with_hook_input(tool_input) {
+                            Ok(payload) => payload,
+                            Err(message) => {
+                                return Ok(Self::failure_response(
+                                    failure_call_id,
+                                    payload_outputs_custom,
+                                    FunctionCallError::RespondToModel(format!(
+                                        "pre_tool_use hook returned {message}"
+                                    )),
+                                ));
+                            }
+                        };
+                    }
+                    hook_context = additional_context;
+                }
+                HookOutcome::Block { reason } => {
+                    return Ok(Self::failure_response(
+                        failure_call_id,
+                        payload_outputs_custom,
+                        FunctionCallError::RespondToModel(format!(
+                            "tool call blocked by hook: {reason}"
+                        )),
+                    ));
+                }
+            }
+        }
+        let hook_input = run_hooks.then(|| payload.hook_input());
+        let cwd = turn.cwd.clone();
+        let hook_sub_id = sub_id.clone();
+        let hook_tool_name = tool_name.clone();
+
         let invocation = ToolInvocation {
             session,
             turn,
@@ -155,15 +211,31 @@ impl ToolRouter {
             payload,
         };
 
-        match self.registry.dispatch(invocation).await {
-            Ok(response) => Ok(response),
-            Err(FunctionCallError::Fatal(message)) => Err(FunctionCallError::Fatal(message)),
-            Err(err) => Ok(Self::failure_response(
-                failure_call_id,
-                payload_outputs_custom,
-                err,
-            )),
+        let mut response = match self.registry.dispatch(invocation).await {
+            Ok(response) => response,
+            Err(FunctionCallError::Fatal(message)) => {
+                return Err(FunctionCallError::Fatal(message));
+            }
+            Err(err) => Self::failure_response(failure_call_id, payload_outputs_custom, err),
+        };
+
+        if let Some(tool_input) = hook_input {
+            let (call_id, tool_output) = hook_output(&response);
+            hook_context.extend(
+                hooks
+                    .post_tool_use(
+                        &cwd,
+                        &hook_sub_id,
+                        &hook_tool_name,
+                        &call_id,
+                        tool_input,
+                        tool_output,
+                    )
+                    .await,
+            );
+            append_hook_context(&mut response, hook_context);
         }
+        Ok(response)
     }
 
     fn failure_response(
@@ -188,3 +260,53 @@ impl ToolRouter {
         }
     }
 }
+
+/// The call id and output of a tool call, as shown to `post_tool_use` hooks.
+fn hook_output(response: &ResponseInputItem) -> (String, Value) {
+    match response {
+        ResponseInputItem::FunctionCallOutput { call_id, output } => (
+            call_id.clone(),
+            json!({ "content": output.content, "success": output.success }),
+        ),
+        ResponseInputItem::CustomToolCallOutput { call_id, output } => {
+            (call_id.clone(), json!({ "content": output }))
+        }
+        ResponseInputItem::McpToolCallOutput { call_id, result } => (
+            call_id.clone(),
+            match result {
+                Ok(result) => serde_json::to_value(result).unwrap_or_default(),
+                Err(err) => json!({ "error": err }),
+            },
+        ),
+        ResponseInputItem::Message { .. } => (String::new(), Value::Null),
+    }
+}
+
+/// Append context requested by hooks to the output sent to the model.
+fn append_hook_context(response: &mut ResponseInputItem, context: Vec<String>) {
+    if context.is_empty() {
+        return;
+    }
+    let context = context.join("\n");
+    match response {
+        ResponseInputItem::FunctionCallOutput { output, .. } => {
+            output.content = format!("{}\n\n{context}", output.content);
+        }
+        ResponseInputItem::CustomToolCallOutput { output, .. } => {
+            *output = format!("{output}\n\n{context}");
+        }
+        ResponseInputItem::McpToolCallOutput {
+            result: Ok(result), ..
+        } => result.content.push(ContentBlock::TextContent(TextContent {
+            annotations: None,
+            text: context,
+            r#type: "text".to_string(),
+        })),
+        ResponseInputItem::McpToolCallOutput {
+            result: Err(err), ..
+        } => {
+            *err = format!("{err}\n\n{context}");
+        }
+        ResponseInputItem::Message { .. } => {}
+    }
+}
@@ -3,7 +3,9 @@ use crate::client_common::tools::ToolSpec;
 use crate::features::Feature;
 use crate::features::Features;
 use crate::model_family::ModelFamily;
+use crate::tools::handlers::PIN_CONTEXT_TOOL_NAME;
 use crate::tools::handlers::PLAN_TOOL;
+use crate::tools::handlers::SPAWN_AGENT_TOOL_NAME;
 use crate::tools::handlers::apply_patch::ApplyPatchToolType;
 use crate::tools::handlers::apply_patch::create_apply_patch_freeform_tool;
 use crate::tools::handlers::apply_patch::create_apply_patch_json_tool;
@@ -31,6 +33,10 @@ pub(crate) struct ToolsConfig {
     pub include_view_image_tool: bool,
     pub experimental_unified_exec_tool: bool,
     pub experimental_supported_tools: Vec<String>,
+    pub spawn_agent_tool: bool,
+    pub pin_context_tool: bool,
+    /// When set, only tools with these names are exposed.
+    pub enabled_tools: Option<Vec<String>>,
 }
 
 pub(crate) struct ToolsConfigParams<'a> {
@@ -50,6 +56,8 @@ impl ToolsConfig {
         let include_apply_patch_tool = features.enabled(Feature::ApplyPatchFreeform);
         let include_web_search_request = features.enabled(Feature::WebSearchRequest);
         let include_view_image_tool = features.enabled(Feature::ViewImageTool);
+        let include_spawn_agent_tool = features.enabled(Feature::SpawnAgent);
+        let include_pin_context_tool = features.enabled(Feature::PinTool);
 
         let shell_type = if use_streamable_shell_tool {
             ConfigShellToolType::Streamable
@@ -79,8 +87,18 @@ impl ToolsConfig {
             include_view_image_tool,
             experimental_unified_exec_tool,
             experimental_supported_tools: model_family.experimental_supported_tools.clone(),
+            spawn_agent_tool: include_spawn_agent_tool,
+            pin_context_tool: include_pin_context_tool,
+            enabled_tools: None,
         }
     }
+
+    /// Restricts the exposed tools to `enabled_tools` (see
+    /// [`crate::config::Config::enabled_tools`]).
+    pub fn with_enabled_tools(mut self, enabled_tools: Option<Vec<String>>) -> Self {
+        self.enabled_tools = enabled_tools;
+        self
+    }
 }
 
 /// Generic JSON‑Schema subset needed for our tool definitions
@@ -257,6 +275,101 @@ fn create_view_image_tool() -> ToolSpec {
     })
 }
 
+fn create_spawn_agent_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "prompt".to_string(),
+        JsonSchema::String {
+            description: Some("The task for the sub-agent, as a self-contained user message. The sub-agent cannot see this conversation.".to_string()),
+        },
+    );
+    properties.insert(
+        "instructions".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional instructions for the sub-agent, used in place of the current user instructions."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "cwd".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional working directory for the sub-agent, relative to the current one. With a workspace-write sandbox it must be inside a directory you can already write to."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "sandbox".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional sandbox for the sub-agent: one of read-only, workspace-write, danger-full-access. Cannot be less restrictive than the current sandbox. Defaults to the current sandbox."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "tools".to_string(),
+        JsonSchema::Array {
+            items: Box::new(JsonSchema::String { description: None }),
+            description: Some(
+                "Optional names of the tools the sub-agent may use (e.g. [\"shell\", \"update_plan\"]). Defaults to the tools available to you."
+                    .to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: SPAWN_AGENT_TOOL_NAME.to_string(),
+        description: r#"Delegates a task to a sub-agent running in a fresh conversation and returns its final message.
+Use it for self-contained investigations whose intermediate steps you do not need to see, to keep your own context small.
+The sub-agent starts without this conversation's history, so include everything it needs in the prompt."#
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["prompt".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_pin_context_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "text".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Content to pin, e.g. a spec, constraint or key finding.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "call_id".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Call id of an earlier tool call whose output should be pinned as-is, e.g. an error log or file excerpt."
+                    .to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: PIN_CONTEXT_TOOL_NAME.to_string(),
+        description: r#"Pins content so it is kept verbatim when the conversation is compacted instead of being folded into a summary.
+Pin things you will need exactly later; pass either `text` or the `call_id` of an earlier tool call, not both."#
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_test_sync_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
@@ -563,6 +676,32 @@ pub(crate) fn create_tools_json_for_chat_completions_api(
     Ok(tools_json)
 }
 
+/// Returns JSON values that are compatible with tool use in the Anthropic
+/// Messages API:
+/// https://docs.anthropic.com/en/docs/agents-and-tools/tool-use/overview
+pub(crate) fn create_tools_json_for_anthropic_messages_api(
+    tools: &[ToolSpec],
+) -> crate::error::Result<Vec<serde_json::Value>> {
+    let mut tools_json = Vec::new();
+    for tool in tools {
+        // Only plain function tools have a Messages API equivalent.
+        if let ToolSpec::Function(ResponsesApiTool {
+            name,
+            description,
+            parameters,
+            ..
+        }) = tool
+        {
+            tools_json.push(json!({
+                "name": name,
+                "description": description,
+                "input_schema": serde_json::to_value(parameters)?,
+            }));
+        }
+    }
+    Ok(tools_json)
+}
+
 pub(crate) fn mcp_tool_to_openai_tool(
     fully_qualified_name: String,
     tool: mcp_types::Tool,
@@ -723,9 +862,11 @@ pub(crate) fn build_specs(
     use crate::tools::handlers::GrepFilesHandler;
     use crate::tools::handlers::ListDirHandler;
     use crate::tools::handlers::McpHandler;
+    use crate::tools::handlers::PinContextHandler;
     use crate::tools::handlers::PlanHandler;
     use crate::tools::handlers::ReadFileHandler;
     use crate::tools::handlers::ShellHandler;
+    use crate::tools::handlers::SpawnAgentHandler;
     use crate::tools::handlers::TestSyncHandler;
     use crate::tools::handlers::UnifiedExecHandler;
     use crate::tools::handlers::ViewImageHandler;
@@ -833,6 +974,16 @@ pub(crate) fn build_specs(
         builder.register_handler("view_image", view_image_handler);
     }
 
+    if config.spawn_agent_tool {
+        builder.push_spec(create_spawn_agent_tool());
+        builder.register_handler(SPAWN_AGENT_TOOL_NAME, Arc::new(SpawnAgentHandler));
+    }
+
+    if config.pin_context_tool {
+        builder.push_spec_with_parallel_support(create_pin_context_tool(), true);
+        builder.register_handler(PIN_CONTEXT_TOOL_NAME, Arc::new(PinContextHandler));
+    }
+
     if let Some(mcp_tools) = mcp_tools {
         let mut entries: Vec<(String, mcp_types::Tool)> = mcp_tools.into_iter().collect();
         entries.sort_by(|a, b| a.0.cmp(&b.0));
@@ -850,6 +1001,10 @@ pub(crate) fn build_specs(
         }
     }
 
+    if let Some(enabled_tools) = &config.enabled_tools {
+        builder.retain_tools(|name| enabled_tools.iter().any(|enabled| enabled == name));
+    }
+
     builder
 }
 
@@ -940,6 +1095,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_specs_spawn_agent_and_enabled_tools() {
+        let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
+        let mut features = Features::with_defaults();
+        features.enable(Feature::PlanTool);
+        features.enable(Feature::SpawnAgent);
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        });
+        let (tools, _) = build_specs(&config, Some(HashMap::new())).build();
+        assert_eq_tool_names(
+            &tools,
+            &["shell", "update_plan", "view_image", "spawn_agent"],
+        );
+
+        let config =
+            config.with_enabled_tools(Some(vec!["shell".to_string(), "spawn_agent".to_string()]));
+        let (tools, _) = build_specs(&config, Some(HashMap::new())).build();
+        assert_eq_tool_names(&tools, &["shell", "spawn_agent"]);
+    }
+
+    #[test]
+    fn test_build_specs_pin_tool() {
+        let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
+        let mut features = Features::with_defaults();
+        features.enable(Feature::PinTool);
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        });
+        let (tools, _) = build_specs(&config, Some(HashMap::new())).build();
+        assert_eq_tool_names(&tools, &["shell", "view_image", "pin_context"]);
+    }
+
     #[test]
     #[ignore]
     fn test_parallel_support_flags() {
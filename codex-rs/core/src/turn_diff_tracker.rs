@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ffi::OsStr;
 use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
@@ -8,12 +10,17 @@ use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
 use sha1::digest::Output;
+use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use crate::protocol::FileChange;
 
 const ZERO_OID: &str = "0000000000000000000000000000000000000000";
 const DEV_NULL: &str = "/dev/null";
+/// Shell commands in a working tree with more dirty paths than this are not tracked.
+const MAX_EXEC_SNAPSHOT_PATHS: usize = 1_000;
+/// Files larger than this are neither snapshotted nor reported as changed by shell commands.
+const MAX_EXEC_SNAPSHOT_FILE_BYTES: u64 = 1024 * 1024;
 
 struct BaselineFileInfo {
     path: PathBuf,
@@ -22,6 +29,97 @@ struct BaselineFileInfo {
     oid: String,
 }
 
+/// Contents of a file captured outside of `apply_patch`, used as a baseline for
+/// files changed by shell commands.
+#[derive(Clone, PartialEq, Eq)]
+struct SnapshotFile {
+    content: Vec<u8>,
+    mode: FileMode,
+}
+
+/// Working tree state of a git repository captured right before a shell command
+/// runs. Only paths that differ from the index (or are untracked) are stored;
+/// every other file is known to match the index and is read back from it if the
+/// command turns out to have changed it.
+struct WorkspaceSnapshot {
+    root: PathBuf,
+    /// Absolute path -> contents before the command (`None` if the path did not exist).
+    dirty: HashMap<PathBuf, Option<SnapshotFile>>,
+    /// Dirty paths too large to snapshot; changes to them are not reported.
+    oversized: HashSet<PathBuf>,
+}
+
+impl WorkspaceSnapshot {
+    /// Capture the dirty paths of the working tree at `root`. Returns None when `root` cannot be
+    /// queried or has too many dirty paths to snapshot. Blocking: runs git and reads files.
+    fn capture(root: PathBuf) -> Option<Self> {
+        let dirty_paths = git_dirty_paths(&root)?;
+        if dirty_paths.len() > MAX_EXEC_SNAPSHOT_PATHS {
+            return None;
+        }
+        let mut dirty = HashMap::new();
+        let mut oversized = HashSet::new();
+        for rel in dirty_paths {
+            let path = root.join(rel);
+            if exceeds_snapshot_size(&path) {
+                oversized.insert(path);
+            } else {
+                let file = read_snapshot_file(&path);
+                dirty.insert(path, file);
+            }
+        }
+        Some(Self {
+            root,
+            dirty,
+            oversized,
+        })
+    }
+
+    /// Compare the working tree against this snapshot and return every path that changed along
+    /// with its contents before the command, sorted by path. Blocking: runs git and reads files.
+    fn changes(self) -> Vec<(PathBuf, Option<SnapshotFile>)> {
+        let Self {
+            root,
+            mut dirty,
+            oversized,
+        } = self;
+        let Some(dirty_after) = git_dirty_paths(&root) else {
+            return Vec::new();
+        };
+        if dirty_after.len() > MAX_EXEC_SNAPSHOT_PATHS {
+            return Vec::new();
+        }
+
+        let mut candidates: Vec<PathBuf> = dirty.keys().cloned().collect();
+        candidates.extend(dirty_after.into_iter().map(|rel| root.join(rel)));
+        candidates.sort();
+        candidates.dedup();
+
+        let mut changes = Vec::new();
+        for path in candidates {
+            if oversized.contains(&path) || exceeds_snapshot_size(&path) {
+                continue;
+            }
+            // Paths that were clean before the command still match the index.
+            let before = match dirty.remove(&path) {
+                Some(before) => before,
+                None => {
+                    let rel = path.strip_prefix(&root).unwrap_or(&path);
+                    match git_index_file(&root, rel) {
+                        Ok(before) => before,
+                        Err(_) => continue,
+                    }
+                }
+            };
+            if before == read_snapshot_file(&path) {
+                continue;
+            }
+            changes.push((path, before));
+        }
+        changes
+    }
+}
+
 /// Tracks sets of changes to files and exposes the overall unified diff.
 /// Internally, the way this works is now:
 /// 1. Maintain an in-memory baseline snapshot of files when they are first seen.
@@ -29,6 +127,9 @@ struct BaselineFileInfo {
 /// 2. Keep a stable internal filename (uuid) per external path for rename tracking.
 /// 3. To compute the aggregated unified diff, compare each baseline snapshot to the current file on disk entirely in-memory
 ///    using the `similar` crate and emit unified diffs with rewritten external paths.
+/// 4. Shell commands do not declare the files they touch, so the git working tree is
+///    snapshotted before each command and compared afterwards; any file the command changed
+///    gets a baseline from that snapshot and is reported like a patched file.
 #[derive(Default)]
 pub struct TurnDiffTracker {
     /// Map external path -> internal filename (uuid).
@@ -40,6 +141,8 @@ pub struct TurnDiffTracker {
     temp_name_to_current_path: HashMap<String, PathBuf>,
     /// Cache of known git worktree roots to avoid repeated filesystem walks.
     git_root_cache: Vec<PathBuf>,
+    /// Call id -> working tree state captured before that shell command ran.
+    exec_snapshots: HashMap<String, WorkspaceSnapshot>,
 }
 
 impl TurnDiffTracker {
@@ -127,6 +230,74 @@ impl TurnDiffTracker {
         }
     }
 
+    /// Front-run a shell command by snapshotting the git working tree that contains `cwd`.
+    /// Does nothing when `cwd` is not inside a git repository. The snapshot runs git and reads
+    /// files, so it happens on a blocking thread without holding the tracker lock.
+    pub async fn on_exec_begin(tracker: &Mutex<Self>, call_id: &str, cwd: &Path) {
+        let Some(root) = tracker.lock().await.find_git_root_cached(cwd) else {
+            return;
+        };
+        let Ok(Some(snapshot)) =
+            tokio::task::spawn_blocking(move || WorkspaceSnapshot::capture(root)).await
+        else {
+            return;
+        };
+        tracker
+            .lock()
+            .await
+            .exec_snapshots
+            .insert(call_id.to_string(), snapshot);
+    }
+
+    /// Compare the working tree against the snapshot taken in [`Self::on_exec_begin`] and start
+    /// tracking every file the command changed, using its pre-command contents as the baseline.
+    /// Returns the paths changed by this command, sorted.
+    pub async fn on_exec_end(tracker: &Mutex<Self>, call_id: &str) -> Vec<PathBuf> {
+        let Some(snapshot) = tracker.lock().await.exec_snapshots.remove(call_id) else {
+            return Vec::new();
+        };
+        let Ok(changes) = tokio::task::spawn_blocking(move || snapshot.changes()).await else {
+            return Vec::new();
+        };
+        tracker.lock().await.track_exec_changes(changes)
+    }
+
+    fn track_exec_changes(
+        &mut self,
+        changes: Vec<(PathBuf, Option<SnapshotFile>)>,
+    ) -> Vec<PathBuf> {
+        let mut changed = Vec::with_capacity(changes.len());
+        for (path, before) in changes {
+            changed.push(path.clone());
+
+            // Files already tracked keep their original baseline.
+            if self.external_to_temp_name.contains_key(&path) {
+                continue;
+            }
+            let internal = Uuid::new_v4().to_string();
+            self.external_to_temp_name
+                .insert(path.clone(), internal.clone());
+            self.temp_name_to_current_path
+                .insert(internal.clone(), path.clone());
+            let baseline_file_info = match before {
+                Some(SnapshotFile { content, mode }) => BaselineFileInfo {
+                    path,
+                    oid: format!("{:x}", git_blob_sha1_hex_bytes(&content)),
+                    content,
+                    mode,
+                },
+                None => BaselineFileInfo {
+                    path,
+                    content: vec![],
+                    mode: FileMode::Regular,
+                    oid: ZERO_OID.to_string(),
+                },
+            };
+            self.baseline_file_info.insert(internal, baseline_file_info);
+        }
+        changed
+    }
+
     fn get_path_for_internal(&self, internal: &str) -> Option<PathBuf> {
         self.temp_name_to_current_path
             .get(internal)
@@ -207,6 +378,7 @@ impl TurnDiffTracker {
         let output = Command::new("git")
             .arg("-C")
             .arg(&root)
+            .arg("--no-optional-locks")
             .arg("hash-object")
             .arg("--")
             .arg(rel)
@@ -368,6 +540,105 @@ impl TurnDiffTracker {
     }
 }
 
+/// List paths (relative to `root`) whose working tree state differs from the index, including
+/// untracked files. Renames are reported as a deletion plus an addition.
+fn git_dirty_paths(root: &Path) -> Option<Vec<PathBuf>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            // `status` would otherwise refresh the index, racing the command's own git calls.
+            "--no-optional-locks",
+            "status",
+            "--porcelain=v1",
+            "-z",
+            "--untracked-files=all",
+            "--no-renames",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // Each record is `XY <path>` terminated by NUL; paths are never quoted with `-z`.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Some(
+        stdout
+            .split('\0')
+            .filter_map(|record| record.get(3..))
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .collect(),
+    )
+}
+
+/// Read the index entry for `rel` in the repository at `root`.
+/// Returns `Ok(None)` if the path is not in the index, and an error if git fails or the blob is
+/// too large to snapshot.
+fn git_index_file(root: &Path, rel: &Path) -> Result<Option<SnapshotFile>> {
+    let stdout = git_stdout(
+        root,
+        [
+            OsStr::new("ls-files"),
+            OsStr::new("--stage"),
+            OsStr::new("--"),
+            rel.as_os_str(),
+        ],
+    )?;
+    // Format: `<mode> <oid> <stage>\t<path>`.
+    let stdout = String::from_utf8_lossy(&stdout);
+    let Some(mut fields) = stdout.lines().next().map(str::split_whitespace) else {
+        return Ok(None);
+    };
+    let (Some(mode), Some(oid)) = (fields.next(), fields.next()) else {
+        return Err(anyhow!("unexpected `git ls-files` output: {stdout}"));
+    };
+    let mode = match mode {
+        "120000" => FileMode::Symlink,
+        #[cfg(unix)]
+        "100755" => FileMode::Executable,
+        _ => FileMode::Regular,
+    };
+    let size = git_stdout(root, ["cat-file", "-s", oid])?;
+    let size: u64 = String::from_utf8_lossy(&size).trim().parse()?;
+    if size > MAX_EXEC_SNAPSHOT_FILE_BYTES {
+        return Err(anyhow!("index blob {oid} is too large to snapshot"));
+    }
+    let content = git_stdout(root, ["cat-file", "blob", oid])?;
+    Ok(Some(SnapshotFile { content, mode }))
+}
+
+fn git_stdout<I, S>(root: &Path, args: I) -> Result<Vec<u8>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .arg("--no-optional-locks")
+        .args(args)
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+fn exceeds_snapshot_size(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|meta| meta.len() > MAX_EXEC_SNAPSHOT_FILE_BYTES)
+}
+
+fn read_snapshot_file(path: &Path) -> Option<SnapshotFile> {
+    let mode = file_mode_for_path(path)?;
+    let content = blob_bytes(path, mode)?;
+    Some(SnapshotFile { content, mode })
+}
+
 /// Compute the Git SHA-1 blob object ID for the given content (bytes).
 fn git_blob_sha1_hex_bytes(data: &[u8]) -> Output<sha1::Sha1> {
     // Git blob hash is sha1 of: "blob <len>\0<data>"
@@ -893,4 +1164,102 @@ index {ZERO_OID}..{right_oid}
         };
         assert_eq!(combined, expected_combined);
     }
+
+    fn git(root: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .envs([
+                ("GIT_CONFIG_GLOBAL", "/dev/null"),
+                ("GIT_CONFIG_NOSYSTEM", "1"),
+            ])
+            .args([
+                "-c",
+                "user.name=Test User",
+                "-c",
+                "user.email=test@example.com",
+            ])
+            .args(args)
+            .current_dir(root)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[tokio::test]
+    async fn exec_changes_are_tracked_against_pre_command_state() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("tracked.txt"), "a\n").unwrap();
+        fs::write(root.join("dirty.txt"), "one\n").unwrap();
+        git(root, &["init"]);
+        git(root, &["add", "."]);
+        git(root, &["commit", "-m", "init"]);
+        // Uncommitted edits that predate the command must not be attributed to it.
+        fs::write(root.join("dirty.txt"), "two\n").unwrap();
+        fs::write(root.join("draft.txt"), "draft\n").unwrap();
+
+        let tracker = Mutex::new(TurnDiffTracker::new());
+        TurnDiffTracker::on_exec_begin(&tracker, "call-1", root).await;
+
+        // Simulate a shell command that edits in place and generates a file.
+        fs::write(root.join("tracked.txt"), "a\nb\n").unwrap();
+        fs::write(root.join("new.txt"), "n\n").unwrap();
+
+        let changed = TurnDiffTracker::on_exec_end(&tracker, "call-1").await;
+        assert_eq!(
+            changed,
+            vec![root.join("new.txt"), root.join("tracked.txt")]
+        );
+
+        let diff = tracker.lock().await.get_unified_diff().unwrap().unwrap();
+        let left_oid = git_blob_sha1_hex("a\n");
+        let right_oid = git_blob_sha1_hex("a\nb\n");
+        let new_oid = git_blob_sha1_hex("n\n");
+        let mode = file_mode_for_path(&root.join("new.txt")).unwrap_or(FileMode::Regular);
+        let expected = format!(
+            r#"diff --git a/new.txt b/new.txt
+new file mode {mode}
+index {ZERO_OID}..{new_oid}
+--- {DEV_NULL}
++++ b/new.txt
+@@ -0,0 +1 @@
++n
+diff --git a/tracked.txt b/tracked.txt
+index {left_oid}..{right_oid}
+--- a/tracked.txt
++++ b/tracked.txt
+@@ -1 +1,2 @@
+ a
++b
+"#,
+        );
+        assert_eq!(diff, expected);
+
+        // A command that changes nothing reports nothing.
+        TurnDiffTracker::on_exec_begin(&tracker, "call-2", root).await;
+        assert_eq!(
+            TurnDiffTracker::on_exec_end(&tracker, "call-2").await,
+            Vec::<PathBuf>::new()
+        );
+    }
+
+    #[tokio::test]
+    async fn exec_changes_skip_files_over_the_size_cap() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("small.txt"), "a\n").unwrap();
+        git(root, &["init"]);
+        git(root, &["add", "."]);
+        git(root, &["commit", "-m", "init"]);
+
+        let tracker = Mutex::new(TurnDiffTracker::new());
+        TurnDiffTracker::on_exec_begin(&tracker, "call-1", root).await;
+        let big = vec![b'x'; MAX_EXEC_SNAPSHOT_FILE_BYTES as usize + 1];
+        fs::write(root.join("big.bin"), big).unwrap();
+        fs::write(root.join("small.txt"), "b\n").unwrap();
+
+        assert_eq!(
+            TurnDiffTracker::on_exec_end(&tracker, "call-1").await,
+            vec![root.join("small.txt")]
+        );
+    }
 }
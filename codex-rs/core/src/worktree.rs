@@ -0,0 +1,125 @@
+//! Runs a session in a `git worktree` of its own so that several sessions can
+//! work on one repository without clobbering each other or the user.
+
+use codex_git_tooling::GitToolingError;
+use codex_git_tooling::SessionWorktree;
+use codex_git_tooling::create_session_worktree;
+
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::git_info::get_git_repo_root;
+use crate::protocol::SandboxPolicy;
+
+/// Directory under `CODEX_HOME` that holds session worktrees.
+const WORKTREES_SUBDIR: &str = "worktrees";
+
+/// Creates a worktree on a new branch from `HEAD` of the repository that
+/// contains `config.cwd`, and points the session's cwd and writable roots at
+/// it.
+pub fn enter_session_worktree(config: &mut Config) -> Result<SessionWorktree, GitToolingError> {
+    let repo_root =
+        get_git_repo_root(&config.cwd).ok_or_else(|| GitToolingError::NotAGitRepository {
+            path: config.cwd.clone(),
+        })?;
+    let id = &Uuid::new_v4().simple().to_string()[..8];
+    let repo_name = repo_root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "repo".to_string());
+    let path = config
+        .codex_home
+        .join(WORKTREES_SUBDIR)
+        .join(format!("{repo_name}-{id}"));
+    let worktree =
+        create_session_worktree(&repo_root, &path, &format!("codex/session-{id}"), None)?;
+
+    // Paths inside the repository move to the same place in the worktree.
+    let relocate = |path: &mut std::path::PathBuf| {
+        if let Ok(relative) = path.strip_prefix(&repo_root) {
+            *path = worktree.path().join(relative);
+        }
+    };
+    relocate(&mut config.cwd);
+    if let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = &mut config.sandbox_policy {
+        writable_roots.iter_mut().for_each(relocate);
+    }
+    Ok(worktree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigOverrides;
+    use crate::config::ConfigToml;
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+    use std::process::Command;
+
+    fn run_git_in(repo: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(repo)
+            .args(args)
+            .status()
+            .expect("git command");
+        assert!(status.success(), "git command failed: {args:?}");
+    }
+
+    #[test]
+    fn session_moves_into_the_worktree() -> anyhow::Result<()> {
+        let codex_home = tempfile::tempdir()?;
+        let repo = tempfile::tempdir()?;
+        let repo_root = repo.path().canonicalize()?;
+        std::fs::create_dir_all(repo_root.join("crates/app"))?;
+        std::fs::write(repo_root.join("crates/app/lib.rs"), "")?;
+        run_git_in(&repo_root, &["init", "--initial-branch=main"]);
+        run_git_in(&repo_root, &["add", "."]);
+        run_git_in(
+            &repo_root,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        );
+
+        let mut config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides {
+                cwd: Some(repo_root.join("crates/app")),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+        let outside = codex_home.path().join("scratch");
+        config.sandbox_policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![repo_root.join("target"), outside.clone()],
+            network_access: false,
+            network_allowlist: Vec::new(),
+            exclude_tmpdir_env_var: false,
+            exclude_slash_tmp: false,
+        };
+
+        let worktree = enter_session_worktree(&mut config)?;
+        assert!(
+            worktree
+                .path()
+                .starts_with(codex_home.path().join("worktrees"))
+        );
+        assert!(worktree.branch().starts_with("codex/session-"));
+        assert_eq!(config.cwd, worktree.path().join("crates/app"));
+        assert!(config.cwd.join("lib.rs").exists());
+        let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = &config.sandbox_policy else {
+            panic!("sandbox policy changed");
+        };
+        assert_eq!(
+            writable_roots,
+            &vec![worktree.path().join("target"), outside]
+        );
+        Ok(())
+    }
+}
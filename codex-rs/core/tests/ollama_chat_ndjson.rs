@@ -0,0 +1,296 @@
+use assert_matches::assert_matches;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use codex_app_server_protocol::AuthMode;
+use codex_core::ContentItem;
+use codex_core::ModelClient;
+use codex_core::ModelProviderInfo;
+use codex_core::Prompt;
+use codex_core::ResponseEvent;
+use codex_core::ResponseItem;
+use codex_core::WireApi;
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::ConversationId;
+use core_test_support::load_default_config_for_test;
+use futures::StreamExt;
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::body_partial_json;
+use wiremock::matchers::header;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+fn network_disabled() -> bool {
+    std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok()
+}
+
+/// Streams `ndjson_body` through a mock `/api/chat` endpoint of a model that
+/// reports a 32k context window, and returns the events up to (but excluding)
+/// the first error, plus that error if any.
+async fn run_stream(ndjson_body: &str) -> (Vec<ResponseEvent>, Option<String>) {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/show"))
+        .and(header("x-test-header", "ollama"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "capabilities": ["completion", "tools", "thinking"],
+            "model_info": {"qwen3.context_length": 32768},
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .and(body_partial_json(json!({
+            "stream": true,
+            "options": {"num_ctx": 32768},
+        })))
+        .respond_with(ndjson_response(ndjson_body))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    stream_once(&server).await
+}
+
+fn ndjson_response(ndjson_body: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200)
+        .insert_header("content-type", "application/x-ndjson")
+        .set_body_bytes(ndjson_body.as_bytes().to_vec())
+}
+
+/// Sends a single prompt to the mock Ollama `server` and collects the events
+/// up to (but excluding) the first error, plus that error if any.
+async fn stream_once(server: &MockServer) -> (Vec<ResponseEvent>, Option<String>) {
+    let provider = ModelProviderInfo {
+        name: "mock".into(),
+        base_url: Some(format!("{}/v1", server.uri())),
+        env_key: None,
+        env_key_instructions: None,
+        wire_api: WireApi::Ollama,
+        query_params: None,
+        http_headers: Some(HashMap::from([(
+            "x-test-header".to_string(),
+            "ollama".to_string(),
+        )])),
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: Some(5_000),
+        requires_openai_auth: false,
+    };
+
+    let codex_home = match TempDir::new() {
+        Ok(dir) => dir,
+        Err(e) => panic!("failed to create TempDir: {e}"),
+    };
+    let mut config = load_default_config_for_test(&codex_home);
+    config.model_provider_id = provider.name.clone();
+    config.model_provider = provider.clone();
+    config.model_context_window = None;
+    let effort = config.model_reasoning_effort;
+    let summary = config.model_reasoning_summary;
+    let config = Arc::new(config);
+
+    let conversation_id = ConversationId::new();
+
+    let otel_event_manager = OtelEventManager::new(
+        conversation_id,
+        config.model.as_str(),
+        config.model_family.slug.as_str(),
+        None,
+        Some("test@test.com".to_string()),
+        Some(AuthMode::ApiKey),
+        false,
+        "test".to_string(),
+    );
+
+    let client = ModelClient::new(
+        Arc::clone(&config),
+        None,
+        otel_event_manager,
+        provider,
+        effort,
+        summary,
+        conversation_id,
+    );
+
+    let mut prompt = Prompt::default();
+    prompt.input = vec![ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: "hello".to_string(),
+        }],
+    }];
+
+    let mut stream = match client.stream(&prompt).await {
+        Ok(s) => s,
+        Err(e) => panic!("stream ollama chat failed: {e}"),
+    };
+    let mut events = Vec::new();
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(ev) => events.push(ev),
+            Err(e) => return (events, Some(e.to_string())),
+        }
+    }
+    (events, None)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn streams_thinking_text_and_tool_calls() {
+    if network_disabled() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let ndjson = concat!(
+        "{\"message\":{\"role\":\"assistant\",\"content\":\"\",\"thinking\":\"plan\"},\"done\":false}\n",
+        "{\"message\":{\"role\":\"assistant\",\"content\":\"hi\"},\"done\":false}\n",
+        "{\"message\":{\"role\":\"assistant\",\"content\":\"\",\"tool_calls\":[{\"function\":{\"name\":\"shell\",\"arguments\":{\"command\":[\"ls\"]}}}]},\"done\":false}\n",
+        "{\"created_at\":\"2026-10-17T00:00:00Z\",\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true,\"done_reason\":\"stop\",\"prompt_eval_count\":12,\"eval_count\":8}\n",
+    );
+
+    let (events, error) = run_stream(ndjson).await;
+    assert_eq!(error, None);
+    assert_eq!(events.len(), 7, "unexpected events: {events:?}");
+
+    assert_matches!(events[0], ResponseEvent::Created);
+
+    match &events[1] {
+        ResponseEvent::ReasoningContentDelta(text) => assert_eq!(text, "plan"),
+        other => panic!("expected reasoning delta, got {other:?}"),
+    }
+
+    match &events[2] {
+        ResponseEvent::OutputTextDelta(text) => assert_eq!(text, "hi"),
+        other => panic!("expected text delta, got {other:?}"),
+    }
+
+    assert_matches!(
+        events[3],
+        ResponseEvent::OutputItemDone(ResponseItem::Reasoning { .. })
+    );
+
+    match &events[4] {
+        ResponseEvent::OutputItemDone(ResponseItem::Message { role, content, .. }) => {
+            assert_eq!(role, "assistant");
+            assert_eq!(
+                content,
+                &vec![ContentItem::OutputText {
+                    text: "hi".to_string()
+                }]
+            );
+        }
+        other => panic!("expected message item, got {other:?}"),
+    }
+
+    match &events[5] {
+        ResponseEvent::OutputItemDone(ResponseItem::FunctionCall {
+            name, arguments, ..
+        }) => {
+            assert_eq!(name, "shell");
+            assert_eq!(arguments, "{\"command\":[\"ls\"]}");
+        }
+        other => panic!("expected function call, got {other:?}"),
+    }
+
+    match &events[6] {
+        ResponseEvent::Completed {
+            token_usage: Some(usage),
+            ..
+        } => {
+            assert_eq!(usage.input_tokens, 12);
+            assert_eq!(usage.output_tokens, 8);
+            assert_eq!(usage.total_tokens, 20);
+        }
+        other => panic!("expected completed event, got {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn error_line_fails_stream() {
+    if network_disabled() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let ndjson = concat!(
+        "{\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":false}\n",
+        "{\"error\":\"model requires more system memory\"}\n",
+    );
+
+    let (events, error) = run_stream(ndjson).await;
+    assert_eq!(events.len(), 1, "unexpected events: {events:?}");
+    assert_matches!(events[0], ResponseEvent::Created);
+    let Some(error) = error else {
+        panic!("expected stream error");
+    };
+    assert!(
+        error.contains("model requires more system memory"),
+        "unexpected error: {error}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn failed_capability_probe_is_retried() {
+    if network_disabled() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/show"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/show"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "capabilities": ["completion", "tools"],
+            "model_info": {"qwen3.context_length": 32768},
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let ndjson = "{\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true,\"done_reason\":\"stop\"}\n";
+    // Only the second request knows the context window.
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .and(body_partial_json(json!({"options": {"num_ctx": 32768}})))
+        .respond_with(ndjson_response(ndjson))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(ndjson_response(ndjson))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    for _ in 0..2 {
+        let (_, error) = stream_once(&server).await;
+        assert_eq!(error, None);
+    }
+}
@@ -0,0 +1,170 @@
+//! Exports spans and metrics for a real turn through the OTLP file exporter.
+//! This is its own test binary because the exporter installs the global
+//! tracer and meter providers.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Result;
+use codex_core::config_types::OtelExporterKind;
+use codex_core::otel_init::build_provider;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningSummary;
+use core_test_support::load_default_config_for_test;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_completed_with_tokens;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+use tempfile::TempDir;
+
+/// Every span in `traces.jsonl`, keyed by name.
+fn read_spans(path: &Path) -> HashMap<String, Vec<Value>> {
+    let mut spans: HashMap<String, Vec<Value>> = HashMap::new();
+    let contents = std::fs::read_to_string(path).expect("read traces");
+    for line in contents.lines() {
+        let request: Value = serde_json::from_str(line).expect("parse traces line");
+        for resource_spans in request["resourceSpans"].as_array().into_iter().flatten() {
+            for scope_spans in resource_spans["scopeSpans"]
+                .as_array()
+                .into_iter()
+                .flatten()
+            {
+                for span in scope_spans["spans"].as_array().into_iter().flatten() {
+                    let name = span["name"].as_str().unwrap_or_default().to_string();
+                    spans.entry(name).or_default().push(span.clone());
+                }
+            }
+        }
+    }
+    spans
+}
+
+fn attribute<'a>(span: &'a Value, key: &str) -> Option<&'a Value> {
+    span["attributes"]
+        .as_array()?
+        .iter()
+        .find(|attribute| attribute["key"] == key)
+        .map(|attribute| &attribute["value"])
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn file_exporter_records_nested_spans_and_metrics() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let otel_home = TempDir::new()?;
+    let mut otel_config = load_default_config_for_test(&otel_home);
+    otel_config.otel.trace_exporter = OtelExporterKind::OtlpFile { path: None };
+    otel_config.otel.metrics_exporter = OtelExporterKind::OtlpFile { path: None };
+    let provider = build_provider(&otel_config, "test")
+        .expect("build provider")
+        .expect("provider is enabled");
+
+    let server = start_mock_server().await;
+    let args = json!({
+        "command": ["/bin/sh", "-c", "exit 3"],
+        "timeout_ms": 5_000,
+    });
+    mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call("call-1", "shell", &serde_json::to_string(&args)?),
+                ev_completed_with_tokens("resp-1", 42),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "done"),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex().build(&server).await?;
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![InputItem::Text {
+                text: "run the command".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+    test.codex.submit(Op::Shutdown).await?;
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::ShutdownComplete)
+    })
+    .await;
+    provider.shutdown();
+
+    let otel_dir = otel_home.path().join("otel");
+    let spans = read_spans(&otel_dir.join("traces.jsonl"));
+    let session = &spans["codex.session"][0];
+    let turn = &spans["codex.turn"][0];
+    let requests = &spans["codex.model_request"];
+    let tool_call = &spans["codex.tool_call"][0];
+
+    assert_eq!(session["parentSpanId"], "");
+    assert_eq!(turn["parentSpanId"], session["spanId"]);
+    assert_eq!(requests.len(), 2);
+    for request in requests {
+        assert_eq!(request["parentSpanId"], turn["spanId"]);
+    }
+    assert_eq!(turn["traceId"], session["traceId"]);
+
+    let first_request = requests
+        .iter()
+        .find(|request| request["spanId"] == tool_call["parentSpanId"])
+        .expect("tool call is a child of a model request");
+    assert!(attribute(first_request, "gen_ai.usage.input_tokens").is_some());
+    assert_eq!(
+        attribute(tool_call, "tool_name"),
+        Some(&json!({ "stringValue": "shell" }))
+    );
+    assert_eq!(
+        attribute(tool_call, "sandbox"),
+        Some(&json!({ "stringValue": "none" }))
+    );
+    assert_eq!(
+        attribute(tool_call, "process.exit_code"),
+        Some(&json!({ "intValue": "3" }))
+    );
+
+    let metrics = std::fs::read_to_string(otel_dir.join("metrics.jsonl"))?;
+    for name in [
+        "codex.tokens",
+        "codex.tool.duration",
+        "codex.api_request.duration",
+    ] {
+        assert!(
+            metrics.contains(&format!("\"name\":\"{name}\"")),
+            "missing metric {name}"
+        );
+    }
+    Ok(())
+}
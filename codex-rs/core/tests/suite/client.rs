@@ -809,7 +809,18 @@ async fn token_count_includes_rate_limits_snapshot() {
         _ => unreachable!(),
     };
 
-    let rate_limit_json = serde_json::to_value(&rate_limit_only).unwrap();
+    let mut rate_limit_json = serde_json::to_value(&rate_limit_only).unwrap();
+    // The request was estimated locally before it was sent.
+    let context_breakdown = rate_limit_json
+        .as_object_mut()
+        .and_then(|event| event.remove("context_breakdown"))
+        .expect("context breakdown");
+    assert!(
+        context_breakdown["instructions"]
+            .as_u64()
+            .unwrap_or_default()
+            > 0
+    );
     pretty_assertions::assert_eq!(
         rate_limit_json,
         json!({
@@ -839,7 +850,12 @@ async fn token_count_includes_rate_limits_snapshot() {
         _ => unreachable!(),
     };
     // Assert full JSON for the final token count event (usage + rate limits)
-    let final_json = serde_json::to_value(&final_payload).unwrap();
+    let mut final_json = serde_json::to_value(&final_payload).unwrap();
+    let final_breakdown = final_json
+        .as_object_mut()
+        .and_then(|event| event.remove("context_breakdown"))
+        .expect("context breakdown");
+    pretty_assertions::assert_eq!(final_breakdown, context_breakdown);
     pretty_assertions::assert_eq!(
         final_json,
         json!({
@@ -958,7 +974,11 @@ async fn usage_limit_error_emits_rate_limit_event() -> anyhow::Result<()> {
         unreachable!();
     };
 
-    let event_json = serde_json::to_value(&event).expect("serialize token count event");
+    let mut event_json = serde_json::to_value(&event).expect("serialize token count event");
+    event_json
+        .as_object_mut()
+        .and_then(|event| event.remove("context_breakdown"))
+        .expect("context breakdown");
     pretty_assertions::assert_eq!(
         event_json,
         json!({
@@ -115,7 +115,10 @@ async fn summarize_context_three_requests_and_instructions() {
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
     // 2) Summarize – second hit should include the summarization prompt.
-    codex.submit(Op::Compact).await.unwrap();
+    codex
+        .submit(Op::Compact { instructions: None })
+        .await
+        .unwrap();
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
     // 3) Next user input – third hit; history should include only the summary.
@@ -680,7 +683,10 @@ async fn manual_compact_retries_after_context_window_error() {
         .unwrap();
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
-    codex.submit(Op::Compact).await.unwrap();
+    codex
+        .submit(Op::Compact { instructions: None })
+        .await
+        .unwrap();
 
     let EventMsg::BackgroundEvent(event) =
         wait_for_event(&codex, |ev| matches!(ev, EventMsg::BackgroundEvent(_))).await
@@ -860,3 +866,102 @@ async fn auto_compact_allows_multiple_attempts_when_interleaved_with_other_turn_
         "second auto compact request should include the summarization prompt"
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn manual_compact_keeps_pinned_items_and_forwards_instructions() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    let request_log = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_assistant_message("m1", FIRST_REPLY),
+                ev_completed("r1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("m2", SUMMARY_TEXT),
+                ev_completed("r2"),
+            ]),
+            sse(vec![ev_completed("r3")]),
+        ],
+    )
+    .await;
+
+    let model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider = model_provider;
+    let codex = ConversationManager::with_auth(CodexAuth::from_api_key("dummy"))
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "hello world".into(),
+            }],
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    // Pin the last response, then compact with guidance for the summarizer.
+    codex.submit(Op::Pin { text: None }).await.unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::BackgroundEvent(_))).await;
+    codex
+        .submit(Op::Compact {
+            instructions: Some("keep the parser notes".to_string()),
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: THIRD_USER_MSG.into(),
+            }],
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = request_log.requests();
+    assert_eq!(requests.len(), 3, "expected turn, compact and follow-up");
+
+    let compact_input = requests[1].input();
+    let compact_prompt = compact_input
+        .last()
+        .and_then(|item| item["content"][0]["text"].as_str())
+        .unwrap_or_default();
+    assert!(compact_prompt.starts_with(SUMMARIZATION_PROMPT));
+    assert!(compact_prompt.ends_with("\nkeep the parser notes"));
+
+    let user_texts: Vec<String> = requests[2]
+        .input()
+        .iter()
+        .filter(|item| item["role"].as_str() == Some("user"))
+        .filter_map(|item| item["content"][0]["text"].as_str())
+        .map(str::to_string)
+        .collect();
+    assert!(
+        user_texts
+            .iter()
+            .any(|text| text == &format!("<pinned_context>\n{FIRST_REPLY}\n</pinned_context>")),
+        "pinned response should survive compaction verbatim: {user_texts:?}"
+    );
+    let bridge = user_texts
+        .iter()
+        .find(|text| text.contains(SUMMARY_TEXT))
+        .expect("bridge message");
+    assert!(
+        !bridge.contains("<pinned_context>"),
+        "pinned items should not be folded into the bridge: {bridge}"
+    );
+}
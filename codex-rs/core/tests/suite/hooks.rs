@@ -0,0 +1,148 @@
+#![cfg(not(target_os = "windows"))]
+
+use anyhow::Result;
+use codex_core::config_types::HookCommand;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningSummary;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+
+fn sh_hook(script: &str, matcher: Option<&str>) -> HookCommand {
+    HookCommand {
+        command: vec!["/bin/sh".to_string(), "-c".to_string(), script.to_string()],
+        matcher: matcher.map(str::to_string),
+        timeout_ms: None,
+    }
+}
+
+async fn submit_turn(test: &TestCodex, prompt: &str) -> Result<()> {
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![InputItem::Text {
+                text: prompt.into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+    Ok(())
+}
+
+fn output_text(output: &Value) -> &str {
+    match output.get("output") {
+        Some(Value::String(text)) => text,
+        Some(Value::Object(obj)) => obj.get("content").and_then(Value::as_str).unwrap_or(""),
+        _ => "",
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tool_hooks_block_and_annotate_calls() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let test = test_codex()
+        .with_config(|config| {
+            config.hooks.pre_tool_use = vec![sh_hook(
+                r#"grep -q '"generated.rs"' && { echo 'generated.rs is generated' >&2; exit 2; }; exit 0"#,
+                Some("shell"),
+            )];
+            config.hooks.post_tool_use = vec![sh_hook(
+                r#"echo '{"additional_context": "post hook ran"}'"#,
+                Some("shell"),
+            )];
+        })
+        .build(&server)
+        .await?;
+
+    let blocked = json!({ "command": ["/bin/touch", "generated.rs"], "timeout_ms": 1_000 });
+    let allowed = json!({ "command": ["/bin/echo", "hello"], "timeout_ms": 1_000 });
+    let responses = vec![
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_function_call("blocked", "shell", &serde_json::to_string(&blocked)?),
+            ev_function_call("allowed", "shell", &serde_json::to_string(&allowed)?),
+            ev_completed("resp-1"),
+        ]),
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-2"),
+        ]),
+    ];
+    let mock = mount_sse_sequence(&server, responses).await;
+
+    submit_turn(&test, "run the commands").await?;
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+
+    let request = mock.requests().pop().expect("follow-up request");
+    let blocked_output = request.function_call_output("blocked");
+    assert_eq!(
+        output_text(&blocked_output),
+        "tool call blocked by hook: generated.rs is generated"
+    );
+    assert!(!test.cwd.path().join("generated.rs").exists());
+
+    let allowed_output = request.function_call_output("allowed");
+    let allowed_text = output_text(&allowed_output);
+    assert!(allowed_text.contains("hello"), "{allowed_text}");
+    assert!(
+        allowed_text.ends_with("\n\npost hook ran"),
+        "{allowed_text}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn user_prompt_submit_hook_can_block_prompt() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let test = test_codex()
+        .with_config(|config| {
+            config.hooks.user_prompt_submit = vec![sh_hook(
+                r#"echo '{"decision": "block", "reason": "no secrets in prompts"}'"#,
+                None,
+            )];
+        })
+        .build(&server)
+        .await?;
+
+    submit_turn(&test, "my password is hunter2").await?;
+    let event = wait_for_event(&test.codex, |event| matches!(event, EventMsg::Error(_))).await;
+    let EventMsg::Error(err) = event else {
+        unreachable!("wait_for_event only returns matching events");
+    };
+
+    assert_eq!(err.message, "Prompt blocked by hook: no secrets in prompts");
+    assert_eq!(
+        server.received_requests().await.unwrap_or_default().len(),
+        0
+    );
+
+    Ok(())
+}
@@ -10,6 +10,7 @@ mod exec;
 mod exec_stream_events;
 mod fork_conversation;
 mod grep_files;
+mod hooks;
 mod json_result;
 mod list_dir;
 mod live_cli;
@@ -21,6 +22,7 @@ mod read_file;
 mod review;
 mod rmcp_client;
 mod rollout_list_find;
+mod scoped_project_docs;
 mod seatbelt;
 mod shell_serialization;
 mod stream_error_allows_next_turn;
@@ -29,5 +31,6 @@ mod tool_harness;
 mod tool_parallelism;
 mod tools;
 mod unified_exec;
+mod usage;
 mod user_notification;
 mod view_image;
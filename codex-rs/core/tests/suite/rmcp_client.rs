@@ -169,6 +169,117 @@ async fn stdio_server_round_trip() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn stdio_server_resources_and_prompts_are_attached_to_turn() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = responses::start_mock_server().await;
+    let response_mock = mount_sse_once_match(
+        &server,
+        any(),
+        responses::sse(vec![
+            responses::ev_assistant_message("msg-1", "done"),
+            responses::ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+
+    let server_name = "rmcp";
+    let rmcp_test_server_bin = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?
+        .path()
+        .to_string_lossy()
+        .into_owned();
+
+    let fixture = test_codex()
+        .with_config(move |config| {
+            config.features.enable(Feature::RmcpClient);
+            config.mcp_servers.insert(
+                server_name.to_string(),
+                McpServerConfig {
+                    transport: McpServerTransportConfig::Stdio {
+                        command: rmcp_test_server_bin,
+                        args: Vec::new(),
+                        env: None,
+                    },
+                    enabled: true,
+                    startup_timeout_sec: Some(Duration::from_secs(10)),
+                    tool_timeout_sec: None,
+                },
+            );
+        })
+        .build(&server)
+        .await?;
+    let session_model = fixture.session_configured.model.clone();
+
+    fixture.codex.submit(Op::ListMcpResources).await?;
+    let EventMsg::McpListResourcesResponse(resources) = wait_for_event(&fixture.codex, |ev| {
+        matches!(ev, EventMsg::McpListResourcesResponse(_))
+    })
+    .await
+    else {
+        unreachable!("event guard guarantees McpListResourcesResponse");
+    };
+    let uris: Vec<&str> = resources.resources[server_name]
+        .iter()
+        .map(|resource| resource.uri.as_str())
+        .collect();
+    assert_eq!(uris, vec!["memo://codex/example-note"]);
+
+    fixture.codex.submit(Op::ListMcpPrompts).await?;
+    let EventMsg::McpListPromptsResponse(prompts) = wait_for_event(&fixture.codex, |ev| {
+        matches!(ev, EventMsg::McpListPromptsResponse(_))
+    })
+    .await
+    else {
+        unreachable!("event guard guarantees McpListPromptsResponse");
+    };
+    let prompt_names: Vec<&str> = prompts.prompts[server_name]
+        .iter()
+        .map(|prompt| prompt.name.as_str())
+        .collect();
+    assert_eq!(prompt_names, vec!["greeting"]);
+
+    fixture
+        .codex
+        .submit(Op::UserTurn {
+            items: vec![
+                InputItem::McpPrompt {
+                    server: server_name.to_string(),
+                    name: "greeting".to_string(),
+                    arguments: Some(HashMap::from([("name".to_string(), "Codex".to_string())])),
+                },
+                InputItem::McpResource {
+                    server: server_name.to_string(),
+                    uri: "memo://codex/example-note".to_string(),
+                },
+            ],
+            final_output_json_schema: None,
+            cwd: fixture.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: session_model,
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+    wait_for_event(&fixture.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let body = response_mock.single_request().body_json().to_string();
+    assert!(
+        body.contains("Say hello to Codex."),
+        "prompt messages should be sent to the model: {body}"
+    );
+    assert!(
+        body.contains("This is a sample MCP resource served by the rmcp test server."),
+        "resource contents should be sent to the model: {body}"
+    );
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn streamable_http_tool_call_round_trip() -> anyhow::Result<()> {
     skip_if_no_network!(Ok(()));
@@ -0,0 +1,112 @@
+#![cfg(not(target_os = "windows"))]
+
+use anyhow::Result;
+use codex_core::config_types::ModelPricing;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::RolloutItem;
+use codex_core::protocol::RolloutLine;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningSummary;
+use core_test_support::responses::ev_completed_with_tokens;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+/// Every token costs a dollar, so the response below costs $10.
+const DOLLAR_PER_TOKEN: ModelPricing = ModelPricing {
+    input: 1_000_000.0,
+    cached_input: None,
+    output: 1_000_000.0,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn session_pauses_over_budget_and_records_cost_in_rollout() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let test = test_codex()
+        .with_config(|config| {
+            config
+                .model_pricing
+                .insert(config.model.clone(), DOLLAR_PER_TOKEN);
+            config.session_budget_usd = Some(5.0);
+        })
+        .build(&server)
+        .await?;
+
+    let command = json!({ "command": ["/bin/echo", "hello"], "timeout_ms": 1_000 });
+    // Only one response is mounted: the follow-up request with the tool
+    // output must not be sent while the session is over budget.
+    let mock = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_function_call("call-1", "shell", &serde_json::to_string(&command)?),
+            ev_completed_with_tokens("resp-1", 10),
+        ]),
+    )
+    .await;
+
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![InputItem::Text {
+                text: "run echo".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+    let event = wait_for_event(
+        &test.codex,
+        |event| matches!(event, EventMsg::BackgroundEvent(ev) if ev.message.starts_with("Paused")),
+    )
+    .await;
+    let EventMsg::BackgroundEvent(paused) = event else {
+        unreachable!("wait_for_event only returns matching events");
+    };
+    assert!(paused.message.contains("$10.00"), "{}", paused.message);
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TaskComplete(_))
+    })
+    .await;
+    // The tool ran, but its output was not sent back to the model.
+    assert_eq!(mock.requests().len(), 1);
+
+    test.codex.submit(Op::Shutdown).await?;
+    wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::ShutdownComplete)
+    })
+    .await;
+
+    let rollout = std::fs::read_to_string(&test.session_configured.rollout_path)?;
+    let usages: Vec<_> = rollout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RolloutLine>(line).ok())
+        .filter_map(|line| match line.item {
+            RolloutItem::SessionMeta(meta_line) => Some(meta_line.meta.usage),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(usages.len(), 2, "initial meta line plus one usage update");
+    assert_eq!(usages[0], None);
+    let usage = usages[1].clone().expect("usage recorded");
+    assert_eq!(usage.cost_usd(), 10.0);
+    assert_eq!(usage.total_tokens(), 10);
+
+    Ok(())
+}
@@ -0,0 +1,425 @@
+//! `codex exec batch`: run the agent over every task in a JSONL file and
+//! write one result line per task.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use clap::Parser;
+use codex_common::CliConfigOverrides;
+use codex_core::AuthManager;
+use codex_core::CodexConversation;
+use codex_core::ConversationManager;
+use codex_core::NewConversation;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::git_info::get_git_repo_root;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::SessionSource;
+use codex_protocol::config_types::SandboxMode;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Parser, Debug)]
+pub struct BatchArgs {
+    /// JSONL file with one task per line, e.g.
+    /// `{"prompt": "...", "cwd": "repo", "model": "gpt-5", "output_schema": "schema.json"}`.
+    #[arg(value_name = "TASKS")]
+    pub tasks: PathBuf,
+
+    /// Write the results to FILE instead of stdout.
+    #[arg(long = "output", short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Maximum number of tasks that run at the same time.
+    #[arg(long = "concurrency", short = 'j', default_value = "4")]
+    pub concurrency: NonZeroUsize,
+
+    /// Model to use for tasks that do not set one.
+    #[arg(long, short = 'm')]
+    pub model: Option<String>,
+
+    /// Configuration profile from config.toml to specify default options.
+    #[arg(long = "profile", short = 'p')]
+    pub config_profile: Option<String>,
+
+    /// Select the sandbox policy to use when executing model-generated shell
+    /// commands.
+    #[arg(long = "sandbox", short = 's', value_enum)]
+    pub sandbox_mode: Option<codex_common::SandboxModeCliArg>,
+
+    /// Convenience alias for low-friction sandboxed automatic execution (--sandbox workspace-write).
+    #[arg(long = "full-auto", default_value_t = false)]
+    pub full_auto: bool,
+
+    /// Allow running tasks outside a Git repository.
+    #[arg(long = "skip-git-repo-check", default_value_t = false)]
+    pub skip_git_repo_check: bool,
+}
+
+/// One line of the tasks file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchTask {
+    prompt: String,
+    /// Working directory; relative paths are resolved against the directory
+    /// of the tasks file.
+    #[serde(default)]
+    cwd: Option<PathBuf>,
+    #[serde(default)]
+    model: Option<String>,
+    /// A JSON Schema, or the path to a file containing one.
+    #[serde(default)]
+    output_schema: Option<Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum BatchStatus {
+    Succeeded,
+    Failed,
+}
+
+/// One line of the results file.
+#[derive(Debug, Serialize)]
+struct BatchResult {
+    /// Line of the task in the tasks file (1-based).
+    line: usize,
+    status: BatchStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cwd: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    final_message: Option<String>,
+    /// The final message parsed as JSON, for tasks with an `output_schema`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    structured_output: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rollout_path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Settings shared by every task in the batch.
+struct BatchContext {
+    conversation_manager: ConversationManager,
+    cli_kv_overrides: Vec<(String, toml::Value)>,
+    model: Option<String>,
+    config_profile: Option<String>,
+    sandbox_mode: Option<SandboxMode>,
+    codex_linux_sandbox_exe: Option<PathBuf>,
+    skip_git_repo_check: bool,
+}
+
+pub async fn run_batch(
+    args: BatchArgs,
+    config_overrides: CliConfigOverrides,
+    codex_linux_sandbox_exe: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("error"));
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(env_filter)
+        .with_writer(std::io::stderr)
+        .try_init();
+
+    let tasks = read_tasks(&args.tasks)?;
+    if tasks.is_empty() {
+        anyhow::bail!("{} contains no tasks", args.tasks.display());
+    }
+    let cli_kv_overrides = config_overrides
+        .parse_overrides()
+        .map_err(|e| anyhow::anyhow!("Error parsing -c overrides: {e}"))?;
+    let sandbox_mode = if args.full_auto {
+        Some(SandboxMode::WorkspaceWrite)
+    } else {
+        args.sandbox_mode.map(Into::<SandboxMode>::into)
+    };
+
+    // Only used to locate CODEX_HOME; each task loads its own config.
+    let config = Config::load_with_cli_overrides(
+        cli_kv_overrides.clone(),
+        ConfigOverrides {
+            config_profile: args.config_profile.clone(),
+            ..Default::default()
+        },
+    )
+    .await?;
+    let auth_manager = AuthManager::shared(config.codex_home.clone(), true);
+    let ctx = Arc::new(BatchContext {
+        conversation_manager: ConversationManager::new(auth_manager, SessionSource::Exec),
+        cli_kv_overrides,
+        model: args.model,
+        config_profile: args.config_profile,
+        sandbox_mode,
+        codex_linux_sandbox_exe,
+        skip_git_repo_check: args.skip_git_repo_check,
+    });
+
+    let mut output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(std::fs::File::create(path)?),
+        None => Box::new(std::io::stdout()),
+    };
+
+    let total = tasks.len();
+    let semaphore = Arc::new(Semaphore::new(args.concurrency.get()));
+    let mut join_set = JoinSet::new();
+    // Task id -> line, so a task that panics still gets a result line.
+    let mut task_lines = HashMap::new();
+    for (line, task) in tasks {
+        let ctx = Arc::clone(&ctx);
+        let semaphore = Arc::clone(&semaphore);
+        let handle = join_set.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            run_task(&ctx, line, task).await
+        });
+        task_lines.insert(handle.id(), line);
+    }
+
+    // Results are written as tasks finish; `line` ties them back to the input.
+    let mut done = 0;
+    let mut failed = 0;
+    while let Some(joined) = join_set.join_next_with_id().await {
+        let result = match joined {
+            Ok((_, result)) => result,
+            Err(err) => BatchResult {
+                error: Some(format!("task did not complete: {err}")),
+                ..BatchResult::failed(task_lines.get(&err.id()).copied().unwrap_or_default())
+            },
+        };
+        done += 1;
+        match &result.error {
+            Some(error) => {
+                failed += 1;
+                eprintln!("[{done}/{total}] line {} failed: {error}", result.line);
+            }
+            None => eprintln!("[{done}/{total}] line {} succeeded", result.line),
+        }
+        writeln!(output, "{}", serde_json::to_string(&result)?)?;
+        output.flush()?;
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{failed} of {total} tasks failed");
+    }
+    Ok(())
+}
+
+/// Reads the tasks file, resolving relative `cwd` and `output_schema` paths
+/// against its directory. Blank lines are skipped.
+fn read_tasks(path: &Path) -> anyhow::Result<Vec<(usize, BatchTask)>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
+    let base_dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.canonicalize()?,
+        _ => std::env::current_dir()?,
+    };
+
+    let mut tasks = Vec::new();
+    for (idx, raw) in contents.lines().enumerate() {
+        let line = idx + 1;
+        if raw.trim().is_empty() {
+            continue;
+        }
+        let mut task: BatchTask = serde_json::from_str(raw)
+            .map_err(|e| anyhow::anyhow!("{}:{line}: invalid task: {e}", path.display()))?;
+        task.cwd = task.cwd.map(|cwd| base_dir.join(cwd));
+        if let Some(Value::String(schema_path)) = &task.output_schema {
+            let schema_path = base_dir.join(schema_path);
+            let schema = std::fs::read_to_string(&schema_path)
+                .map_err(anyhow::Error::from)
+                .and_then(|s| Ok(serde_json::from_str(&s)?))
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "{}:{line}: failed to load output schema {}: {e}",
+                        path.display(),
+                        schema_path.display()
+                    )
+                })?;
+            task.output_schema = Some(schema);
+        }
+        tasks.push((line, task));
+    }
+    Ok(tasks)
+}
+
+impl BatchResult {
+    fn failed(line: usize) -> Self {
+        Self {
+            line,
+            status: BatchStatus::Failed,
+            cwd: None,
+            model: None,
+            final_message: None,
+            structured_output: None,
+            rollout_path: None,
+            error: None,
+        }
+    }
+}
+
+async fn run_task(ctx: &BatchContext, line: usize, task: BatchTask) -> BatchResult {
+    let mut result = BatchResult::failed(line);
+    match run_task_inner(ctx, task, &mut result).await {
+        Ok(()) => result.status = BatchStatus::Succeeded,
+        Err(err) => result.error = Some(err.to_string()),
+    }
+    result
+}
+
+/// Runs a single task in a fresh session, filling in `result` as it goes so
+/// that a failed task still reports where its rollout was written.
+async fn run_task_inner(
+    ctx: &BatchContext,
+    task: BatchTask,
+    result: &mut BatchResult,
+) -> anyhow::Result<()> {
+    let overrides = ConfigOverrides {
+        model: task.model.or_else(|| ctx.model.clone()),
+        config_profile: ctx.config_profile.clone(),
+        // Nobody is around to approve anything.
+        approval_policy: Some(AskForApproval::Never),
+        sandbox_mode: ctx.sandbox_mode,
+        cwd: task.cwd,
+        codex_linux_sandbox_exe: ctx.codex_linux_sandbox_exe.clone(),
+        ..Default::default()
+    };
+    let config = Config::load_with_cli_overrides(ctx.cli_kv_overrides.clone(), overrides).await?;
+    result.cwd = Some(config.cwd.clone());
+    result.model = Some(config.model.clone());
+    if !ctx.skip_git_repo_check && get_git_repo_root(&config.cwd).is_none() {
+        anyhow::bail!(
+            "{} is not inside a Git repository and --skip-git-repo-check was not specified",
+            config.cwd.display()
+        );
+    }
+
+    let NewConversation {
+        conversation_id,
+        conversation,
+        session_configured,
+    } = ctx
+        .conversation_manager
+        .new_conversation(config.clone())
+        .await?;
+    result.rollout_path = Some(session_configured.rollout_path);
+
+    let expects_json = task.output_schema.is_some();
+    let turn = run_turn(
+        &conversation,
+        &config,
+        task.prompt,
+        task.output_schema,
+        result,
+    )
+    .await;
+
+    // Flush the rollout before reporting it, even if the turn failed midway.
+    if conversation.submit(Op::Shutdown).await.is_ok() {
+        while let Ok(event) = conversation.next_event().await {
+            if matches!(event.msg, EventMsg::ShutdownComplete) {
+                break;
+            }
+        }
+    }
+    ctx.conversation_manager
+        .remove_conversation(&conversation_id)
+        .await;
+
+    if let Some(message) = turn? {
+        anyhow::bail!(message);
+    }
+    if expects_json {
+        let message = result.final_message.as_deref().unwrap_or_default();
+        let value = serde_json::from_str(message)
+            .map_err(|e| anyhow::anyhow!("final message is not valid JSON: {e}"))?;
+        result.structured_output = Some(value);
+    }
+    Ok(())
+}
+
+/// Submits the task's prompt and waits for the turn to finish. Returns the
+/// last error reported by the session, if any.
+async fn run_turn(
+    conversation: &CodexConversation,
+    config: &Config,
+    prompt: String,
+    output_schema: Option<Value>,
+    result: &mut BatchResult,
+) -> anyhow::Result<Option<String>> {
+    conversation
+        .submit(Op::UserTurn {
+            items: vec![InputItem::Text { text: prompt }],
+            cwd: config.cwd.clone(),
+            approval_policy: config.approval_policy,
+            sandbox_policy: config.sandbox_policy.clone(),
+            model: config.model.clone(),
+            effort: config.model_reasoning_effort,
+            summary: config.model_reasoning_summary,
+            final_output_json_schema: output_schema,
+        })
+        .await?;
+
+    let mut error = None;
+    loop {
+        match conversation.next_event().await?.msg {
+            EventMsg::Error(err) => error = Some(err.message),
+            EventMsg::TaskComplete(ev) => {
+                result.final_message = ev.last_agent_message;
+                return Ok(error);
+            }
+            EventMsg::TurnAborted(ev) => {
+                error.get_or_insert_with(|| format!("turn aborted: {:?}", ev.reason));
+                return Ok(error);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn read_tasks_resolves_paths_against_the_tasks_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let base = dir.path().canonicalize().expect("canonicalize");
+        std::fs::write(base.join("schema.json"), r#"{"type":"object"}"#).expect("write schema");
+        let tasks_path = base.join("tasks.jsonl");
+        std::fs::write(
+            &tasks_path,
+            "{\"prompt\":\"a\",\"cwd\":\"repo\",\"output_schema\":\"schema.json\"}\n\n{\"prompt\":\"b\",\"output_schema\":{\"type\":\"string\"}}\n",
+        )
+        .expect("write tasks");
+
+        let tasks = read_tasks(&tasks_path).expect("read tasks");
+        let lines: Vec<usize> = tasks.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![1, 3]);
+        assert_eq!(tasks[0].1.cwd, Some(base.join("repo")));
+        assert_eq!(
+            tasks[0].1.output_schema,
+            Some(serde_json::json!({"type": "object"}))
+        );
+        assert_eq!(tasks[1].1.cwd, None);
+        assert_eq!(
+            tasks[1].1.output_schema,
+            Some(serde_json::json!({"type": "string"}))
+        );
+
+        std::fs::write(&tasks_path, "{\"prompt\":\"a\",\"repo\":\"x\"}\n").expect("write tasks");
+        let err = read_tasks(&tasks_path).expect_err("unknown field");
+        assert!(err.to_string().contains(":1: invalid task"), "{err}");
+    }
+}
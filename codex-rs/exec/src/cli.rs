@@ -3,6 +3,9 @@ use clap::ValueEnum;
 use codex_common::CliConfigOverrides;
 use std::path::PathBuf;
 
+use crate::batch::BatchArgs;
+use crate::review::ReviewArgs;
+
 #[derive(Parser, Debug)]
 #[command(version)]
 pub struct Cli {
@@ -56,6 +59,11 @@ pub struct Cli {
     #[arg(long = "output-schema", value_name = "FILE")]
     pub output_schema: Option<PathBuf>,
 
+    /// Work in a new git worktree and branch instead of the current working
+    /// tree. The branch is kept when the run ends.
+    #[arg(long = "worktree", default_value_t = false)]
+    pub worktree: bool,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 
@@ -85,6 +93,12 @@ pub struct Cli {
 pub enum Command {
     /// Resume a previous session by id or pick the most recent with --last.
     Resume(ResumeArgs),
+
+    /// Review code changes and print the findings as JSON, SARIF, GitHub annotations or markdown.
+    Review(ReviewArgs),
+
+    /// Run every task in a JSONL file and write one result per task as JSONL.
+    Batch(BatchArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -17,6 +17,9 @@ use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::StreamErrorEvent;
+use codex_core::protocol::SubAgentBeginEvent;
+use codex_core::protocol::SubAgentEndEvent;
+use codex_core::protocol::SubAgentEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnDiffEvent;
@@ -484,7 +487,9 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::GetHistoryEntryResponse(_) => {
                 // Currently ignored in exec output.
             }
-            EventMsg::McpListToolsResponse(_) => {
+            EventMsg::McpListToolsResponse(_)
+            | EventMsg::McpListResourcesResponse(_)
+            | EventMsg::McpListPromptsResponse(_) => {
                 // Currently ignored in exec output.
             }
             EventMsg::ListCustomPromptsResponse(_) => {
@@ -498,6 +503,29 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     view.path.display()
                 );
             }
+            EventMsg::SubAgentBegin(SubAgentBeginEvent { prompt, cwd, .. }) => {
+                ts_msg!(
+                    self,
+                    "{}\n{} in {}",
+                    "sub-agent".style(self.italic).style(self.magenta),
+                    prompt.style(self.bold),
+                    cwd.display()
+                );
+            }
+            EventMsg::SubAgentEvent(SubAgentEvent { msg, .. }) => {
+                // Nested events are rendered like the parent's own.
+                self.process_event(Event {
+                    id: String::new(),
+                    msg: *msg,
+                });
+            }
+            EventMsg::SubAgentEnd(SubAgentEndEvent { success, .. }) => {
+                if success {
+                    ts_msg!(self, "{}", "sub-agent finished".style(self.magenta));
+                } else {
+                    ts_msg!(self, "{}", "sub-agent failed".style(self.red));
+                }
+            }
             EventMsg::TurnAborted(abort_reason) => match abort_reason.reason {
                 TurnAbortReason::Interrupted => {
                     ts_msg!(self, "task interrupted");
@@ -4,12 +4,16 @@
 // For both modes, any other output must be written to stderr.
 #![deny(clippy::print_stdout)]
 
+mod batch;
 mod cli;
 mod event_processor;
 mod event_processor_with_human_output;
 pub mod event_processor_with_jsonl_output;
 pub mod exec_events;
+mod review;
 
+pub use batch::BatchArgs;
+pub use batch::run_batch;
 pub use cli::Cli;
 use codex_core::AuthManager;
 use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
@@ -31,6 +35,9 @@ use codex_protocol::config_types::SandboxMode;
 use event_processor_with_human_output::EventProcessorWithHumanOutput;
 use event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
+pub use review::ReviewArgs;
+pub use review::ReviewOutputFormat;
+pub use review::run_review;
 use serde_json::Value;
 use std::io::IsTerminal;
 use std::io::Read;
@@ -70,15 +77,33 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         prompt,
         output_schema: output_schema_path,
         include_plan_tool,
+        worktree,
         config_overrides,
     } = cli;
 
+    if let Some(ExecCommand::Review(mut args)) = command {
+        // Flags given before `review` apply to the review as well.
+        args.model = args.model.or(model_cli_arg);
+        args.config_profile = args.config_profile.or(config_profile);
+        args.cwd = args.cwd.or(cwd);
+        args.skip_git_repo_check |= skip_git_repo_check;
+        return run_review(args, config_overrides, codex_linux_sandbox_exe).await;
+    }
+    if let Some(ExecCommand::Batch(mut args)) = command {
+        args.model = args.model.or(model_cli_arg);
+        args.config_profile = args.config_profile.or(config_profile);
+        args.sandbox_mode = args.sandbox_mode.or(sandbox_mode_cli_arg);
+        args.full_auto |= full_auto;
+        args.skip_git_repo_check |= skip_git_repo_check;
+        return run_batch(args, config_overrides, codex_linux_sandbox_exe).await;
+    }
+
     // Determine the prompt source (parent or subcommand) and read from stdin if needed.
     let prompt_arg = match &command {
         // Allow prompt before the subcommand by falling back to the parent-level prompt
         // when the Resume subcommand did not provide its own prompt.
         Some(ExecCommand::Resume(args)) => args.prompt.clone().or(prompt),
-        None => prompt,
+        Some(ExecCommand::Review(_)) | Some(ExecCommand::Batch(_)) | None => prompt,
     };
 
     let prompt = match prompt_arg {
@@ -181,6 +206,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         include_view_image_tool: None,
         show_raw_agent_reasoning: oss.then_some(true),
         tools_web_search_request: None,
+        worktree: worktree.then_some(true),
     };
     // Parse `-c` overrides.
     let cli_kv_overrides = match config_overrides.parse_overrides() {
@@ -191,7 +217,12 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     };
 
-    let config = Config::load_with_cli_overrides(cli_kv_overrides, overrides).await?;
+    let mut config = Config::load_with_cli_overrides(cli_kv_overrides, overrides).await?;
+    let session_worktree = if config.worktree {
+        Some(codex_core::worktree::enter_session_worktree(&mut config)?)
+    } else {
+        None
+    };
     let approve_all_enabled = config.features.enabled(Feature::ApproveAll);
 
     let otel = codex_core::otel_init::build_provider(&config, env!("CARGO_PKG_VERSION"));
@@ -228,7 +259,8 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     };
 
     if oss {
-        codex_ollama::ensure_oss_ready(&config)
+        let mut reporter = codex_ollama::CliProgressReporter::new();
+        codex_ollama::ensure_oss_ready(&config, &mut reporter)
             .await
             .map_err(|e| anyhow::anyhow!("OSS setup failed: {e}"))?;
     }
@@ -401,6 +433,12 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     }
     event_processor.print_final_output();
+    if let Some(worktree) = session_worktree
+        && worktree.keep_if_changed("Codex session changes")?
+    {
+        let branch = worktree.branch();
+        eprintln!("Changes are on branch {branch}; run `git merge {branch}` to merge them.");
+    }
     if error_seen {
         std::process::exit(1);
     }
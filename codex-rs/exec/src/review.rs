@@ -0,0 +1,270 @@
+//! `codex exec review`: run a code review without the TUI and print the
+//! findings in a machine-readable format.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use clap::ValueEnum;
+use codex_common::CliConfigOverrides;
+use codex_core::AuthManager;
+use codex_core::ConversationManager;
+use codex_core::NewConversation;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::git_info::get_git_repo_root;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::ReviewExportFormat;
+use codex_core::protocol::ReviewOutputEvent;
+use codex_core::protocol::ReviewRequest;
+use codex_core::protocol::ReviewTarget;
+use codex_core::protocol::SessionSource;
+use codex_core::review_format::export_review;
+use codex_protocol::config_types::SandboxMode;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Parser, Debug, Default)]
+pub struct ReviewArgs {
+    /// Review the changes on the current branch since it diverged from BRANCH.
+    #[arg(long = "base", value_name = "BRANCH", conflicts_with_all = ["commit", "patch"])]
+    pub base: Option<String>,
+
+    /// Review a single commit, or a range of commits written as FROM..TO.
+    #[arg(long = "commit", value_name = "REV", conflicts_with = "patch")]
+    pub commit: Option<String>,
+
+    /// Review the changes in a unified diff file, e.g. a pull request patch.
+    #[arg(long = "patch", value_name = "FILE")]
+    pub patch: Option<PathBuf>,
+
+    /// Format of the findings written to stdout (or `--output`).
+    #[arg(long = "format", value_enum, default_value_t = ReviewOutputFormat::Json)]
+    pub format: ReviewOutputFormat,
+
+    /// Write the findings to FILE instead of stdout.
+    #[arg(long = "output", value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Model the reviewer should use.
+    #[arg(long, short = 'm')]
+    pub model: Option<String>,
+
+    /// Configuration profile from config.toml to specify default options.
+    #[arg(long = "profile", short = 'p')]
+    pub config_profile: Option<String>,
+
+    /// Directory of the repository to review.
+    #[clap(long = "cd", short = 'C', value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Allow reviewing a patch file outside a Git repository.
+    #[arg(long = "skip-git-repo-check", default_value_t = false)]
+    pub skip_git_repo_check: bool,
+
+    /// Additional instructions for the reviewer, e.g. what to focus on.
+    #[arg(value_name = "PROMPT", value_hint = clap::ValueHint::Other)]
+    pub prompt: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum ReviewOutputFormat {
+    /// The `ReviewOutputEvent` as JSON.
+    #[default]
+    Json,
+    /// A SARIF 2.1.0 log for code scanning tools.
+    Sarif,
+    /// GitHub Actions workflow commands that annotate the findings.
+    Github,
+    /// A markdown report.
+    Markdown,
+}
+
+impl From<ReviewOutputFormat> for ReviewExportFormat {
+    fn from(format: ReviewOutputFormat) -> Self {
+        match format {
+            ReviewOutputFormat::Json => ReviewExportFormat::Json,
+            ReviewOutputFormat::Sarif => ReviewExportFormat::Sarif,
+            ReviewOutputFormat::Github => ReviewExportFormat::Github,
+            ReviewOutputFormat::Markdown => ReviewExportFormat::Markdown,
+        }
+    }
+}
+
+impl ReviewArgs {
+    /// Returns the target selected by the flags; uncommitted changes when
+    /// none is given.
+    fn target(&self) -> anyhow::Result<ReviewTarget> {
+        if let Some(branch) = &self.base {
+            return Ok(ReviewTarget::BaseBranch {
+                branch: branch.clone(),
+            });
+        }
+        if let Some(path) = &self.patch {
+            return Ok(ReviewTarget::PatchFile { path: path.clone() });
+        }
+        match self.commit.as_deref() {
+            Some(rev) if rev.contains("...") => Err(anyhow::anyhow!(
+                "`--commit {rev}`: use `--base` to review against a merge base"
+            )),
+            Some(rev) => Ok(match rev.split_once("..") {
+                Some((from, to)) => ReviewTarget::CommitRange {
+                    from: if from.is_empty() { "HEAD" } else { from }.to_string(),
+                    to: if to.is_empty() { "HEAD" } else { to }.to_string(),
+                },
+                None => ReviewTarget::Commit {
+                    sha: rev.to_string(),
+                },
+            }),
+            None => Ok(ReviewTarget::UncommittedChanges),
+        }
+    }
+}
+
+pub async fn run_review(
+    args: ReviewArgs,
+    config_overrides: CliConfigOverrides,
+    codex_linux_sandbox_exe: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("error"));
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(env_filter)
+        .with_writer(std::io::stderr)
+        .try_init();
+
+    let target = args.target()?;
+    let cli_kv_overrides = config_overrides
+        .parse_overrides()
+        .map_err(|e| anyhow::anyhow!("Error parsing -c overrides: {e}"))?;
+    // Reviews only read the repository.
+    let overrides = ConfigOverrides {
+        model: args.model.clone(),
+        review_model: args.model.clone(),
+        config_profile: args.config_profile.clone(),
+        approval_policy: Some(AskForApproval::Never),
+        sandbox_mode: Some(SandboxMode::ReadOnly),
+        cwd: args.cwd.clone().map(|p| p.canonicalize().unwrap_or(p)),
+        codex_linux_sandbox_exe,
+        ..Default::default()
+    };
+    let config = Config::load_with_cli_overrides(cli_kv_overrides, overrides).await?;
+
+    let repo_root = get_git_repo_root(&config.cwd);
+    if !args.skip_git_repo_check && repo_root.is_none() {
+        anyhow::bail!("Not inside a Git repository and --skip-git-repo-check was not specified.");
+    }
+
+    let review_output = review(&config, target, args.prompt.unwrap_or_default()).await?;
+    let root = repo_root.unwrap_or_else(|| config.cwd.clone());
+    let mut rendered = export_review(&review_output, args.format.into(), &root)?;
+    if !rendered.ends_with('\n') {
+        rendered.push('\n');
+    }
+    match args.output {
+        Some(path) => std::fs::write(&path, rendered)?,
+        None => {
+            #[allow(clippy::print_stdout)]
+            {
+                print!("{rendered}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs the review in a fresh session and returns its findings.
+async fn review(
+    config: &Config,
+    target: ReviewTarget,
+    prompt: String,
+) -> anyhow::Result<ReviewOutputEvent> {
+    let auth_manager = AuthManager::shared(config.codex_home.clone(), true);
+    let conversation_manager = ConversationManager::new(auth_manager, SessionSource::Exec);
+    let NewConversation { conversation, .. } = conversation_manager
+        .new_conversation(config.clone())
+        .await?;
+
+    conversation
+        .submit(Op::Review {
+            review_request: ReviewRequest {
+                prompt,
+                user_facing_hint: String::new(),
+                target: Some(target),
+            },
+        })
+        .await?;
+
+    let mut started = false;
+    let mut error = None;
+    let mut review_output = None;
+    loop {
+        let event = conversation.next_event().await?;
+        match event.msg {
+            EventMsg::EnteredReviewMode(request) => {
+                started = true;
+                eprintln!("Reviewing {}...", request.user_facing_hint);
+            }
+            EventMsg::ExitedReviewMode(ev) => review_output = ev.review_output,
+            EventMsg::Error(err) => {
+                eprintln!("ERROR: {}", err.message);
+                error = Some(err.message);
+                // A review that failed to start never completes.
+                if !started {
+                    break;
+                }
+            }
+            EventMsg::TaskComplete(_) => break,
+            _ => {}
+        }
+    }
+
+    // Flush the rollout before exiting.
+    conversation.submit(Op::Shutdown).await?;
+    while let Ok(event) = conversation.next_event().await {
+        if matches!(event.msg, EventMsg::ShutdownComplete) {
+            break;
+        }
+    }
+
+    match (review_output, error) {
+        (Some(review_output), _) => Ok(review_output),
+        (None, Some(message)) => Err(anyhow::anyhow!(message)),
+        (None, None) => Err(anyhow::anyhow!("the review ended without a result")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn args_for(commit: &str) -> ReviewArgs {
+        ReviewArgs {
+            commit: Some(commit.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn commit_flag_accepts_single_commits_and_ranges() {
+        assert_eq!(
+            args_for("abc123").target().expect("target"),
+            ReviewTarget::Commit {
+                sha: "abc123".to_string()
+            }
+        );
+        assert_eq!(
+            args_for("main..").target().expect("target"),
+            ReviewTarget::CommitRange {
+                from: "main".to_string(),
+                to: "HEAD".to_string(),
+            }
+        );
+        assert!(args_for("main...feature").target().is_err());
+        assert_eq!(
+            ReviewArgs::default().target().expect("target"),
+            ReviewTarget::UncommittedChanges
+        );
+    }
+}
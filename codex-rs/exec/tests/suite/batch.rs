@@ -0,0 +1,74 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use wiremock::matchers::any;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn batch_writes_a_result_per_task_and_fails_if_any_task_fails() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+    let cwd = test.cwd_path();
+    std::fs::create_dir_all(cwd.join("repo/.git"))?;
+    std::fs::create_dir_all(cwd.join("plain"))?;
+
+    let schema = serde_json::json!({
+        "type": "object",
+        "properties": { "answer": { "type": "string" } },
+        "required": ["answer"],
+        "additionalProperties": false
+    });
+    let tasks = [
+        serde_json::json!({ "prompt": "what is the answer?", "cwd": "repo", "output_schema": schema }),
+        serde_json::json!({ "prompt": "not a repo", "cwd": "plain" }),
+    ];
+    let tasks = tasks.map(|task| task.to_string()).join("\n");
+    std::fs::write(cwd.join("tasks.jsonl"), tasks)?;
+
+    let server = responses::start_mock_server().await;
+    let body = responses::sse(vec![
+        responses::ev_response_created("resp1"),
+        responses::ev_assistant_message("m1", r#"{"answer":"42"}"#),
+        responses::ev_completed("resp1"),
+    ]);
+    let response_mock = responses::mount_sse_once_match(&server, any(), body).await;
+
+    let output = test
+        .cmd_with_server(&server)
+        .arg("batch")
+        .arg("tasks.jsonl")
+        .arg("--output")
+        .arg("results.jsonl")
+        .output()?;
+    assert!(!output.status.success(), "{output:?}");
+
+    let mut results: Vec<Value> = std::fs::read_to_string(cwd.join("results.jsonl"))?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    results.sort_by_key(|result| result["line"].as_u64());
+    assert_eq!(results.len(), 2);
+
+    let succeeded = &results[0];
+    assert_eq!(succeeded["status"], "succeeded");
+    assert_eq!(succeeded["final_message"], r#"{"answer":"42"}"#);
+    assert_eq!(
+        succeeded["structured_output"],
+        serde_json::json!({ "answer": "42" })
+    );
+    let rollout_path = succeeded["rollout_path"].as_str().expect("rollout path");
+    assert!(std::path::Path::new(rollout_path).exists());
+
+    let failed = &results[1];
+    assert_eq!(failed["line"], 2);
+    assert_eq!(failed["status"], "failed");
+    let error = failed["error"].as_str().expect("error");
+    assert!(error.contains("not inside a Git repository"), "{error}");
+
+    let request = response_mock.single_request().body_json();
+    assert_eq!(request["text"]["format"]["schema"], schema);
+
+    Ok(())
+}
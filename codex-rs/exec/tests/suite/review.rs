@@ -0,0 +1,72 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use wiremock::matchers::any;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn review_patch_file_prints_sarif() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+    let cwd = test.cwd_path().canonicalize()?;
+
+    let patch = "\
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1 +1 @@
+-fn add(a: i32, b: i32) -> i32 { a + b }
++fn add(a: i32, b: i32) -> i32 { a - b }
+";
+    std::fs::write(cwd.join("fix.patch"), patch)?;
+
+    let review_json = serde_json::json!({
+        "findings": [{
+            "title": "[P1] add subtracts",
+            "body": "`add` now returns `a - b`.",
+            "confidence_score": 0.9,
+            "priority": 1,
+            "code_location": {
+                "absolute_file_path": cwd.join("src/lib.rs"),
+                "line_range": { "start": 1, "end": 1 }
+            }
+        }],
+        "overall_correctness": "patch is incorrect",
+        "overall_explanation": "The change breaks addition.",
+        "overall_confidence_score": 0.8
+    });
+    let server = responses::start_mock_server().await;
+    let body = responses::sse(vec![
+        responses::ev_response_created("resp1"),
+        responses::ev_assistant_message("m1", &review_json.to_string()),
+        responses::ev_completed("resp1"),
+    ]);
+    let response_mock = responses::mount_sse_once_match(&server, any(), body).await;
+
+    let output = test
+        .cmd_with_server(&server)
+        .arg("review")
+        .arg("--skip-git-repo-check")
+        .arg("-C")
+        .arg(&cwd)
+        .arg("--patch")
+        .arg("fix.patch")
+        .arg("--format")
+        .arg("sarif")
+        .output()?;
+    assert!(output.status.success(), "{output:?}");
+
+    let sarif: Value = serde_json::from_slice(&output.stdout)?;
+    let result = &sarif["runs"][0]["results"][0];
+    assert_eq!(result["level"], "error");
+    assert_eq!(
+        result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "src/lib.rs"
+    );
+
+    let request = response_mock.single_request().body_json().to_string();
+    assert!(request.contains("a - b"), "patch missing from request");
+
+    Ok(())
+}
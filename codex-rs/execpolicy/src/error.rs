@@ -88,6 +88,13 @@ pub enum Error {
     CannotCheckRelativePath {
         file: PathBuf,
     },
+    ShellScriptParseError {
+        script: String,
+    },
+    UnsupportedShellSyntax {
+        kind: String,
+        text: String,
+    },
     CannotCanonicalizePath {
         file: String,
         #[serde_as(as = "DisplayFromStr")]
@@ -15,6 +15,13 @@ impl ExecCall {
             args: args.iter().map(|&s| s.into()).collect(),
         }
     }
+
+    /// Returns the program followed by its arguments.
+    pub fn to_argv(&self) -> Vec<String> {
+        std::iter::once(self.program.clone())
+            .chain(self.args.iter().cloned())
+            .collect()
+    }
 }
 
 impl Display for ExecCall {
@@ -98,7 +98,7 @@ impl ExecvChecker {
     }
 }
 
-fn ensure_absolute_path(path: &str, cwd: &Option<OsString>) -> Result<PathBuf> {
+pub(crate) fn ensure_absolute_path(path: &str, cwd: &Option<OsString>) -> Result<PathBuf> {
     let file = PathBuf::from(path);
     let result = if file.is_relative() {
         match cwd {
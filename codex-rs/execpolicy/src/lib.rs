@@ -11,9 +11,11 @@ mod exec_call;
 mod execv_checker;
 mod opt;
 mod policy;
+mod policy_files;
 mod policy_parser;
 mod program;
 mod sed_command;
+mod shell_script;
 mod valid_exec;
 
 pub use arg_matcher::ArgMatcher;
@@ -24,7 +26,12 @@ pub use error::Result;
 pub use exec_call::ExecCall;
 pub use execv_checker::ExecvChecker;
 pub use opt::Opt;
+pub use policy::CheckExplanation;
 pub use policy::Policy;
+pub use policy::ScriptCommandCheck;
+pub use policy_files::POLICY_FILE_EXTENSION;
+pub use policy_files::load_policy_file;
+pub use policy_files::policy_files_in_dir;
 pub use policy_parser::PolicyParser;
 pub use program::Forbidden;
 pub use program::MatchedExec;
@@ -32,6 +39,9 @@ pub use program::NegativeExamplePassedCheck;
 pub use program::PositiveExampleFailedCheck;
 pub use program::ProgramSpec;
 pub use sed_command::parse_sed_command;
+pub use shell_script::Redirect;
+pub use shell_script::ScriptCommand;
+pub use shell_script::parse_shell_script;
 pub use valid_exec::MatchedArg;
 pub use valid_exec::MatchedFlag;
 pub use valid_exec::MatchedOpt;
@@ -5,6 +5,7 @@ use codex_execpolicy::ExecCall;
 use codex_execpolicy::MatchedExec;
 use codex_execpolicy::Policy;
 use codex_execpolicy::PolicyParser;
+use codex_execpolicy::ScriptCommand;
 use codex_execpolicy::ValidExec;
 use codex_execpolicy::get_default_policy;
 use serde::Deserialize;
@@ -49,6 +50,21 @@ pub enum Command {
         #[serde(deserialize_with = "deserialize_from_json")]
         exec: ExecArg,
     },
+
+    /// Checks each command in a shell script, as run by `bash -c`.
+    #[clap(name = "check-script")]
+    CheckScript {
+        /// Directory that relative redirect targets are resolved against.
+        /// Defaults to the current directory.
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+
+        /// Folder that the script may redirect output into (repeatable).
+        #[arg(long = "writeable-folder", value_name = "DIR")]
+        writeable_folders: Vec<PathBuf>,
+
+        script: String,
+    },
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -75,6 +91,38 @@ fn main() -> Result<()> {
     let policy = policy.map_err(StarlarkError::into_anyhow)?;
 
     let exec = match args.command {
+        Command::CheckScript {
+            cwd,
+            writeable_folders,
+            script,
+        } => {
+            let cwd = match cwd {
+                Some(cwd) => cwd,
+                None => std::env::current_dir()?,
+            };
+            let (json, exit_code) = match check_script(
+                &policy,
+                &script,
+                &cwd,
+                &writeable_folders,
+                args.require_safe,
+            ) {
+                Ok((output, exit_code)) => (serde_json::to_string(&output)?, exit_code),
+                Err(error) => {
+                    let exit_code = if args.require_safe {
+                        MIGHT_BE_SAFE_EXIT_CODE
+                    } else {
+                        0
+                    };
+                    (
+                        serde_json::to_string(&Output::Unverified { error })?,
+                        exit_code,
+                    )
+                }
+            };
+            println!("{json}");
+            std::process::exit(exit_code);
+        }
         Command::Check { command } => match command.split_first() {
             Some((first, rest)) => ExecArg {
                 program: first.to_string(),
@@ -100,9 +148,48 @@ fn check_command(
     check: bool,
 ) -> (Output, i32) {
     let exec_call = ExecCall { program, args };
-    match policy.check(&exec_call) {
+    let result = policy.check(&exec_call);
+    let might_write_files =
+        matches!(&result, Ok(MatchedExec::Match { exec }) if exec.might_write_files());
+    classify(result, might_write_files, check)
+}
+
+fn check_script(
+    policy: &Policy,
+    script: &str,
+    cwd: &std::path::Path,
+    writeable_folders: &[PathBuf],
+    check: bool,
+) -> codex_execpolicy::Result<(ScriptOutput, i32)> {
+    let mut result = "safe";
+    let mut exit_code = 0;
+    let mut commands = Vec::new();
+    for command_check in policy.check_script(script, cwd, writeable_folders)? {
+        let might_write_files = command_check.might_write_files();
+        let (output, command_exit_code) = classify(command_check.result, might_write_files, true);
+        // The exit codes are ordered by severity, so the most severe verdict
+        // determines the result for the whole script.
+        if command_exit_code > exit_code {
+            exit_code = command_exit_code;
+            result = output.result_name();
+        }
+        commands.push(ScriptCommandOutput {
+            command: command_check.command,
+            output,
+        });
+    }
+    let exit_code = if check { exit_code } else { 0 };
+    Ok((ScriptOutput { result, commands }, exit_code))
+}
+
+fn classify(
+    result: codex_execpolicy::Result<MatchedExec>,
+    might_write_files: bool,
+    check: bool,
+) -> (Output, i32) {
+    match result {
         Ok(MatchedExec::Match { exec }) => {
-            if exec.might_write_files() {
+            if might_write_files {
                 let exit_code = if check {
                     MATCHED_BUT_WRITES_FILES_EXIT_CODE
                 } else {
@@ -148,6 +235,32 @@ pub enum Output {
     Unverified { error: codex_execpolicy::Error },
 }
 
+impl Output {
+    fn result_name(&self) -> &'static str {
+        match self {
+            Output::Safe { .. } => "safe",
+            Output::Match { .. } => "match",
+            Output::Forbidden { .. } => "forbidden",
+            Output::Unverified { .. } => "unverified",
+        }
+    }
+}
+
+/// The result of `check-script`: the most severe verdict across all of the
+/// commands in the script, followed by the verdict for each command.
+#[derive(Debug, Serialize)]
+pub struct ScriptOutput {
+    result: &'static str,
+    commands: Vec<ScriptCommandOutput>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScriptCommandOutput {
+    command: ScriptCommand,
+    #[serde(flatten)]
+    output: Output,
+}
+
 fn deserialize_from_json<'de, D>(deserializer: D) -> Result<ExecArg, D::Error>
 where
     D: de::Deserializer<'de>,
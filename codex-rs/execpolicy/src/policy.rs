@@ -1,3 +1,7 @@
+use std::ffi::OsString;
+use std::path::Path;
+use std::path::PathBuf;
+
 use multimap::MultiMap;
 use regex_lite::Error as RegexError;
 use regex_lite::Regex;
@@ -7,15 +11,28 @@ use crate::Forbidden;
 use crate::MatchedExec;
 use crate::NegativeExamplePassedCheck;
 use crate::ProgramSpec;
+use crate::ScriptCommand;
 use crate::error::Error;
 use crate::error::Result;
+use crate::execv_checker::ensure_absolute_path;
+use crate::parse_shell_script;
 use crate::policy_parser::ForbiddenProgramRegex;
 use crate::program::PositiveExampleFailedCheck;
 
 pub struct Policy {
     programs: MultiMap<String, ProgramSpec>,
     forbidden_program_regexes: Vec<ForbiddenProgramRegex>,
-    forbidden_substrings_pattern: Option<Regex>,
+    forbidden_substrings_patterns: Vec<Regex>,
+}
+
+/// The result of checking an [`ExecCall`], along with the rule that decided
+/// it.
+#[derive(Debug)]
+pub struct CheckExplanation {
+    pub result: Result<MatchedExec>,
+    /// Where the deciding rule was defined, e.g. `team.policy:12`. `None` if
+    /// no rule matched or the rule's location is unknown.
+    pub rule: Option<String>,
 }
 
 impl Policy {
@@ -24,48 +41,86 @@ impl Policy {
         forbidden_program_regexes: Vec<ForbiddenProgramRegex>,
         forbidden_substrings: Vec<String>,
     ) -> std::result::Result<Self, RegexError> {
-        let forbidden_substrings_pattern = if forbidden_substrings.is_empty() {
-            None
-        } else {
+        let mut forbidden_substrings_patterns = Vec::new();
+        if !forbidden_substrings.is_empty() {
             let escaped_substrings = forbidden_substrings
                 .iter()
                 .map(|s| regex_lite::escape(s))
                 .collect::<Vec<_>>()
                 .join("|");
-            Some(Regex::new(&format!("({escaped_substrings})"))?)
-        };
+            forbidden_substrings_patterns.push(Regex::new(&format!("({escaped_substrings})"))?);
+        }
         Ok(Self {
             programs,
             forbidden_program_regexes,
-            forbidden_substrings_pattern,
+            forbidden_substrings_patterns,
         })
     }
 
+    /// Adds the rules from `other` to this policy. Program specs from `other`
+    /// are tried before the existing specs for the same program, so a
+    /// user-defined rule takes precedence over a built-in one.
+    pub fn merge(&mut self, other: Policy) {
+        let Policy {
+            programs,
+            forbidden_program_regexes,
+            forbidden_substrings_patterns,
+        } = other;
+        for (program, mut specs) in programs {
+            if let Some(existing) = self.programs.remove(&program) {
+                specs.extend(existing);
+            }
+            self.programs.insert_many(program, specs);
+        }
+        self.forbidden_program_regexes
+            .extend(forbidden_program_regexes);
+        self.forbidden_substrings_patterns
+            .extend(forbidden_substrings_patterns);
+    }
+
     pub fn check(&self, exec_call: &ExecCall) -> Result<MatchedExec> {
+        self.explain(exec_call).result
+    }
+
+    /// Like [`Policy::check`], but also reports which rule decided the result.
+    pub fn explain(&self, exec_call: &ExecCall) -> CheckExplanation {
         let ExecCall { program, args } = &exec_call;
-        for ForbiddenProgramRegex { regex, reason } in &self.forbidden_program_regexes {
+        for ForbiddenProgramRegex {
+            regex,
+            reason,
+            source,
+        } in &self.forbidden_program_regexes
+        {
             if regex.is_match(program) {
-                return Ok(MatchedExec::Forbidden {
-                    cause: Forbidden::Program {
-                        program: program.clone(),
-                        exec_call: exec_call.clone(),
-                    },
-                    reason: reason.clone(),
-                });
+                return CheckExplanation {
+                    result: Ok(MatchedExec::Forbidden {
+                        cause: Forbidden::Program {
+                            program: program.clone(),
+                            exec_call: exec_call.clone(),
+                        },
+                        reason: reason.clone(),
+                    }),
+                    rule: source.clone(),
+                };
             }
         }
 
         for arg in args {
-            if let Some(regex) = &self.forbidden_substrings_pattern
-                && regex.is_match(arg)
+            if self
+                .forbidden_substrings_patterns
+                .iter()
+                .any(|regex| regex.is_match(arg))
             {
-                return Ok(MatchedExec::Forbidden {
-                    cause: Forbidden::Arg {
-                        arg: arg.clone(),
-                        exec_call: exec_call.clone(),
-                    },
-                    reason: format!("arg `{arg}` contains forbidden substring"),
-                });
+                return CheckExplanation {
+                    result: Ok(MatchedExec::Forbidden {
+                        cause: Forbidden::Arg {
+                            arg: arg.clone(),
+                            exec_call: exec_call.clone(),
+                        },
+                        reason: format!("arg `{arg}` contains forbidden substring"),
+                    }),
+                    rule: None,
+                };
             }
         }
 
@@ -75,14 +130,93 @@ impl Policy {
         if let Some(spec_list) = self.programs.get_vec(program) {
             for spec in spec_list {
                 match spec.check(exec_call) {
-                    Ok(matched_exec) => return Ok(matched_exec),
+                    Ok(matched_exec) => {
+                        return CheckExplanation {
+                            result: Ok(matched_exec),
+                            rule: spec.source.clone(),
+                        };
+                    }
                     Err(err) => {
                         last_err = Err(err);
                     }
                 }
             }
         }
-        last_err
+        CheckExplanation {
+            result: last_err,
+            rule: None,
+        }
+    }
+
+    /// Splits `script` into the commands it would run and checks each one
+    /// against the policy. A command that redirects output to a path outside
+    /// `writeable_folders` is forbidden; relative redirect targets are resolved
+    /// against `cwd`.
+    ///
+    /// Returns an error if the script cannot be split into commands
+    /// statically, in which case none of it can be verified.
+    ///
+    /// The caller is responsible for ensuring `writeable_folders` are in
+    /// canonical form.
+    pub fn check_script(
+        &self,
+        script: &str,
+        cwd: &Path,
+        writeable_folders: &[PathBuf],
+    ) -> Result<Vec<ScriptCommandCheck>> {
+        let cwd = Some(OsString::from(cwd));
+        let checks = parse_shell_script(script)?
+            .into_iter()
+            .map(|command| {
+                let CheckExplanation { result, rule } =
+                    self.check_script_command(&command, &cwd, writeable_folders);
+                ScriptCommandCheck {
+                    command,
+                    result,
+                    rule,
+                }
+            })
+            .collect();
+        Ok(checks)
+    }
+
+    fn check_script_command(
+        &self,
+        command: &ScriptCommand,
+        cwd: &Option<OsString>,
+        writeable_folders: &[PathBuf],
+    ) -> CheckExplanation {
+        for redirect in &command.redirects {
+            let Some(path) = redirect.written_path() else {
+                continue;
+            };
+            let file = match ensure_absolute_path(path, cwd) {
+                Ok(file) => file,
+                Err(err) => {
+                    return CheckExplanation {
+                        result: Err(err),
+                        rule: None,
+                    };
+                }
+            };
+            if !writeable_folders
+                .iter()
+                .any(|folder| file.starts_with(folder))
+            {
+                let result = Ok(MatchedExec::Forbidden {
+                    cause: Forbidden::Redirect {
+                        redirect: redirect.clone(),
+                        exec_call: command.exec_call.clone(),
+                    },
+                    reason: format!(
+                        "redirects output to `{}`, which is outside the writeable folders",
+                        file.display()
+                    ),
+                });
+                return CheckExplanation { result, rule: None };
+            }
+        }
+        self.explain(&command.exec_call)
     }
 
     pub fn check_each_good_list_individually(&self) -> Vec<PositiveExampleFailedCheck> {
@@ -101,3 +235,28 @@ impl Policy {
         violations
     }
 }
+
+/// The verdict for one command within a shell script.
+#[derive(Debug)]
+pub struct ScriptCommandCheck {
+    pub command: ScriptCommand,
+    pub result: Result<MatchedExec>,
+    /// Where the rule that decided `result` was defined, if any.
+    pub rule: Option<String>,
+}
+
+impl ScriptCommandCheck {
+    /// Whether running the command might write files, either through its
+    /// arguments or through a redirection.
+    pub fn might_write_files(&self) -> bool {
+        let redirect_writes = self
+            .command
+            .redirects
+            .iter()
+            .any(|redirect| redirect.written_path().is_some());
+        match &self.result {
+            Ok(MatchedExec::Match { exec }) => redirect_writes || exec.might_write_files(),
+            _ => redirect_writes,
+        }
+    }
+}
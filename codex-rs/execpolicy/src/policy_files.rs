@@ -0,0 +1,74 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use starlark::Error as StarlarkError;
+
+use crate::Policy;
+use crate::PolicyParser;
+
+/// Extension of the policy files that are loaded from a policy directory.
+pub const POLICY_FILE_EXTENSION: &str = "policy";
+
+/// Returns the `*.policy` files directly inside `dir`, sorted by file name. A
+/// missing directory contains no policy files.
+pub fn policy_files_in_dir(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_file()
+            && path.extension().and_then(|ext| ext.to_str()) == Some(POLICY_FILE_EXTENSION)
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Parses the policy file at `path` and verifies that every `should_match`
+/// example matches and every `should_not_match` example does not, so a broken
+/// rule is reported when the file is loaded rather than silently ignored.
+pub fn load_policy_file(path: &Path) -> anyhow::Result<Policy> {
+    let unparsed_policy = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let policy = PolicyParser::new(&path.to_string_lossy(), &unparsed_policy)
+        .parse()
+        .map_err(StarlarkError::into_anyhow)
+        .with_context(|| format!("failed to parse {}", path.display()))?;
+
+    let mut violations = Vec::new();
+    for failed in policy.check_each_good_list_individually() {
+        violations.push(format!(
+            "should_match example `{}` did not match: {:?}",
+            shell_join(&failed.program, &failed.args),
+            failed.error
+        ));
+    }
+    for passed in policy.check_each_bad_list_individually() {
+        violations.push(format!(
+            "should_not_match example `{}` matched",
+            shell_join(&passed.program, &passed.args)
+        ));
+    }
+    if !violations.is_empty() {
+        anyhow::bail!(
+            "invalid examples in {}:\n  {}",
+            path.display(),
+            violations.join("\n  ")
+        );
+    }
+    Ok(policy)
+}
+
+fn shell_join(program: &str, args: &[String]) -> String {
+    std::iter::once(program)
+        .chain(args.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -75,6 +75,8 @@ impl PolicyParser {
 pub struct ForbiddenProgramRegex {
     pub regex: regex_lite::Regex,
     pub reason: String,
+    /// Where the rule was defined, e.g. `team.policy:12`.
+    pub source: Option<String>,
 }
 
 #[derive(Debug, ProvidesStaticType)]
@@ -112,12 +114,23 @@ impl PolicyBuilder {
         forbidden_substrings.extend_from_slice(substrings);
     }
 
-    fn add_forbidden_program_regex(&self, regex: Regex, reason: String) {
+    fn add_forbidden_program_regex(&self, regex: Regex, reason: String, source: Option<String>) {
         let mut forbidden_program_regexes = self.forbidden_program_regexes.borrow_mut();
-        forbidden_program_regexes.push(ForbiddenProgramRegex { regex, reason });
+        forbidden_program_regexes.push(ForbiddenProgramRegex {
+            regex,
+            reason,
+            source,
+        });
     }
 }
 
+/// Formats the location of the builtin call currently being evaluated as
+/// `file:line`.
+fn call_site(eval: &Evaluator) -> Option<String> {
+    eval.call_stack_top_location()
+        .map(|span| format!("{}:{}", span.filename(), span.resolve_span().begin.line + 1))
+}
+
 #[starlark_module]
 fn policy_builtins(builder: &mut GlobalsBuilder) {
     fn define_program<'v>(
@@ -149,7 +162,7 @@ fn policy_builtins(builder: &mut GlobalsBuilder) {
             }
         }
 
-        let program_spec = ProgramSpec::new(
+        let mut program_spec = ProgramSpec::new(
             program,
             system_path,
             option_bundling,
@@ -168,6 +181,7 @@ fn policy_builtins(builder: &mut GlobalsBuilder) {
                 .map(|v| v.items.to_vec())
                 .collect(),
         );
+        program_spec.source = call_site(eval);
 
         #[expect(clippy::unwrap_used)]
         let policy_builder = eval
@@ -208,7 +222,7 @@ fn policy_builtins(builder: &mut GlobalsBuilder) {
             .downcast_ref::<PolicyBuilder>()
             .unwrap();
         let compiled_regex = regex_lite::Regex::new(&regex)?;
-        policy_builder.add_forbidden_program_regex(compiled_regex, reason);
+        policy_builder.add_forbidden_program_regex(compiled_regex, reason, call_site(eval));
         Ok(NoneType)
     }
 
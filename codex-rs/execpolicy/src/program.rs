@@ -4,6 +4,7 @@ use std::collections::HashSet;
 
 use crate::ArgType;
 use crate::ExecCall;
+use crate::Redirect;
 use crate::arg_matcher::ArgMatcher;
 use crate::arg_resolver::PositionalArg;
 use crate::arg_resolver::resolve_observed_args_with_patterns;
@@ -23,6 +24,8 @@ pub struct ProgramSpec {
     pub combined_format: bool,
     pub allowed_options: HashMap<String, Opt>,
     pub arg_patterns: Vec<ArgMatcher>,
+    /// Where the spec was defined, e.g. `team.policy:12`.
+    pub source: Option<String>,
     forbidden: Option<String>,
     required_options: HashSet<String>,
     should_match: Vec<Vec<String>>,
@@ -58,6 +61,7 @@ impl ProgramSpec {
             combined_format,
             allowed_options,
             arg_patterns,
+            source: None,
             forbidden,
             required_options,
             should_match,
@@ -85,6 +89,10 @@ pub enum Forbidden {
     Exec {
         exec: ValidExec,
     },
+    Redirect {
+        redirect: Redirect,
+        exec_call: ExecCall,
+    },
 }
 
 impl ProgramSpec {
@@ -0,0 +1,261 @@
+use serde::Serialize;
+use tree_sitter::Node;
+use tree_sitter::Parser;
+use tree_sitter_bash::LANGUAGE as BASH;
+
+use crate::ExecCall;
+use crate::error::Error;
+use crate::error::Result;
+
+/// A single command that a shell script would run, along with the
+/// redirections that apply to it (including those inherited from an enclosing
+/// subshell).
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ScriptCommand {
+    pub exec_call: ExecCall,
+    pub redirects: Vec<Redirect>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Redirect {
+    /// The redirection operator, including an explicit file descriptor, if
+    /// any, e.g. `>`, `2>>`, or `>&`.
+    pub operator: String,
+    pub target: String,
+}
+
+impl Redirect {
+    /// Returns the path this redirection may create or modify, if any.
+    /// Duplicating or closing a file descriptor (`2>&1`, `>&-`) and writing to
+    /// `/dev/null`, `/dev/stdout`, or `/dev/stderr` do not touch the filesystem.
+    pub fn written_path(&self) -> Option<&str> {
+        let op = self
+            .operator
+            .trim_start_matches(|c: char| c.is_ascii_digit());
+        let writes = match op {
+            ">" | ">>" | ">|" | "&>" | "&>>" | "<>" => true,
+            // `>&word` writes to `word` unless it names a file descriptor.
+            ">&" => !is_fd_target(&self.target),
+            _ => false,
+        };
+        let device = matches!(
+            self.target.as_str(),
+            "/dev/null" | "/dev/stdout" | "/dev/stderr"
+        );
+        (writes && !device).then_some(self.target.as_str())
+    }
+}
+
+fn is_fd_target(target: &str) -> bool {
+    target == "-" || (!target.is_empty() && target.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Splits a shell script into the commands it would run. Commands may be
+/// combined with `|`, `|&`, `&&`, `||`, and `;`, grouped with subshells, and
+/// redirected to or from literal paths.
+///
+/// Anything whose effect cannot be determined statically (variable or command
+/// expansion, globs, control flow, heredocs, background jobs, etc.) yields an
+/// error so the caller can treat the script as unverified.
+pub fn parse_shell_script(script: &str) -> Result<Vec<ScriptCommand>> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&BASH.into())
+        .map_err(|err| Error::InternalInvariantViolation {
+            message: format!("failed to load bash grammar: {err}"),
+        })?;
+    let tree = parser
+        .parse(script, None)
+        .ok_or_else(|| Error::ShellScriptParseError {
+            script: script.to_string(),
+        })?;
+    let root = tree.root_node();
+    if root.has_error() {
+        return Err(Error::ShellScriptParseError {
+            script: script.to_string(),
+        });
+    }
+
+    let mut commands = Vec::new();
+    collect_commands(root, script, &[], &mut commands)?;
+    Ok(commands)
+}
+
+fn collect_commands(
+    node: Node,
+    src: &str,
+    redirects: &[Redirect],
+    commands: &mut Vec<ScriptCommand>,
+) -> Result<()> {
+    match node.kind() {
+        "program" | "list" | "pipeline" | "subshell" => {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                if child.is_named() {
+                    collect_commands(child, src, redirects, commands)?;
+                } else if !is_allowed_operator(child.kind()) {
+                    return Err(unsupported(child, src));
+                }
+            }
+            Ok(())
+        }
+        "redirected_statement" => {
+            let body = node
+                .child_by_field_name("body")
+                .ok_or_else(|| unsupported(node, src))?;
+            let mut redirected = redirects.to_vec();
+            let mut cursor = node.walk();
+            for child in node.children_by_field_name("redirect", &mut cursor) {
+                redirected.push(parse_redirect(child, src)?);
+            }
+            collect_redirected_commands(body, src, redirects, &redirected, commands)
+        }
+        "command" => {
+            commands.push(parse_command(node, src, redirects)?);
+            Ok(())
+        }
+        "comment" => Ok(()),
+        _ => Err(unsupported(node, src)),
+    }
+}
+
+/// tree-sitter-bash attaches the redirection in `a && (b) > out` to the whole
+/// list, whereas bash applies it only to the last command of the list (or
+/// pipeline), so only that command receives `redirected`.
+fn collect_redirected_commands(
+    node: Node,
+    src: &str,
+    redirects: &[Redirect],
+    redirected: &[Redirect],
+    commands: &mut Vec<ScriptCommand>,
+) -> Result<()> {
+    if !matches!(node.kind(), "list" | "pipeline") {
+        return collect_commands(node, src, redirected, commands);
+    }
+    let last = node.named_child(node.named_child_count().saturating_sub(1));
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if Some(child) == last {
+            collect_redirected_commands(child, src, redirects, redirected, commands)?;
+        } else if child.is_named() {
+            collect_commands(child, src, redirects, commands)?;
+        } else if !is_allowed_operator(child.kind()) {
+            return Err(unsupported(child, src));
+        }
+    }
+    Ok(())
+}
+
+fn is_allowed_operator(kind: &str) -> bool {
+    matches!(kind, "&&" | "||" | "|" | "|&" | ";" | "(" | ")")
+}
+
+fn parse_command(node: Node, src: &str, redirects: &[Redirect]) -> Result<ScriptCommand> {
+    let mut words = Vec::new();
+    let mut redirects = redirects.to_vec();
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "command_name" => {
+                let name = child
+                    .named_child(0)
+                    .ok_or_else(|| unsupported(child, src))?;
+                words.push(parse_word(name, src)?);
+            }
+            "file_redirect" => redirects.push(parse_redirect(child, src)?),
+            _ => words.push(parse_word(child, src)?),
+        }
+    }
+    let Some((program, args)) = words.split_first() else {
+        return Err(unsupported(node, src));
+    };
+    Ok(ScriptCommand {
+        exec_call: ExecCall {
+            program: program.clone(),
+            args: args.to_vec(),
+        },
+        redirects,
+    })
+}
+
+fn parse_redirect(node: Node, src: &str) -> Result<Redirect> {
+    if node.kind() != "file_redirect" {
+        return Err(unsupported(node, src));
+    }
+    let mut operator = String::new();
+    let mut target = None;
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "file_descriptor" => operator.push_str(text(child, src)?),
+            _ if !child.is_named() => operator.push_str(child.kind()),
+            _ if target.is_none() => target = Some(parse_word(child, src)?),
+            _ => return Err(unsupported(node, src)),
+        }
+    }
+    let target = target.ok_or_else(|| unsupported(node, src))?;
+    Ok(Redirect { operator, target })
+}
+
+/// Resolves a word to the literal string the shell would pass along, or fails
+/// if the shell would expand it.
+fn parse_word(node: Node, src: &str) -> Result<String> {
+    match node.kind() {
+        "word" | "number" => {
+            let word = text(node, src)?;
+            if word.starts_with('~') || word.contains(['\\', '*', '?', '[', '{']) {
+                return Err(unsupported(node, src));
+            }
+            Ok(word.to_string())
+        }
+        "raw_string" => text(node, src)?
+            .strip_prefix('\'')
+            .and_then(|s| s.strip_suffix('\''))
+            .map(str::to_string)
+            .ok_or_else(|| unsupported(node, src)),
+        "string" => {
+            let mut value = String::new();
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                match child.kind() {
+                    "\"" => {}
+                    "string_content" => {
+                        let content = text(child, src)?;
+                        if content.contains(['\\', '`']) {
+                            return Err(unsupported(node, src));
+                        }
+                        value.push_str(content);
+                    }
+                    _ => return Err(unsupported(node, src)),
+                }
+            }
+            Ok(value)
+        }
+        "concatenation" => {
+            let mut value = String::new();
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                value.push_str(&parse_word(child, src)?);
+            }
+            Ok(value)
+        }
+        _ => Err(unsupported(node, src)),
+    }
+}
+
+fn text<'a>(node: Node, src: &'a str) -> Result<&'a str> {
+    node.utf8_text(src.as_bytes())
+        .map_err(|err| Error::InternalInvariantViolation {
+            message: format!("invalid utf-8 in shell script: {err}"),
+        })
+}
+
+fn unsupported(node: Node, src: &str) -> Error {
+    Error::UnsupportedShellSyntax {
+        kind: node.kind().to_string(),
+        text: src
+            .get(node.start_byte()..node.end_byte())
+            .unwrap_or_default()
+            .to_string(),
+    }
+}
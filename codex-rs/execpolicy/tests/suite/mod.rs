@@ -6,5 +6,7 @@ mod head;
 mod literal;
 mod ls;
 mod parse_sed_command;
+mod policy_files;
 mod pwd;
 mod sed;
+mod shell_script;
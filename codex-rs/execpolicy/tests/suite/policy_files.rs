@@ -0,0 +1,123 @@
+extern crate codex_execpolicy;
+
+use std::path::Path;
+
+use codex_execpolicy::Error;
+use codex_execpolicy::ExecCall;
+use codex_execpolicy::MatchedExec;
+use codex_execpolicy::get_default_policy;
+use codex_execpolicy::load_policy_file;
+use codex_execpolicy::policy_files_in_dir;
+use tempfile::TempDir;
+
+const TEAM_POLICY: &str = r#"
+define_program(
+    program="./x.py",
+    args=["check"],
+    should_match=[["check"]],
+    should_not_match=[["dist"]],
+)
+
+define_program(
+    program="ls",
+    forbidden="listing is not allowed here",
+    should_match=[[]],
+)
+"#;
+
+fn write_policy(dir: &Path, name: &str, contents: &str) -> anyhow::Result<std::path::PathBuf> {
+    let path = dir.join(name);
+    std::fs::write(&path, contents)?;
+    Ok(path)
+}
+
+#[test]
+fn test_policy_files_in_dir() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    write_policy(dir.path(), "b.policy", "")?;
+    write_policy(dir.path(), "a.policy", "")?;
+    write_policy(dir.path(), "notes.txt", "")?;
+
+    assert_eq!(
+        vec![dir.path().join("a.policy"), dir.path().join("b.policy")],
+        policy_files_in_dir(dir.path())?
+    );
+    assert!(policy_files_in_dir(&dir.path().join("missing"))?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_merged_rules_take_precedence_and_report_their_source() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = write_policy(dir.path(), "team.policy", TEAM_POLICY)?;
+    let mut policy = get_default_policy().map_err(starlark::Error::into_anyhow)?;
+    policy.merge(load_policy_file(&path)?);
+
+    let explanation = policy.explain(&ExecCall::new("./x.py", &["check"]));
+    assert!(matches!(
+        explanation.result,
+        Ok(MatchedExec::Match { ref exec }) if !exec.might_write_files()
+    ));
+    assert_eq!(Some(format!("{}:2", path.display())), explanation.rule);
+
+    // The user-defined `ls` rule is tried before the built-in one.
+    let explanation = policy.explain(&ExecCall::new("ls", &[]));
+    assert!(matches!(
+        explanation.result,
+        Ok(MatchedExec::Forbidden { ref reason, .. }) if reason == "listing is not allowed here"
+    ));
+    assert_eq!(Some(format!("{}:9", path.display())), explanation.rule);
+
+    // Built-in rules still apply when no user-defined rule matches.
+    let explanation = policy.explain(&ExecCall::new("pwd", &[]));
+    assert!(matches!(explanation.result, Ok(MatchedExec::Match { .. })));
+    assert_eq!(Some("#default:98".to_string()), explanation.rule);
+
+    let explanation = policy.explain(&ExecCall::new("./x.py", &["dist"]));
+    assert!(matches!(
+        explanation.result,
+        Err(Error::LiteralValueDidNotMatch { .. })
+    ));
+    assert_eq!(None, explanation.rule);
+    Ok(())
+}
+
+#[test]
+fn test_invalid_examples_are_reported_at_load_time() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = write_policy(
+        dir.path(),
+        "broken.policy",
+        r#"
+define_program(
+    program="./x.py",
+    args=["check"],
+    should_match=[["check", "--fix"]],
+    should_not_match=[["check"]],
+)
+"#,
+    )?;
+
+    let err = load_policy_file(&path)
+        .err()
+        .ok_or_else(|| anyhow::anyhow!("expected an error"))?;
+    let message = err.to_string();
+    assert!(message.contains("broken.policy"), "{message}");
+    assert!(
+        message.contains("should_match example `./x.py check --fix` did not match"),
+        "{message}"
+    );
+    assert!(
+        message.contains("should_not_match example `./x.py check` matched"),
+        "{message}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_syntax_errors_are_reported_at_load_time() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = write_policy(dir.path(), "syntax.policy", "define_program(")?;
+    assert!(load_policy_file(&path).is_err());
+    Ok(())
+}
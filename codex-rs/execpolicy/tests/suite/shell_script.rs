@@ -0,0 +1,183 @@
+extern crate codex_execpolicy;
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_execpolicy::Error;
+use codex_execpolicy::ExecCall;
+use codex_execpolicy::Forbidden;
+use codex_execpolicy::MatchedExec;
+use codex_execpolicy::Policy;
+use codex_execpolicy::Redirect;
+use codex_execpolicy::ScriptCommand;
+use codex_execpolicy::get_default_policy;
+use codex_execpolicy::parse_shell_script;
+
+#[expect(clippy::expect_used)]
+fn setup() -> Policy {
+    get_default_policy().expect("failed to load default policy")
+}
+
+fn command(program: &str, args: &[&str], redirects: &[(&str, &str)]) -> ScriptCommand {
+    ScriptCommand {
+        exec_call: ExecCall::new(program, args),
+        redirects: redirects
+            .iter()
+            .map(|(operator, target)| Redirect {
+                operator: operator.to_string(),
+                target: target.to_string(),
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn test_splits_compound_script() {
+    assert_eq!(
+        Ok(vec![
+            command("ls", &["-l", "foo"], &[]),
+            command("pwd", &[], &[]),
+            command("head", &["-n", "5"], &[]),
+            command("cat", &["bar baz"], &[]),
+            command("echo", &["--name=quoted value"], &[]),
+        ]),
+        parse_shell_script(
+            "ls -l foo && pwd | head -n 5; (cat 'bar baz') || echo --name=\"quoted value\""
+        )
+    );
+}
+
+#[test]
+fn test_redirects_apply_to_enclosed_commands() {
+    assert_eq!(
+        Ok(vec![
+            command("ls", &[], &[(">", "out.txt"), ("2>&", "1")]),
+            command("pwd", &[], &[(">>", "log.txt")]),
+            command("cat", &[], &[(">>", "log.txt"), ("<", "in.txt")]),
+        ]),
+        parse_shell_script("ls > out.txt 2>&1; (pwd; cat < in.txt) >> log.txt")
+    );
+    // The trailing redirection only applies to the last command of the list.
+    assert_eq!(
+        Ok(vec![
+            command("pwd", &[], &[]),
+            command("ls", &[], &[(">", "out.txt")]),
+        ]),
+        parse_shell_script("pwd && (ls) > out.txt")
+    );
+}
+
+#[test]
+fn test_redirect_written_path() {
+    let redirect = |operator: &str, target: &str| Redirect {
+        operator: operator.to_string(),
+        target: target.to_string(),
+    };
+    assert_eq!(Some("out.txt"), redirect(">", "out.txt").written_path());
+    assert_eq!(Some("out.txt"), redirect("2>>", "out.txt").written_path());
+    assert_eq!(Some("out.txt"), redirect(">&", "out.txt").written_path());
+    assert_eq!(None, redirect("2>&", "1").written_path());
+    assert_eq!(None, redirect(">", "/dev/null").written_path());
+    assert_eq!(None, redirect(">", "/dev/stderr").written_path());
+    assert_eq!(None, redirect("<", "in.txt").written_path());
+}
+
+#[test]
+fn test_rejects_constructs_that_cannot_be_checked_statically() {
+    for script in [
+        "echo $HOME",
+        "ls $(pwd)",
+        "ls `pwd`",
+        "ls *.rs",
+        "ls ~/src",
+        "FOO=1 ls",
+        "sleep 1 &",
+        "for f in a b; do cat $f; done",
+        "if true; then ls; fi",
+        "cat <<EOF\nhi\nEOF",
+        "diff <(ls a) <(ls b)",
+        "ls > $OUT",
+    ] {
+        assert!(
+            matches!(
+                parse_shell_script(script),
+                Err(Error::UnsupportedShellSyntax { .. })
+            ),
+            "expected {script:?} to be rejected, got {:?}",
+            parse_shell_script(script)
+        );
+    }
+}
+
+#[test]
+fn test_rejects_malformed_script() {
+    assert_eq!(
+        Err(Error::ShellScriptParseError {
+            script: "ls &&".to_string()
+        }),
+        parse_shell_script("ls &&")
+    );
+}
+
+#[test]
+fn test_check_script_verdict_per_command() -> codex_execpolicy::Result<()> {
+    let policy = setup();
+    let checks = policy.check_script(
+        "ls -l foo 2>/dev/null && head -n 5 foo; rm -rf /",
+        Path::new("/work"),
+        &[PathBuf::from("/work")],
+    )?;
+    let results: Vec<_> = checks
+        .iter()
+        .map(|check| {
+            (
+                check.command.exec_call.program.as_str(),
+                check.result.is_ok(),
+            )
+        })
+        .collect();
+    assert_eq!(vec![("ls", true), ("head", true), ("rm", false)], results);
+    assert!(checks.iter().all(|check| !check.might_write_files()));
+    Ok(())
+}
+
+#[test]
+fn test_check_script_redirect_inside_writeable_folder() -> codex_execpolicy::Result<()> {
+    let policy = setup();
+    let checks = policy.check_script(
+        "ls > out.txt",
+        Path::new("/work"),
+        &[PathBuf::from("/work")],
+    )?;
+    assert_eq!(1, checks.len());
+    assert!(matches!(checks[0].result, Ok(MatchedExec::Match { .. })));
+    assert!(checks[0].might_write_files());
+    Ok(())
+}
+
+#[test]
+fn test_check_script_rejects_redirect_outside_writeable_folder() -> codex_execpolicy::Result<()> {
+    let policy = setup();
+    let checks = policy.check_script(
+        "pwd && (ls ../elsewhere) > ../notes.txt",
+        Path::new("/work/repo"),
+        &[PathBuf::from("/work/repo")],
+    )?;
+    assert_eq!(2, checks.len());
+    assert!(matches!(checks[0].result, Ok(MatchedExec::Match { .. })));
+    assert_eq!(
+        Ok(MatchedExec::Forbidden {
+            cause: Forbidden::Redirect {
+                redirect: Redirect {
+                    operator: ">".to_string(),
+                    target: "../notes.txt".to_string(),
+                },
+                exec_call: ExecCall::new("ls", &["../elsewhere"]),
+            },
+            reason: "redirects output to `/work/notes.txt`, which is outside the writeable folders"
+                .to_string(),
+        }),
+        checks[1].result
+    );
+    Ok(())
+}
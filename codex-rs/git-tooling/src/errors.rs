@@ -22,6 +22,10 @@ pub enum GitToolingError {
     },
     #[error("{path:?} is not a git repository")]
     NotAGitRepository { path: PathBuf },
+    #[error("{path:?} has no commits yet")]
+    MissingHead { path: PathBuf },
+    #[error("`{revision}` does not name a commit in {path:?}")]
+    UnknownRevision { path: PathBuf, revision: String },
     #[error("path {path:?} must be relative to the repository root")]
     NonRelativePath { path: PathBuf },
     #[error("path {path:?} escapes the repository root")]
@@ -1,4 +1,5 @@
 use std::ffi::OsString;
+use std::fmt;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -160,6 +161,60 @@ pub fn restore_to_commit(repo_path: &Path, commit_id: &str) -> Result<(), GitToo
     Ok(())
 }
 
+/// Summary of the changes between two commits, as reported by `git diff --numstat`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffStat {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+impl fmt::Display for DiffStat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let files = self.files_changed;
+        let noun = if files == 1 { "file" } else { "files" };
+        write!(
+            f,
+            "{files} {noun} changed, +{} -{}",
+            self.insertions, self.deletions
+        )
+    }
+}
+
+/// Compute the diff stat between two commits, e.g. two ghost commits.
+pub fn diff_stat(
+    repo_path: &Path,
+    from_commit: &str,
+    to_commit: &str,
+) -> Result<DiffStat, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+
+    let repo_root = resolve_repository_root(repo_path)?;
+    let output = run_git_for_stdout(
+        repo_root.as_path(),
+        vec![
+            OsString::from("diff"),
+            OsString::from("--numstat"),
+            OsString::from("--no-renames"),
+            OsString::from(from_commit),
+            OsString::from(to_commit),
+        ],
+        None,
+    )?;
+
+    let mut stat = DiffStat::default();
+    for line in output.lines().filter(|line| !line.is_empty()) {
+        let mut fields = line.splitn(3, '\t');
+        // Binary files report `-` instead of line counts.
+        let insertions = fields.next().and_then(|n| n.parse::<usize>().ok());
+        let deletions = fields.next().and_then(|n| n.parse::<usize>().ok());
+        stat.files_changed += 1;
+        stat.insertions += insertions.unwrap_or(0);
+        stat.deletions += deletions.unwrap_or(0);
+    }
+    Ok(stat)
+}
+
 /// Returns the default author and committer identity for ghost commits.
 fn default_commit_identity() -> Vec<(OsString, OsString)> {
     vec![
@@ -4,13 +4,23 @@ mod errors;
 mod ghost_commits;
 mod operations;
 mod platform;
+mod undo_history;
+mod worktree;
 
 pub use errors::GitToolingError;
 pub use ghost_commits::CreateGhostCommitOptions;
+pub use ghost_commits::DiffStat;
 pub use ghost_commits::create_ghost_commit;
+pub use ghost_commits::diff_stat;
 pub use ghost_commits::restore_ghost_commit;
 pub use ghost_commits::restore_to_commit;
 pub use platform::create_symlink;
+pub use undo_history::TurnSnapshot;
+pub use undo_history::UndoHistory;
+pub use worktree::SessionWorktree;
+pub use worktree::WorktreeExitAction;
+pub use worktree::create_session_worktree;
+pub use worktree::worktree_diff;
 
 /// Details of a ghost commit created from a repository state.
 #[derive(Debug, Clone, PartialEq, Eq)]
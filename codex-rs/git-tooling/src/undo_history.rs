@@ -0,0 +1,406 @@
+use std::ffi::OsString;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::CreateGhostCommitOptions;
+use crate::DiffStat;
+use crate::GhostCommit;
+use crate::GitToolingError;
+use crate::create_ghost_commit;
+use crate::diff_stat;
+use crate::operations::repo_subdir;
+use crate::operations::resolve_repository_root;
+use crate::operations::run_git_for_stdout;
+use crate::restore_ghost_commit;
+
+/// The state of the working tree at the start of a turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TurnSnapshot {
+    commit: GhostCommit,
+    label: String,
+}
+
+impl TurnSnapshot {
+    /// Ghost commit capturing the working tree before the turn ran.
+    pub fn commit(&self) -> &GhostCommit {
+        &self.commit
+    }
+
+    /// Caller-provided description of the turn, e.g. the user's prompt.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// Per-session stack of ghost commits, one per turn, that supports undoing
+/// and redoing any number of turns.
+///
+/// Positions are measured in turns: position `i` is the working tree as it
+/// was before turn `i` ran, and position `turns().len()` is the most recent
+/// state. Recording a new turn after an undo discards the undone turns.
+#[derive(Debug)]
+pub struct UndoHistory {
+    repo_path: PathBuf,
+    max_turns: usize,
+    snapshots: Vec<TurnSnapshot>,
+    /// Index of the snapshot the working tree was restored to, or `None` while
+    /// no turn is undone.
+    restored: Option<usize>,
+    /// The working tree as it was when the first turn was undone, so that redo
+    /// can return to it.
+    latest: Option<GhostCommit>,
+}
+
+impl UndoHistory {
+    /// Creates an empty history for the repository containing `repo_path`
+    /// that retains at most `max_turns` turns.
+    pub fn new(repo_path: impl Into<PathBuf>, max_turns: usize) -> Self {
+        Self {
+            repo_path: repo_path.into(),
+            max_turns,
+            snapshots: Vec::new(),
+            restored: None,
+            latest: None,
+        }
+    }
+
+    pub fn repo_path(&self) -> &Path {
+        &self.repo_path
+    }
+
+    /// Turns that can be undone, oldest first.
+    pub fn turns(&self) -> &[TurnSnapshot] {
+        &self.snapshots
+    }
+
+    /// Current position in the history; see [`UndoHistory`].
+    pub fn position(&self) -> usize {
+        self.restored.unwrap_or(self.snapshots.len())
+    }
+
+    /// Number of turns that can currently be undone.
+    pub fn undo_depth(&self) -> usize {
+        self.position()
+    }
+
+    /// Number of undone turns that can be redone.
+    pub fn redo_depth(&self) -> usize {
+        self.snapshots.len() - self.position()
+    }
+
+    /// Captures the working tree before a new turn runs. Any undone turns are
+    /// discarded and the oldest turn is dropped once `max_turns` is exceeded.
+    pub fn record_turn(&mut self, label: impl Into<String>) -> Result<(), GitToolingError> {
+        let commit = create_ghost_commit(&CreateGhostCommitOptions::new(&self.repo_path))?;
+        if let Some(index) = self.restored.take() {
+            self.snapshots.truncate(index);
+            self.latest = None;
+        }
+        self.snapshots.push(TurnSnapshot {
+            commit,
+            label: label.into(),
+        });
+        if self.snapshots.len() > self.max_turns {
+            let excess = self.snapshots.len() - self.max_turns;
+            self.snapshots.drain(..excess);
+        }
+        Ok(())
+    }
+
+    /// Restores the working tree to before the last `steps` turns that are
+    /// still applied. Returns the number of turns actually undone, which is
+    /// smaller than `steps` when the history is shorter.
+    pub fn undo(&mut self, steps: usize) -> Result<usize, GitToolingError> {
+        let steps = steps.min(self.undo_depth());
+        self.move_to(self.position() - steps)?;
+        Ok(steps)
+    }
+
+    /// Re-applies up to `steps` undone turns. Returns the number of turns
+    /// actually redone.
+    pub fn redo(&mut self, steps: usize) -> Result<usize, GitToolingError> {
+        let steps = steps.min(self.redo_depth());
+        self.move_to(self.position() + steps)?;
+        Ok(steps)
+    }
+
+    /// Moves the working tree to `position`; see [`UndoHistory`].
+    pub fn move_to(&mut self, position: usize) -> Result<(), GitToolingError> {
+        let position = position.min(self.snapshots.len());
+        if position == self.position() {
+            return Ok(());
+        }
+
+        let latest = match self.latest.take() {
+            Some(latest) => latest,
+            None => create_ghost_commit(&CreateGhostCommitOptions::new(&self.repo_path))?,
+        };
+        let current = match self.restored {
+            Some(index) => self.snapshots[index].commit.id().to_string(),
+            None => latest.id().to_string(),
+        };
+        let target = self
+            .snapshots
+            .get(position)
+            .map(TurnSnapshot::commit)
+            .unwrap_or(&latest);
+        let restored = restore_ghost_commit(&self.repo_path, target)
+            .and_then(|()| remove_files_missing_from(&self.repo_path, &current, target.id()));
+        if let Err(err) = restored {
+            if self.restored.is_some() {
+                self.latest = Some(latest);
+            }
+            return Err(err);
+        }
+
+        if position == self.snapshots.len() {
+            self.restored = None;
+        } else {
+            self.restored = Some(position);
+            self.latest = Some(latest);
+        }
+        Ok(())
+    }
+
+    /// Diff stat of each turn, in the same order as [`UndoHistory::turns`].
+    /// The last turn is compared against the current working tree, or against
+    /// the most recent state if turns have been undone.
+    pub fn turn_diff_stats(&self) -> Result<Vec<DiffStat>, GitToolingError> {
+        let Some(last) = self.snapshots.last() else {
+            return Ok(Vec::new());
+        };
+        let latest = match &self.latest {
+            Some(latest) => latest.clone(),
+            None => create_ghost_commit(&CreateGhostCommitOptions::new(&self.repo_path))?,
+        };
+        let mut stats = Vec::with_capacity(self.snapshots.len());
+        for pair in self.snapshots.windows(2) {
+            stats.push(diff_stat(
+                &self.repo_path,
+                pair[0].commit.id(),
+                pair[1].commit.id(),
+            )?);
+        }
+        stats.push(diff_stat(&self.repo_path, last.commit.id(), latest.id())?);
+        Ok(stats)
+    }
+}
+
+/// `git restore` leaves untracked files alone, so files that the turns being
+/// undone (or redone) created (or deleted) would survive the restore. Removes
+/// every path that exists in the `from` snapshot but not in `to`; files the
+/// user created outside of any snapshot are not touched.
+fn remove_files_missing_from(
+    repo_path: &Path,
+    from: &str,
+    to: &str,
+) -> Result<(), GitToolingError> {
+    let repo_root = resolve_repository_root(repo_path)?;
+    let prefix = repo_subdir(repo_root.as_path(), repo_path);
+    let removed = run_git_for_stdout(
+        repo_root.as_path(),
+        vec![
+            OsString::from("diff"),
+            OsString::from("--name-only"),
+            OsString::from("--no-renames"),
+            OsString::from("--diff-filter=D"),
+            OsString::from("-z"),
+            OsString::from(from),
+            OsString::from(to),
+            OsString::from("--"),
+            prefix
+                .map(PathBuf::into_os_string)
+                .unwrap_or_else(|| OsString::from(".")),
+        ],
+        None,
+    )?;
+
+    for relative in removed.split('\0').filter(|path| !path.is_empty()) {
+        let path = repo_root.join(relative);
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::NotFound => continue,
+            Err(err) => return Err(err.into()),
+        }
+        // Drop directories that only existed for the removed files.
+        let mut dir = path.parent();
+        while let Some(parent) = dir
+            && parent != repo_root
+            && std::fs::remove_dir(parent).is_ok()
+        {
+            dir = parent.parent();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+
+    /// Runs a git command in the test repository and asserts success.
+    fn run_git_in(repo_path: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(repo_path)
+            .args(args)
+            .status()
+            .expect("git command");
+        assert!(status.success(), "git command failed: {args:?}");
+    }
+
+    /// Initializes a repository with a single committed file.
+    fn init_test_repo(repo: &Path) {
+        run_git_in(repo, &["init", "--initial-branch=main"]);
+        run_git_in(repo, &["config", "core.autocrlf", "false"]);
+        std::fs::write(repo.join("notes.txt"), "v0\n").expect("write notes");
+        run_git_in(repo, &["add", "notes.txt"]);
+        run_git_in(
+            repo,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        );
+    }
+
+    fn read_notes(repo: &Path) -> String {
+        std::fs::read_to_string(repo.join("notes.txt")).expect("read notes")
+    }
+
+    /// The `turn-N.txt` files present in the working tree, sorted by name.
+    fn created_files(repo: &Path) -> Vec<String> {
+        let mut files: Vec<String> = std::fs::read_dir(repo)
+            .expect("read repo")
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter(|name| name.starts_with("turn-"))
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Records three turns, each of which rewrites `notes.txt` and creates a
+    /// new `turn-N.txt` file.
+    fn history_with_three_turns(repo: &Path) -> Result<UndoHistory, GitToolingError> {
+        let mut history = UndoHistory::new(repo, 10);
+        for turn in 1..=3 {
+            history.record_turn(format!("turn {turn}"))?;
+            std::fs::write(repo.join("notes.txt"), format!("v{turn}\n"))?;
+            std::fs::write(repo.join(format!("turn-{turn}.txt")), "new\n")?;
+        }
+        Ok(history)
+    }
+
+    #[test]
+    /// Undoing several turns at once and redoing them one at a time walks
+    /// through each recorded state.
+    fn undo_and_redo_multiple_turns() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+        let mut history = history_with_three_turns(repo)?;
+        assert_eq!(history.position(), 3);
+
+        assert_eq!(history.undo(2)?, 2);
+        assert_eq!(history.position(), 1);
+        assert_eq!(read_notes(repo), "v1\n");
+        assert_eq!(created_files(repo), vec!["turn-1.txt"]);
+
+        assert_eq!(history.redo(1)?, 1);
+        assert_eq!(read_notes(repo), "v2\n");
+        assert_eq!(created_files(repo), vec!["turn-1.txt", "turn-2.txt"]);
+        assert_eq!(history.redo(5)?, 1);
+        assert_eq!(history.position(), 3);
+        assert_eq!(read_notes(repo), "v3\n");
+        assert_eq!(
+            created_files(repo),
+            vec!["turn-1.txt", "turn-2.txt", "turn-3.txt"]
+        );
+        assert_eq!(history.redo(1)?, 0);
+
+        assert_eq!(history.undo(10)?, 3);
+        assert_eq!(read_notes(repo), "v0\n");
+        assert_eq!(created_files(repo), Vec::<String>::new());
+        assert_eq!(history.undo(1)?, 0);
+
+        // Files created by the undone turns come back with `/redo`; files the
+        // user created in the meantime are left alone.
+        std::fs::write(repo.join("scratch.txt"), "mine\n")?;
+        assert_eq!(history.redo(3)?, 3);
+        assert!(repo.join("scratch.txt").exists());
+        assert_eq!(read_notes(repo), "v3\n");
+        assert_eq!(
+            created_files(repo),
+            vec!["turn-1.txt", "turn-2.txt", "turn-3.txt"]
+        );
+        assert_eq!(
+            std::fs::read_to_string(repo.join("turn-2.txt"))?,
+            "new\n".to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    /// A new turn after an undo discards the undone turns.
+    fn record_turn_after_undo_discards_redo_history() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+        let mut history = history_with_three_turns(repo)?;
+
+        history.undo(2)?;
+        history.record_turn("turn 4")?;
+        let labels: Vec<&str> = history.turns().iter().map(TurnSnapshot::label).collect();
+        assert_eq!(labels, vec!["turn 1", "turn 4"]);
+        assert_eq!(history.redo_depth(), 0);
+        assert_eq!(history.redo(1)?, 0);
+        assert_eq!(read_notes(repo), "v1\n");
+        Ok(())
+    }
+
+    #[test]
+    /// Only the most recent `max_turns` turns are kept.
+    fn oldest_turns_are_dropped() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+        let mut history = UndoHistory::new(repo, 2);
+        for turn in 1..=3 {
+            history.record_turn(format!("turn {turn}"))?;
+        }
+        let labels: Vec<&str> = history.turns().iter().map(TurnSnapshot::label).collect();
+        assert_eq!(labels, vec!["turn 2", "turn 3"]);
+        Ok(())
+    }
+
+    #[test]
+    /// Each turn's diff stat covers the changes made during that turn, both
+    /// before and after undoing.
+    fn turn_diff_stats_report_changes_per_turn() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+        let mut history = history_with_three_turns(repo)?;
+        let expected = vec![
+            DiffStat {
+                files_changed: 2,
+                insertions: 2,
+                deletions: 1,
+            };
+            3
+        ];
+        assert_eq!(history.turn_diff_stats()?, expected);
+
+        history.undo(3)?;
+        assert_eq!(history.turn_diff_stats()?, expected);
+        assert_eq!(expected[0].to_string(), "2 files changed, +2 -1");
+        Ok(())
+    }
+}
@@ -0,0 +1,425 @@
+use std::ffi::OsString;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::GitToolingError;
+use crate::operations::ensure_git_repository;
+use crate::operations::resolve_head;
+use crate::operations::resolve_repository_root;
+use crate::operations::run_git_for_status;
+use crate::operations::run_git_for_stdout;
+
+/// A `git worktree` checked out on a branch of its own, so that a session can
+/// edit files without touching the user's working tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionWorktree {
+    repo_root: PathBuf,
+    path: PathBuf,
+    branch: String,
+    base: String,
+}
+
+/// What to do with a session's branch once the session is over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorktreeExitAction {
+    /// Merge the branch into whatever is checked out in the repository.
+    Merge,
+    /// Cherry-pick the branch's commits onto whatever is checked out in the
+    /// repository.
+    CherryPick,
+    /// Leave the branch for the user to deal with.
+    Keep,
+    /// Delete the branch and everything on it.
+    Discard,
+}
+
+/// Creates a worktree at `worktree_path` on a new `branch` that starts at
+/// `base_rev`, or at `HEAD` when it is `None`, of the repository containing
+/// `repo_path`.
+pub fn create_session_worktree(
+    repo_path: &Path,
+    worktree_path: &Path,
+    branch: &str,
+    base_rev: Option<&str>,
+) -> Result<SessionWorktree, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = resolve_repository_root(repo_path)?;
+    let base = match base_rev {
+        Some(revision) => resolve_commit(&repo_root, revision)?,
+        None => resolve_head(&repo_root)?.ok_or_else(|| GitToolingError::MissingHead {
+            path: repo_root.clone(),
+        })?,
+    };
+    if let Some(parent) = worktree_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    run_git_for_status(
+        &repo_root,
+        [
+            OsString::from("worktree"),
+            OsString::from("add"),
+            OsString::from("-b"),
+            OsString::from(branch),
+            OsString::from(worktree_path),
+            OsString::from(&base),
+        ],
+        None,
+    )?;
+    Ok(SessionWorktree {
+        repo_root,
+        path: worktree_path.to_path_buf(),
+        branch: branch.to_string(),
+        base,
+    })
+}
+
+impl SessionWorktree {
+    /// Root of the repository the worktree was created from.
+    pub fn repo_root(&self) -> &Path {
+        &self.repo_root
+    }
+
+    /// Directory the worktree is checked out in.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Branch checked out in the worktree.
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    /// Commit the branch was created from.
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    /// Commits everything that is not yet committed in the worktree to its
+    /// branch. Returns `false` when there was nothing to commit.
+    pub fn commit_pending_changes(&self, message: &str) -> Result<bool, GitToolingError> {
+        run_git_for_status(&self.path, ["add", "--all"], None)?;
+        let status = run_git_for_stdout(&self.path, ["status", "--porcelain"], None)?;
+        if status.is_empty() {
+            return Ok(false);
+        }
+        let env = commit_identity(&self.path);
+        run_git_for_status(
+            &self.path,
+            [
+                OsString::from("commit"),
+                OsString::from("--no-verify"),
+                OsString::from("-m"),
+                OsString::from(message),
+            ],
+            Some(env.as_slice()),
+        )?;
+        Ok(true)
+    }
+
+    /// Number of commits on the branch since it was created.
+    pub fn commit_count(&self) -> Result<usize, GitToolingError> {
+        let range = format!("{}..{}", self.base, self.branch);
+        let count = run_git_for_stdout(&self.repo_root, ["rev-list", "--count", &range], None)?;
+        Ok(count.parse().unwrap_or(0))
+    }
+
+    /// Commits whatever is pending and removes the worktree, keeping the
+    /// branch only if the session committed something to it. Returns whether
+    /// the branch was kept.
+    pub fn keep_if_changed(&self, message: &str) -> Result<bool, GitToolingError> {
+        self.commit_pending_changes(message)?;
+        let keep = self.commit_count()? > 0;
+        self.finish(if keep {
+            WorktreeExitAction::Keep
+        } else {
+            WorktreeExitAction::Discard
+        })?;
+        Ok(keep)
+    }
+
+    /// Applies `action` and removes the worktree. The branch is deleted once
+    /// its commits have been merged, cherry-picked or discarded; if merging
+    /// or cherry-picking fails, the operation is aborted and the branch kept.
+    pub fn finish(&self, action: WorktreeExitAction) -> Result<(), GitToolingError> {
+        let result = match action {
+            WorktreeExitAction::Merge => {
+                run_git_for_status(&self.repo_root, ["merge", "--no-edit", &self.branch], None)
+                    .inspect_err(|_| {
+                        let _ = run_git_for_status(&self.repo_root, ["merge", "--abort"], None);
+                    })
+            }
+            WorktreeExitAction::CherryPick => {
+                let range = format!("{}..{}", self.base, self.branch);
+                run_git_for_status(&self.repo_root, ["cherry-pick", &range], None).inspect_err(
+                    |_| {
+                        let _ =
+                            run_git_for_status(&self.repo_root, ["cherry-pick", "--abort"], None);
+                    },
+                )
+            }
+            WorktreeExitAction::Keep | WorktreeExitAction::Discard => Ok(()),
+        };
+
+        run_git_for_status(
+            &self.repo_root,
+            [
+                OsString::from("worktree"),
+                OsString::from("remove"),
+                OsString::from("--force"),
+                OsString::from(&self.path),
+            ],
+            None,
+        )?;
+        result?;
+        if action != WorktreeExitAction::Keep {
+            run_git_for_status(&self.repo_root, ["branch", "-D", &self.branch], None)?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolves `revision` to the id of the commit it names.
+fn resolve_commit(repo_root: &Path, revision: &str) -> Result<String, GitToolingError> {
+    run_git_for_stdout(
+        repo_root,
+        [
+            OsString::from("rev-parse"),
+            OsString::from("--verify"),
+            OsString::from("--quiet"),
+            OsString::from("--end-of-options"),
+            OsString::from(format!("{revision}^{{commit}}")),
+        ],
+        None,
+    )
+    .map_err(|err| match err {
+        GitToolingError::GitCommand { .. } => GitToolingError::UnknownRevision {
+            path: repo_root.to_path_buf(),
+            revision: revision.to_string(),
+        },
+        other => other,
+    })
+}
+
+/// Returns a diff from `base` to the current contents of the worktree at
+/// `worktree_path`, including files that are not tracked yet. Only the path is
+/// needed, so this also works for worktrees created by another process.
+pub fn worktree_diff(worktree_path: &Path, base: &str) -> Result<String, GitToolingError> {
+    run_git_for_status(worktree_path, ["add", "--all"], None)?;
+    let diff = run_git_for_stdout(worktree_path, ["diff", "--cached", "--binary", base], None)?;
+    // Output is trimmed, but `git apply` needs the final newline back.
+    if diff.is_empty() {
+        Ok(diff)
+    } else {
+        Ok(format!("{diff}\n"))
+    }
+}
+
+/// Commits are made with the user's identity when one is configured and fall
+/// back to a Codex identity otherwise, so that committing never fails.
+fn commit_identity(path: &Path) -> Vec<(OsString, OsString)> {
+    if run_git_for_stdout(path, ["config", "user.email"], None).is_ok() {
+        return Vec::new();
+    }
+    [
+        ("GIT_AUTHOR_NAME", "Codex"),
+        ("GIT_AUTHOR_EMAIL", "codex@codex.local"),
+        ("GIT_COMMITTER_NAME", "Codex"),
+        ("GIT_COMMITTER_EMAIL", "codex@codex.local"),
+    ]
+    .into_iter()
+    .map(|(key, value)| (OsString::from(key), OsString::from(value)))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+
+    /// Runs a git command in the test repository and returns its trimmed stdout.
+    fn run_git_in(repo_path: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .current_dir(repo_path)
+            .args(args)
+            .output()
+            .expect("git command");
+        assert!(output.status.success(), "git command failed: {args:?}");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    /// Initializes a repository with one commit on `main`.
+    fn init_test_repo(repo: &Path) {
+        run_git_in(repo, &["init", "--initial-branch=main"]);
+        run_git_in(repo, &["config", "core.autocrlf", "false"]);
+        run_git_in(repo, &["config", "user.name", "Tester"]);
+        run_git_in(repo, &["config", "user.email", "test@example.com"]);
+        std::fs::write(repo.join("README.md"), "hello\n").expect("write readme");
+        run_git_in(repo, &["add", "README.md"]);
+        run_git_in(repo, &["commit", "-m", "init"]);
+    }
+
+    fn worktree_with_change(repo: &Path, worktrees: &Path) -> SessionWorktree {
+        let worktree =
+            create_session_worktree(repo, &worktrees.join("session"), "codex/session", None)
+                .expect("create worktree");
+        std::fs::write(worktree.path().join("new.txt"), "from the agent\n").expect("write file");
+        assert!(
+            worktree
+                .commit_pending_changes("Codex session changes")
+                .expect("commit")
+        );
+        assert_eq!(worktree.commit_count().expect("count"), 1);
+        worktree
+    }
+
+    #[test]
+    fn merge_brings_changes_into_the_repository_and_deletes_the_branch()
+    -> Result<(), GitToolingError> {
+        let repo = tempfile::tempdir()?;
+        let worktrees = tempfile::tempdir()?;
+        init_test_repo(repo.path());
+
+        let worktree = worktree_with_change(repo.path(), worktrees.path());
+        // The user's working tree is untouched until the session finishes.
+        assert!(!repo.path().join("new.txt").exists());
+
+        worktree.finish(WorktreeExitAction::Merge)?;
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("new.txt"))?,
+            "from the agent\n"
+        );
+        assert!(!worktree.path().exists());
+        assert_eq!(
+            run_git_in(repo.path(), &["branch", "--list", "codex/*"]),
+            ""
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn keep_removes_the_worktree_but_not_the_branch() -> Result<(), GitToolingError> {
+        let repo = tempfile::tempdir()?;
+        let worktrees = tempfile::tempdir()?;
+        init_test_repo(repo.path());
+
+        let worktree = worktree_with_change(repo.path(), worktrees.path());
+        assert!(!worktree.commit_pending_changes("nothing")?);
+
+        worktree.finish(WorktreeExitAction::Keep)?;
+        assert!(!worktree.path().exists());
+        assert!(!repo.path().join("new.txt").exists());
+        assert_eq!(
+            run_git_in(repo.path(), &["log", "-1", "--format=%s", "codex/session"]),
+            "Codex session changes"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn keep_if_changed_discards_sessions_that_changed_nothing() -> Result<(), GitToolingError> {
+        let repo = tempfile::tempdir()?;
+        let worktrees = tempfile::tempdir()?;
+        init_test_repo(repo.path());
+
+        let idle = create_session_worktree(
+            repo.path(),
+            &worktrees.path().join("idle"),
+            "codex/idle",
+            None,
+        )?;
+        assert!(!idle.keep_if_changed("Codex session changes")?);
+        assert!(!idle.path().exists());
+
+        let busy = create_session_worktree(
+            repo.path(),
+            &worktrees.path().join("busy"),
+            "codex/busy",
+            None,
+        )?;
+        std::fs::write(busy.path().join("new.txt"), "uncommitted\n")?;
+        assert!(busy.keep_if_changed("Codex session changes")?);
+        assert!(!busy.path().exists());
+        assert_eq!(
+            run_git_in(repo.path(), &["branch", "--list", "codex/*"]),
+            "codex/busy"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn worktree_starts_at_the_requested_revision() -> Result<(), GitToolingError> {
+        let repo = tempfile::tempdir()?;
+        let worktrees = tempfile::tempdir()?;
+        init_test_repo(repo.path());
+        run_git_in(repo.path(), &["branch", "release"]);
+        let release = run_git_in(repo.path(), &["rev-parse", "release"]);
+        std::fs::write(repo.path().join("README.md"), "changed\n")?;
+        run_git_in(repo.path(), &["commit", "-am", "change"]);
+
+        let worktree = create_session_worktree(
+            repo.path(),
+            &worktrees.path().join("release"),
+            "codex/release",
+            Some("release"),
+        )?;
+        assert_eq!(worktree.base(), release);
+        assert_eq!(
+            std::fs::read_to_string(worktree.path().join("README.md"))?,
+            "hello\n"
+        );
+
+        let err = create_session_worktree(
+            repo.path(),
+            &worktrees.path().join("missing"),
+            "codex/missing",
+            Some("no-such-branch"),
+        )
+        .expect_err("unknown revision");
+        assert!(
+            matches!(err, GitToolingError::UnknownRevision { ref revision, .. } if revision == "no-such-branch"),
+            "{err}"
+        );
+        assert!(!worktrees.path().join("missing").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn worktree_diff_includes_untracked_and_committed_changes() -> Result<(), GitToolingError> {
+        let repo = tempfile::tempdir()?;
+        let worktrees = tempfile::tempdir()?;
+        init_test_repo(repo.path());
+
+        let worktree = worktree_with_change(repo.path(), worktrees.path());
+        std::fs::write(worktree.path().join("README.md"), "hello again\n")?;
+
+        let diff = worktree_diff(worktree.path(), worktree.base())?;
+        assert!(diff.contains("+++ b/new.txt"), "diff: {diff}");
+        assert!(diff.contains("+hello again"), "diff: {diff}");
+        assert!(diff.ends_with('\n'));
+        Ok(())
+    }
+
+    #[test]
+    fn cherry_pick_failure_keeps_the_branch() -> Result<(), GitToolingError> {
+        let repo = tempfile::tempdir()?;
+        let worktrees = tempfile::tempdir()?;
+        init_test_repo(repo.path());
+
+        let worktree = worktree_with_change(repo.path(), worktrees.path());
+        std::fs::write(repo.path().join("new.txt"), "from the user\n")?;
+        run_git_in(repo.path(), &["add", "new.txt"]);
+        run_git_in(repo.path(), &["commit", "-m", "conflicting"]);
+
+        assert!(worktree.finish(WorktreeExitAction::CherryPick).is_err());
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("new.txt"))?,
+            "from the user\n"
+        );
+        assert_eq!(
+            run_git_in(repo.path(), &["branch", "--list", "codex/*"]),
+            "codex/session"
+        );
+        Ok(())
+    }
+}
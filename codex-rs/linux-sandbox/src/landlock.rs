@@ -31,7 +31,12 @@ pub(crate) fn apply_sandbox_policy_to_current_thread(
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
 ) -> Result<()> {
-    if !sandbox_policy.has_full_network_access() {
+    if !sandbox_policy.network_allowlist().is_empty() {
+        // The command already runs in an isolated network namespace whose
+        // only way out is the allowlisting proxy, so sockets must keep
+        // working; still forbid ptrace like the network filter does.
+        install_ptrace_seccomp_filter_on_current_thread()?;
+    } else if !sandbox_policy.has_full_network_access() {
         install_network_seccomp_filter_on_current_thread()?;
     }
 
@@ -124,6 +129,21 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
     rules.insert(libc::SYS_socket, vec![unix_only_rule.clone()]);
     rules.insert(libc::SYS_socketpair, vec![unix_only_rule]); // always deny (Unix can use socketpair but fine, keep open?)
 
+    apply_seccomp_deny_rules(rules)
+}
+
+/// Installs a seccomp filter that only blocks `ptrace`.
+fn install_ptrace_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
+    let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
+    rules.insert(libc::SYS_ptrace, vec![]);
+    apply_seccomp_deny_rules(rules)
+}
+
+/// Applies `rules` so that matching syscalls fail with `EPERM` and everything
+/// else is allowed.
+fn apply_seccomp_deny_rules(
+    rules: BTreeMap<i64, Vec<SeccompRule>>,
+) -> std::result::Result<(), SandboxErr> {
     let filter = SeccompFilter::new(
         rules,
         SeccompAction::Allow,                     // default – allow
@@ -3,6 +3,8 @@ use std::ffi::CString;
 use std::path::PathBuf;
 
 use crate::landlock::apply_sandbox_policy_to_current_thread;
+use crate::network_proxy::export_proxy_env;
+use crate::network_proxy::isolate_network_behind_proxy;
 
 #[derive(Debug, Parser)]
 pub struct LandlockCommand {
@@ -24,6 +26,16 @@ pub fn run_main() -> ! {
         command,
     } = LandlockCommand::parse();
 
+    // Must run before any other threads exist: this forks the proxy and relay
+    // processes and moves the command into an isolated network namespace.
+    let network_allowlist = sandbox_policy.network_allowlist();
+    if !network_allowlist.is_empty() {
+        match isolate_network_behind_proxy(network_allowlist) {
+            Ok(proxy_url) => unsafe { export_proxy_env(&proxy_url) },
+            Err(e) => panic!("error setting up network allowlist: {e:?}"),
+        }
+    }
+
     if let Err(e) = apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd) {
         panic!("error running landlock: {e:?}");
     }
@@ -0,0 +1,555 @@
+//! Network allowlisting for the Linux sandbox.
+//!
+//! When a [`SandboxPolicy`](codex_core::protocol::SandboxPolicy) carries a
+//! network allowlist, the sandboxed command runs in a fresh user + network
+//! namespace whose only interface is loopback. A relay inside the namespace
+//! listens on `127.0.0.1` and forwards every connection over a Unix socket to
+//! an HTTP proxy that stays in the host namespace. That proxy only opens
+//! upstream connections to allowlisted hosts, so `HTTP_PROXY`/`HTTPS_PROXY`
+//! aware tools work while everything else has no route out.
+//!
+//! Process layout (all processes die with their parent via
+//! `PR_SET_PDEATHSIG`):
+//!
+//! ```text
+//! codex-linux-sandbox (host netns)      -- filtering proxy on a Unix socket
+//! └── relay (isolated netns)            -- 127.0.0.1:<port> -> Unix socket
+//!     └── command (isolated netns)      -- landlock + execvp
+//! ```
+
+use std::io;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Write;
+use std::net::Shutdown;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::os::unix::net::UnixListener;
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Upper bound on the size of a request head the proxy is willing to buffer.
+const MAX_REQUEST_HEAD_BYTES: usize = 16 * 1024;
+
+/// Timeout for establishing upstream connections.
+const UPSTREAM_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Hosts bypassing the proxy: the command's own loopback services.
+const NO_PROXY_HOSTS: &str = "localhost,127.0.0.1,::1";
+
+/// A single `host`, `host:port`, or `*.domain[:port]` allowlist entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AllowlistEntry {
+    host: HostPattern,
+    /// `None` allows every port.
+    port: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HostPattern {
+    Exact(String),
+    /// Matches any subdomain of the given domain (but not the domain itself).
+    Subdomains(String),
+}
+
+/// Parsed form of `sandbox_workspace_write.network_allowlist`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NetworkAllowlist {
+    entries: Vec<AllowlistEntry>,
+}
+
+impl NetworkAllowlist {
+    pub(crate) fn parse<S: AsRef<str>>(entries: &[S]) -> io::Result<Self> {
+        let entries = entries
+            .iter()
+            .map(|entry| parse_entry(entry.as_ref()))
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(Self { entries })
+    }
+
+    pub(crate) fn allows(&self, host: &str, port: u16) -> bool {
+        let host = normalize_host(host);
+        self.entries.iter().any(|entry| {
+            let host_matches = match &entry.host {
+                HostPattern::Exact(expected) => *expected == host,
+                HostPattern::Subdomains(domain) => host
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|prefix| prefix.len() > 1 && prefix.ends_with('.')),
+            };
+            host_matches && entry.port.is_none_or(|expected| expected == port)
+        })
+    }
+}
+
+fn parse_entry(entry: &str) -> io::Result<AllowlistEntry> {
+    let invalid = || {
+        io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid network_allowlist entry: {entry:?}"),
+        )
+    };
+
+    let entry = entry.trim();
+    let (host, port) = split_host_port(entry).ok_or_else(invalid)?;
+    let port = match port {
+        Some(port) => Some(port.parse::<u16>().map_err(|_| invalid())?),
+        None => None,
+    };
+    let host = normalize_host(host);
+    let host = match host.strip_prefix("*.") {
+        Some(domain) => HostPattern::Subdomains(domain.to_string()),
+        None => HostPattern::Exact(host),
+    };
+    match &host {
+        HostPattern::Exact(h) | HostPattern::Subdomains(h)
+            if h.is_empty() || h.contains(['*', '/', ' ']) =>
+        {
+            Err(invalid())
+        }
+        _ => Ok(AllowlistEntry { host, port }),
+    }
+}
+
+/// Splits `host[:port]`, accepting bracketed IPv6 literals (`[::1]:443`).
+fn split_host_port(s: &str) -> Option<(&str, Option<&str>)> {
+    if let Some(rest) = s.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        return match rest {
+            "" => Some((host, None)),
+            _ => Some((host, Some(rest.strip_prefix(':')?))),
+        };
+    }
+    match s.rsplit_once(':') {
+        // A bare IPv6 literal has several colons and no port.
+        Some((host, _)) if host.contains(':') => Some((s, None)),
+        Some((host, port)) => Some((host, Some(port))),
+        None => Some((s, None)),
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_end_matches('.')
+        .to_ascii_lowercase()
+}
+
+/// Moves the current process into an isolated network namespace that can
+/// only reach the hosts in `allowlist` through a local proxy.
+///
+/// Returns (in the process that should go on to exec the command) the proxy
+/// URL to export to the command. The proxy and relay live in forked parent
+/// processes that exit with the command's status and never return.
+pub(crate) fn isolate_network_behind_proxy(allowlist: &[String]) -> io::Result<String> {
+    let allowlist = Arc::new(NetworkAllowlist::parse(allowlist)?);
+
+    let socket_path =
+        std::env::temp_dir().join(format!("codex-network-proxy-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&socket_path);
+    let proxy_listener = UnixListener::bind(&socket_path)?;
+
+    match fork()? {
+        Some(relay_pid) => {
+            std::thread::spawn(move || serve_filtering_proxy(proxy_listener, allowlist));
+            let status = wait_for_child(relay_pid);
+            let _ = std::fs::remove_file(&socket_path);
+            std::process::exit(status);
+        }
+        None => {
+            drop(proxy_listener);
+            set_parent_death_signal()?;
+        }
+    }
+
+    enter_isolated_network_namespace()?;
+    let relay_listener = TcpListener::bind(("127.0.0.1", 0))?;
+    let proxy_url = format!("http://{}", relay_listener.local_addr()?);
+
+    match fork()? {
+        Some(command_pid) => {
+            std::thread::spawn(move || serve_loopback_relay(relay_listener, socket_path));
+            std::process::exit(wait_for_child(command_pid));
+        }
+        None => {
+            drop(relay_listener);
+            set_parent_death_signal()?;
+        }
+    }
+
+    Ok(proxy_url)
+}
+
+/// Exports `proxy_url` through the environment variables honored by common
+/// HTTP clients.
+///
+/// # Safety
+/// Must only be called while the process is single-threaded.
+pub(crate) unsafe fn export_proxy_env(proxy_url: &str) {
+    for key in [
+        "HTTP_PROXY",
+        "HTTPS_PROXY",
+        "ALL_PROXY",
+        "http_proxy",
+        "https_proxy",
+        "all_proxy",
+    ] {
+        unsafe { std::env::set_var(key, proxy_url) };
+    }
+    for key in ["NO_PROXY", "no_proxy"] {
+        unsafe { std::env::set_var(key, NO_PROXY_HOSTS) };
+    }
+}
+
+/// Returns `Some(child_pid)` in the parent and `None` in the child.
+fn fork() -> io::Result<Option<libc::pid_t>> {
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => Ok(None),
+        pid => Ok(Some(pid)),
+    }
+}
+
+fn set_parent_death_signal() -> io::Result<()> {
+    if unsafe { libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    // The parent may already be gone if it died before prctl() ran.
+    if unsafe { libc::getppid() } == 1 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Waits for `pid` and maps its status to an exit code the way shells do.
+fn wait_for_child(pid: libc::pid_t) -> i32 {
+    let mut status: libc::c_int = 0;
+    loop {
+        if unsafe { libc::waitpid(pid, &mut status, 0) } != -1 {
+            break;
+        }
+        if io::Error::last_os_error().kind() != ErrorKind::Interrupted {
+            return 1;
+        }
+    }
+    if libc::WIFEXITED(status) {
+        libc::WEXITSTATUS(status)
+    } else if libc::WIFSIGNALED(status) {
+        128 + libc::WTERMSIG(status)
+    } else {
+        1
+    }
+}
+
+/// Unshares a user and network namespace, maps the current uid/gid into it,
+/// and brings up the loopback interface.
+fn enter_isolated_network_namespace() -> io::Result<()> {
+    let uid = unsafe { libc::geteuid() };
+    let gid = unsafe { libc::getegid() };
+
+    if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    std::fs::write("/proc/self/setgroups", "deny")?;
+    std::fs::write("/proc/self/uid_map", format!("{uid} {uid} 1"))?;
+    std::fs::write("/proc/self/gid_map", format!("{gid} {gid} 1"))?;
+
+    bring_up_loopback()
+}
+
+fn bring_up_loopback() -> io::Result<()> {
+    let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+    if fd == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut ifr: libc::ifreq = unsafe { std::mem::zeroed() };
+    for (dst, src) in ifr.ifr_name.iter_mut().zip(b"lo\0") {
+        *dst = *src as libc::c_char;
+    }
+
+    let result = (|| {
+        if unsafe { libc::ioctl(fd, libc::SIOCGIFFLAGS as _, &mut ifr) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        unsafe {
+            ifr.ifr_ifru.ifru_flags |= (libc::IFF_UP | libc::IFF_RUNNING) as libc::c_short;
+        }
+        if unsafe { libc::ioctl(fd, libc::SIOCSIFFLAGS as _, &ifr) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    })();
+
+    unsafe { libc::close(fd) };
+    result
+}
+
+/// Accepts connections from inside the namespace and forwards them to the
+/// filtering proxy's Unix socket.
+fn serve_loopback_relay(listener: TcpListener, socket_path: PathBuf) {
+    for client in listener.incoming().flatten() {
+        let socket_path = socket_path.clone();
+        std::thread::spawn(move || {
+            if let Ok(upstream) = UnixStream::connect(&socket_path) {
+                pipe_bidirectional(client, upstream);
+            }
+        });
+    }
+}
+
+fn serve_filtering_proxy(listener: UnixListener, allowlist: Arc<NetworkAllowlist>) {
+    for client in listener.incoming().flatten() {
+        let allowlist = Arc::clone(&allowlist);
+        std::thread::spawn(move || {
+            let _ = handle_proxy_connection(client, &allowlist);
+        });
+    }
+}
+
+/// The parts of an HTTP proxy request needed to route it.
+#[derive(Debug, PartialEq, Eq)]
+struct ProxyRequest {
+    host: String,
+    port: u16,
+    /// `true` for `CONNECT host:port` tunnels.
+    tunnel: bool,
+    /// Bytes to send upstream before relaying the rest of the stream (empty
+    /// for tunnels).
+    forwarded_head: Vec<u8>,
+}
+
+fn handle_proxy_connection(mut client: UnixStream, allowlist: &NetworkAllowlist) -> io::Result<()> {
+    let (head, body_prefix) = read_request_head(&mut client)?;
+    let request = match parse_proxy_request(&head) {
+        Some(request) => request,
+        None => return write_status(&mut client, "400 Bad Request"),
+    };
+
+    if !allowlist.allows(&request.host, request.port) {
+        return write_status(&mut client, "403 Forbidden");
+    }
+
+    let mut upstream = match connect_upstream(&request.host, request.port) {
+        Ok(upstream) => upstream,
+        Err(_) => return write_status(&mut client, "502 Bad Gateway"),
+    };
+
+    if request.tunnel {
+        client.write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")?;
+    } else {
+        upstream.write_all(&request.forwarded_head)?;
+    }
+    upstream.write_all(&body_prefix)?;
+
+    pipe_bidirectional(client, upstream);
+    Ok(())
+}
+
+fn connect_upstream(host: &str, port: u16) -> io::Result<TcpStream> {
+    use std::net::ToSocketAddrs;
+
+    let mut last_err = io::Error::new(ErrorKind::NotFound, format!("could not resolve {host}"));
+    for addr in (normalize_host(host).as_str(), port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, UPSTREAM_CONNECT_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_err = err,
+        }
+    }
+    Err(last_err)
+}
+
+fn write_status(client: &mut UnixStream, status: &str) -> io::Result<()> {
+    let body = format!("codex sandbox network allowlist: {status}\n");
+    write!(
+        client,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Reads up to and including the blank line that ends the request head.
+/// Returns the head and any bytes read past it.
+fn read_request_head<R: Read>(reader: &mut R) -> io::Result<(Vec<u8>, Vec<u8>)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = reader.read(&mut chunk)?;
+        if n == 0 {
+            return Err(ErrorKind::UnexpectedEof.into());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            let rest = buf.split_off(end + 4);
+            return Ok((buf, rest));
+        }
+        if buf.len() > MAX_REQUEST_HEAD_BYTES {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "request head too large",
+            ));
+        }
+    }
+}
+
+/// Parses either `CONNECT host:port HTTP/1.1` or an absolute-form request
+/// such as `GET http://host:port/path HTTP/1.1`, which is rewritten to
+/// origin-form before being forwarded.
+fn parse_proxy_request(head: &[u8]) -> Option<ProxyRequest> {
+    let head = std::str::from_utf8(head).ok()?;
+    let (request_line, rest) = head.split_once("\r\n")?;
+    let mut parts = request_line.split(' ');
+    let (method, target, version) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = split_host_port(target)?;
+        return Some(ProxyRequest {
+            host: host.to_string(),
+            port: port?.parse().ok()?,
+            tunnel: true,
+            forwarded_head: Vec::new(),
+        });
+    }
+
+    let without_scheme = target.strip_prefix("http://")?;
+    let (authority, path) = match without_scheme.find('/') {
+        Some(idx) => without_scheme.split_at(idx),
+        None => (without_scheme, "/"),
+    };
+    let (host, port) = split_host_port(authority)?;
+    let port = match port {
+        Some(port) => port.parse().ok()?,
+        None => 80,
+    };
+    Some(ProxyRequest {
+        host: host.to_string(),
+        port,
+        tunnel: false,
+        forwarded_head: format!("{method} {path} {version}\r\n{rest}").into_bytes(),
+    })
+}
+
+/// Byte streams that can be split into independent read and write halves.
+trait Duplex: Read + Write + Send + Sized + 'static {
+    fn try_clone_stream(&self) -> io::Result<Self>;
+    fn shutdown_write(&self);
+}
+
+impl Duplex for TcpStream {
+    fn try_clone_stream(&self) -> io::Result<Self> {
+        self.try_clone()
+    }
+
+    fn shutdown_write(&self) {
+        let _ = self.shutdown(Shutdown::Write);
+    }
+}
+
+impl Duplex for UnixStream {
+    fn try_clone_stream(&self) -> io::Result<Self> {
+        self.try_clone()
+    }
+
+    fn shutdown_write(&self) {
+        let _ = self.shutdown(Shutdown::Write);
+    }
+}
+
+/// Copies bytes in both directions until each side has closed its half.
+fn pipe_bidirectional<A: Duplex, B: Duplex>(mut a: A, mut b: B) {
+    let (Ok(mut a_write), Ok(mut b_read)) = (a.try_clone_stream(), b.try_clone_stream()) else {
+        return;
+    };
+    let b_to_a = std::thread::spawn(move || {
+        let _ = io::copy(&mut b_read, &mut a_write);
+        a_write.shutdown_write();
+    });
+    let _ = io::copy(&mut a, &mut b);
+    b.shutdown_write();
+    let _ = b_to_a.join();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn allowlist_matches_hosts_ports_and_wildcards() {
+        let allowlist = NetworkAllowlist::parse(&[
+            "crates.internal.example:443",
+            "*.pypi.internal.example",
+            "Mirror.Example.",
+            "[::1]:8080",
+        ])
+        .expect("valid allowlist");
+
+        assert!(allowlist.allows("crates.internal.example", 443));
+        assert!(!allowlist.allows("crates.internal.example", 80));
+        assert!(allowlist.allows("files.pypi.internal.example", 443));
+        assert!(allowlist.allows("a.b.pypi.internal.example", 80));
+        assert!(!allowlist.allows("pypi.internal.example", 443));
+        assert!(!allowlist.allows("evilpypi.internal.example", 443));
+        assert!(allowlist.allows("mirror.example", 22));
+        assert!(allowlist.allows("[::1]", 8080));
+        assert!(!allowlist.allows("example.com", 443));
+    }
+
+    #[test]
+    fn allowlist_rejects_malformed_entries() {
+        for entry in ["", "host:notaport", "host:70000", "*", "a/b", "[::1"] {
+            assert!(
+                NetworkAllowlist::parse(&[entry]).is_err(),
+                "{entry:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_connect_request() {
+        let request =
+            parse_proxy_request(b"CONNECT crates.io:443 HTTP/1.1\r\nHost: crates.io\r\n\r\n");
+        assert_eq!(
+            request,
+            Some(ProxyRequest {
+                host: "crates.io".to_string(),
+                port: 443,
+                tunnel: true,
+                forwarded_head: Vec::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn rewrites_absolute_form_request() {
+        let request = parse_proxy_request(
+            b"GET http://mirror.example:8080/index?q=1 HTTP/1.1\r\nHost: mirror.example:8080\r\n\r\n",
+        )
+        .expect("request should parse");
+        assert_eq!(request.host, "mirror.example");
+        assert_eq!(request.port, 8080);
+        assert!(!request.tunnel);
+        assert_eq!(
+            String::from_utf8(request.forwarded_head).expect("utf8"),
+            "GET /index?q=1 HTTP/1.1\r\nHost: mirror.example:8080\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_requests() {
+        assert_eq!(parse_proxy_request(b"GET /relative HTTP/1.1\r\n\r\n"), None);
+        assert_eq!(
+            parse_proxy_request(b"GET https://example.com/ HTTP/1.1\r\n\r\n"),
+            None
+        );
+        assert_eq!(
+            parse_proxy_request(b"CONNECT example.com HTTP/1.1\r\n\r\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,131 @@
+#![cfg(target_os = "linux")]
+use codex_core::config_types::ShellEnvironmentPolicy;
+use codex_core::error::CodexErr;
+use codex_core::error::SandboxErr;
+use codex_core::exec::ExecParams;
+use codex_core::exec::ExecToolCallOutput;
+use codex_core::exec::SandboxType;
+use codex_core::exec::process_exec_tool_call;
+use codex_core::exec_env::create_env;
+use codex_core::protocol::SandboxPolicy;
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpListener;
+use std::path::PathBuf;
+
+#[cfg(not(target_arch = "aarch64"))]
+const NETWORK_TIMEOUT_MS: u64 = 5_000;
+#[cfg(target_arch = "aarch64")]
+const NETWORK_TIMEOUT_MS: u64 = 10_000;
+
+/// Starts an HTTP server on the host's loopback interface that answers every
+/// request with `200 OK` and returns its port.
+#[expect(clippy::expect_used)]
+fn spawn_http_server() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server");
+    let port = listener.local_addr().expect("local addr").port();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf);
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok");
+        }
+    });
+    port
+}
+
+#[expect(clippy::expect_used)]
+async fn run_with_allowlist(cmd: &[&str], network_allowlist: Vec<String>) -> ExecToolCallOutput {
+    let cwd = std::env::current_dir().expect("cwd should exist");
+    let sandbox_cwd = cwd.clone();
+    let params = ExecParams {
+        command: cmd.iter().copied().map(str::to_owned).collect(),
+        cwd,
+        timeout_ms: Some(NETWORK_TIMEOUT_MS),
+        env: create_env(&ShellEnvironmentPolicy::default()),
+        with_escalated_permissions: None,
+        justification: None,
+    };
+
+    let sandbox_policy = SandboxPolicy::WorkspaceWrite {
+        writable_roots: vec![],
+        network_access: false,
+        network_allowlist,
+        exclude_tmpdir_env_var: true,
+        exclude_slash_tmp: true,
+    };
+    let sandbox_program = env!("CARGO_BIN_EXE_codex-linux-sandbox");
+    let codex_linux_sandbox_exe = Some(PathBuf::from(sandbox_program));
+    let result = process_exec_tool_call(
+        params,
+        SandboxType::LinuxSeccomp,
+        &sandbox_policy,
+        sandbox_cwd.as_path(),
+        &codex_linux_sandbox_exe,
+        None,
+    )
+    .await;
+
+    match result {
+        Ok(output) => output,
+        Err(CodexErr::Sandbox(SandboxErr::Denied { output })) => *output,
+        _ => panic!("unexpected sandbox result: {result:?}"),
+    }
+}
+
+#[tokio::test]
+async fn allowlisted_host_is_reachable_through_proxy() {
+    let port = spawn_http_server();
+    let url = format!("http://127.0.0.1:{port}/");
+    // `--noproxy ''` overrides the NO_PROXY default for loopback so curl
+    // goes through the proxy to the host's loopback interface.
+    let output = run_with_allowlist(
+        &["curl", "-sf", "--noproxy", "", &url],
+        vec![format!("127.0.0.1:{port}")],
+    )
+    .await;
+
+    if output.exit_code == 127 {
+        eprintln!("skipping test: curl is not installed");
+        return;
+    }
+    assert_eq!(
+        output.exit_code, 0,
+        "stdout:\n{}\nstderr:\n{}",
+        output.stdout.text, output.stderr.text
+    );
+    assert_eq!(output.stdout.text, "ok");
+}
+
+#[tokio::test]
+async fn host_outside_allowlist_is_blocked() {
+    let port = spawn_http_server();
+    let url = format!("http://127.0.0.1:{port}/");
+    let output = run_with_allowlist(
+        &["curl", "-sf", "--noproxy", "", &url],
+        vec!["crates.internal.example:443".to_string()],
+    )
+    .await;
+
+    assert_ne!(output.exit_code, 0, "proxy should refuse {url}");
+}
+
+#[tokio::test]
+async fn direct_connections_bypassing_proxy_fail() {
+    let port = spawn_http_server();
+    let output = run_with_allowlist(
+        &[
+            "bash",
+            "-c",
+            &format!("echo hi > /dev/tcp/127.0.0.1/{port}"),
+        ],
+        vec![format!("127.0.0.1:{port}")],
+    )
+    .await;
+
+    assert_ne!(
+        output.exit_code, 0,
+        "loopback of the host must not be reachable"
+    );
+}
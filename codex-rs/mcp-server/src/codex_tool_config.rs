@@ -97,29 +97,30 @@ impl From<CodexToolCallSandboxMode> for SandboxMode {
     }
 }
 
-/// Builds a `Tool` definition (JSON schema etc.) for the Codex tool-call.
-pub(crate) fn create_tool_for_codex_tool_call_param() -> Tool {
+/// Builds the input schema for a tool whose arguments deserialize into `T`.
+pub(crate) fn create_tool_input_schema<T: JsonSchema>() -> ToolInputSchema {
     let schema = SchemaSettings::draft2019_09()
         .with(|s| {
             s.inline_subschemas = true;
             s.option_add_null_type = false;
         })
         .into_generator()
-        .into_root_schema_for::<CodexToolCallParam>();
+        .into_root_schema_for::<T>();
 
     #[expect(clippy::expect_used)]
-    let schema_value =
-        serde_json::to_value(&schema).expect("Codex tool schema should serialise to JSON");
+    let schema_value = serde_json::to_value(&schema).expect("tool schema should serialise to JSON");
 
-    let tool_input_schema =
-        serde_json::from_value::<ToolInputSchema>(schema_value).unwrap_or_else(|e| {
-            panic!("failed to create Tool from schema: {e}");
-        });
+    serde_json::from_value::<ToolInputSchema>(schema_value).unwrap_or_else(|e| {
+        panic!("failed to create Tool from schema: {e}");
+    })
+}
 
+/// Builds a `Tool` definition (JSON schema etc.) for the Codex tool-call.
+pub(crate) fn create_tool_for_codex_tool_call_param() -> Tool {
     Tool {
         name: "codex".to_string(),
         title: Some("Codex".to_string()),
-        input_schema: tool_input_schema,
+        input_schema: create_tool_input_schema::<CodexToolCallParam>(),
         // TODO(mbolin): This should be defined.
         output_schema: None,
         description: Some(
@@ -164,6 +165,7 @@ impl CodexToolCallParam {
             include_view_image_tool: None,
             show_raw_agent_reasoning: None,
             tools_web_search_request: None,
+            worktree: None,
         };
 
         let cli_overrides = cli_overrides
@@ -191,27 +193,10 @@ pub struct CodexToolCallReplyParam {
 
 /// Builds a `Tool` definition for the `codex-reply` tool-call.
 pub(crate) fn create_tool_for_codex_tool_call_reply_param() -> Tool {
-    let schema = SchemaSettings::draft2019_09()
-        .with(|s| {
-            s.inline_subschemas = true;
-            s.option_add_null_type = false;
-        })
-        .into_generator()
-        .into_root_schema_for::<CodexToolCallReplyParam>();
-
-    #[expect(clippy::expect_used)]
-    let schema_value =
-        serde_json::to_value(&schema).expect("Codex reply tool schema should serialise to JSON");
-
-    let tool_input_schema =
-        serde_json::from_value::<ToolInputSchema>(schema_value).unwrap_or_else(|e| {
-            panic!("failed to create Tool from schema: {e}");
-        });
-
     Tool {
         name: "codex-reply".to_string(),
         title: Some("Codex Reply".to_string()),
-        input_schema: tool_input_schema,
+        input_schema: create_tool_input_schema::<CodexToolCallReplyParam>(),
         output_schema: None,
         description: Some(
             "Continue a Codex conversation by providing the conversation id and prompt."
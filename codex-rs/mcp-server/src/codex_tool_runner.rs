@@ -9,6 +9,9 @@ use crate::exec_approval::handle_exec_approval_request;
 use crate::outgoing_message::OutgoingMessageSender;
 use crate::outgoing_message::OutgoingNotificationMeta;
 use crate::patch_approval::handle_patch_approval_request;
+use crate::progress::ProgressReporter;
+use crate::session_registry::SessionInfo;
+use crate::session_registry::SessionRegistry;
 use codex_core::CodexConversation;
 use codex_core::ConversationManager;
 use codex_core::NewConversation;
@@ -22,11 +25,15 @@ use codex_core::protocol::InputItem;
 use codex_core::protocol::Op;
 use codex_core::protocol::Submission;
 use codex_core::protocol::TaskCompleteEvent;
+use codex_core::protocol::TurnAbortedEvent;
+use codex_core::protocol::TurnDiffEvent;
 use codex_protocol::ConversationId;
+use codex_protocol::config_types::SandboxMode;
 use mcp_types::CallToolResult;
 use mcp_types::ContentBlock;
 use mcp_types::RequestId;
 use mcp_types::TextContent;
+use serde::Serialize;
 use serde_json::json;
 use tokio::sync::Mutex;
 
@@ -36,6 +43,7 @@ pub(crate) const INVALID_PARAMS_ERROR_CODE: i64 = -32602;
 ///
 /// On completion (success or error) the function sends the appropriate
 /// `tools/call` response so the LLM can continue the conversation.
+#[allow(clippy::too_many_arguments)]
 pub async fn run_codex_tool_session(
     id: RequestId,
     initial_prompt: String,
@@ -43,7 +51,11 @@ pub async fn run_codex_tool_session(
     outgoing: Arc<OutgoingMessageSender>,
     conversation_manager: Arc<ConversationManager>,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
+    session_registry: Arc<SessionRegistry>,
+    progress: Option<ProgressReporter>,
 ) {
+    let cwd = config.cwd.clone();
+    let workspace_write_policy = config.sandbox_policy_for_mode(SandboxMode::WorkspaceWrite);
     let NewConversation {
         conversation_id,
         conversation,
@@ -65,6 +77,17 @@ pub async fn run_codex_tool_session(
         }
     };
 
+    let session_info = SessionInfo::new(
+        session_configured.model.clone(),
+        cwd,
+        session_configured.rollout_path.clone(),
+        workspace_write_policy,
+    )
+    .await;
+    session_registry
+        .register(conversation_id, session_info, &outgoing)
+        .await;
+
     let session_configured_event = Event {
         // Use a fake id value for now.
         id: "".to_string(),
@@ -106,13 +129,17 @@ pub async fn run_codex_tool_session(
 
     run_codex_tool_session_inner(
         conversation,
+        conversation_id,
         outgoing,
         id,
         running_requests_id_to_codex_uuid,
+        session_registry,
+        progress,
     )
     .await;
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn run_codex_tool_session_reply(
     conversation: Arc<CodexConversation>,
     outgoing: Arc<OutgoingMessageSender>,
@@ -120,18 +147,43 @@ pub async fn run_codex_tool_session_reply(
     prompt: String,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
     conversation_id: ConversationId,
+    session_registry: Arc<SessionRegistry>,
+    progress: Option<ProgressReporter>,
+) {
+    run_codex_tool_session_op(
+        conversation,
+        outgoing,
+        request_id,
+        Op::UserInput {
+            items: vec![InputItem::Text { text: prompt }],
+        },
+        running_requests_id_to_codex_uuid,
+        conversation_id,
+        session_registry,
+        progress,
+    )
+    .await;
+}
+
+/// Submit `op` to an existing session and stream its events back to the
+/// client until the task it starts completes.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_codex_tool_session_op(
+    conversation: Arc<CodexConversation>,
+    outgoing: Arc<OutgoingMessageSender>,
+    request_id: RequestId,
+    op: Op,
+    running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
+    conversation_id: ConversationId,
+    session_registry: Arc<SessionRegistry>,
+    progress: Option<ProgressReporter>,
 ) {
     running_requests_id_to_codex_uuid
         .lock()
         .await
         .insert(request_id.clone(), conversation_id);
-    if let Err(e) = conversation
-        .submit(Op::UserInput {
-            items: vec![InputItem::Text { text: prompt }],
-        })
-        .await
-    {
-        tracing::error!("Failed to submit user input: {e}");
+    if let Err(e) = conversation.submit(op).await {
+        tracing::error!("Failed to submit {request_id:?} to Codex: {e}");
         // unregister the id so we don't keep it in the map
         running_requests_id_to_codex_uuid
             .lock()
@@ -142,23 +194,32 @@ pub async fn run_codex_tool_session_reply(
 
     run_codex_tool_session_inner(
         conversation,
+        conversation_id,
         outgoing,
         request_id,
         running_requests_id_to_codex_uuid,
+        session_registry,
+        progress,
     )
     .await;
 }
 
 async fn run_codex_tool_session_inner(
     codex: Arc<CodexConversation>,
+    conversation_id: ConversationId,
     outgoing: Arc<OutgoingMessageSender>,
     request_id: RequestId,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
+    session_registry: Arc<SessionRegistry>,
+    mut progress: Option<ProgressReporter>,
 ) {
     let request_id_str = match &request_id {
         RequestId::String(s) => s.clone(),
         RequestId::Integer(n) => n.to_string(),
     };
+    // Result of the completed task, held back until the rollout has been
+    // flushed so that readers of the session resource see the whole turn.
+    let mut completed: Option<CallToolResult> = None;
 
     // Stream events until the task needs to pause for user interaction or
     // completes.
@@ -171,6 +232,9 @@ async fn run_codex_tool_session_inner(
                         Some(OutgoingNotificationMeta::new(Some(request_id.clone()))),
                     )
                     .await;
+                if let Some(progress) = progress.as_mut() {
+                    progress.report(&event.msg).await;
+                }
 
                 match event.msg {
                     EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
@@ -199,7 +263,13 @@ async fn run_codex_tool_session_inner(
                         let result = json!({
                             "error": err_event.message,
                         });
-                        outgoing.send_response(request_id.clone(), result).await;
+                        complete_tool_call(
+                            &outgoing,
+                            &request_id,
+                            result,
+                            &running_requests_id_to_codex_uuid,
+                        )
+                        .await;
                         break;
                     }
                     EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
@@ -236,14 +306,61 @@ async fn run_codex_tool_session_inner(
                             is_error: None,
                             structured_content: None,
                         };
-                        outgoing.send_response(request_id.clone(), result).await;
-                        // unregister the id so we don't keep it in the map
-                        running_requests_id_to_codex_uuid
-                            .lock()
-                            .await
-                            .remove(&request_id);
+                        if let Err(e) = codex.submit(Op::GetPath).await {
+                            tracing::error!("Failed to flush rollout: {e}");
+                            complete_tool_call(
+                                &outgoing,
+                                &request_id,
+                                result,
+                                &running_requests_id_to_codex_uuid,
+                            )
+                            .await;
+                            break;
+                        }
+                        completed = Some(result);
+                    }
+                    EventMsg::ConversationPath(_) => {
+                        if let Some(result) = completed.take() {
+                            complete_tool_call(
+                                &outgoing,
+                                &request_id,
+                                result,
+                                &running_requests_id_to_codex_uuid,
+                            )
+                            .await;
+                            session_registry
+                                .notify_updated(&conversation_id, &outgoing)
+                                .await;
+                            break;
+                        }
+                    }
+                    EventMsg::TurnAborted(TurnAbortedEvent { reason }) => {
+                        let result = CallToolResult {
+                            content: vec![ContentBlock::TextContent(TextContent {
+                                r#type: "text".to_string(),
+                                text: format!("Turn aborted: {reason:?}"),
+                                annotations: None,
+                            })],
+                            is_error: Some(true),
+                            structured_content: None,
+                        };
+                        complete_tool_call(
+                            &outgoing,
+                            &request_id,
+                            result,
+                            &running_requests_id_to_codex_uuid,
+                        )
+                        .await;
+                        session_registry
+                            .notify_updated(&conversation_id, &outgoing)
+                            .await;
                         break;
                     }
+                    EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => {
+                        session_registry
+                            .record_turn_diff(&conversation_id, unified_diff)
+                            .await;
+                    }
                     EventMsg::SessionConfigured(_) => {
                         tracing::error!("unexpected SessionConfigured event");
                     }
@@ -265,6 +382,8 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::McpToolCallBegin(_)
                     | EventMsg::McpToolCallEnd(_)
                     | EventMsg::McpListToolsResponse(_)
+                    | EventMsg::McpListResourcesResponse(_)
+                    | EventMsg::McpListPromptsResponse(_)
                     | EventMsg::ListCustomPromptsResponse(_)
                     | EventMsg::ExecCommandBegin(_)
                     | EventMsg::ExecCommandOutputDelta(_)
@@ -273,18 +392,18 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::StreamError(_)
                     | EventMsg::PatchApplyBegin(_)
                     | EventMsg::PatchApplyEnd(_)
-                    | EventMsg::TurnDiff(_)
                     | EventMsg::WebSearchBegin(_)
                     | EventMsg::WebSearchEnd(_)
                     | EventMsg::GetHistoryEntryResponse(_)
                     | EventMsg::PlanUpdate(_)
-                    | EventMsg::TurnAborted(_)
-                    | EventMsg::ConversationPath(_)
                     | EventMsg::UserMessage(_)
                     | EventMsg::ShutdownComplete
                     | EventMsg::ViewImageToolCall(_)
                     | EventMsg::EnteredReviewMode(_)
-                    | EventMsg::ExitedReviewMode(_) => {
+                    | EventMsg::ExitedReviewMode(_)
+                    | EventMsg::SubAgentBegin(_)
+                    | EventMsg::SubAgentEvent(_)
+                    | EventMsg::SubAgentEnd(_) => {
                         // For now, we do not do anything extra for these
                         // events. Note that
                         // send(codex_event_to_notification(&event)) above has
@@ -306,9 +425,34 @@ async fn run_codex_tool_session_inner(
                     // structured way.
                     structured_content: None,
                 };
-                outgoing.send_response(request_id.clone(), result).await;
+                complete_tool_call(
+                    &outgoing,
+                    &request_id,
+                    result,
+                    &running_requests_id_to_codex_uuid,
+                )
+                .await;
                 break;
             }
         }
     }
 }
+
+/// Unregisters the tool call and sends its response. A call the client
+/// cancelled was already unregistered and, per the MCP spec, gets no
+/// response.
+async fn complete_tool_call<T: Serialize>(
+    outgoing: &OutgoingMessageSender,
+    request_id: &RequestId,
+    result: T,
+    running_requests_id_to_codex_uuid: &Mutex<HashMap<RequestId, ConversationId>>,
+) {
+    let was_running = running_requests_id_to_codex_uuid
+        .lock()
+        .await
+        .remove(request_id)
+        .is_some();
+    if was_running {
+        outgoing.send_response(request_id.clone(), result).await;
+    }
+}
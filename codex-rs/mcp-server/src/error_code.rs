@@ -1,2 +1,4 @@
 pub(crate) const INVALID_REQUEST_ERROR_CODE: i64 = -32600;
 pub(crate) const INTERNAL_ERROR_CODE: i64 = -32603;
+/// MCP-specific code for `resources/read` of an unknown URI.
+pub(crate) const RESOURCE_NOT_FOUND_ERROR_CODE: i64 = -32002;
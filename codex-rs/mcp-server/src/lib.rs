@@ -27,6 +27,9 @@ mod exec_approval;
 pub(crate) mod message_processor;
 mod outgoing_message;
 mod patch_approval;
+mod progress;
+mod session_registry;
+mod session_tool_config;
 
 use crate::message_processor::MessageProcessor;
 use crate::outgoing_message::OutgoingMessage;
@@ -38,6 +41,10 @@ pub use crate::exec_approval::ExecApprovalElicitRequestParams;
 pub use crate::exec_approval::ExecApprovalResponse;
 pub use crate::patch_approval::PatchApprovalElicitRequestParams;
 pub use crate::patch_approval::PatchApprovalResponse;
+pub use crate::session_tool_config::CodexCompactParam;
+pub use crate::session_tool_config::CodexListSessionsParam;
+pub use crate::session_tool_config::CodexSessionParam;
+pub use crate::session_tool_config::CodexSetPolicyParam;
 
 /// Size of the bounded channels used to communicate between tasks. The value
 /// is a balance between throughput and memory usage – 128 messages should be
@@ -1,20 +1,49 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::path::PathBuf;
 
 use crate::codex_tool_config::CodexToolCallParam;
 use crate::codex_tool_config::CodexToolCallReplyParam;
+use crate::codex_tool_config::CodexToolCallSandboxMode;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_param;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_reply_param;
+use crate::codex_tool_runner::INVALID_PARAMS_ERROR_CODE;
+use crate::error_code::INTERNAL_ERROR_CODE;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+use crate::error_code::RESOURCE_NOT_FOUND_ERROR_CODE;
 use crate::outgoing_message::OutgoingMessageSender;
+use crate::progress::ProgressReporter;
+use crate::progress::progress_token;
+use crate::session_registry::ROLLOUT_MIME_TYPE;
+use crate::session_registry::SESSION_URI_TEMPLATE;
+use crate::session_registry::SessionRegistry;
+use crate::session_registry::parse_session_uri;
+use crate::session_registry::session_uri;
+use crate::session_tool_config::COMPACT_TOOL_NAME;
+use crate::session_tool_config::CodexCompactParam;
+use crate::session_tool_config::CodexListSessionsParam;
+use crate::session_tool_config::CodexSessionParam;
+use crate::session_tool_config::CodexSetPolicyParam;
+use crate::session_tool_config::DIFF_TOOL_NAME;
+use crate::session_tool_config::INTERRUPT_TOOL_NAME;
+use crate::session_tool_config::LIST_SESSIONS_TOOL_NAME;
+use crate::session_tool_config::SET_POLICY_TOOL_NAME;
+use crate::session_tool_config::create_tool_for_compact;
+use crate::session_tool_config::create_tool_for_diff;
+use crate::session_tool_config::create_tool_for_interrupt;
+use crate::session_tool_config::create_tool_for_list_sessions;
+use crate::session_tool_config::create_tool_for_set_policy;
 use codex_protocol::ConversationId;
 use codex_protocol::protocol::SessionSource;
 
 use codex_core::AuthManager;
+use codex_core::CodexConversation;
 use codex_core::ConversationManager;
 use codex_core::config::Config;
 use codex_core::default_client::USER_AGENT_SUFFIX;
 use codex_core::default_client::get_codex_user_agent;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::Submission;
 use mcp_types::CallToolRequestParams;
 use mcp_types::CallToolResult;
@@ -25,12 +54,22 @@ use mcp_types::JSONRPCErrorError;
 use mcp_types::JSONRPCNotification;
 use mcp_types::JSONRPCRequest;
 use mcp_types::JSONRPCResponse;
+use mcp_types::ListResourceTemplatesResult;
+use mcp_types::ListResourcesResult;
 use mcp_types::ListToolsResult;
 use mcp_types::ModelContextProtocolRequest;
+use mcp_types::ProgressToken;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
 use mcp_types::RequestId;
+use mcp_types::Resource;
+use mcp_types::ResourceTemplate;
+use mcp_types::ServerCapabilitiesResources;
 use mcp_types::ServerCapabilitiesTools;
 use mcp_types::ServerNotification;
 use mcp_types::TextContent;
+use mcp_types::TextResourceContents;
+use serde::de::DeserializeOwned;
 use serde_json::json;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -42,6 +81,7 @@ pub(crate) struct MessageProcessor {
     codex_linux_sandbox_exe: Option<PathBuf>,
     conversation_manager: Arc<ConversationManager>,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
+    session_registry: Arc<SessionRegistry>,
 }
 
 impl MessageProcessor {
@@ -62,12 +102,15 @@ impl MessageProcessor {
             codex_linux_sandbox_exe,
             conversation_manager,
             running_requests_id_to_codex_uuid: Arc::new(Mutex::new(HashMap::new())),
+            session_registry: Arc::new(SessionRegistry::default()),
         }
     }
 
     pub(crate) async fn process_request(&mut self, request: JSONRPCRequest) {
         // Hold on to the ID so we can respond.
         let request_id = request.id.clone();
+        // The typed params drop `_meta`, so read the progress token first.
+        let progress_token = progress_token(&request);
 
         let client_request = match McpClientRequest::try_from(request) {
             Ok(client_request) => client_request,
@@ -86,19 +129,20 @@ impl MessageProcessor {
                 self.handle_ping(request_id, params).await;
             }
             McpClientRequest::ListResourcesRequest(params) => {
-                self.handle_list_resources(params);
+                self.handle_list_resources(request_id, params).await;
             }
             McpClientRequest::ListResourceTemplatesRequest(params) => {
-                self.handle_list_resource_templates(params);
+                self.handle_list_resource_templates(request_id, params)
+                    .await;
             }
             McpClientRequest::ReadResourceRequest(params) => {
-                self.handle_read_resource(params);
+                self.handle_read_resource(request_id, params).await;
             }
             McpClientRequest::SubscribeRequest(params) => {
-                self.handle_subscribe(params);
+                self.handle_subscribe(request_id, params).await;
             }
             McpClientRequest::UnsubscribeRequest(params) => {
-                self.handle_unsubscribe(params);
+                self.handle_unsubscribe(request_id, params).await;
             }
             McpClientRequest::ListPromptsRequest(params) => {
                 self.handle_list_prompts(params);
@@ -110,7 +154,8 @@ impl MessageProcessor {
                 self.handle_list_tools(request_id, params).await;
             }
             McpClientRequest::CallToolRequest(params) => {
-                self.handle_call_tool(request_id, params).await;
+                self.handle_call_tool(request_id, params, progress_token)
+                    .await;
             }
             McpClientRequest::SetLevelRequest(params) => {
                 self.handle_set_level(params);
@@ -205,7 +250,10 @@ impl MessageProcessor {
                 experimental: None,
                 logging: None,
                 prompts: None,
-                resources: None,
+                resources: Some(ServerCapabilitiesResources {
+                    list_changed: Some(true),
+                    subscribe: Some(true),
+                }),
                 tools: Some(ServerCapabilitiesTools {
                     list_changed: Some(true),
                 }),
@@ -242,40 +290,140 @@ impl MessageProcessor {
             .await;
     }
 
-    fn handle_list_resources(
+    /// Every session started by this server is exposed as a resource whose
+    /// contents are the session's rollout.
+    async fn handle_list_resources(
         &self,
+        id: RequestId,
         params: <mcp_types::ListResourcesRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/list -> params: {:?}", params);
+        let resources = self
+            .session_registry
+            .list()
+            .await
+            .into_iter()
+            .map(|(conversation_id, info)| Resource {
+                annotations: None,
+                description: Some(format!(
+                    "Rollout of the Codex session in {}",
+                    info.cwd.display()
+                )),
+                mime_type: Some(ROLLOUT_MIME_TYPE.to_string()),
+                name: conversation_id.to_string(),
+                size: None,
+                title: Some(format!("Codex session {conversation_id}")),
+                uri: session_uri(&conversation_id),
+            })
+            .collect();
+        let result = ListResourcesResult {
+            next_cursor: None,
+            resources,
+        };
+        self.send_response::<mcp_types::ListResourcesRequest>(id, result)
+            .await;
     }
 
-    fn handle_list_resource_templates(
+    async fn handle_list_resource_templates(
         &self,
+        id: RequestId,
         params:
             <mcp_types::ListResourceTemplatesRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/templates/list -> params: {:?}", params);
+        let result = ListResourceTemplatesResult {
+            next_cursor: None,
+            resource_templates: vec![ResourceTemplate {
+                annotations: None,
+                description: Some("Rollout of a Codex session started by this server".to_string()),
+                mime_type: Some(ROLLOUT_MIME_TYPE.to_string()),
+                name: "codex-session".to_string(),
+                title: Some("Codex session".to_string()),
+                uri_template: SESSION_URI_TEMPLATE.to_string(),
+            }],
+        };
+        self.send_response::<mcp_types::ListResourceTemplatesRequest>(id, result)
+            .await;
     }
 
-    fn handle_read_resource(
+    async fn handle_read_resource(
         &self,
+        id: RequestId,
         params: <mcp_types::ReadResourceRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/read -> params: {:?}", params);
+        let uri = params.uri;
+        let info = match parse_session_uri(&uri) {
+            Some(conversation_id) => self.session_registry.get(&conversation_id).await,
+            None => None,
+        };
+        let Some(info) = info else {
+            let error = JSONRPCErrorError {
+                code: RESOURCE_NOT_FOUND_ERROR_CODE,
+                message: format!("Resource not found: {uri}"),
+                data: None,
+            };
+            self.outgoing.send_error(id, error).await;
+            return;
+        };
+
+        match tokio::fs::read_to_string(&info.rollout_path).await {
+            Ok(text) => {
+                let result = ReadResourceResult {
+                    contents: vec![ReadResourceResultContents::TextResourceContents(
+                        TextResourceContents {
+                            mime_type: Some(ROLLOUT_MIME_TYPE.to_string()),
+                            text,
+                            uri,
+                        },
+                    )],
+                };
+                self.send_response::<mcp_types::ReadResourceRequest>(id, result)
+                    .await;
+            }
+            Err(e) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!(
+                        "Failed to read rollout {}: {e}",
+                        info.rollout_path.display()
+                    ),
+                    data: None,
+                };
+                self.outgoing.send_error(id, error).await;
+            }
+        }
     }
 
-    fn handle_subscribe(
+    async fn handle_subscribe(
         &self,
+        id: RequestId,
         params: <mcp_types::SubscribeRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/subscribe -> params: {:?}", params);
+        if parse_session_uri(&params.uri).is_none() {
+            let error = JSONRPCErrorError {
+                code: INVALID_PARAMS_ERROR_CODE,
+                message: format!("Not a Codex session resource: {}", params.uri),
+                data: None,
+            };
+            self.outgoing.send_error(id, error).await;
+            return;
+        }
+        self.session_registry.subscribe(params.uri).await;
+        self.send_response::<mcp_types::SubscribeRequest>(id, json!({}))
+            .await;
     }
 
-    fn handle_unsubscribe(
+    async fn handle_unsubscribe(
         &self,
+        id: RequestId,
         params: <mcp_types::UnsubscribeRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/unsubscribe -> params: {:?}", params);
+        self.session_registry.unsubscribe(&params.uri).await;
+        self.send_response::<mcp_types::UnsubscribeRequest>(id, json!({}))
+            .await;
     }
 
     fn handle_list_prompts(
@@ -302,6 +450,11 @@ impl MessageProcessor {
             tools: vec![
                 create_tool_for_codex_tool_call_param(),
                 create_tool_for_codex_tool_call_reply_param(),
+                create_tool_for_list_sessions(),
+                create_tool_for_interrupt(),
+                create_tool_for_diff(),
+                create_tool_for_set_policy(),
+                create_tool_for_compact(),
             ],
             next_cursor: None,
         };
@@ -314,16 +467,24 @@ impl MessageProcessor {
         &self,
         id: RequestId,
         params: <mcp_types::CallToolRequest as mcp_types::ModelContextProtocolRequest>::Params,
+        progress_token: Option<ProgressToken>,
     ) {
         tracing::info!("tools/call -> params: {:?}", params);
         let CallToolRequestParams { name, arguments } = params;
+        let progress =
+            progress_token.map(|token| ProgressReporter::new(self.outgoing.clone(), token));
 
         match name.as_str() {
-            "codex" => self.handle_tool_call_codex(id, arguments).await,
+            "codex" => self.handle_tool_call_codex(id, arguments, progress).await,
             "codex-reply" => {
-                self.handle_tool_call_codex_session_reply(id, arguments)
+                self.handle_tool_call_codex_session_reply(id, arguments, progress)
                     .await
             }
+            LIST_SESSIONS_TOOL_NAME => self.handle_tool_call_list_sessions(id, arguments).await,
+            INTERRUPT_TOOL_NAME => self.handle_tool_call_interrupt(id, arguments).await,
+            DIFF_TOOL_NAME => self.handle_tool_call_diff(id, arguments).await,
+            SET_POLICY_TOOL_NAME => self.handle_tool_call_set_policy(id, arguments).await,
+            COMPACT_TOOL_NAME => self.handle_tool_call_compact(id, arguments, progress).await,
             _ => {
                 let result = CallToolResult {
                     content: vec![ContentBlock::TextContent(TextContent {
@@ -339,7 +500,12 @@ impl MessageProcessor {
             }
         }
     }
-    async fn handle_tool_call_codex(&self, id: RequestId, arguments: Option<serde_json::Value>) {
+    async fn handle_tool_call_codex(
+        &self,
+        id: RequestId,
+        arguments: Option<serde_json::Value>,
+        progress: Option<ProgressReporter>,
+    ) {
         let (initial_prompt, config): (String, Config) = match arguments {
             Some(json_val) => match serde_json::from_value::<CodexToolCallParam>(json_val) {
                 Ok(tool_cfg) => match tool_cfg
@@ -401,6 +567,7 @@ impl MessageProcessor {
         let outgoing = self.outgoing.clone();
         let conversation_manager = self.conversation_manager.clone();
         let running_requests_id_to_codex_uuid = self.running_requests_id_to_codex_uuid.clone();
+        let session_registry = self.session_registry.clone();
 
         // Spawn an async task to handle the Codex session so that we do not
         // block the synchronous message-processing loop.
@@ -413,6 +580,8 @@ impl MessageProcessor {
                 outgoing,
                 conversation_manager,
                 running_requests_id_to_codex_uuid,
+                session_registry,
+                progress,
             )
             .await;
         });
@@ -422,6 +591,7 @@ impl MessageProcessor {
         &self,
         request_id: RequestId,
         arguments: Option<serde_json::Value>,
+        progress: Option<ProgressReporter>,
     ) {
         tracing::info!("tools/call -> params: {:?}", arguments);
 
@@ -516,6 +686,7 @@ impl MessageProcessor {
             let outgoing = outgoing.clone();
             let prompt = prompt.clone();
             let running_requests_id_to_codex_uuid = running_requests_id_to_codex_uuid.clone();
+            let session_registry = self.session_registry.clone();
 
             async move {
                 crate::codex_tool_runner::run_codex_tool_session_reply(
@@ -525,12 +696,256 @@ impl MessageProcessor {
                     prompt,
                     running_requests_id_to_codex_uuid,
                     conversation_id,
+                    session_registry,
+                    progress,
                 )
                 .await;
             }
         });
     }
 
+    async fn handle_tool_call_list_sessions(
+        &self,
+        request_id: RequestId,
+        arguments: Option<serde_json::Value>,
+    ) {
+        let CodexListSessionsParam {} =
+            match parse_tool_arguments(LIST_SESSIONS_TOOL_NAME, arguments) {
+                Ok(params) => params,
+                Err(result) => {
+                    self.outgoing.send_response(request_id, result).await;
+                    return;
+                }
+            };
+
+        let running: HashSet<ConversationId> = self
+            .running_requests_id_to_codex_uuid
+            .lock()
+            .await
+            .values()
+            .copied()
+            .collect();
+        let sessions: Vec<serde_json::Value> = self
+            .session_registry
+            .list()
+            .await
+            .into_iter()
+            .map(|(conversation_id, info)| {
+                let status = if running.contains(&conversation_id) {
+                    "running"
+                } else {
+                    "idle"
+                };
+                json!({
+                    "conversationId": conversation_id.to_string(),
+                    "model": info.model,
+                    "cwd": info.cwd,
+                    "rolloutPath": info.rollout_path,
+                    "uri": session_uri(&conversation_id),
+                    "status": status,
+                })
+            })
+            .collect();
+        let structured_content = json!({ "sessions": sessions });
+        let result = CallToolResult {
+            content: vec![ContentBlock::TextContent(TextContent {
+                r#type: "text".to_string(),
+                text: structured_content.to_string(),
+                annotations: None,
+            })],
+            is_error: None,
+            structured_content: Some(structured_content),
+        };
+        self.outgoing.send_response(request_id, result).await;
+    }
+
+    async fn handle_tool_call_interrupt(
+        &self,
+        request_id: RequestId,
+        arguments: Option<serde_json::Value>,
+    ) {
+        let session =
+            match parse_tool_arguments::<CodexSessionParam>(INTERRUPT_TOOL_NAME, arguments) {
+                Ok(params) => self.find_session(&params.conversation_id).await,
+                Err(result) => Err(result),
+            };
+        let result = match session {
+            Ok((conversation_id, codex)) => {
+                if !self.is_running(conversation_id).await {
+                    text_result(format!("No turn is running in session {conversation_id}"))
+                } else if let Err(e) = codex.submit(Op::Interrupt).await {
+                    error_result(format!("Failed to submit interrupt to Codex: {e}"))
+                } else {
+                    text_result(format!("Interrupted session {conversation_id}"))
+                }
+            }
+            Err(result) => result,
+        };
+        self.outgoing.send_response(request_id, result).await;
+    }
+
+    async fn handle_tool_call_diff(
+        &self,
+        request_id: RequestId,
+        arguments: Option<serde_json::Value>,
+    ) {
+        let session = match parse_tool_arguments::<CodexSessionParam>(DIFF_TOOL_NAME, arguments) {
+            Ok(params) => self.find_session(&params.conversation_id).await,
+            Err(result) => Err(result),
+        };
+        let result = match session {
+            Ok((conversation_id, _)) => match self.session_registry.get(&conversation_id).await {
+                Some(info) => match info.diff().await {
+                    Some(diff) => text_result(diff),
+                    None if info.base_snapshot.is_some() => error_result(format!(
+                        "Failed to compute the diff of session {conversation_id}"
+                    )),
+                    None => text_result(String::new()),
+                },
+                None => error_result(format!(
+                    "Session not found for conversation_id: {conversation_id}"
+                )),
+            },
+            Err(result) => result,
+        };
+        self.outgoing.send_response(request_id, result).await;
+    }
+
+    async fn handle_tool_call_set_policy(
+        &self,
+        request_id: RequestId,
+        arguments: Option<serde_json::Value>,
+    ) {
+        let params =
+            match parse_tool_arguments::<CodexSetPolicyParam>(SET_POLICY_TOOL_NAME, arguments) {
+                Ok(params) => params,
+                Err(result) => {
+                    self.outgoing.send_response(request_id, result).await;
+                    return;
+                }
+            };
+        if params.approval_policy.is_none() && params.sandbox.is_none() {
+            let result = error_result(
+                "Nothing to change; pass `approvalPolicy`, `sandbox` or both.".to_string(),
+            );
+            self.outgoing.send_response(request_id, result).await;
+            return;
+        }
+
+        let session = match self.find_session(&params.conversation_id).await {
+            Ok((conversation_id, codex)) => match self.session_registry.get(&conversation_id).await
+            {
+                Some(info) => Ok((conversation_id, codex, info)),
+                None => Err(error_result(format!(
+                    "Session not found for conversation_id: {conversation_id}"
+                ))),
+            },
+            Err(result) => Err(result),
+        };
+        let result = match session {
+            Ok((conversation_id, codex, info)) => {
+                let sandbox_policy = params.sandbox.map(|mode| match mode {
+                    CodexToolCallSandboxMode::ReadOnly => SandboxPolicy::new_read_only_policy(),
+                    CodexToolCallSandboxMode::WorkspaceWrite => info.workspace_write_policy,
+                    CodexToolCallSandboxMode::DangerFullAccess => SandboxPolicy::DangerFullAccess,
+                });
+                let op = Op::OverrideTurnContext {
+                    cwd: None,
+                    approval_policy: params.approval_policy.map(Into::into),
+                    sandbox_policy,
+                    model: None,
+                    effort: None,
+                    summary: None,
+                };
+                match codex.submit(op).await {
+                    Ok(_) => {
+                        text_result(format!("Updated the policy of session {conversation_id}"))
+                    }
+                    Err(e) => error_result(format!("Failed to update the policy: {e}")),
+                }
+            }
+            Err(result) => result,
+        };
+        self.outgoing.send_response(request_id, result).await;
+    }
+
+    async fn handle_tool_call_compact(
+        &self,
+        request_id: RequestId,
+        arguments: Option<serde_json::Value>,
+        progress: Option<ProgressReporter>,
+    ) {
+        let session = match parse_tool_arguments::<CodexCompactParam>(COMPACT_TOOL_NAME, arguments)
+        {
+            Ok(params) => self
+                .find_session(&params.conversation_id)
+                .await
+                .map(|(conversation_id, codex)| (params, conversation_id, codex)),
+            Err(result) => Err(result),
+        };
+        let (params, conversation_id, codex) = match session {
+            Ok(session) => session,
+            Err(result) => {
+                self.outgoing.send_response(request_id, result).await;
+                return;
+            }
+        };
+        // Only one task may consume a session's events at a time.
+        if self.is_running(conversation_id).await {
+            let result = error_result(format!(
+                "Session {conversation_id} is running a turn; wait for it to finish or interrupt it first"
+            ));
+            self.outgoing.send_response(request_id, result).await;
+            return;
+        }
+
+        let outgoing = self.outgoing.clone();
+        let running_requests_id_to_codex_uuid = self.running_requests_id_to_codex_uuid.clone();
+        let session_registry = self.session_registry.clone();
+        tokio::spawn(async move {
+            crate::codex_tool_runner::run_codex_tool_session_op(
+                codex,
+                outgoing,
+                request_id,
+                Op::Compact {
+                    instructions: params.instructions,
+                },
+                running_requests_id_to_codex_uuid,
+                conversation_id,
+                session_registry,
+                progress,
+            )
+            .await;
+        });
+    }
+
+    /// Looks up the session a session-control tool was called for.
+    async fn find_session(
+        &self,
+        conversation_id: &str,
+    ) -> Result<(ConversationId, Arc<CodexConversation>), CallToolResult> {
+        let conversation_id = ConversationId::from_string(conversation_id)
+            .map_err(|e| error_result(format!("Failed to parse conversation_id: {e}")))?;
+        let codex = self
+            .conversation_manager
+            .get_conversation(conversation_id)
+            .await
+            .map_err(|_| {
+                error_result(format!(
+                    "Session not found for conversation_id: {conversation_id}"
+                ))
+            })?;
+        Ok((conversation_id, codex))
+    }
+
+    async fn is_running(&self, conversation_id: ConversationId) -> bool {
+        self.running_requests_id_to_codex_uuid
+            .lock()
+            .await
+            .values()
+            .any(|id| *id == conversation_id)
+    }
+
     fn handle_set_level(
         &self,
         params: <mcp_types::SetLevelRequest as mcp_types::ModelContextProtocolRequest>::Params,
@@ -560,11 +975,12 @@ impl MessageProcessor {
             RequestId::Integer(i) => i.to_string(),
         };
 
-        // Obtain the conversation id while holding the first lock, then release.
+        // Unregister the request before interrupting so that the runner, on
+        // seeing the aborted turn, does not respond to a cancelled request.
         let conversation_id = {
-            let map_guard = self.running_requests_id_to_codex_uuid.lock().await;
-            match map_guard.get(&request_id) {
-                Some(id) => *id,
+            let mut map_guard = self.running_requests_id_to_codex_uuid.lock().await;
+            match map_guard.remove(&request_id) {
+                Some(id) => id,
                 None => {
                     tracing::warn!("Session not found for request_id: {}", request_id_string);
                     return;
@@ -595,13 +1011,7 @@ impl MessageProcessor {
             .await;
         if let Err(e) = err {
             tracing::error!("Failed to submit interrupt to Codex: {e}");
-            return;
         }
-        // unregister the id so we don't keep it in the map
-        self.running_requests_id_to_codex_uuid
-            .lock()
-            .await
-            .remove(&request_id);
     }
 
     fn handle_progress_notification(
@@ -649,3 +1059,33 @@ impl MessageProcessor {
         tracing::info!("notifications/message -> params: {:?}", params);
     }
 }
+
+/// Deserializes the arguments of a session-control tool. Tools without
+/// required arguments may be called without any.
+fn parse_tool_arguments<T: DeserializeOwned>(
+    tool_name: &str,
+    arguments: Option<serde_json::Value>,
+) -> Result<T, CallToolResult> {
+    let arguments = arguments.unwrap_or_else(|| json!({}));
+    serde_json::from_value(arguments)
+        .map_err(|e| error_result(format!("Failed to parse arguments for {tool_name}: {e}")))
+}
+
+fn text_result(text: String) -> CallToolResult {
+    CallToolResult {
+        content: vec![ContentBlock::TextContent(TextContent {
+            r#type: "text".to_string(),
+            text,
+            annotations: None,
+        })],
+        is_error: None,
+        structured_content: None,
+    }
+}
+
+fn error_result(text: String) -> CallToolResult {
+    CallToolResult {
+        is_error: Some(true),
+        ..text_result(text)
+    }
+}
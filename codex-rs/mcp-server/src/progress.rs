@@ -0,0 +1,197 @@
+//! `notifications/progress` for tool calls whose request carried a
+//! `_meta.progressToken`, so that callers can follow a long-running Codex
+//! task step by step instead of waiting for the final `tools/call` response.
+
+use std::sync::Arc;
+
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExecCommandBeginEvent;
+use codex_core::protocol::ExecCommandEndEvent;
+use codex_core::protocol::McpToolCallBeginEvent;
+use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::WebSearchEndEvent;
+use codex_protocol::plan_tool::StepStatus;
+use codex_protocol::plan_tool::UpdatePlanArgs;
+use mcp_types::JSONRPCRequest;
+use mcp_types::ModelContextProtocolNotification;
+use mcp_types::ProgressNotification;
+use mcp_types::ProgressNotificationParams;
+use mcp_types::ProgressToken;
+
+use crate::outgoing_message::OutgoingMessageSender;
+use crate::outgoing_message::OutgoingNotification;
+
+/// Returns the `progressToken` the client attached to `request`, if any.
+pub(crate) fn progress_token(request: &JSONRPCRequest) -> Option<ProgressToken> {
+    let token = request
+        .params
+        .as_ref()?
+        .get("_meta")?
+        .get("progressToken")?;
+    serde_json::from_value(token.clone()).ok()
+}
+
+/// Reports the steps of a single tool call against the caller's token.
+pub(crate) struct ProgressReporter {
+    outgoing: Arc<OutgoingMessageSender>,
+    token: ProgressToken,
+    steps: u64,
+}
+
+impl ProgressReporter {
+    pub(crate) fn new(outgoing: Arc<OutgoingMessageSender>, token: ProgressToken) -> Self {
+        Self {
+            outgoing,
+            token,
+            steps: 0,
+        }
+    }
+
+    /// Sends a progress notification if `msg` is a step worth reporting. The
+    /// total number of steps is unknown, so `progress` simply counts them.
+    pub(crate) async fn report(&mut self, msg: &EventMsg) {
+        let Some(message) = progress_message(msg) else {
+            return;
+        };
+        self.steps += 1;
+        let params = ProgressNotificationParams {
+            message: Some(message),
+            progress: self.steps as f64,
+            progress_token: self.token.clone(),
+            total: None,
+        };
+        self.outgoing
+            .send_notification(OutgoingNotification {
+                method: ProgressNotification::METHOD.to_string(),
+                params: serde_json::to_value(params).ok(),
+            })
+            .await;
+    }
+}
+
+fn progress_message(msg: &EventMsg) -> Option<String> {
+    match msg {
+        EventMsg::ExecCommandBegin(ExecCommandBeginEvent { command, .. }) => {
+            Some(format!("Running `{}`", display_command(command)))
+        }
+        EventMsg::ExecCommandEnd(ExecCommandEndEvent { exit_code, .. }) if *exit_code != 0 => {
+            Some(format!("Command exited with code {exit_code}"))
+        }
+        EventMsg::PatchApplyBegin(PatchApplyBeginEvent { changes, .. }) => {
+            let mut paths: Vec<String> = changes
+                .keys()
+                .map(|path| path.display().to_string())
+                .collect();
+            paths.sort();
+            Some(format!("Applying patch to {}", paths.join(", ")))
+        }
+        EventMsg::PatchApplyEnd(PatchApplyEndEvent { success: false, .. }) => {
+            Some("Failed to apply patch".to_string())
+        }
+        EventMsg::PlanUpdate(UpdatePlanArgs { plan, .. }) => {
+            let completed = plan
+                .iter()
+                .filter(|item| matches!(item.status, StepStatus::Completed))
+                .count();
+            let mut message = format!("Plan updated: {completed}/{} steps completed", plan.len());
+            if let Some(current) = plan
+                .iter()
+                .find(|item| matches!(item.status, StepStatus::InProgress))
+            {
+                message.push_str(&format!("; working on: {}", current.step));
+            }
+            Some(message)
+        }
+        EventMsg::McpToolCallBegin(McpToolCallBeginEvent { invocation, .. }) => {
+            Some(format!("Calling {}.{}", invocation.server, invocation.tool))
+        }
+        EventMsg::WebSearchEnd(WebSearchEndEvent { query, .. }) => {
+            Some(format!("Searched the web for \"{query}\""))
+        }
+        _ => None,
+    }
+}
+
+/// Shows the script of `bash -lc <script>` rather than the wrapper.
+fn display_command(command: &[String]) -> String {
+    match command {
+        [shell, flag, script] if (shell == "bash" || shell == "sh") && flag.starts_with('-') => {
+            script.clone()
+        }
+        _ => shlex::try_join(command.iter().map(String::as_str))
+            .unwrap_or_else(|_| command.join(" ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::plan_tool::PlanItemArg;
+    use mcp_types::JSONRPC_VERSION;
+    use mcp_types::RequestId;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn progress_token_is_read_from_request_meta() {
+        let mut request = JSONRPCRequest {
+            jsonrpc: JSONRPC_VERSION.into(),
+            id: RequestId::Integer(1),
+            method: "tools/call".to_string(),
+            params: Some(json!({
+                "name": "codex",
+                "arguments": {},
+                "_meta": { "progressToken": "abc" },
+            })),
+        };
+        assert_eq!(
+            progress_token(&request),
+            Some(ProgressToken::String("abc".to_string()))
+        );
+
+        request.params = Some(json!({ "name": "codex", "arguments": {} }));
+        assert_eq!(progress_token(&request), None);
+    }
+
+    #[test]
+    fn progress_messages_describe_turn_steps() {
+        let begin = EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+            call_id: "call-1".to_string(),
+            command: vec!["bash".into(), "-lc".into(), "cargo test -p foo".into()],
+            cwd: "/tmp".into(),
+            parsed_cmd: Vec::new(),
+        });
+        assert_eq!(
+            progress_message(&begin),
+            Some("Running `cargo test -p foo`".to_string())
+        );
+
+        let plan = EventMsg::PlanUpdate(UpdatePlanArgs {
+            explanation: None,
+            plan: vec![
+                PlanItemArg {
+                    step: "Read the code".to_string(),
+                    status: StepStatus::Completed,
+                },
+                PlanItemArg {
+                    step: "Fix the bug".to_string(),
+                    status: StepStatus::InProgress,
+                },
+                PlanItemArg {
+                    step: "Add a test".to_string(),
+                    status: StepStatus::Pending,
+                },
+            ],
+        });
+        assert_eq!(
+            progress_message(&plan),
+            Some("Plan updated: 1/3 steps completed; working on: Fix the bug".to_string())
+        );
+
+        let agent_message = EventMsg::AgentMessage(codex_core::protocol::AgentMessageEvent {
+            message: "hi".to_string(),
+        });
+        assert_eq!(progress_message(&agent_message), None);
+    }
+}
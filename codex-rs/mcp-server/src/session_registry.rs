@@ -0,0 +1,235 @@
+//! Book-keeping for the Codex sessions started through this server. It backs
+//! the session-control tools (`codex-list-sessions`, `codex-diff`, ...) and
+//! exposes each session's rollout as a `codex://sessions/<id>` resource.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use codex_core::git_info::diff_between;
+use codex_core::git_info::snapshot_working_tree;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::ConversationId;
+use mcp_types::ModelContextProtocolNotification;
+use mcp_types::ResourceListChangedNotification;
+use mcp_types::ResourceUpdatedNotification;
+use mcp_types::ResourceUpdatedNotificationParams;
+use tokio::sync::Mutex;
+
+use crate::outgoing_message::OutgoingMessageSender;
+use crate::outgoing_message::OutgoingNotification;
+
+const SESSION_URI_PREFIX: &str = "codex://sessions/";
+
+/// URI template advertised through `resources/templates/list`.
+pub(crate) const SESSION_URI_TEMPLATE: &str = "codex://sessions/{conversationId}";
+
+/// Rollouts are stored as one JSON object per line.
+pub(crate) const ROLLOUT_MIME_TYPE: &str = "application/jsonl";
+
+pub(crate) fn session_uri(conversation_id: &ConversationId) -> String {
+    format!("{SESSION_URI_PREFIX}{conversation_id}")
+}
+
+pub(crate) fn parse_session_uri(uri: &str) -> Option<ConversationId> {
+    let id = uri.strip_prefix(SESSION_URI_PREFIX)?;
+    ConversationId::from_string(id).ok()
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SessionInfo {
+    pub model: String,
+    pub cwd: PathBuf,
+    pub rollout_path: PathBuf,
+    /// Snapshot of the working tree under `cwd` when the session started,
+    /// uncommitted and untracked files included, or `None` if `cwd` is not
+    /// inside a git repository.
+    pub base_snapshot: Option<String>,
+    /// Cumulative diff of the most recent turn, as reported by `TurnDiff`.
+    pub last_turn_diff: Option<String>,
+    /// The `workspace-write` policy built from the session's config, so that
+    /// `codex-set-policy` keeps its writable roots and network settings.
+    pub workspace_write_policy: SandboxPolicy,
+    started: u64,
+}
+
+impl SessionInfo {
+    pub(crate) async fn new(
+        model: String,
+        cwd: PathBuf,
+        rollout_path: PathBuf,
+        workspace_write_policy: SandboxPolicy,
+    ) -> Self {
+        let base_snapshot = snapshot_working_tree(&cwd).await;
+        Self {
+            model,
+            cwd,
+            rollout_path,
+            base_snapshot,
+            last_turn_diff: None,
+            workspace_write_policy,
+            started: 0,
+        }
+    }
+
+    /// Everything the session changed: the working tree against the snapshot
+    /// taken when the session started, so changes that were already there are
+    /// left out, otherwise the diff of its most recent turn.
+    pub(crate) async fn diff(&self) -> Option<String> {
+        match &self.base_snapshot {
+            Some(base) => {
+                let current = snapshot_working_tree(&self.cwd).await?;
+                diff_between(&self.cwd, base, &current).await
+            }
+            None => self.last_turn_diff.clone(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct SessionRegistry {
+    sessions: Mutex<HashMap<ConversationId, SessionInfo>>,
+    subscriptions: Mutex<HashSet<String>>,
+}
+
+impl SessionRegistry {
+    /// Records a newly started session and tells the client that the resource
+    /// list changed.
+    pub(crate) async fn register(
+        &self,
+        conversation_id: ConversationId,
+        mut info: SessionInfo,
+        outgoing: &OutgoingMessageSender,
+    ) {
+        {
+            let mut sessions = self.sessions.lock().await;
+            info.started = sessions.len() as u64;
+            sessions.insert(conversation_id, info);
+        }
+        outgoing
+            .send_notification(OutgoingNotification {
+                method: ResourceListChangedNotification::METHOD.to_string(),
+                params: None,
+            })
+            .await;
+    }
+
+    pub(crate) async fn get(&self, conversation_id: &ConversationId) -> Option<SessionInfo> {
+        self.sessions.lock().await.get(conversation_id).cloned()
+    }
+
+    /// All sessions in the order they were started.
+    pub(crate) async fn list(&self) -> Vec<(ConversationId, SessionInfo)> {
+        let mut sessions: Vec<_> = self
+            .sessions
+            .lock()
+            .await
+            .iter()
+            .map(|(id, info)| (*id, info.clone()))
+            .collect();
+        sessions.sort_by_key(|(_, info)| info.started);
+        sessions
+    }
+
+    pub(crate) async fn record_turn_diff(&self, conversation_id: &ConversationId, diff: String) {
+        if let Some(info) = self.sessions.lock().await.get_mut(conversation_id) {
+            info.last_turn_diff = Some(diff);
+        }
+    }
+
+    pub(crate) async fn subscribe(&self, uri: String) {
+        self.subscriptions.lock().await.insert(uri);
+    }
+
+    pub(crate) async fn unsubscribe(&self, uri: &str) {
+        self.subscriptions.lock().await.remove(uri);
+    }
+
+    /// Sends `notifications/resources/updated` for the session's rollout if
+    /// the client subscribed to it.
+    pub(crate) async fn notify_updated(
+        &self,
+        conversation_id: &ConversationId,
+        outgoing: &OutgoingMessageSender,
+    ) {
+        let uri = session_uri(conversation_id);
+        if !self.subscriptions.lock().await.contains(&uri) {
+            return;
+        }
+        let params = ResourceUpdatedNotificationParams { uri };
+        outgoing
+            .send_notification(OutgoingNotification {
+                method: ResourceUpdatedNotification::METHOD.to_string(),
+                params: serde_json::to_value(params).ok(),
+            })
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(repo: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .envs([
+                ("GIT_CONFIG_GLOBAL", "/dev/null"),
+                ("GIT_CONFIG_NOSYSTEM", "1"),
+            ])
+            .args(args)
+            .current_dir(repo)
+            .status()
+            .expect("git should run");
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn session_uri_round_trips() {
+        let conversation_id = ConversationId::new();
+        let uri = session_uri(&conversation_id);
+        assert_eq!(uri, format!("codex://sessions/{conversation_id}"));
+        assert_eq!(parse_session_uri(&uri), Some(conversation_id));
+        assert_eq!(parse_session_uri("codex://sessions/not-a-uuid"), None);
+        assert_eq!(
+            parse_session_uri(&format!("file:///{conversation_id}")),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn diff_leaves_out_changes_made_before_the_session() {
+        let temp = TempDir::new().expect("tempdir");
+        let repo = temp.path();
+        git(repo, &["init", "--quiet"]);
+        git(repo, &["config", "user.name", "Test User"]);
+        git(repo, &["config", "user.email", "test@example.com"]);
+        std::fs::write(repo.join("committed.txt"), "one\n").expect("write");
+        std::fs::write(repo.join("edited.txt"), "one\n").expect("write");
+        git(repo, &["add", "."]);
+        git(repo, &["commit", "--quiet", "-m", "initial"]);
+
+        // The tree is already dirty when the session starts.
+        std::fs::write(repo.join("committed.txt"), "one\nuser\n").expect("write");
+        std::fs::write(repo.join("untracked.txt"), "user\n").expect("write");
+
+        let info = SessionInfo::new(
+            "o3".to_string(),
+            repo.to_path_buf(),
+            repo.join("rollout.jsonl"),
+            SandboxPolicy::new_workspace_write_policy(),
+        )
+        .await;
+        assert!(info.base_snapshot.is_some());
+
+        std::fs::write(repo.join("edited.txt"), "one\nsession\n").expect("write");
+
+        let diff = info.diff().await.expect("diff");
+        assert!(diff.contains("+session"), "{diff}");
+        assert!(!diff.contains("committed.txt"), "{diff}");
+        assert!(!diff.contains("untracked.txt"), "{diff}");
+    }
+}
@@ -0,0 +1,183 @@
+//! Arguments and `Tool` definitions for the tools that control a running
+//! Codex session: `codex-list-sessions`, `codex-interrupt`, `codex-diff`,
+//! `codex-set-policy` and `codex-compact`.
+
+use mcp_types::Tool;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::codex_tool_config::CodexToolCallApprovalPolicy;
+use crate::codex_tool_config::CodexToolCallSandboxMode;
+use crate::codex_tool_config::create_tool_input_schema;
+
+pub(crate) const LIST_SESSIONS_TOOL_NAME: &str = "codex-list-sessions";
+pub(crate) const INTERRUPT_TOOL_NAME: &str = "codex-interrupt";
+pub(crate) const DIFF_TOOL_NAME: &str = "codex-diff";
+pub(crate) const SET_POLICY_TOOL_NAME: &str = "codex-set-policy";
+pub(crate) const COMPACT_TOOL_NAME: &str = "codex-compact";
+
+/// `codex-list-sessions` takes no arguments.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct CodexListSessionsParam {}
+
+/// Arguments for the tools that only need to know which session to act on.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexSessionParam {
+    /// The conversation id for this Codex session.
+    pub conversation_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexSetPolicyParam {
+    /// The conversation id for this Codex session.
+    pub conversation_id: String,
+
+    /// Approval policy for shell commands generated by the model:
+    /// `untrusted`, `on-failure`, `on-request`, `never`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_policy: Option<CodexToolCallApprovalPolicy>,
+
+    /// Sandbox mode: `read-only`, `workspace-write`, or `danger-full-access`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<CodexToolCallSandboxMode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexCompactParam {
+    /// The conversation id for this Codex session.
+    pub conversation_id: String,
+
+    /// Extra guidance for what the summary should preserve.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+}
+
+pub(crate) fn create_tool_for_list_sessions() -> Tool {
+    Tool {
+        name: LIST_SESSIONS_TOOL_NAME.to_string(),
+        title: Some("List Codex Sessions".to_string()),
+        input_schema: create_tool_input_schema::<CodexListSessionsParam>(),
+        output_schema: None,
+        description: Some(
+            "List the Codex sessions started by this server with their model, working directory, rollout resource and whether a turn is running."
+                .to_string(),
+        ),
+        annotations: None,
+    }
+}
+
+pub(crate) fn create_tool_for_interrupt() -> Tool {
+    Tool {
+        name: INTERRUPT_TOOL_NAME.to_string(),
+        title: Some("Interrupt Codex Session".to_string()),
+        input_schema: create_tool_input_schema::<CodexSessionParam>(),
+        output_schema: None,
+        description: Some(
+            "Interrupt the running turn of a Codex session. The pending `codex` or `codex-reply` call returns once the turn is aborted."
+                .to_string(),
+        ),
+        annotations: None,
+    }
+}
+
+pub(crate) fn create_tool_for_diff() -> Tool {
+    Tool {
+        name: DIFF_TOOL_NAME.to_string(),
+        title: Some("Codex Session Diff".to_string()),
+        input_schema: create_tool_input_schema::<CodexSessionParam>(),
+        output_schema: None,
+        description: Some(
+            "Return the unified diff of the changes made in a Codex session's working directory since the session started."
+                .to_string(),
+        ),
+        annotations: None,
+    }
+}
+
+pub(crate) fn create_tool_for_set_policy() -> Tool {
+    Tool {
+        name: SET_POLICY_TOOL_NAME.to_string(),
+        title: Some("Set Codex Session Policy".to_string()),
+        input_schema: create_tool_input_schema::<CodexSetPolicyParam>(),
+        output_schema: None,
+        description: Some(
+            "Change the approval policy and/or sandbox mode of a Codex session. Takes effect from the next turn."
+                .to_string(),
+        ),
+        annotations: None,
+    }
+}
+
+pub(crate) fn create_tool_for_compact() -> Tool {
+    Tool {
+        name: COMPACT_TOOL_NAME.to_string(),
+        title: Some("Compact Codex Session".to_string()),
+        input_schema: create_tool_input_schema::<CodexCompactParam>(),
+        output_schema: None,
+        description: Some(
+            "Summarize a Codex session's history to free up its context window. Returns once the summary is recorded."
+                .to_string(),
+        ),
+        annotations: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn verify_list_sessions_tool_json_schema() {
+        let tool = create_tool_for_list_sessions();
+        let tool_json = serde_json::to_value(&tool).expect("tool serializes");
+        assert_eq!(tool_json["name"], "codex-list-sessions");
+        assert_eq!(
+            tool_json["inputSchema"],
+            serde_json::json!({ "type": "object" })
+        );
+    }
+
+    #[test]
+    fn verify_set_policy_tool_json_schema() {
+        let tool = create_tool_for_set_policy();
+        let tool_json = serde_json::to_value(&tool).expect("tool serializes");
+        let expected_input_schema = serde_json::json!({
+          "properties": {
+            "approvalPolicy": {
+              "description": "Approval policy for shell commands generated by the model: `untrusted`, `on-failure`, `on-request`, `never`.",
+              "enum": [
+                "untrusted",
+                "on-failure",
+                "on-request",
+                "never"
+              ],
+              "type": "string"
+            },
+            "conversationId": {
+              "description": "The conversation id for this Codex session.",
+              "type": "string"
+            },
+            "sandbox": {
+              "description": "Sandbox mode: `read-only`, `workspace-write`, or `danger-full-access`.",
+              "enum": [
+                "read-only",
+                "workspace-write",
+                "danger-full-access"
+              ],
+              "type": "string"
+            },
+          },
+          "required": [
+            "conversationId",
+          ],
+          "type": "object",
+        });
+        assert_eq!(tool_json["name"], "codex-set-policy");
+        assert_eq!(tool_json["inputSchema"], expected_input_schema);
+    }
+}
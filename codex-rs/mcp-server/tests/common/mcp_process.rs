@@ -14,6 +14,7 @@ use assert_cmd::prelude::*;
 use codex_mcp_server::CodexToolCallParam;
 
 use mcp_types::CallToolRequestParams;
+use mcp_types::CancelledNotificationParams;
 use mcp_types::ClientCapabilities;
 use mcp_types::Implementation;
 use mcp_types::InitializeRequestParams;
@@ -24,7 +25,9 @@ use mcp_types::JSONRPCRequest;
 use mcp_types::JSONRPCResponse;
 use mcp_types::ModelContextProtocolNotification;
 use mcp_types::ModelContextProtocolRequest;
+use mcp_types::ReadResourceRequestParams;
 use mcp_types::RequestId;
+use mcp_types::SubscribeRequestParams;
 use pretty_assertions::assert_eq;
 use serde_json::json;
 use std::process::Command as StdCommand;
@@ -156,6 +159,10 @@ impl McpProcess {
                 id: RequestId::Integer(request_id),
                 result: json!({
                     "capabilities": {
+                        "resources": {
+                            "listChanged": true,
+                            "subscribe": true
+                        },
                         "tools": {
                             "listChanged": true
                         },
@@ -200,6 +207,80 @@ impl McpProcess {
         .await
     }
 
+    /// Like [`Self::send_codex_tool_call`], but asks for progress
+    /// notifications tagged with `progress_token`.
+    pub async fn send_codex_tool_call_with_progress_token(
+        &mut self,
+        params: CodexToolCallParam,
+        progress_token: &str,
+    ) -> anyhow::Result<i64> {
+        let params = json!({
+            "name": "codex",
+            "arguments": serde_json::to_value(params)?,
+            "_meta": { "progressToken": progress_token },
+        });
+        self.send_request(mcp_types::CallToolRequest::METHOD, Some(params))
+            .await
+    }
+
+    /// Cancels the request `request_id` with `notifications/cancelled`.
+    pub async fn send_cancelled_notification(&mut self, request_id: i64) -> anyhow::Result<()> {
+        let params = CancelledNotificationParams {
+            reason: None,
+            request_id: RequestId::Integer(request_id),
+        };
+        self.send_jsonrpc_message(JSONRPCMessage::Notification(JSONRPCNotification {
+            jsonrpc: JSONRPC_VERSION.into(),
+            method: mcp_types::CancelledNotification::METHOD.into(),
+            params: Some(serde_json::to_value(params)?),
+        }))
+        .await
+    }
+
+    /// Calls the tool `name`, e.g. one of the session-control tools.
+    pub async fn send_tool_call(
+        &mut self,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> anyhow::Result<i64> {
+        let params = CallToolRequestParams {
+            name: name.to_string(),
+            arguments: Some(arguments),
+        };
+        self.send_request(
+            mcp_types::CallToolRequest::METHOD,
+            Some(serde_json::to_value(params)?),
+        )
+        .await
+    }
+
+    pub async fn send_list_resources_request(&mut self) -> anyhow::Result<i64> {
+        self.send_request(mcp_types::ListResourcesRequest::METHOD, None)
+            .await
+    }
+
+    pub async fn send_read_resource_request(&mut self, uri: &str) -> anyhow::Result<i64> {
+        let params = ReadResourceRequestParams {
+            uri: uri.to_string(),
+        };
+        self.send_request(
+            mcp_types::ReadResourceRequest::METHOD,
+            Some(serde_json::to_value(params)?),
+        )
+        .await
+    }
+
+    pub async fn send_subscribe_request(&mut self, uri: &str) -> anyhow::Result<i64> {
+        let params = SubscribeRequestParams {
+            uri: uri.to_string(),
+        };
+        self.send_request(
+            mcp_types::SubscribeRequest::METHOD,
+            Some(serde_json::to_value(params)?),
+        )
+        .await
+    }
+
     async fn send_request(
         &mut self,
         method: &str,
@@ -297,6 +378,35 @@ impl McpProcess {
         }
     }
 
+    /// Reads notifications until one with the given `method` is observed.
+    pub async fn read_stream_until_notification_message(
+        &mut self,
+        method: &str,
+    ) -> anyhow::Result<JSONRPCNotification> {
+        eprintln!("in read_stream_until_notification_message({method})");
+
+        loop {
+            let message = self.read_jsonrpc_message().await?;
+            match message {
+                JSONRPCMessage::Notification(notification) => {
+                    if notification.method == method {
+                        return Ok(notification);
+                    }
+                    eprintln!("ignoring notification: {notification:?}");
+                }
+                JSONRPCMessage::Request(_) => {
+                    anyhow::bail!("unexpected JSONRPCMessage::Request: {message:?}");
+                }
+                JSONRPCMessage::Error(_) => {
+                    anyhow::bail!("unexpected JSONRPCMessage::Error: {message:?}");
+                }
+                JSONRPCMessage::Response(_) => {
+                    anyhow::bail!("unexpected JSONRPCMessage::Response: {message:?}");
+                }
+            }
+        }
+    }
+
     /// Reads notifications until a legacy TaskComplete event is observed:
     /// Method "codex/event" with params.msg.type == "task_complete".
     pub async fn read_stream_until_legacy_task_complete_notification(
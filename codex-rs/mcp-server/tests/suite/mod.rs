@@ -1 +1,3 @@
 mod codex_tool;
+mod progress;
+mod session_tools;
@@ -0,0 +1,164 @@
+use codex_mcp_server::CodexToolCallParam;
+use core_test_support::skip_if_no_network;
+use mcp_test_support::McpProcess;
+use mcp_test_support::create_final_assistant_message_sse_response;
+use mcp_test_support::create_full_access_config_toml;
+use mcp_test_support::create_mock_chat_completions_server;
+use mcp_test_support::create_shell_sse_response;
+use mcp_test_support::to_response;
+use mcp_types::CallToolResult;
+use mcp_types::RequestId;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::time::timeout;
+use wiremock::MockServer;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(20);
+
+/// A `codex` call made with a progress token reports each command it runs.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn codex_tool_reports_progress_for_commands() {
+    skip_if_no_network!();
+
+    if let Err(err) = codex_tool_reports_progress_for_commands_impl().await {
+        panic!("failure: {err}");
+    }
+}
+
+async fn codex_tool_reports_progress_for_commands_impl() -> anyhow::Result<()> {
+    let cwd = TempDir::new()?;
+    let (mut mcp_process, _server, _codex_home) = start_mcp_process(vec![
+        create_shell_sse_response(
+            vec!["echo".to_string(), "hello".to_string()],
+            Some(cwd.path()),
+            Some(5_000),
+            "call1234",
+        )?,
+        create_final_assistant_message_sse_response("Done!")?,
+    ])
+    .await?;
+
+    let codex_request_id = mcp_process
+        .send_codex_tool_call_with_progress_token(
+            CodexToolCallParam {
+                cwd: Some(cwd.path().to_string_lossy().to_string()),
+                prompt: "say hello".to_string(),
+                ..Default::default()
+            },
+            "progress-1",
+        )
+        .await?;
+
+    let progress = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp_process.read_stream_until_notification_message("notifications/progress"),
+    )
+    .await??;
+    assert_eq!(
+        progress.params,
+        Some(json!({
+            "progressToken": "progress-1",
+            "progress": 1.0,
+            "message": "Running `echo hello`",
+        }))
+    );
+
+    let codex_response: CallToolResult = to_response(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp_process.read_stream_until_response_message(RequestId::Integer(codex_request_id)),
+        )
+        .await??,
+    )?;
+    assert_eq!(codex_response.is_error, None);
+    Ok(())
+}
+
+/// Cancelling a `codex` call interrupts the running command and leaves the
+/// session idle, ready for the next call.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn cancelled_codex_tool_call_interrupts_the_session() {
+    skip_if_no_network!();
+
+    if let Err(err) = cancelled_codex_tool_call_interrupts_the_session_impl().await {
+        panic!("failure: {err}");
+    }
+}
+
+async fn cancelled_codex_tool_call_interrupts_the_session_impl() -> anyhow::Result<()> {
+    let cwd = TempDir::new()?;
+    let (mut mcp_process, _server, _codex_home) =
+        start_mcp_process(vec![create_shell_sse_response(
+            vec!["sleep".to_string(), "60".to_string()],
+            Some(cwd.path()),
+            Some(120_000),
+            "call1234",
+        )?])
+        .await?;
+
+    let codex_request_id = mcp_process
+        .send_codex_tool_call_with_progress_token(
+            CodexToolCallParam {
+                cwd: Some(cwd.path().to_string_lossy().to_string()),
+                prompt: "wait a minute".to_string(),
+                ..Default::default()
+            },
+            "progress-1",
+        )
+        .await?;
+    // Cancel once the command is running.
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp_process.read_stream_until_notification_message("notifications/progress"),
+    )
+    .await??;
+    mcp_process
+        .send_cancelled_notification(codex_request_id)
+        .await?;
+
+    timeout(DEFAULT_READ_TIMEOUT, async {
+        loop {
+            let event = mcp_process
+                .read_stream_until_notification_message("codex/event")
+                .await?;
+            let is_turn_aborted = event
+                .params
+                .as_ref()
+                .and_then(|params| params["msg"]["type"].as_str())
+                == Some("turn_aborted");
+            if is_turn_aborted {
+                return anyhow::Ok(());
+            }
+        }
+    })
+    .await??;
+
+    let list_id = mcp_process
+        .send_tool_call("codex-list-sessions", json!({}))
+        .await?;
+    let list: CallToolResult = to_response(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp_process.read_stream_until_response_message(RequestId::Integer(list_id)),
+        )
+        .await??,
+    )?;
+    let status = list
+        .structured_content
+        .as_ref()
+        .map(|content| content["sessions"][0]["status"].clone());
+    assert_eq!(status, Some(json!("idle")));
+    Ok(())
+}
+
+async fn start_mcp_process(
+    responses: Vec<String>,
+) -> anyhow::Result<(McpProcess, MockServer, TempDir)> {
+    let server = create_mock_chat_completions_server(responses).await;
+    let codex_home = TempDir::new()?;
+    create_full_access_config_toml(codex_home.path(), &server.uri())?;
+    let mut mcp_process = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp_process.initialize()).await??;
+    Ok((mcp_process, server, codex_home))
+}
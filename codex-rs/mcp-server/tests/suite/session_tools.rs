@@ -0,0 +1,244 @@
+use std::path::Path;
+use std::process::Command;
+
+use codex_mcp_server::CodexToolCallParam;
+use core_test_support::skip_if_no_network;
+use mcp_test_support::McpProcess;
+use mcp_test_support::create_apply_patch_sse_response;
+use mcp_test_support::create_final_assistant_message_sse_response;
+use mcp_test_support::create_full_access_config_toml;
+use mcp_test_support::create_mock_chat_completions_server;
+use mcp_test_support::to_response;
+use mcp_types::CallToolResult;
+use mcp_types::ContentBlock;
+use mcp_types::ListResourcesResult;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use mcp_types::RequestId;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(20);
+
+/// Drives a session through the session-control tools and the rollout
+/// resource: list it, read its diff, change its policy, subscribe to its
+/// rollout and observe the update when a reply completes.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn session_tools_control_a_running_session() {
+    skip_if_no_network!();
+
+    if let Err(err) = session_tools_control_a_running_session_impl().await {
+        panic!("failure: {err}");
+    }
+}
+
+async fn session_tools_control_a_running_session_impl() -> anyhow::Result<()> {
+    let cwd = TempDir::new()?;
+    let test_file = cwd.path().join("destination_file.txt");
+    std::fs::write(&test_file, "original content\n")?;
+    init_git_repo(cwd.path())?;
+
+    let patch_content = format!(
+        "*** Begin Patch\n*** Update File: {}\n-original content\n+modified content\n*** End Patch",
+        test_file.as_path().to_string_lossy()
+    );
+    let server = create_mock_chat_completions_server(vec![
+        create_apply_patch_sse_response(&patch_content, "call1234")?,
+        create_final_assistant_message_sse_response("Patched!")?,
+        create_final_assistant_message_sse_response("You're welcome!")?,
+    ])
+    .await;
+    let codex_home = TempDir::new()?;
+    create_full_access_config_toml(codex_home.path(), &server.uri())?;
+    let mut mcp_process = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp_process.initialize()).await??;
+
+    let codex_request_id = mcp_process
+        .send_codex_tool_call(CodexToolCallParam {
+            cwd: Some(cwd.path().to_string_lossy().to_string()),
+            prompt: "please modify the test file".to_string(),
+            ..Default::default()
+        })
+        .await?;
+    let codex_response: CallToolResult = to_response(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp_process.read_stream_until_response_message(RequestId::Integer(codex_request_id)),
+        )
+        .await??,
+    )?;
+    assert_eq!(text(&codex_response), "Patched!");
+
+    // The session is listed, idle, with its rollout resource.
+    let list_id = mcp_process
+        .send_tool_call("codex-list-sessions", json!({}))
+        .await?;
+    let list: CallToolResult = to_response(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp_process.read_stream_until_response_message(RequestId::Integer(list_id)),
+        )
+        .await??,
+    )?;
+    let sessions = list
+        .structured_content
+        .as_ref()
+        .and_then(|content| content["sessions"].as_array())
+        .cloned()
+        .unwrap_or_default();
+    assert_eq!(sessions.len(), 1);
+    let session = &sessions[0];
+    assert_eq!(session["status"], "idle");
+    assert_eq!(session["model"], "mock-model");
+    let conversation_id = session["conversationId"].as_str().unwrap_or_default();
+    let uri = format!("codex://sessions/{conversation_id}");
+    assert_eq!(session["uri"], uri);
+
+    let resources_id = mcp_process.send_list_resources_request().await?;
+    let resources: ListResourcesResult = to_response(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp_process.read_stream_until_response_message(RequestId::Integer(resources_id)),
+        )
+        .await??,
+    )?;
+    let uris: Vec<&str> = resources.resources.iter().map(|r| r.uri.as_str()).collect();
+    assert_eq!(uris, vec![uri.as_str()]);
+
+    // The diff covers the patch applied during the first turn.
+    let diff_id = mcp_process
+        .send_tool_call("codex-diff", json!({ "conversationId": conversation_id }))
+        .await?;
+    let diff: CallToolResult = to_response(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp_process.read_stream_until_response_message(RequestId::Integer(diff_id)),
+        )
+        .await??,
+    )?;
+    assert_eq!(diff.is_error, None);
+    let diff_text = text(&diff);
+    assert!(
+        diff_text.contains("-original content\n+modified content"),
+        "unexpected diff: {diff_text}"
+    );
+
+    let policy_id = mcp_process
+        .send_tool_call(
+            "codex-set-policy",
+            json!({
+                "conversationId": conversation_id,
+                "approvalPolicy": "on-request",
+                "sandbox": "read-only",
+            }),
+        )
+        .await?;
+    let policy: CallToolResult = to_response(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp_process.read_stream_until_response_message(RequestId::Integer(policy_id)),
+        )
+        .await??,
+    )?;
+    assert_eq!(policy.is_error, None);
+
+    let interrupt_id = mcp_process
+        .send_tool_call(
+            "codex-interrupt",
+            json!({ "conversationId": conversation_id }),
+        )
+        .await?;
+    let interrupt: CallToolResult = to_response(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp_process.read_stream_until_response_message(RequestId::Integer(interrupt_id)),
+        )
+        .await??,
+    )?;
+    assert_eq!(
+        text(&interrupt),
+        format!("No turn is running in session {conversation_id}")
+    );
+
+    // Subscribing to the rollout reports the next completed turn.
+    let subscribe_id = mcp_process.send_subscribe_request(&uri).await?;
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp_process.read_stream_until_response_message(RequestId::Integer(subscribe_id)),
+    )
+    .await??;
+
+    let reply_id = mcp_process
+        .send_tool_call(
+            "codex-reply",
+            json!({ "conversationId": conversation_id, "prompt": "thank you" }),
+        )
+        .await?;
+    let reply: CallToolResult = to_response(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp_process.read_stream_until_response_message(RequestId::Integer(reply_id)),
+        )
+        .await??,
+    )?;
+    assert_eq!(text(&reply), "You're welcome!");
+    let updated = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp_process.read_stream_until_notification_message("notifications/resources/updated"),
+    )
+    .await??;
+    assert_eq!(updated.params, Some(json!({ "uri": uri })));
+
+    // The rollout already holds the completed reply.
+    let read_id = mcp_process.send_read_resource_request(&uri).await?;
+    let read: ReadResourceResult = to_response(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp_process.read_stream_until_response_message(RequestId::Integer(read_id)),
+        )
+        .await??,
+    )?;
+    let [ReadResourceResultContents::TextResourceContents(rollout)] = read.contents.as_slice()
+    else {
+        anyhow::bail!("expected a single text resource, got {:?}", read.contents);
+    };
+    assert_eq!(rollout.uri, uri);
+    assert!(rollout.text.contains("thank you"));
+    assert!(rollout.text.contains("You're welcome!"));
+
+    Ok(())
+}
+
+fn text(result: &CallToolResult) -> String {
+    result
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::TextContent(text) => Some(text.text.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn init_git_repo(dir: &Path) -> anyhow::Result<()> {
+    for args in [
+        vec!["init", "--quiet"],
+        vec!["add", "."],
+        vec![
+            "-c",
+            "user.name=Test",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "--quiet",
+            "-m",
+            "initial",
+        ],
+    ] {
+        let status = Command::new("git").args(&args).current_dir(dir).status()?;
+        anyhow::ensure!(status.success(), "git {args:?} failed");
+    }
+    Ok(())
+}
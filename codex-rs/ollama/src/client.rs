@@ -12,6 +12,7 @@ use crate::url::base_url_to_host_root;
 use crate::url::is_openai_compatible_base_url;
 use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
 use codex_core::ModelProviderInfo;
+use codex_core::OllamaModelCapabilities;
 use codex_core::WireApi;
 use codex_core::config::Config;
 
@@ -124,6 +125,55 @@ impl OllamaClient {
         Ok(names)
     }
 
+    /// Return what `model` supports (tool calling, context length, ...) as
+    /// reported by `/api/show`.
+    pub async fn fetch_model_capabilities(
+        &self,
+        model: &str,
+    ) -> io::Result<OllamaModelCapabilities> {
+        let url = format!("{}/api/show", self.host_root.trim_end_matches('/'));
+        let resp = self
+            .client
+            .post(url)
+            .json(&serde_json::json!({"model": model}))
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "failed to show model {model}: HTTP {}",
+                resp.status()
+            )));
+        }
+        let val = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        Ok(OllamaModelCapabilities::from_show_response(&val))
+    }
+
+    /// Load `model` into memory. Large models can take longer to load than
+    /// the stream idle timeout, so this is done up front rather than on the
+    /// first turn.
+    pub async fn load_model(&self, model: &str) -> io::Result<()> {
+        let url = format!("{}/api/generate", self.host_root.trim_end_matches('/'));
+        let resp = self
+            .client
+            .post(url)
+            .json(&serde_json::json!({"model": model, "stream": false}))
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        let status = resp.status();
+        let val = resp.json::<JsonValue>().await.unwrap_or_default();
+        if let Some(err) = val.get("error").and_then(|e| e.as_str()) {
+            return Err(io::Error::other(format!("failed to load {model}: {err}")));
+        }
+        if !status.is_success() {
+            return Err(io::Error::other(format!(
+                "failed to load {model}: HTTP {status}"
+            )));
+        }
+        Ok(())
+    }
+
     /// Start a model pull and emit streaming events. The returned stream ends when
     /// a Success event is observed or the server closes the connection.
     pub async fn pull_model_stream(
@@ -268,6 +318,53 @@ mod tests {
         assert!(models.contains(&"mistral".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_fetch_model_capabilities_and_load_model() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_fetch_model_capabilities_and_load_model",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/show"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_raw(
+                    serde_json::json!({
+                        "capabilities": ["completion", "tools"],
+                        "model_info": {"qwen2.context_length": 32768}
+                    })
+                    .to_string(),
+                    "application/json",
+                ),
+            )
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/generate"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw(
+                serde_json::json!({"done": true, "done_reason": "load"}).to_string(),
+                "application/json",
+            ))
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::from_host_root(server.uri());
+        let caps = client
+            .fetch_model_capabilities("qwen2.5-coder:7b")
+            .await
+            .expect("fetch capabilities");
+        assert!(caps.tools);
+        assert_eq!(caps.context_length, Some(32_768));
+        client
+            .load_model("qwen2.5-coder:7b")
+            .await
+            .expect("load model");
+    }
+
     #[tokio::test]
     async fn test_probe_server_happy_path_openai_compat_and_native() {
         if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
@@ -4,6 +4,7 @@ mod pull;
 mod url;
 
 pub use client::OllamaClient;
+use codex_core::OllamaModelCapabilities;
 use codex_core::config::Config;
 pub use pull::CliProgressReporter;
 pub use pull::PullEvent;
@@ -17,7 +18,11 @@ pub const DEFAULT_OSS_MODEL: &str = "gpt-oss:20b";
 ///
 /// - Ensures a local Ollama server is reachable.
 /// - Checks if the model exists locally and pulls it if missing.
-pub async fn ensure_oss_ready(config: &Config) -> std::io::Result<()> {
+/// - Loads the model into memory and probes its capabilities, if possible.
+pub async fn ensure_oss_ready(
+    config: &Config,
+    reporter: &mut dyn PullProgressReporter,
+) -> std::io::Result<()> {
     // Only download when the requested model is the default OSS model (or when -m is not provided).
     let model = config.model.as_ref();
 
@@ -28,10 +33,7 @@ pub async fn ensure_oss_ready(config: &Config) -> std::io::Result<()> {
     match ollama_client.fetch_models().await {
         Ok(models) => {
             if !models.iter().any(|m| m == model) {
-                let mut reporter = crate::CliProgressReporter::new();
-                ollama_client
-                    .pull_with_reporter(model, &mut reporter)
-                    .await?;
+                ollama_client.pull_with_reporter(model, reporter).await?;
             }
         }
         Err(err) => {
@@ -40,5 +42,37 @@ pub async fn ensure_oss_ready(config: &Config) -> std::io::Result<()> {
         }
     }
 
-    Ok(())
+    reporter.on_event(&PullEvent::Status(format!("Loading model {model}...")))?;
+    let ready = match ollama_client.load_model(model).await {
+        Ok(()) => match ollama_client.fetch_model_capabilities(model).await {
+            Ok(caps) => describe_capabilities(model, &caps),
+            Err(err) => {
+                tracing::warn!("Failed to query capabilities of {model}: {err}.");
+                format!("Model {model} loaded")
+            }
+        },
+        Err(err) => {
+            // Not fatal either: the server loads the model on the first request.
+            tracing::warn!("Failed to preload {model}: {err}.");
+            format!("Could not preload {model}; it will be loaded on the first request")
+        }
+    };
+    reporter.on_event(&PullEvent::Status(ready))?;
+    reporter.on_event(&PullEvent::Success)
+}
+
+fn describe_capabilities(model: &str, caps: &OllamaModelCapabilities) -> String {
+    let mut details = Vec::new();
+    if let Some(context_length) = caps.context_length {
+        details.push(format!("{context_length}-token context"));
+    }
+    if caps.thinking {
+        details.push("thinking".to_string());
+    }
+    if caps.tools {
+        details.push("tools".to_string());
+    } else {
+        details.push("no tool support: commands and edits are unavailable".to_string());
+    }
+    format!("Model {model} loaded ({})", details.join(", "))
 }
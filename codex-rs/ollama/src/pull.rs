@@ -13,7 +13,7 @@ pub enum PullEvent {
         total: Option<u64>,
         completed: Option<u64>,
     },
-    /// The pull finished successfully.
+    /// The pull (or, for `ensure_oss_ready`, the model load) finished successfully.
     Success,
 
     /// Error event with a message.
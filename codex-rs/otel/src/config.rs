@@ -7,7 +7,12 @@ pub struct OtelSettings {
     pub service_name: String,
     pub service_version: String,
     pub codex_home: PathBuf,
+    /// Exporter for log events.
     pub exporter: OtelExporter,
+    /// Exporter for session, turn, model request and tool call spans.
+    pub trace_exporter: OtelExporter,
+    /// Exporter for token, latency and approval metrics.
+    pub metrics_exporter: OtelExporter,
 }
 
 #[derive(Clone, Debug)]
@@ -30,4 +35,9 @@ pub enum OtelExporter {
         headers: HashMap<String, String>,
         protocol: OtelHttpProtocol,
     },
+    /// Appends OTLP/JSON export requests, one per line, to `logs.jsonl`,
+    /// `traces.jsonl` and `metrics.jsonl` inside `path`.
+    OtlpFile {
+        path: PathBuf,
+    },
 }
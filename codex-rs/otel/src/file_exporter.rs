@@ -0,0 +1,153 @@
+//! Exporters that append every batch as one line of OTLP/JSON, the same
+//! encoding an OTLP/HTTP collector accepts, so telemetry can be inspected or
+//! replayed without running a collector.
+
+use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceRequest;
+use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use opentelemetry_proto::transform::common::tonic::ResourceAttributesWithSchema;
+use opentelemetry_proto::transform::logs::tonic::group_logs_by_resource_and_scope;
+use opentelemetry_proto::transform::trace::tonic::group_spans_by_resource_and_scope;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::error::OTelSdkError;
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::logs::LogBatch;
+use opentelemetry_sdk::logs::LogExporter;
+use opentelemetry_sdk::metrics::Temporality;
+use opentelemetry_sdk::metrics::data::ResourceMetrics;
+use opentelemetry_sdk::metrics::exporter::PushMetricExporter;
+use opentelemetry_sdk::trace::SpanData;
+use opentelemetry_sdk::trace::SpanExporter;
+use serde::Serialize;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::future::Future;
+use std::future::ready;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub(crate) const LOGS_FILE: &str = "logs.jsonl";
+pub(crate) const TRACES_FILE: &str = "traces.jsonl";
+pub(crate) const METRICS_FILE: &str = "metrics.jsonl";
+
+#[derive(Debug)]
+struct JsonLinesFile {
+    file: Mutex<File>,
+}
+
+impl JsonLinesFile {
+    fn open(path: &Path) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    fn append<T: Serialize>(&self, value: &T) -> OTelSdkResult {
+        let mut line = serde_json::to_vec(value)
+            .map_err(|e| OTelSdkError::InternalFailure(format!("failed to encode batch: {e}")))?;
+        line.push(b'\n');
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| OTelSdkError::InternalFailure("export file lock poisoned".to_string()))?;
+        file.write_all(&line)
+            .and_then(|()| file.flush())
+            .map_err(|e| OTelSdkError::InternalFailure(format!("failed to write batch: {e}")))
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct FileLogExporter {
+    file: JsonLinesFile,
+    resource: ResourceAttributesWithSchema,
+}
+
+impl FileLogExporter {
+    pub(crate) fn new(dir: &Path) -> std::io::Result<Self> {
+        Ok(Self {
+            file: JsonLinesFile::open(&dir.join(LOGS_FILE))?,
+            resource: ResourceAttributesWithSchema::default(),
+        })
+    }
+}
+
+impl LogExporter for FileLogExporter {
+    fn export(&self, batch: LogBatch<'_>) -> impl Future<Output = OTelSdkResult> + Send {
+        let request = ExportLogsServiceRequest {
+            resource_logs: group_logs_by_resource_and_scope(batch, &self.resource),
+        };
+        ready(self.file.append(&request))
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.resource = resource.into();
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct FileSpanExporter {
+    file: JsonLinesFile,
+    resource: ResourceAttributesWithSchema,
+}
+
+impl FileSpanExporter {
+    pub(crate) fn new(dir: &Path) -> std::io::Result<Self> {
+        Ok(Self {
+            file: JsonLinesFile::open(&dir.join(TRACES_FILE))?,
+            resource: ResourceAttributesWithSchema::default(),
+        })
+    }
+}
+
+impl SpanExporter for FileSpanExporter {
+    fn export(&self, batch: Vec<SpanData>) -> impl Future<Output = OTelSdkResult> + Send {
+        let request = ExportTraceServiceRequest {
+            resource_spans: group_spans_by_resource_and_scope(batch, &self.resource),
+        };
+        ready(self.file.append(&request))
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.resource = resource.into();
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct FileMetricExporter {
+    file: JsonLinesFile,
+}
+
+impl FileMetricExporter {
+    pub(crate) fn new(dir: &Path) -> std::io::Result<Self> {
+        Ok(Self {
+            file: JsonLinesFile::open(&dir.join(METRICS_FILE))?,
+        })
+    }
+}
+
+impl PushMetricExporter for FileMetricExporter {
+    fn export(&self, metrics: &ResourceMetrics) -> impl Future<Output = OTelSdkResult> + Send {
+        ready(
+            self.file
+                .append(&ExportMetricsServiceRequest::from(metrics)),
+        )
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        Ok(())
+    }
+
+    fn shutdown_with_timeout(&self, _timeout: Duration) -> OTelSdkResult {
+        Ok(())
+    }
+
+    fn temporality(&self) -> Temporality {
+        Temporality::Cumulative
+    }
+}
@@ -1,5 +1,8 @@
 pub mod config;
 
+#[cfg(feature = "otel")]
+mod file_exporter;
+
 pub mod otel_event_manager;
 #[cfg(feature = "otel")]
 pub mod otel_provider;
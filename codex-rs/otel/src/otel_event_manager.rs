@@ -9,18 +9,33 @@ use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::InputItem;
 use codex_protocol::protocol::ReviewDecision;
 use codex_protocol::protocol::SandboxPolicy;
+use codex_protocol::protocol::TokenUsage;
 use eventsource_stream::Event as StreamEvent;
 use eventsource_stream::EventStreamError as StreamError;
+use opentelemetry::Context;
+use opentelemetry::KeyValue;
+use opentelemetry::context::FutureExt;
+use opentelemetry::global;
+use opentelemetry::global::BoxedSpan;
+use opentelemetry::metrics::Counter;
+use opentelemetry::metrics::Histogram;
+use opentelemetry::trace::Status;
+use opentelemetry::trace::TraceContextExt;
+use opentelemetry::trace::Tracer;
 use reqwest::Error;
 use reqwest::Response;
 use serde::Serialize;
 use std::borrow::Cow;
 use std::fmt::Display;
+use std::ops::Deref;
 use std::time::Duration;
 use std::time::Instant;
 use strum_macros::Display;
 use tokio::time::error::Elapsed;
 
+/// Name of the tracer and meter every Codex span and metric is recorded with.
+const INSTRUMENTATION_SCOPE: &str = "codex";
+
 #[derive(Debug, Clone, Serialize, Display)]
 #[serde(rename_all = "snake_case")]
 pub enum ToolDecisionSource {
@@ -44,6 +59,96 @@ pub struct OtelEventMetadata {
 #[derive(Debug, Clone)]
 pub struct OtelEventManager {
     metadata: OtelEventMetadata,
+    metrics: OtelMetrics,
+}
+
+/// Metric instruments, built once per manager from the meter provider that
+/// is installed when the manager is created.
+#[derive(Debug, Clone)]
+struct OtelMetrics {
+    api_request_duration: Histogram<f64>,
+    tool_duration: Histogram<f64>,
+    tool_decisions: Counter<u64>,
+    tokens: Counter<u64>,
+}
+
+impl OtelMetrics {
+    fn new() -> Self {
+        let meter = global::meter(INSTRUMENTATION_SCOPE);
+        Self {
+            api_request_duration: meter
+                .f64_histogram("codex.api_request.duration")
+                .with_description("Time until the model API responded to a request")
+                .with_unit("ms")
+                .build(),
+            tool_duration: meter
+                .f64_histogram("codex.tool.duration")
+                .with_description("Time spent running tool calls")
+                .with_unit("ms")
+                .build(),
+            tool_decisions: meter
+                .u64_counter("codex.tool.decisions")
+                .with_description("Approval decisions made for tool calls")
+                .build(),
+            tokens: meter
+                .u64_counter("codex.tokens")
+                .with_description("Tokens used by model requests")
+                .with_unit("{token}")
+                .build(),
+        }
+    }
+}
+
+/// A span that outlives a single call, such as the session or a turn. Spans
+/// started while a future runs under [`OtelSpan::instrument`] become its
+/// children.
+#[derive(Debug, Clone, Default)]
+pub struct OtelSpan {
+    cx: Context,
+}
+
+impl OtelSpan {
+    /// The span the current task is running under, so that it can be carried
+    /// over to a spawned task.
+    pub fn current() -> Self {
+        Self {
+            cx: Context::current(),
+        }
+    }
+
+    pub fn instrument<F: Future>(&self, future: F) -> impl Future<Output = F::Output> + use<F> {
+        future.with_context(self.cx.clone())
+    }
+
+    pub fn end(&self) {
+        self.cx.span().end();
+    }
+
+    /// Ends the span once the returned guard is dropped, so that it is ended
+    /// even if the future holding the guard is aborted.
+    pub fn end_on_drop(self) -> OtelSpanGuard {
+        OtelSpanGuard { span: self }
+    }
+}
+
+/// Ends its span when dropped. See [`OtelSpan::end_on_drop`].
+#[derive(Debug, Default)]
+pub struct OtelSpanGuard {
+    span: OtelSpan,
+}
+
+impl Deref for OtelSpanGuard {
+    type Target = OtelSpan;
+
+    fn deref(&self) -> &OtelSpan {
+        &self.span
+    }
+}
+
+impl Drop for OtelSpanGuard {
+    fn drop(&mut self) {
+        self.span.end();
+    }
 }
 
 impl OtelEventManager {
@@ -70,6 +175,7 @@ impl OtelEventManager {
                 app_version: env!("CARGO_PKG_VERSION"),
                 terminal_type,
             },
+            metrics: OtelMetrics::new(),
         }
     }
 
@@ -80,6 +186,8 @@ impl OtelEventManager {
         manager
     }
 
+    /// Logs the start of the conversation and starts the root span every turn
+    /// of the session is recorded under.
     #[allow(clippy::too_many_arguments)]
     pub fn conversation_starts(
         &self,
@@ -93,7 +201,7 @@ impl OtelEventManager {
         sandbox_policy: SandboxPolicy,
         mcp_servers: Vec<&str>,
         active_profile: Option<String>,
-    ) {
+    ) -> OtelSpan {
         tracing::event!(
             tracing::Level::INFO,
             event.name = "codex.conversation_starts",
@@ -116,7 +224,52 @@ impl OtelEventManager {
             sandbox_policy = %sandbox_policy,
             mcp_servers = mcp_servers.join(", "),
             active_profile = active_profile,
-        )
+        );
+
+        let mut attributes = self.span_attributes();
+        attributes.extend([
+            KeyValue::new("app.version", self.metadata.app_version),
+            KeyValue::new("provider_name", provider_name.to_owned()),
+            KeyValue::new("approval_policy", approval_policy.to_string()),
+            KeyValue::new("sandbox_policy", sandbox_policy.to_string()),
+        ]);
+        let span = self.start_span("codex.session", attributes, &Context::new());
+        OtelSpan {
+            cx: Context::new().with_span(span),
+        }
+    }
+
+    /// Starts the span for one turn of the session.
+    pub fn turn_span(&self, session: &OtelSpan, sub_id: &str) -> OtelSpan {
+        let mut attributes = self.span_attributes();
+        attributes.push(KeyValue::new("turn.id", sub_id.to_owned()));
+        let span = self.start_span("codex.turn", attributes, &session.cx);
+        OtelSpan {
+            cx: session.cx.with_span(span),
+        }
+    }
+
+    /// Runs one attempt at sampling the model, including the tool calls it
+    /// makes, inside a `codex.model_request` span.
+    pub async fn log_model_request<F, Fut, T, E>(&self, attempt: u64, f: F) -> Result<T, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: Display,
+    {
+        let mut attributes = self.span_attributes();
+        attributes.push(KeyValue::new("attempt", attempt as i64));
+        let parent = Context::current();
+        let span = self.start_span("codex.model_request", attributes, &parent);
+        let cx = parent.with_span(span);
+
+        let result = f().with_context(cx.clone()).await;
+
+        if let Err(error) = &result {
+            cx.span().set_status(Status::error(error.to_string()));
+        }
+        cx.span().end();
+        result
     }
 
     pub async fn log_request<F, Fut>(&self, attempt: u64, f: F) -> Result<Response, Error>
@@ -151,6 +304,21 @@ impl OtelEventManager {
             attempt = attempt,
         );
 
+        let mut attributes = vec![KeyValue::new("model", self.metadata.model.clone())];
+        if let Some(status) = status {
+            attributes.push(KeyValue::new(
+                "http.response.status_code",
+                i64::from(status),
+            ));
+            Context::current().span().set_attribute(KeyValue::new(
+                "http.response.status_code",
+                i64::from(status),
+            ));
+        }
+        self.metrics
+            .api_request_duration
+            .record(duration_ms(duration), &attributes);
+
         response
     }
 
@@ -308,6 +476,36 @@ impl OtelEventManager {
         );
     }
 
+    /// Records the tokens a model request used on its span and in the
+    /// `codex.tokens` counter.
+    pub fn token_usage(&self, usage: &TokenUsage) {
+        let counts = [
+            ("input", usage.input_tokens),
+            ("cached_input", usage.cached_input_tokens),
+            ("output", usage.output_tokens),
+            ("reasoning_output", usage.reasoning_output_tokens),
+        ];
+
+        let cx = Context::current();
+        let span = cx.span();
+        for (kind, count) in counts {
+            span.set_attribute(KeyValue::new(
+                format!("gen_ai.usage.{kind}_tokens"),
+                count as i64,
+            ));
+        }
+
+        for (kind, count) in counts {
+            self.metrics.tokens.add(
+                count,
+                &[
+                    KeyValue::new("model", self.metadata.model.clone()),
+                    KeyValue::new("token.type", kind),
+                ],
+            );
+        }
+    }
+
     pub fn user_prompt(&self, items: &[InputItem]) {
         let prompt = items
             .iter()
@@ -364,6 +562,27 @@ impl OtelEventManager {
             decision = %decision.to_string().to_lowercase(),
             source = %source.to_string(),
         );
+
+        let attributes = [
+            KeyValue::new("tool_name", tool_name.to_owned()),
+            KeyValue::new("decision", decision.to_string().to_lowercase()),
+            KeyValue::new("source", source.to_string()),
+        ];
+        Context::current()
+            .span()
+            .add_event("codex.tool_decision", attributes.to_vec());
+        self.metrics.tool_decisions.add(1, &attributes);
+    }
+
+    /// Records how a command was sandboxed and how it exited on the span of
+    /// the tool call that ran it.
+    pub fn record_exec(&self, sandbox: &str, exit_code: Option<i32>) {
+        let cx = Context::current();
+        let span = cx.span();
+        span.set_attribute(KeyValue::new("sandbox", sandbox.to_owned()));
+        if let Some(exit_code) = exit_code {
+            span.set_attribute(KeyValue::new("process.exit_code", i64::from(exit_code)));
+        }
     }
 
     pub async fn log_tool_result<F, Fut, E>(
@@ -378,8 +597,17 @@ impl OtelEventManager {
         Fut: Future<Output = Result<(String, bool), E>>,
         E: Display,
     {
+        let mut attributes = self.span_attributes();
+        attributes.extend([
+            KeyValue::new("tool_name", tool_name.to_owned()),
+            KeyValue::new("call_id", call_id.to_owned()),
+        ]);
+        let parent = Context::current();
+        let span = self.start_span("codex.tool_call", attributes, &parent);
+        let cx = parent.with_span(span);
+
         let start = Instant::now();
-        let result = f().await;
+        let result = f().with_context(cx.clone()).await;
         let duration = start.elapsed();
 
         let (output, success) = match &result {
@@ -387,6 +615,14 @@ impl OtelEventManager {
             Err(error) => (Cow::Owned(error.to_string()), false),
         };
 
+        let span = cx.span();
+        span.set_attribute(KeyValue::new("success", success));
+        if !success {
+            span.set_status(Status::error(output.to_string()));
+        }
+        span.end();
+        self.record_tool_duration(tool_name, duration, success);
+
         let success_str = if success { "true" } else { "false" };
 
         tracing::event!(
@@ -442,6 +678,7 @@ impl OtelEventManager {
         success: bool,
         output: &str,
     ) {
+        self.record_tool_duration(tool_name, duration, success);
         let success_str = if success { "true" } else { "false" };
 
         tracing::event!(
@@ -464,6 +701,41 @@ impl OtelEventManager {
             output = %output,
         );
     }
+
+    fn record_tool_duration(&self, tool_name: &str, duration: Duration, success: bool) {
+        self.metrics.tool_duration.record(
+            duration_ms(duration),
+            &[
+                KeyValue::new("tool_name", tool_name.to_owned()),
+                KeyValue::new("success", success),
+            ],
+        );
+    }
+
+    fn span_attributes(&self) -> Vec<KeyValue> {
+        vec![
+            KeyValue::new("conversation.id", self.metadata.conversation_id.to_string()),
+            KeyValue::new("model", self.metadata.model.clone()),
+            KeyValue::new("slug", self.metadata.slug.clone()),
+        ]
+    }
+
+    fn start_span(
+        &self,
+        name: &'static str,
+        attributes: Vec<KeyValue>,
+        parent: &Context,
+    ) -> BoxedSpan {
+        let tracer = global::tracer(INSTRUMENTATION_SCOPE);
+        tracer
+            .span_builder(name)
+            .with_attributes(attributes)
+            .start_with_context(&tracer, parent)
+    }
+}
+
+fn duration_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
 }
 
 fn timestamp() -> String {
@@ -1,18 +1,27 @@
 use crate::config::OtelExporter;
 use crate::config::OtelHttpProtocol;
 use crate::config::OtelSettings;
+use crate::file_exporter::FileLogExporter;
+use crate::file_exporter::FileMetricExporter;
+use crate::file_exporter::FileSpanExporter;
 use opentelemetry::KeyValue;
+use opentelemetry::global;
 use opentelemetry_otlp::LogExporter;
+use opentelemetry_otlp::MetricExporter;
 use opentelemetry_otlp::Protocol;
+use opentelemetry_otlp::SpanExporter;
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_otlp::WithHttpConfig;
 use opentelemetry_otlp::WithTonicConfig;
 use opentelemetry_sdk::Resource;
 use opentelemetry_sdk::logs::SdkLoggerProvider;
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::trace::SdkTracerProvider;
 use opentelemetry_semantic_conventions as semconv;
 use reqwest::header::HeaderMap;
 use reqwest::header::HeaderName;
 use reqwest::header::HeaderValue;
+use std::collections::HashMap;
 use std::error::Error;
 use tonic::metadata::MetadataMap;
 use tracing::debug;
@@ -21,14 +30,33 @@ const ENV_ATTRIBUTE: &str = "env";
 
 pub struct OtelProvider {
     pub logger: SdkLoggerProvider,
+    pub tracer: Option<SdkTracerProvider>,
+    pub meter: Option<SdkMeterProvider>,
 }
 
 impl OtelProvider {
     pub fn shutdown(&self) {
         let _ = self.logger.shutdown();
+        if let Some(tracer) = &self.tracer {
+            let _ = tracer.shutdown();
+        }
+        if let Some(meter) = &self.meter {
+            let _ = meter.shutdown();
+        }
     }
 
+    /// Builds the providers for every configured signal. Trace and metric
+    /// providers are also installed as the global providers, which is where
+    /// `OtelEventManager` records spans and metrics.
     pub fn from(settings: &OtelSettings) -> Result<Option<Self>, Box<dyn Error>> {
+        if matches!(settings.exporter, OtelExporter::None)
+            && matches!(settings.trace_exporter, OtelExporter::None)
+            && matches!(settings.metrics_exporter, OtelExporter::None)
+        {
+            debug!("No exporter enabled in OTLP settings.");
+            return Ok(None);
+        }
+
         let resource = Resource::builder()
             .with_service_name(settings.service_name.clone())
             .with_attributes(vec![
@@ -40,64 +68,166 @@ impl OtelProvider {
             ])
             .build();
 
-        let mut builder = SdkLoggerProvider::builder().with_resource(resource);
+        let logger = build_logger_provider(&settings.exporter, resource.clone())?;
+        let tracer = build_tracer_provider(&settings.trace_exporter, resource.clone())?;
+        let meter = build_meter_provider(&settings.metrics_exporter, resource)?;
 
-        match &settings.exporter {
-            OtelExporter::None => {
-                debug!("No exporter enabled in OTLP settings.");
-                return Ok(None);
-            }
-            OtelExporter::OtlpGrpc { endpoint, headers } => {
-                debug!("Using OTLP Grpc exporter: {}", endpoint);
+        if let Some(tracer) = &tracer {
+            global::set_tracer_provider(tracer.clone());
+        }
+        if let Some(meter) = &meter {
+            global::set_meter_provider(meter.clone());
+        }
+
+        Ok(Some(Self {
+            logger,
+            tracer,
+            meter,
+        }))
+    }
+}
 
-                let mut header_map = HeaderMap::new();
-                for (key, value) in headers {
-                    if let Ok(name) = HeaderName::from_bytes(key.as_bytes())
-                        && let Ok(val) = HeaderValue::from_str(value)
-                    {
-                        header_map.insert(name, val);
-                    }
-                }
+impl Drop for OtelProvider {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
 
-                let exporter = LogExporter::builder()
+fn build_logger_provider(
+    exporter: &OtelExporter,
+    resource: Resource,
+) -> Result<SdkLoggerProvider, Box<dyn Error>> {
+    let builder = SdkLoggerProvider::builder().with_resource(resource);
+    let builder = match exporter {
+        OtelExporter::None => builder,
+        OtelExporter::OtlpGrpc { endpoint, headers } => {
+            debug!("Using OTLP Grpc log exporter: {}", endpoint);
+            builder.with_batch_exporter(
+                LogExporter::builder()
                     .with_tonic()
                     .with_endpoint(endpoint)
-                    .with_metadata(MetadataMap::from_headers(header_map))
-                    .build()?;
-
-                builder = builder.with_batch_exporter(exporter);
-            }
-            OtelExporter::OtlpHttp {
-                endpoint,
-                headers,
-                protocol,
-            } => {
-                debug!("Using OTLP Http exporter: {}", endpoint);
-
-                let protocol = match protocol {
-                    OtelHttpProtocol::Binary => Protocol::HttpBinary,
-                    OtelHttpProtocol::Json => Protocol::HttpJson,
-                };
+                    .with_metadata(grpc_metadata(headers))
+                    .build()?,
+            )
+        }
+        OtelExporter::OtlpHttp {
+            endpoint,
+            headers,
+            protocol,
+        } => {
+            debug!("Using OTLP Http log exporter: {}", endpoint);
+            builder.with_batch_exporter(
+                LogExporter::builder()
+                    .with_http()
+                    .with_endpoint(endpoint)
+                    .with_protocol(http_protocol(protocol))
+                    .with_headers(headers.clone())
+                    .build()?,
+            )
+        }
+        OtelExporter::OtlpFile { path } => {
+            debug!("Using OTLP file log exporter: {}", path.display());
+            builder.with_batch_exporter(FileLogExporter::new(path)?)
+        }
+    };
+    Ok(builder.build())
+}
 
-                let exporter = LogExporter::builder()
+fn build_tracer_provider(
+    exporter: &OtelExporter,
+    resource: Resource,
+) -> Result<Option<SdkTracerProvider>, Box<dyn Error>> {
+    let builder = SdkTracerProvider::builder().with_resource(resource);
+    let builder = match exporter {
+        OtelExporter::None => return Ok(None),
+        OtelExporter::OtlpGrpc { endpoint, headers } => {
+            debug!("Using OTLP Grpc span exporter: {}", endpoint);
+            builder.with_batch_exporter(
+                SpanExporter::builder()
+                    .with_tonic()
+                    .with_endpoint(endpoint)
+                    .with_metadata(grpc_metadata(headers))
+                    .build()?,
+            )
+        }
+        OtelExporter::OtlpHttp {
+            endpoint,
+            headers,
+            protocol,
+        } => {
+            debug!("Using OTLP Http span exporter: {}", endpoint);
+            builder.with_batch_exporter(
+                SpanExporter::builder()
                     .with_http()
                     .with_endpoint(endpoint)
-                    .with_protocol(protocol)
+                    .with_protocol(http_protocol(protocol))
                     .with_headers(headers.clone())
-                    .build()?;
+                    .build()?,
+            )
+        }
+        OtelExporter::OtlpFile { path } => {
+            debug!("Using OTLP file span exporter: {}", path.display());
+            builder.with_batch_exporter(FileSpanExporter::new(path)?)
+        }
+    };
+    Ok(Some(builder.build()))
+}
 
-                builder = builder.with_batch_exporter(exporter);
-            }
+fn build_meter_provider(
+    exporter: &OtelExporter,
+    resource: Resource,
+) -> Result<Option<SdkMeterProvider>, Box<dyn Error>> {
+    let builder = SdkMeterProvider::builder().with_resource(resource);
+    let builder = match exporter {
+        OtelExporter::None => return Ok(None),
+        OtelExporter::OtlpGrpc { endpoint, headers } => {
+            debug!("Using OTLP Grpc metric exporter: {}", endpoint);
+            builder.with_periodic_exporter(
+                MetricExporter::builder()
+                    .with_tonic()
+                    .with_endpoint(endpoint)
+                    .with_metadata(grpc_metadata(headers))
+                    .build()?,
+            )
+        }
+        OtelExporter::OtlpHttp {
+            endpoint,
+            headers,
+            protocol,
+        } => {
+            debug!("Using OTLP Http metric exporter: {}", endpoint);
+            builder.with_periodic_exporter(
+                MetricExporter::builder()
+                    .with_http()
+                    .with_endpoint(endpoint)
+                    .with_protocol(http_protocol(protocol))
+                    .with_headers(headers.clone())
+                    .build()?,
+            )
         }
+        OtelExporter::OtlpFile { path } => {
+            debug!("Using OTLP file metric exporter: {}", path.display());
+            builder.with_periodic_exporter(FileMetricExporter::new(path)?)
+        }
+    };
+    Ok(Some(builder.build()))
+}
 
-        Ok(Some(Self {
-            logger: builder.build(),
-        }))
+fn grpc_metadata(headers: &HashMap<String, String>) -> MetadataMap {
+    let mut header_map = HeaderMap::new();
+    for (key, value) in headers {
+        if let Ok(name) = HeaderName::from_bytes(key.as_bytes())
+            && let Ok(val) = HeaderValue::from_str(value)
+        {
+            header_map.insert(name, val);
+        }
     }
+    MetadataMap::from_headers(header_map)
 }
 
-impl Drop for OtelProvider {
-    fn drop(&mut self) {
-        let _ = self.logger.shutdown();
+fn http_protocol(protocol: &OtelHttpProtocol) -> Protocol {
+    match protocol {
+        OtelHttpProtocol::Binary => Protocol::HttpBinary,
+        OtelHttpProtocol::Json => Protocol::HttpJson,
     }
 }
@@ -234,6 +234,9 @@ impl From<Vec<InputItem>> for ResponseInputItem {
                             None
                         }
                     },
+                    // MCP references are resolved by core before the turn
+                    // starts; anything left unresolved is dropped.
+                    InputItem::McpResource { .. } | InputItem::McpPrompt { .. } => None,
                 })
                 .collect::<Vec<ContentItem>>(),
         }
@@ -3,6 +3,7 @@
 //! Uses a SQ (Submission Queue) / EQ (Event Queue) pattern to asynchronously communicate
 //! between user and agent.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::fmt;
 use std::path::Path;
@@ -21,6 +22,9 @@ use crate::num_format::format_with_separators;
 use crate::parse_command::ParsedCommand;
 use crate::plan_tool::UpdatePlanArgs;
 use mcp_types::CallToolResult;
+use mcp_types::Prompt as McpPromptDefinition;
+use mcp_types::Resource as McpResourceDefinition;
+use mcp_types::ResourceTemplate as McpResourceTemplate;
 use mcp_types::Tool as McpTool;
 use serde::Deserialize;
 use serde::Serialize;
@@ -35,6 +39,8 @@ pub const USER_INSTRUCTIONS_OPEN_TAG: &str = "<user_instructions>";
 pub const USER_INSTRUCTIONS_CLOSE_TAG: &str = "</user_instructions>";
 pub const ENVIRONMENT_CONTEXT_OPEN_TAG: &str = "<environment_context>";
 pub const ENVIRONMENT_CONTEXT_CLOSE_TAG: &str = "</environment_context>";
+pub const PINNED_CONTEXT_OPEN_TAG: &str = "<pinned_context>";
+pub const PINNED_CONTEXT_CLOSE_TAG: &str = "</pinned_context>";
 pub const USER_MESSAGE_BEGIN: &str = "## My request for Codex:";
 
 /// Submission Queue Entry - requests from user
@@ -163,13 +169,33 @@ pub enum Op {
     /// Reply is delivered via `EventMsg::McpListToolsResponse`.
     ListMcpTools,
 
+    /// Request the resources and resource templates exposed by all configured
+    /// MCP servers. Reply is delivered via `EventMsg::McpListResourcesResponse`.
+    ListMcpResources,
+
+    /// Request the prompts exposed by all configured MCP servers.
+    /// Reply is delivered via `EventMsg::McpListPromptsResponse`.
+    ListMcpPrompts,
+
     /// Request the list of available custom prompts.
     ListCustomPrompts,
 
     /// Request the agent to summarize the current conversation context.
     /// The agent will use its existing context (either conversation history or previous response id)
     /// to generate a summary which will be returned as an AgentMessage event.
-    Compact,
+    Compact {
+        /// Optional guidance for the summarizer, e.g. what to focus on.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        instructions: Option<String>,
+    },
+
+    /// Pin content so that compaction keeps it verbatim instead of folding
+    /// it into the summary. When `text` is `None`, the most recent assistant
+    /// message is pinned.
+    Pin {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        text: Option<String>,
+    },
 
     /// Request a code review from the agent.
     Review { review_request: ReviewRequest },
@@ -233,6 +259,14 @@ pub enum SandboxPolicy {
         #[serde(default)]
         network_access: bool,
 
+        /// Hosts that commands may still reach when `network_access` is
+        /// `false`. Entries are `host`, `host:port`, or `*.domain[:port]`.
+        /// Currently only enforced by the Linux sandbox, which routes traffic
+        /// through a filtering proxy; other platforms keep the network
+        /// disabled.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        network_allowlist: Vec<String>,
+
         /// When set to `true`, will NOT include the per-user `TMPDIR`
         /// environment variable among the default writable roots. Defaults to
         /// `false`.
@@ -298,6 +332,7 @@ impl SandboxPolicy {
         SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![],
             network_access: false,
+            network_allowlist: vec![],
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
         }
@@ -324,6 +359,20 @@ impl SandboxPolicy {
         }
     }
 
+    /// Returns the hosts outbound traffic is restricted to when the network is
+    /// otherwise disabled. Empty when the policy grants full network access or
+    /// no allowlist is configured.
+    pub fn network_allowlist(&self) -> &[String] {
+        match self {
+            SandboxPolicy::WorkspaceWrite {
+                network_access: false,
+                network_allowlist,
+                ..
+            } => network_allowlist,
+            _ => &[],
+        }
+    }
+
     /// Returns the list of writable roots (tailored to the current working
     /// directory) together with subpaths that should remain read‑only under
     /// each writable root.
@@ -336,6 +385,7 @@ impl SandboxPolicy {
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
                 network_access: _,
+                network_allowlist: _,
             } => {
                 // Start from explicitly configured writable roots.
                 let mut roots: Vec<PathBuf> = writable_roots.clone();
@@ -405,6 +455,22 @@ pub enum InputItem {
     LocalImage {
         path: std::path::PathBuf,
     },
+
+    /// Resource exposed by an MCP server, e.g. referenced as `@server:uri`.
+    /// This is replaced with the resource contents before the turn starts.
+    McpResource {
+        server: String,
+        uri: String,
+    },
+
+    /// Prompt exposed by an MCP server. This is replaced with the messages
+    /// returned by `prompts/get` before the turn starts.
+    McpPrompt {
+        server: String,
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        arguments: Option<HashMap<String, String>>,
+    },
 }
 
 /// Event Queue Entry - events from agent
@@ -505,6 +571,12 @@ pub enum EventMsg {
     /// List of MCP tools available to the agent.
     McpListToolsResponse(McpListToolsResponseEvent),
 
+    /// List of resources exposed by MCP servers.
+    McpListResourcesResponse(McpListResourcesResponseEvent),
+
+    /// List of prompts exposed by MCP servers.
+    McpListPromptsResponse(McpListPromptsResponseEvent),
+
     /// List of custom prompts available to the agent.
     ListCustomPromptsResponse(ListCustomPromptsResponseEvent),
 
@@ -522,6 +594,16 @@ pub enum EventMsg {
 
     /// Exited review mode with an optional final result to apply.
     ExitedReviewMode(ExitedReviewModeEvent),
+
+    /// A `spawn_agent` tool call started a child conversation.
+    SubAgentBegin(SubAgentBeginEvent),
+
+    /// Event emitted by a child conversation, nested under the `spawn_agent`
+    /// call that started it.
+    SubAgentEvent(SubAgentEvent),
+
+    /// The child conversation started by a `spawn_agent` call finished.
+    SubAgentEnd(SubAgentEndEvent),
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
@@ -546,7 +628,7 @@ pub struct TaskStartedEvent {
     pub model_context_window: Option<u64>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default, TS)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, TS)]
 pub struct TokenUsage {
     #[ts(type = "number")]
     pub input_tokens: u64,
@@ -627,6 +709,42 @@ impl TokenUsageInfo {
 pub struct TokenCountEvent {
     pub info: Option<TokenUsageInfo>,
     pub rate_limits: Option<RateLimitSnapshot>,
+    /// Local estimate of what the most recent request sent to the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_breakdown: Option<ContextBreakdown>,
+}
+
+/// Estimated token counts for the parts of a model request, computed locally
+/// with the model's tokenizer before the request is sent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, TS)]
+pub struct ContextBreakdown {
+    /// System instructions, including the base prompt.
+    #[ts(type = "number")]
+    pub instructions: u64,
+    /// JSON schemas of the tools offered to the model.
+    #[ts(type = "number")]
+    pub tools: u64,
+    /// User messages, including AGENTS.md and environment context.
+    #[ts(type = "number")]
+    pub user_messages: u64,
+    #[ts(type = "number")]
+    pub assistant_messages: u64,
+    #[ts(type = "number")]
+    pub reasoning: u64,
+    /// Tool calls made by the model and the outputs returned to it.
+    #[ts(type = "number")]
+    pub tool_calls: u64,
+}
+
+impl ContextBreakdown {
+    pub fn total(&self) -> u64 {
+        self.instructions
+            + self.tools
+            + self.user_messages
+            + self.assistant_messages
+            + self.reasoning
+            + self.tool_calls
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
@@ -765,6 +883,8 @@ pub enum InputMessageKind {
     UserInstructions,
     /// XML-wrapped environment context (<environment_context>...)
     EnvironmentContext,
+    /// XML-wrapped content pinned across compactions (<pinned_context>...)
+    PinnedContext,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
@@ -793,6 +913,10 @@ where
             && ends_with_ignore_ascii_case(trimmed, USER_INSTRUCTIONS_CLOSE_TAG)
         {
             InputMessageKind::UserInstructions
+        } else if starts_with_ignore_ascii_case(trimmed, PINNED_CONTEXT_OPEN_TAG)
+            && ends_with_ignore_ascii_case(trimmed, PINNED_CONTEXT_CLOSE_TAG)
+        {
+            InputMessageKind::PinnedContext
         } else {
             InputMessageKind::Plain
         }
@@ -961,6 +1085,8 @@ pub enum SessionSource {
     VSCode,
     Exec,
     Mcp,
+    /// Child conversation started by the `spawn_agent` tool.
+    SubAgent,
     #[serde(other)]
     Unknown,
 }
@@ -975,6 +1101,11 @@ pub struct SessionMeta {
     pub instructions: Option<String>,
     #[serde(default)]
     pub source: SessionSource,
+    /// Cumulative usage of the session. The first line of a rollout has none;
+    /// the session appends its `SessionMeta` again with the running total
+    /// after every task, so the last line with this `id` is the current one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<SessionUsage>,
 }
 
 impl Default for SessionMeta {
@@ -987,10 +1118,46 @@ impl Default for SessionMeta {
             cli_version: String::new(),
             instructions: None,
             source: SessionSource::default(),
+            usage: None,
         }
     }
 }
 
+/// Token usage and cost of a session, per model.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, TS)]
+pub struct SessionUsage {
+    pub models: BTreeMap<String, ModelUsage>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, TS)]
+pub struct ModelUsage {
+    pub token_usage: TokenUsage,
+    /// Cost in US dollars; `None` when no price is known for the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+impl SessionUsage {
+    /// Total cost of the models with a known price.
+    pub fn cost_usd(&self) -> f64 {
+        self.models
+            .values()
+            .filter_map(|usage| usage.cost_usd)
+            .sum()
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.models
+            .values()
+            .map(|usage| usage.token_usage.total_tokens)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.models.is_empty()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, TS)]
 pub struct SessionMetaLine {
     #[serde(flatten)]
@@ -1062,6 +1229,41 @@ pub struct GitInfo {
 pub struct ReviewRequest {
     pub prompt: String,
     pub user_facing_hint: String,
+    /// The changes to review. When set, core resolves the target to a diff
+    /// and `prompt` carries optional extra instructions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<ReviewTarget>,
+}
+
+/// A set of changes a review can cover.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReviewTarget {
+    /// Staged, unstaged and untracked changes in the working tree.
+    UncommittedChanges,
+    /// Changes on the current branch since it diverged from `branch`.
+    BaseBranch { branch: String },
+    /// The changes introduced by a single commit.
+    Commit { sha: String },
+    /// The changes between two revisions, as in `git diff from to`.
+    CommitRange { from: String, to: String },
+    /// A unified diff read from a file, e.g. a downloaded pull request patch.
+    PatchFile { path: PathBuf },
+}
+
+/// Formats a [`ReviewOutputEvent`] can be exported to.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewExportFormat {
+    /// The `ReviewOutputEvent` itself, as pretty-printed JSON.
+    #[default]
+    Json,
+    /// A SARIF 2.1.0 log for code scanning tools.
+    Sarif,
+    /// GitHub Actions workflow commands (`::warning file=...::message`).
+    Github,
+    /// A markdown report, e.g. for a pull request comment.
+    Markdown,
 }
 
 /// Structured review result produced by a child review session.
@@ -1139,6 +1341,38 @@ pub struct ExecCommandEndEvent {
     pub formatted_output: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct SubAgentBeginEvent {
+    /// Identifier of the `spawn_agent` call; pairs with SubAgentEnd.
+    pub call_id: String,
+    /// Id of the child conversation.
+    pub conversation_id: ConversationId,
+    /// Task handed to the child agent.
+    pub prompt: String,
+    /// Working directory of the child conversation.
+    pub cwd: PathBuf,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct SubAgentEvent {
+    /// Identifier of the `spawn_agent` call that started the child.
+    pub call_id: String,
+    /// The event as emitted by the child conversation.
+    pub msg: Box<EventMsg>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct SubAgentEndEvent {
+    /// Identifier of the `spawn_agent` call that started the child.
+    pub call_id: String,
+    /// Id of the child conversation.
+    pub conversation_id: ConversationId,
+    /// Final message of the child agent, if it produced one.
+    pub last_agent_message: Option<String>,
+    /// Whether the child finished its task without error or interruption.
+    pub success: bool,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
 pub struct ViewImageToolCallEvent {
     /// Identifier for the originating tool call.
@@ -1253,6 +1487,22 @@ pub struct McpListToolsResponseEvent {
     pub auth_statuses: std::collections::HashMap<String, McpAuthStatus>,
 }
 
+/// Response payload for `Op::ListMcpResources`.
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct McpListResourcesResponseEvent {
+    /// Server name -> resources exposed by that server.
+    pub resources: HashMap<String, Vec<McpResourceDefinition>>,
+    /// Server name -> resource templates exposed by that server.
+    pub resource_templates: HashMap<String, Vec<McpResourceTemplate>>,
+}
+
+/// Response payload for `Op::ListMcpPrompts`.
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct McpListPromptsResponseEvent {
+    /// Server name -> prompts exposed by that server.
+    pub prompts: HashMap<String, Vec<McpPromptDefinition>>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "snake_case")]
 #[ts(rename_all = "snake_case")]
@@ -1319,6 +1569,11 @@ pub enum ReviewDecision {
     /// remainder of the session.
     ApprovedForSession,
 
+    /// User has approved this command and wants to automatically approve any
+    /// future identical instances run inside the same project, in this and
+    /// future sessions. This persists an approval rule under `CODEX_HOME`.
+    ApprovedForProject,
+
     /// User has denied this command and the agent should not execute it, but
     /// it should continue the session and try something else.
     #[default]
@@ -0,0 +1,297 @@
+//! Cassettes: recorded request/response pairs that let the proxy replay model
+//! traffic offline.
+//!
+//! A cassette is a directory containing one `NNNNNN.json` file per
+//! interaction, numbered in the order the requests arrived. Replay matches an
+//! incoming request against the recorded ones by its normalized body (see
+//! [`normalize_request_body`]); identical requests are served in recorded
+//! order.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use anyhow::Context;
+use anyhow::Result;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Top-level request fields that differ between otherwise identical runs and
+/// are therefore ignored when matching.
+const VOLATILE_REQUEST_FIELDS: &[&str] = &["prompt_cache_key"];
+
+/// Response headers that are never written to a cassette.
+const SKIPPED_RESPONSE_HEADERS: &[&str] = &["date", "set-cookie"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    /// The request body as sent by the client. Stored as JSON when it parses,
+    /// otherwise as a string.
+    pub body: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RecordedResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    /// The raw response body, e.g. the full SSE stream.
+    pub body: String,
+}
+
+/// Returns the key used to match a request body against recorded requests:
+/// the body re-serialized with sorted object keys and without
+/// [`VOLATILE_REQUEST_FIELDS`]. Bodies that are not JSON are used verbatim.
+pub(crate) fn normalize_request_body(body: &[u8]) -> String {
+    match serde_json::from_slice::<Value>(body) {
+        Ok(value) => normalize_json(&value, true).to_string(),
+        Err(_) => String::from_utf8_lossy(body).into_owned(),
+    }
+}
+
+fn normalize_json(value: &Value, top_level: bool) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map
+                .keys()
+                .filter(|key| !(top_level && VOLATILE_REQUEST_FIELDS.contains(&key.as_str())))
+                .collect();
+            keys.sort();
+            let mut normalized = serde_json::Map::new();
+            for key in keys {
+                normalized.insert(key.clone(), normalize_json(&map[key], false));
+            }
+            Value::Object(normalized)
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| normalize_json(item, false))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+impl RecordedRequest {
+    pub(crate) fn new(method: &str, path: &str, body: &[u8]) -> Self {
+        Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: serde_json::from_slice(body)
+                .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(body).into_owned())),
+        }
+    }
+}
+
+/// Writes interactions into a cassette directory as they complete.
+pub(crate) struct Recorder {
+    dir: PathBuf,
+    next_index: AtomicUsize,
+}
+
+impl Recorder {
+    /// Creates `dir` if needed. Numbering continues after any interactions
+    /// already present so an existing cassette can be extended.
+    pub(crate) fn new(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create cassette dir {}", dir.display()))?;
+        let existing = interaction_files(dir)?.len();
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            next_index: AtomicUsize::new(existing),
+        })
+    }
+
+    /// Reserves the slot for a request in arrival order.
+    pub(crate) fn next_index(&self) -> usize {
+        self.next_index.fetch_add(1, Ordering::SeqCst)
+    }
+
+    pub(crate) fn record(&self, index: usize, mut interaction: Interaction) -> Result<()> {
+        interaction.response.headers.retain(|(name, _)| {
+            !SKIPPED_RESPONSE_HEADERS.contains(&name.to_ascii_lowercase().as_str())
+        });
+        let path = self.dir.join(format!("{index:06}.json"));
+        let mut data = serde_json::to_string_pretty(&interaction)?;
+        data.push('\n');
+        fs::write(&path, data).with_context(|| format!("failed to write {}", path.display()))
+    }
+}
+
+/// Serves recorded interactions back, keyed by normalized request body.
+pub(crate) struct Replayer {
+    interactions: Mutex<HashMap<String, VecDeque<RecordedResponse>>>,
+}
+
+impl Replayer {
+    pub(crate) fn load(dir: &Path) -> Result<Self> {
+        let mut interactions: HashMap<String, VecDeque<RecordedResponse>> = HashMap::new();
+        for path in interaction_files(dir)? {
+            let data = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            let interaction: Interaction = serde_json::from_str(&data)
+                .with_context(|| format!("failed to parse {}", path.display()))?;
+            let key = match &interaction.request.body {
+                Value::String(raw) => raw.clone(),
+                body => normalize_json(body, true).to_string(),
+            };
+            interactions
+                .entry(key)
+                .or_default()
+                .push_back(interaction.response);
+        }
+        Ok(Self {
+            interactions: Mutex::new(interactions),
+        })
+    }
+
+    /// Returns the next recorded response for `request_body`. Once every
+    /// recording for a body has been served, the last one keeps being
+    /// returned so client retries still get an answer.
+    pub(crate) fn take(&self, request_body: &[u8]) -> Option<RecordedResponse> {
+        let key = normalize_request_body(request_body);
+        let mut interactions = self
+            .interactions
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let queue = interactions.get_mut(&key)?;
+        if queue.len() > 1 {
+            queue.pop_front()
+        } else {
+            queue.front().cloned()
+        }
+    }
+}
+
+/// Lists the `*.json` interaction files in `dir`, sorted by name.
+fn interaction_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in
+        fs::read_dir(dir).with_context(|| format!("failed to read cassette {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Wraps an upstream response body, copying every byte read through it and
+/// handing the complete body to `on_eof` once the stream is exhausted.
+pub(crate) struct TeeReader<R, F>
+where
+    F: FnOnce(&[u8]),
+{
+    inner: R,
+    captured: Vec<u8>,
+    on_eof: Option<F>,
+}
+
+impl<R, F> TeeReader<R, F>
+where
+    F: FnOnce(&[u8]),
+{
+    pub(crate) fn new(inner: R, on_eof: F) -> Self {
+        Self {
+            inner,
+            captured: Vec::new(),
+            on_eof: Some(on_eof),
+        }
+    }
+}
+
+impl<R: Read, F> Read for TeeReader<R, F>
+where
+    F: FnOnce(&[u8]),
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n == 0 {
+            if let Some(on_eof) = self.on_eof.take() {
+                on_eof(&self.captured);
+            }
+        } else {
+            self.captured.extend_from_slice(&buf[..n]);
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn normalization_ignores_key_order_and_volatile_fields() {
+        let a = br#"{"model":"gpt-5","prompt_cache_key":"abc","input":[{"role":"user","type":"message"}]}"#;
+        let b = br#"{"input":[{"type":"message","role":"user"}],"prompt_cache_key":"xyz","model":"gpt-5"}"#;
+        assert_eq!(normalize_request_body(a), normalize_request_body(b));
+        assert_eq!(
+            normalize_request_body(a),
+            r#"{"input":[{"role":"user","type":"message"}],"model":"gpt-5"}"#
+        );
+    }
+
+    #[test]
+    fn recorded_interactions_replay_in_order() -> Result<()> {
+        let dir = TempDir::new()?;
+        let recorder = Recorder::new(dir.path())?;
+        let body = br#"{"model":"gpt-5","prompt_cache_key":"first"}"#;
+        for response in ["data: one\n\n", "data: two\n\n"] {
+            let mut tee = TeeReader::new(response.as_bytes(), |captured: &[u8]| {
+                let interaction = Interaction {
+                    request: RecordedRequest::new("POST", "/v1/responses", body),
+                    response: RecordedResponse {
+                        status: 200,
+                        headers: vec![
+                            ("content-type".to_string(), "text/event-stream".to_string()),
+                            ("date".to_string(), "today".to_string()),
+                        ],
+                        body: String::from_utf8_lossy(captured).into_owned(),
+                    },
+                };
+                recorder
+                    .record(recorder.next_index(), interaction)
+                    .expect("record interaction");
+            });
+            std::io::copy(&mut tee, &mut std::io::sink())?;
+        }
+
+        let replayer = Replayer::load(dir.path())?;
+        let replay_body = br#"{"prompt_cache_key":"second","model":"gpt-5"}"#;
+        let bodies: Vec<String> = (0..3)
+            .filter_map(|_| replayer.take(replay_body))
+            .map(|response| response.body)
+            .collect();
+        assert_eq!(
+            bodies,
+            vec!["data: one\n\n", "data: two\n\n", "data: two\n\n"]
+        );
+        let response = replayer.take(replay_body).expect("recorded response");
+        assert_eq!(
+            response.headers,
+            vec![("content-type".to_string(), "text/event-stream".to_string())]
+        );
+        assert_eq!(replayer.take(br#"{"model":"other"}"#), None);
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
 use std::fs::File;
 use std::fs::{self};
+use std::io::Cursor;
+use std::io::Read;
 use std::io::Write;
 use std::net::SocketAddr;
 use std::net::TcpListener;
@@ -26,7 +28,14 @@ use tiny_http::Response;
 use tiny_http::Server;
 use tiny_http::StatusCode;
 
+mod cassette;
 mod read_api_key;
+use cassette::Interaction;
+use cassette::RecordedRequest;
+use cassette::RecordedResponse;
+use cassette::Recorder;
+use cassette::Replayer;
+use cassette::TeeReader;
 use read_api_key::read_auth_header_from_stdin;
 
 /// CLI arguments for the proxy.
@@ -44,6 +53,26 @@ pub struct Args {
     /// Enable HTTP shutdown endpoint at GET /shutdown
     #[arg(long)]
     pub http_shutdown: bool,
+
+    /// Record every forwarded request and its full response into this
+    /// cassette directory.
+    #[arg(long, value_name = "DIR", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Serve responses recorded in this cassette directory instead of
+    /// forwarding upstream. No API key is read in this mode.
+    #[arg(long, value_name = "DIR")]
+    pub replay: Option<PathBuf>,
+}
+
+/// How requests that pass validation are answered.
+#[derive(Clone)]
+enum Mode {
+    Forward {
+        auth_header: &'static str,
+        recorder: Option<Arc<Recorder>>,
+    },
+    Replay(Arc<Replayer>),
 }
 
 #[derive(Serialize)]
@@ -54,7 +83,18 @@ struct ServerInfo {
 
 /// Entry point for the library main, for parity with other crates.
 pub fn run_main(args: Args) -> Result<()> {
-    let auth_header = read_auth_header_from_stdin()?;
+    let mode = match args.replay.as_deref() {
+        Some(dir) => Mode::Replay(Arc::new(Replayer::load(dir)?)),
+        None => Mode::Forward {
+            auth_header: read_auth_header_from_stdin()?,
+            recorder: args
+                .record
+                .as_deref()
+                .map(Recorder::new)
+                .transpose()?
+                .map(Arc::new),
+        },
+    };
 
     let (listener, bound_addr) = bind_listener(args.port)?;
     if let Some(path) = args.server_info.as_ref() {
@@ -75,14 +115,27 @@ pub fn run_main(args: Args) -> Result<()> {
     let http_shutdown = args.http_shutdown;
     for request in server.incoming_requests() {
         let client = client.clone();
+        let mode = mode.clone();
         std::thread::spawn(move || {
             if http_shutdown && request.method() == &Method::Get && request.url() == "/shutdown" {
                 let _ = request.respond(Response::new_empty(StatusCode(200)));
                 std::process::exit(0);
             }
 
-            if let Err(e) = forward_request(&client, auth_header, request) {
-                eprintln!("forwarding error: {e}");
+            match mode {
+                Mode::Forward {
+                    auth_header,
+                    recorder,
+                } => {
+                    if let Err(e) = forward_request(&client, auth_header, recorder, request) {
+                        eprintln!("forwarding error: {e}");
+                    }
+                }
+                Mode::Replay(replayer) => {
+                    if let Err(e) = replay_request(&replayer, request) {
+                        eprintln!("replay error: {e}");
+                    }
+                }
             }
         });
     }
@@ -115,22 +168,70 @@ fn write_server_info(path: &Path, port: u16) -> Result<()> {
     Ok(())
 }
 
-fn forward_request(client: &Client, auth_header: &'static str, mut req: Request) -> Result<()> {
-    // Only allow POST /v1/responses exactly, no query string.
-    let method = req.method().clone();
-    let url_path = req.url().to_string();
-    let allow = method == Method::Post && url_path == "/v1/responses";
+/// Reads the body of an allowed request. Anything other than
+/// `POST /v1/responses` (exactly, no query string) is answered with `403` and
+/// yields `None`.
+fn read_allowed_request_body(mut req: Request) -> Result<Option<(Request, Vec<u8>)>> {
+    let allow = req.method() == &Method::Post && req.url() == "/v1/responses";
 
     if !allow {
         let resp = Response::new_empty(StatusCode(403));
         let _ = req.respond(resp);
-        return Ok(());
+        return Ok(None);
     }
 
-    // Read request body
     let mut body = Vec::new();
-    let mut reader = req.as_reader();
-    std::io::Read::read_to_end(&mut reader, &mut body)?;
+    req.as_reader().read_to_end(&mut body)?;
+    Ok(Some((req, body)))
+}
+
+fn replay_request(replayer: &Replayer, req: Request) -> Result<()> {
+    let Some((req, body)) = read_allowed_request_body(req)? else {
+        return Ok(());
+    };
+
+    let Some(recorded) = replayer.take(&body) else {
+        let resp = Response::from_string("no recorded interaction matches this request")
+            .with_status_code(StatusCode(404));
+        let _ = req.respond(resp);
+        return Err(anyhow!("no recorded interaction matches request body"));
+    };
+
+    let headers = recorded
+        .headers
+        .iter()
+        .filter_map(|(name, value)| Header::from_bytes(name.as_bytes(), value.as_bytes()).ok())
+        .collect();
+    let body = recorded.body.into_bytes();
+    let content_length = body.len();
+    let response = Response::new(
+        StatusCode(recorded.status),
+        headers,
+        Cursor::new(body),
+        Some(content_length),
+        None,
+    );
+
+    let _ = req.respond(response);
+    Ok(())
+}
+
+fn forward_request(
+    client: &Client,
+    auth_header: &'static str,
+    recorder: Option<Arc<Recorder>>,
+    req: Request,
+) -> Result<()> {
+    let Some((req, body)) = read_allowed_request_body(req)? else {
+        return Ok(());
+    };
+    // Reserve the cassette slot now so interactions are numbered in the order
+    // requests arrived rather than the order their responses finished.
+    let recording = recorder.map(|recorder| {
+        let index = recorder.next_index();
+        let request = RecordedRequest::new(req.method().as_str(), req.url(), &body);
+        (recorder, index, request)
+    });
 
     // Build headers for upstream, forwarding everything from the incoming
     // request except Authorization (we replace it below).
@@ -173,6 +274,7 @@ fn forward_request(client: &Client, auth_header: &'static str, mut req: Request)
     // `tiny_http::Response`.
     let status = upstream_resp.status();
     let mut response_headers = Vec::new();
+    let mut recorded_headers = Vec::new();
     for (name, value) in upstream_resp.headers().iter() {
         // Skip headers that tiny_http manages itself.
         if matches!(
@@ -185,6 +287,9 @@ fn forward_request(client: &Client, auth_header: &'static str, mut req: Request)
         if let Ok(header) = Header::from_bytes(name.as_str().as_bytes(), value.as_bytes()) {
             response_headers.push(header);
         }
+        if let Ok(value) = value.to_str() {
+            recorded_headers.push((name.as_str().to_string(), value.to_string()));
+        }
     }
 
     let content_length = upstream_resp.content_length().and_then(|len| {
@@ -195,10 +300,29 @@ fn forward_request(client: &Client, auth_header: &'static str, mut req: Request)
         }
     });
 
+    let response_body: Box<dyn Read + Send> = match recording {
+        Some((recorder, index, request)) => {
+            Box::new(TeeReader::new(upstream_resp, move |captured: &[u8]| {
+                let interaction = Interaction {
+                    request,
+                    response: RecordedResponse {
+                        status: status.as_u16(),
+                        headers: recorded_headers,
+                        body: String::from_utf8_lossy(captured).into_owned(),
+                    },
+                };
+                if let Err(e) = recorder.record(index, interaction) {
+                    eprintln!("recording error: {e}");
+                }
+            }))
+        }
+        None => Box::new(upstream_resp),
+    };
+
     let response = Response::new(
         StatusCode(status.as_u16()),
         response_headers,
-        upstream_resp,
+        response_body,
         content_length,
         None,
     );
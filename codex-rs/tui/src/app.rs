@@ -22,6 +22,7 @@ use codex_core::model_family::find_family_for_model;
 use codex_core::protocol::SessionSource;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_git_tooling::SessionWorktree;
 use codex_protocol::ConversationId;
 use color_eyre::eyre::Result;
 use color_eyre::eyre::WrapErr;
@@ -45,6 +46,8 @@ pub struct AppExitInfo {
     pub token_usage: TokenUsage,
     pub conversation_id: Option<ConversationId>,
     pub update_action: Option<UpdateAction>,
+    /// Worktree the session ran in, when started with `--worktree`.
+    pub session_worktree: Option<SessionWorktree>,
 }
 
 pub(crate) struct App {
@@ -178,6 +181,7 @@ impl App {
             token_usage: app.token_usage(),
             conversation_id: app.chat_widget.conversation_id(),
             update_action: app.pending_update_action,
+            session_worktree: None,
         })
     }
 
@@ -379,6 +383,12 @@ impl App {
             AppEvent::UpdateSandboxPolicy(policy) => {
                 self.chat_widget.set_sandbox_policy(policy);
             }
+            AppEvent::RevokeApprovalRule(id) => {
+                self.chat_widget.revoke_approval_rule(&id);
+            }
+            AppEvent::RestoreTurnSnapshot(position) => {
+                self.chat_widget.restore_turn_snapshot(position);
+            }
             AppEvent::OpenReviewBranchPicker(cwd) => {
                 self.chat_widget.show_review_branch_picker(&cwd).await;
             }
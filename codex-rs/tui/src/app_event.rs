@@ -73,6 +73,13 @@ pub(crate) enum AppEvent {
     /// Update the current sandbox policy in the running app and widget.
     UpdateSandboxPolicy(SandboxPolicy),
 
+    /// Delete a persisted approval rule by id.
+    RevokeApprovalRule(String),
+
+    /// Restore the workspace to before the given turn of the undo history, or
+    /// to the latest state if the index is past the last turn.
+    RestoreTurnSnapshot(usize),
+
     /// Forwarded conversation history snapshot from the current conversation.
     ConversationHistory(ConversationPathResponseEvent),
 
@@ -29,8 +29,12 @@ use super::footer::toggle_shortcut_mode;
 use super::paste_burst::CharDecision;
 use super::paste_burst::PasteBurst;
 use crate::bottom_pane::paste_burst::FlushResult;
+use crate::bottom_pane::prompt_args::McpPromptCommand;
+use crate::bottom_pane::prompt_args::McpPromptInvocation;
+use crate::bottom_pane::prompt_args::command_with_arg_placeholders;
 use crate::bottom_pane::prompt_args::expand_custom_prompt;
 use crate::bottom_pane::prompt_args::expand_if_numeric_with_positional_args;
+use crate::bottom_pane::prompt_args::parse_mcp_prompt_command;
 use crate::bottom_pane::prompt_args::parse_slash_name;
 use crate::bottom_pane::prompt_args::prompt_argument_names;
 use crate::bottom_pane::prompt_args::prompt_command_with_arg_placeholders;
@@ -49,6 +53,7 @@ use crate::clipboard_paste::normalize_pasted_path;
 use crate::clipboard_paste::pasted_image_format;
 use crate::history_cell;
 use crate::ui_consts::LIVE_PREFIX_COLS;
+use codex_common::fuzzy_match::fuzzy_match;
 use codex_file_search::FileMatch;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -66,6 +71,14 @@ const LARGE_PASTE_CHAR_THRESHOLD: usize = 1000;
 pub enum InputResult {
     Submitted(String),
     Command(SlashCommand),
+    /// A built-in command followed by arguments, e.g. `/undo 3`.
+    CommandWithArgs(SlashCommand, String),
+    /// A prompt exposed by an MCP server, e.g. `/mcp:docs:summarize topic=auth`.
+    /// `command` is the text the user submitted.
+    McpPrompt {
+        command: String,
+        invocation: McpPromptInvocation,
+    },
     None,
 }
 
@@ -106,6 +119,10 @@ pub(crate) struct ChatComposer {
     // When true, disables paste-burst logic and inserts characters immediately.
     disable_paste_burst: bool,
     custom_prompts: Vec<CustomPrompt>,
+    mcp_prompts: Vec<McpPromptCommand>,
+    /// Server name -> URIs of the resources it exposes. Servers that only
+    /// expose resource templates are present with an empty list.
+    mcp_resources: HashMap<String, Vec<String>>,
     footer_mode: FooterMode,
     footer_hint_override: Option<Vec<(String, String)>>,
     context_window_percent: Option<u8>,
@@ -149,6 +166,8 @@ impl ChatComposer {
             paste_burst: PasteBurst::default(),
             disable_paste_burst: false,
             custom_prompts: Vec::new(),
+            mcp_prompts: Vec::new(),
+            mcp_resources: HashMap::new(),
             footer_mode: FooterMode::ShortcutSummary,
             footer_hint_override: None,
             context_window_percent: None,
@@ -490,6 +509,16 @@ impl ChatComposer {
                                 }
                             }
                         }
+                        CommandItem::McpPrompt(idx) => {
+                            if let Some(command) = popup.mcp_prompt(idx) {
+                                let (text, cursor) = command_with_arg_placeholders(
+                                    &command.command_name(),
+                                    &command.argument_names(),
+                                );
+                                self.textarea.set_text(&text);
+                                cursor_target = Some(cursor);
+                            }
+                        }
                     }
                     if let Some(pos) = cursor_target {
                         self.textarea.set_cursor(pos);
@@ -519,8 +548,9 @@ impl ChatComposer {
                 if let Some(sel) = popup.selected_item() {
                     match sel {
                         CommandItem::Builtin(cmd) => {
+                            let result = builtin_command_result(cmd, first_line);
                             self.textarea.set_text("");
-                            return (InputResult::Command(cmd), true);
+                            return (result, true);
                         }
                         CommandItem::UserPrompt(idx) => {
                             if let Some(prompt) = popup.prompt(idx) {
@@ -543,6 +573,28 @@ impl ChatComposer {
                             }
                             return (InputResult::None, true);
                         }
+                        CommandItem::McpPrompt(idx) => {
+                            let Some(command) = popup.mcp_prompt(idx) else {
+                                return (InputResult::None, true);
+                            };
+                            let name = command.command_name();
+                            let args = command.argument_names();
+                            let typed_command = parse_slash_name(first_line)
+                                .is_some_and(|(typed, _)| typed == name);
+                            if !typed_command {
+                                if !args.is_empty() {
+                                    let (text, cursor) =
+                                        command_with_arg_placeholders(&name, &args);
+                                    self.textarea.set_text(&text);
+                                    self.textarea.set_cursor(cursor);
+                                    return (InputResult::None, true);
+                                }
+                                self.textarea.set_text(&format!("/{name}"));
+                            }
+                            // Submit through the regular path so arguments are validated.
+                            self.active_popup = ActivePopup::None;
+                            return self.handle_key_event_without_popup(key_event);
+                        }
                     }
                 }
                 // Fallback to default newline handling if no command selected.
@@ -908,22 +960,23 @@ impl ChatComposer {
                 modifiers: KeyModifiers::NONE,
                 ..
             } => {
-                // If the first line is a bare built-in slash command (no args),
-                // dispatch it even when the slash popup isn't visible. This preserves
-                // the workflow: type a prefix ("/di"), press Tab to complete to
-                // "/diff ", then press Enter to run it. Tab moves the cursor beyond
-                // the '/name' token and our caret-based heuristic hides the popup,
-                // but Enter should still dispatch the command rather than submit
-                // literal text.
+                // If the first line is a bare built-in slash command (no args, or
+                // args for a command that accepts them), dispatch it even when the
+                // slash popup isn't visible. This preserves the workflow: type a
+                // prefix ("/di"), press Tab to complete to "/diff ", then press
+                // Enter to run it. Tab moves the cursor beyond the '/name' token
+                // and our caret-based heuristic hides the popup, but Enter should
+                // still dispatch the command rather than submit literal text.
                 let first_line = self.textarea.text().lines().next().unwrap_or("");
                 if let Some((name, rest)) = parse_slash_name(first_line)
-                    && rest.is_empty()
                     && let Some((_n, cmd)) = built_in_slash_commands()
                         .into_iter()
                         .find(|(n, _)| *n == name)
+                    && (rest.is_empty() || cmd.takes_args())
                 {
+                    let result = builtin_command_result(cmd, first_line);
                     self.textarea.set_text("");
-                    return (InputResult::Command(cmd), true);
+                    return (result, true);
                 }
                 // If we're in a paste-like burst capture, treat Enter as part of the burst
                 // and accumulate it rather than submitting or inserting immediately.
@@ -1001,7 +1054,11 @@ impl ChatComposer {
                                     .any(|prompt| prompt.name == prompt_name)
                             })
                             .unwrap_or(false);
-                        if !is_builtin && !is_known_prompt {
+                        let is_mcp_prompt = self
+                            .mcp_prompts
+                            .iter()
+                            .any(|prompt| prompt.command_name() == name);
+                        if !is_builtin && !is_known_prompt && !is_mcp_prompt {
                             let message = format!(
                                 r#"Unrecognized command '/{name}'. Type "/" for a list of supported commands."#
                             );
@@ -1015,6 +1072,28 @@ impl ChatComposer {
                     }
                 }
 
+                match parse_mcp_prompt_command(&text, &self.mcp_prompts) {
+                    Ok(Some(invocation)) => {
+                        self.history.record_local_submission(&text);
+                        return (
+                            InputResult::McpPrompt {
+                                command: text,
+                                invocation,
+                            },
+                            true,
+                        );
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                            history_cell::new_error_event(err.user_message()),
+                        )));
+                        self.textarea.set_text(&original_input);
+                        self.textarea.set_cursor(original_input.len());
+                        return (InputResult::None, true);
+                    }
+                }
+
                 let expanded_prompt = match expand_custom_prompt(&text, &self.custom_prompts) {
                     Ok(expanded) => expanded,
                     Err(err) => {
@@ -1443,6 +1522,7 @@ impl ChatComposer {
             _ => {
                 if is_editing_slash_command_name {
                     let mut command_popup = CommandPopup::new(self.custom_prompts.clone());
+                    command_popup.set_mcp_prompts(self.mcp_prompts.clone());
                     command_popup.on_composer_text_change(first_line.to_string());
                     self.active_popup = ActivePopup::Command(command_popup);
                 }
@@ -1457,6 +1537,79 @@ impl ChatComposer {
         }
     }
 
+    pub(crate) fn set_mcp_prompts(&mut self, prompts: Vec<McpPromptCommand>) {
+        self.mcp_prompts = prompts.clone();
+        if let ActivePopup::Command(popup) = &mut self.active_popup {
+            popup.set_mcp_prompts(prompts);
+        }
+    }
+
+    pub(crate) fn set_mcp_resources(&mut self, resources: HashMap<String, Vec<String>>) {
+        self.mcp_resources = resources;
+    }
+
+    /// Returns the `(server, uri)` pairs referenced as `@server:uri` in `text`
+    /// for servers that expose resources.
+    pub(crate) fn mcp_resource_mentions(&self, text: &str) -> Vec<(String, String)> {
+        let mut mentions: Vec<(String, String)> = Vec::new();
+        for token in text.split_whitespace() {
+            let Some((server, uri)) = token
+                .strip_prefix('@')
+                .and_then(|mention| mention.split_once(':'))
+            else {
+                continue;
+            };
+            // Allow mentions at the end of a sentence, e.g. "see @docs:readme."
+            let uri = uri.trim_end_matches(['.', ',', ';', '!', '?']);
+            if uri.is_empty() || !self.mcp_resources.contains_key(server) {
+                continue;
+            }
+            let mention = (server.to_string(), uri.to_string());
+            if !mentions.contains(&mention) {
+                mentions.push(mention);
+            }
+        }
+        mentions
+    }
+
+    /// Completions for an `@server:` token, or `None` if `query` does not
+    /// start with the name of a server that exposes resources.
+    fn mcp_resource_matches(&self, query: &str) -> Option<Vec<FileMatch>> {
+        let (server, uri_query) = query.split_once(':')?;
+        let uris = self.mcp_resources.get(server)?;
+        let prefix_len = server.len() + 2;
+        let mut matches: Vec<(FileMatch, i32)> = uris
+            .iter()
+            .filter_map(|uri| {
+                let (indices, score) = if uri_query.is_empty() {
+                    (Vec::new(), 0)
+                } else {
+                    fuzzy_match(uri, uri_query)?
+                };
+                let file_match = FileMatch {
+                    score: 0,
+                    // Keep the `@server:` prefix so the inserted token still
+                    // refers to the resource.
+                    path: format!("@{server}:{uri}"),
+                    indices: Some(
+                        indices
+                            .into_iter()
+                            .map(|i| (i + prefix_len) as u32)
+                            .collect(),
+                    ),
+                };
+                Some((file_match, score))
+            })
+            .collect();
+        matches.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.path.cmp(&b.0.path)));
+        Some(
+            matches
+                .into_iter()
+                .map(|(file_match, _)| file_match)
+                .collect(),
+        )
+    }
+
     /// Synchronize `self.file_search_popup` with the current text in the textarea.
     /// Note this is only called when self.active_popup is NOT Command.
     fn sync_file_search_popup(&mut self) {
@@ -1475,7 +1628,9 @@ impl ChatComposer {
             return;
         }
 
-        if !query.is_empty() {
+        // `@server:` tokens complete against MCP resources instead of files.
+        let resource_matches = self.mcp_resource_matches(&query);
+        if !query.is_empty() && resource_matches.is_none() {
             self.app_event_tx
                 .send(AppEvent::StartFileSearch(query.clone()));
         }
@@ -1498,6 +1653,11 @@ impl ChatComposer {
                 self.active_popup = ActivePopup::File(popup);
             }
         }
+        if let (Some(matches), ActivePopup::File(popup)) =
+            (resource_matches, &mut self.active_popup)
+        {
+            popup.set_matches(&query, matches);
+        }
 
         self.current_file_query = Some(query);
         self.dismissed_file_popup_token = None;
@@ -1597,6 +1757,20 @@ impl WidgetRef for ChatComposer {
     }
 }
 
+/// Builds the result for dispatching `cmd`, passing along any arguments typed
+/// after `/name` on `first_line` if the command accepts them.
+fn builtin_command_result(cmd: SlashCommand, first_line: &str) -> InputResult {
+    let args = parse_slash_name(first_line)
+        .filter(|(name, _)| *name == cmd.command())
+        .map(|(_, rest)| rest.trim_end())
+        .unwrap_or_default();
+    if args.is_empty() || !cmd.takes_args() {
+        InputResult::Command(cmd)
+    } else {
+        InputResult::CommandWithArgs(cmd, args.to_string())
+    }
+}
+
 fn prompt_selection_action(
     prompt: &CustomPrompt,
     first_line: &str,
@@ -2268,7 +2442,7 @@ mod tests {
                 Some(CommandItem::Builtin(cmd)) => {
                     assert_eq!(cmd.command(), "model")
                 }
-                Some(CommandItem::UserPrompt(_)) => {
+                Some(CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_)) => {
                     panic!("unexpected prompt selected for '/mo'")
                 }
                 None => panic!("no selected command for '/mo'"),
@@ -2321,6 +2495,15 @@ mod tests {
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
+            InputResult::CommandWithArgs(cmd, args) => {
+                panic!(
+                    "expected '/init' without args, got /{} {args}",
+                    cmd.command()
+                )
+            }
+            InputResult::McpPrompt { command, .. } => {
+                panic!("expected command dispatch, got MCP prompt {command}")
+            }
             InputResult::None => panic!("expected Command result for '/init'"),
         }
         assert!(composer.textarea.is_empty(), "composer should be cleared");
@@ -2394,6 +2577,15 @@ mod tests {
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch after Tab completion, got literal submit: {text}")
             }
+            InputResult::CommandWithArgs(cmd, args) => {
+                panic!(
+                    "expected '/diff' without args, got /{} {args}",
+                    cmd.command()
+                )
+            }
+            InputResult::McpPrompt { command, .. } => {
+                panic!("expected command dispatch, got MCP prompt {command}")
+            }
             InputResult::None => panic!("expected Command result for '/diff'"),
         }
         assert!(composer.textarea.is_empty());
@@ -2427,6 +2619,15 @@ mod tests {
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
+            InputResult::CommandWithArgs(cmd, args) => {
+                panic!(
+                    "expected '/mention' without args, got /{} {args}",
+                    cmd.command()
+                )
+            }
+            InputResult::McpPrompt { command, .. } => {
+                panic!("expected command dispatch, got MCP prompt {command}")
+            }
             InputResult::None => panic!("expected Command result for '/mention'"),
         }
         assert!(composer.textarea.is_empty(), "composer should be cleared");
@@ -2434,6 +2635,22 @@ mod tests {
         assert_eq!(composer.textarea.text(), "@");
     }
 
+    #[test]
+    fn builtin_command_args_are_passed_only_to_commands_that_take_them() {
+        assert_eq!(
+            builtin_command_result(SlashCommand::Undo, "/undo 3 "),
+            InputResult::CommandWithArgs(SlashCommand::Undo, "3".to_string())
+        );
+        assert_eq!(
+            builtin_command_result(SlashCommand::Undo, "/undo"),
+            InputResult::Command(SlashCommand::Undo)
+        );
+        assert_eq!(
+            builtin_command_result(SlashCommand::Diff, "/diff --stat"),
+            InputResult::Command(SlashCommand::Diff)
+        );
+    }
+
     #[test]
     fn test_multiple_pastes_submission() {
         use crossterm::event::KeyCode;
@@ -2912,6 +3129,93 @@ mod tests {
         assert!(composer.textarea.is_empty());
     }
 
+    #[test]
+    fn mcp_prompt_submission_returns_invocation() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+
+        composer.set_mcp_prompts(vec![McpPromptCommand {
+            server: "docs".to_string(),
+            prompt: mcp_types::Prompt {
+                arguments: Some(vec![mcp_types::PromptArgument {
+                    description: None,
+                    name: "topic".to_string(),
+                    required: Some(true),
+                    title: None,
+                }]),
+                description: None,
+                name: "summarize".to_string(),
+                title: None,
+            },
+        }]);
+
+        // Selecting the prompt from the popup inserts argument placeholders.
+        type_chars_humanlike(&mut composer, &['/', 'm', 'c', 'p', ':']);
+        composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(composer.textarea.text(), "/mcp:docs:summarize topic=\"\"");
+
+        composer.textarea.set_text("/mcp:docs:summarize topic=auth");
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert_eq!(
+            InputResult::McpPrompt {
+                command: "/mcp:docs:summarize topic=auth".to_string(),
+                invocation: McpPromptInvocation {
+                    server: "docs".to_string(),
+                    name: "summarize".to_string(),
+                    arguments: HashMap::from([("topic".to_string(), "auth".to_string())]),
+                },
+            },
+            result
+        );
+        assert!(composer.textarea.is_empty());
+    }
+
+    #[test]
+    fn mcp_resource_mentions_complete_and_resolve() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        composer.set_mcp_resources(HashMap::from([(
+            "docs".to_string(),
+            vec![
+                "docs://guides/setup".to_string(),
+                "docs://schema/user".to_string(),
+            ],
+        )]));
+
+        type_chars_humanlike(
+            &mut composer,
+            &['@', 'd', 'o', 'c', 's', ':', 's', 'c', 'h'],
+        );
+        composer.handle_key_event(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+        assert_eq!(composer.textarea.text(), "@docs:docs://schema/user ");
+
+        assert_eq!(
+            composer.mcp_resource_mentions(
+                "compare @docs:docs://schema/user with @docs:docs://guides/setup. @other:x"
+            ),
+            vec![
+                ("docs".to_string(), "docs://schema/user".to_string()),
+                ("docs".to_string(), "docs://guides/setup".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn custom_prompt_submission_accepts_quoted_values() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
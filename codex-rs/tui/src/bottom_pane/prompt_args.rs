@@ -1,6 +1,7 @@
 use codex_protocol::custom_prompts::CustomPrompt;
 use codex_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
 use lazy_static::lazy_static;
+use mcp_types::Prompt as McpPrompt;
 use regex_lite::Regex;
 use shlex::Shlex;
 use std::collections::HashMap;
@@ -11,6 +12,55 @@ lazy_static! {
         Regex::new(r"\$[A-Z][A-Z0-9_]*").unwrap_or_else(|_| std::process::abort());
 }
 
+/// Prefix for slash commands that invoke prompts exposed by MCP servers,
+/// e.g. `/mcp:docs:summarize`.
+pub const MCP_PROMPT_CMD_PREFIX: &str = "mcp";
+
+/// A prompt exposed by an MCP server, offered as a slash command.
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpPromptCommand {
+    pub server: String,
+    pub prompt: McpPrompt,
+}
+
+impl McpPromptCommand {
+    /// Command name without the leading `/`, e.g. `mcp:docs:summarize`.
+    pub fn command_name(&self) -> String {
+        format!(
+            "{MCP_PROMPT_CMD_PREFIX}:{}:{}",
+            self.server, self.prompt.name
+        )
+    }
+
+    /// Names of the arguments the prompt accepts, in declaration order.
+    pub fn argument_names(&self) -> Vec<String> {
+        self.prompt
+            .arguments
+            .iter()
+            .flatten()
+            .map(|argument| argument.name.clone())
+            .collect()
+    }
+
+    fn required_argument_names(&self) -> Vec<String> {
+        self.prompt
+            .arguments
+            .iter()
+            .flatten()
+            .filter(|argument| argument.required.unwrap_or(false))
+            .map(|argument| argument.name.clone())
+            .collect()
+    }
+}
+
+/// A parsed `/mcp:server:prompt key=value …` invocation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpPromptInvocation {
+    pub server: String,
+    pub name: String,
+    pub arguments: HashMap<String, String>,
+}
+
 #[derive(Debug)]
 pub enum PromptArgsError {
     MissingAssignment { token: String },
@@ -192,6 +242,44 @@ pub fn expand_custom_prompt(
     Ok(Some(expanded))
 }
 
+/// Parses a message of the form `/mcp:server:prompt key=value …` that refers to
+/// one of `mcp_prompts`.
+///
+/// Returns `Ok(None)` if the text does not name a known MCP prompt, and an
+/// error if the arguments are malformed or a required argument is missing.
+pub fn parse_mcp_prompt_command(
+    text: &str,
+    mcp_prompts: &[McpPromptCommand],
+) -> Result<Option<McpPromptInvocation>, PromptExpansionError> {
+    let Some((name, rest)) = parse_slash_name(text) else {
+        return Ok(None);
+    };
+    let Some(command) = mcp_prompts.iter().find(|p| p.command_name() == name) else {
+        return Ok(None);
+    };
+
+    let arguments = parse_prompt_inputs(rest).map_err(|error| PromptExpansionError::Args {
+        command: format!("/{name}"),
+        error,
+    })?;
+    let missing: Vec<String> = command
+        .required_argument_names()
+        .into_iter()
+        .filter(|k| !arguments.contains_key(k))
+        .collect();
+    if !missing.is_empty() {
+        return Err(PromptExpansionError::MissingArgs {
+            command: format!("/{name}"),
+            missing,
+        });
+    }
+    Ok(Some(McpPromptInvocation {
+        server: command.server.clone(),
+        name: command.prompt.name.clone(),
+        arguments,
+    }))
+}
+
 /// Detect whether `content` contains numeric placeholders ($1..$9) or `$ARGUMENTS`.
 pub fn prompt_has_numeric_placeholders(content: &str) -> bool {
     if content.contains("$ARGUMENTS") {
@@ -299,7 +387,13 @@ pub fn expand_numeric_placeholders(content: &str, args: &[String]) -> String {
 /// Constructs a command text for a custom prompt with arguments.
 /// Returns the text and the cursor position (inside the first double quote).
 pub fn prompt_command_with_arg_placeholders(name: &str, args: &[String]) -> (String, usize) {
-    let mut text = format!("/{PROMPTS_CMD_PREFIX}:{name}");
+    command_with_arg_placeholders(&format!("{PROMPTS_CMD_PREFIX}:{name}"), args)
+}
+
+/// Constructs `/command arg="" …` for any slash command name.
+/// Returns the text and the cursor position (inside the first double quote).
+pub fn command_with_arg_placeholders(command: &str, args: &[String]) -> (String, usize) {
+    let mut text = format!("/{command}");
     let mut cursor: usize = text.len();
     for (i, arg) in args.iter().enumerate() {
         text.push_str(format!(" {arg}=\"\"").as_str());
@@ -403,4 +497,58 @@ mod tests {
         let out = expand_custom_prompt("/prompts:my-prompt", &prompts).unwrap();
         assert_eq!(out, Some("literal $$USER".to_string()));
     }
+
+    fn summarize_command() -> McpPromptCommand {
+        McpPromptCommand {
+            server: "docs".to_string(),
+            prompt: McpPrompt {
+                arguments: Some(vec![
+                    mcp_types::PromptArgument {
+                        description: None,
+                        name: "topic".to_string(),
+                        required: Some(true),
+                        title: None,
+                    },
+                    mcp_types::PromptArgument {
+                        description: None,
+                        name: "style".to_string(),
+                        required: None,
+                        title: None,
+                    },
+                ]),
+                description: None,
+                name: "summarize".to_string(),
+                title: None,
+            },
+        }
+    }
+
+    #[test]
+    fn mcp_prompt_command_parses_arguments() {
+        let commands = vec![summarize_command()];
+        let out =
+            parse_mcp_prompt_command("/mcp:docs:summarize topic=\"auth flow\"", &commands).unwrap();
+        assert_eq!(
+            out,
+            Some(McpPromptInvocation {
+                server: "docs".to_string(),
+                name: "summarize".to_string(),
+                arguments: HashMap::from([("topic".to_string(), "auth flow".to_string())]),
+            })
+        );
+        assert_eq!(
+            parse_mcp_prompt_command("/mcp:docs:unknown", &commands).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn mcp_prompt_command_requires_required_arguments() {
+        let commands = vec![summarize_command()];
+        let err = parse_mcp_prompt_command("/mcp:docs:summarize style=short", &commands)
+            .unwrap_err()
+            .user_message();
+        assert!(err.contains("/mcp:docs:summarize"));
+        assert!(err.contains("topic"));
+    }
 }
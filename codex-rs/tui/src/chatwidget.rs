@@ -3,6 +3,9 @@ use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use codex_core::approval_rules::ApprovalScope;
+use codex_core::approval_rules::load_approval_rules;
+use codex_core::approval_rules::remove_approval_rule;
 use codex_core::config::Config;
 use codex_core::config_types::Notifications;
 use codex_core::git_info::current_branch_name;
@@ -16,6 +19,7 @@ use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::ContextBreakdown;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
@@ -26,6 +30,8 @@ use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::InputItem;
 use codex_core::protocol::InputMessageKind;
 use codex_core::protocol::ListCustomPromptsResponseEvent;
+use codex_core::protocol::McpListPromptsResponseEvent;
+use codex_core::protocol::McpListResourcesResponseEvent;
 use codex_core::protocol::McpListToolsResponseEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
@@ -33,7 +39,11 @@ use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::ReviewRequest;
+use codex_core::protocol::ReviewTarget;
 use codex_core::protocol::StreamErrorEvent;
+use codex_core::protocol::SubAgentBeginEvent;
+use codex_core::protocol::SubAgentEndEvent;
+use codex_core::protocol::SubAgentEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
@@ -68,6 +78,8 @@ use crate::bottom_pane::BottomPane;
 use crate::bottom_pane::BottomPaneParams;
 use crate::bottom_pane::CancellationEvent;
 use crate::bottom_pane::InputResult;
+use crate::bottom_pane::McpPromptCommand;
+use crate::bottom_pane::McpPromptInvocation;
 use crate::bottom_pane::SelectionAction;
 use crate::bottom_pane::SelectionItem;
 use crate::bottom_pane::SelectionViewParams;
@@ -78,6 +90,7 @@ use crate::diff_render::display_path_for;
 use crate::exec_cell::CommandOutput;
 use crate::exec_cell::ExecCell;
 use crate::exec_cell::new_active_exec_command;
+use crate::exec_command::strip_bash_lc_and_escape;
 use crate::get_git_diff::get_git_diff;
 use crate::history_cell;
 use crate::history_cell::AgentMessageCell;
@@ -86,6 +99,7 @@ use crate::history_cell::McpToolCallCell;
 use crate::markdown::append_markdown;
 use crate::render::renderable::ColumnRenderable;
 use crate::slash_command::SlashCommand;
+use crate::slash_command::beta_features_enabled;
 use crate::status::RateLimitSnapshotDisplay;
 use crate::text_formatting::truncate_text;
 use crate::tui::FrameRequester;
@@ -110,15 +124,12 @@ use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
 use codex_file_search::FileMatch;
-use codex_git_tooling::CreateGhostCommitOptions;
-use codex_git_tooling::GhostCommit;
 use codex_git_tooling::GitToolingError;
-use codex_git_tooling::create_ghost_commit;
-use codex_git_tooling::restore_ghost_commit;
+use codex_git_tooling::UndoHistory;
 use codex_protocol::plan_tool::UpdatePlanArgs;
 use strum::IntoEnumIterator;
 
-const MAX_TRACKED_GHOST_COMMITS: usize = 20;
+const MAX_TRACKED_GHOST_COMMITS: usize = 100;
 
 // Track information about an in-flight exec command.
 struct RunningCommand {
@@ -232,6 +243,8 @@ pub(crate) struct ChatWidget {
     session_header: SessionHeader,
     initial_user_message: Option<UserMessage>,
     token_info: Option<TokenUsageInfo>,
+    // Estimated composition of the last request, shown by /status.
+    context_breakdown: Option<ContextBreakdown>,
     rate_limit_snapshot: Option<RateLimitSnapshotDisplay>,
     rate_limit_warnings: RateLimitWarningState,
     // Stream lifecycle controller
@@ -261,8 +274,8 @@ pub(crate) struct ChatWidget {
     pending_notification: Option<Notification>,
     // Simple review mode flag; used to adjust layout and banners.
     is_review_mode: bool,
-    // List of ghost commits corresponding to each turn.
-    ghost_snapshots: Vec<GhostCommit>,
+    // Ghost commits taken at the start of each turn, for `/undo` and `/redo`.
+    undo_history: UndoHistory,
     ghost_snapshots_disabled: bool,
     // Whether to add a final message separator after the last message
     needs_final_message_separator: bool,
@@ -273,6 +286,8 @@ pub(crate) struct ChatWidget {
 struct UserMessage {
     text: String,
     image_paths: Vec<PathBuf>,
+    /// Set when `text` invokes an MCP server prompt.
+    mcp_prompt: Option<McpPromptInvocation>,
 }
 
 impl From<String> for UserMessage {
@@ -280,6 +295,7 @@ impl From<String> for UserMessage {
         Self {
             text,
             image_paths: Vec::new(),
+            mcp_prompt: None,
         }
     }
 }
@@ -288,7 +304,11 @@ fn create_initial_user_message(text: String, image_paths: Vec<PathBuf>) -> Optio
     if text.is_empty() && image_paths.is_empty() {
         None
     } else {
-        Some(UserMessage { text, image_paths })
+        Some(UserMessage {
+            text,
+            image_paths,
+            mcp_prompt: None,
+        })
     }
 }
 
@@ -337,6 +357,11 @@ impl ChatWidget {
         }
         // Ask codex-core to enumerate custom prompts for this session.
         self.submit_op(Op::ListCustomPrompts);
+        if !self.config.mcp_servers.is_empty() {
+            // Ask for MCP resources and prompts so they can be offered in the composer.
+            self.submit_op(Op::ListMcpResources);
+            self.submit_op(Op::ListMcpPrompts);
+        }
         if let Some(user_message) = self.initial_user_message.take() {
             self.submit_user_message(user_message);
         }
@@ -578,6 +603,40 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn on_sub_agent_begin(&mut self, event: SubAgentBeginEvent) {
+        self.flush_answer_stream_with_separator();
+        self.add_to_history(history_cell::new_sub_agent_begin(&event.prompt));
+        self.set_status_header(String::from("Running sub-agent"));
+        self.request_redraw();
+    }
+
+    /// Nested events are not rendered individually; the status header tracks
+    /// what the sub-agent is currently doing.
+    fn on_sub_agent_event(&mut self, event: SubAgentEvent) {
+        let header = match *event.msg {
+            EventMsg::ExecCommandBegin(ev) => format!(
+                "Sub-agent running {}",
+                strip_bash_lc_and_escape(&ev.command)
+            ),
+            EventMsg::McpToolCallBegin(ev) => format!(
+                "Sub-agent calling {}.{}",
+                ev.invocation.server, ev.invocation.tool
+            ),
+            EventMsg::PatchApplyBegin(_) => String::from("Sub-agent editing files"),
+            _ => return,
+        };
+        self.set_status_header(header);
+    }
+
+    fn on_sub_agent_end(&mut self, event: SubAgentEndEvent) {
+        self.add_to_history(history_cell::new_sub_agent_end(
+            event.success,
+            event.last_agent_message.as_deref(),
+        ));
+        self.set_status_header(String::from("Working"));
+        self.request_redraw();
+    }
+
     fn on_patch_apply_end(&mut self, event: codex_core::protocol::PatchApplyEndEvent) {
         let ev2 = event.clone();
         self.defer_or_handle(
@@ -939,6 +998,7 @@ impl ChatWidget {
                 initial_images,
             ),
             token_info: None,
+            context_breakdown: None,
             rate_limit_snapshot: None,
             rate_limit_warnings: RateLimitWarningState::default(),
             stream_controller: None,
@@ -955,8 +1015,8 @@ impl ChatWidget {
             suppress_session_configured_redraw: false,
             pending_notification: None,
             is_review_mode: false,
-            ghost_snapshots: Vec::new(),
-            ghost_snapshots_disabled: true,
+            undo_history: UndoHistory::new(config.cwd, MAX_TRACKED_GHOST_COMMITS),
+            ghost_snapshots_disabled: !beta_features_enabled(),
             needs_final_message_separator: false,
             last_rendered_width: std::cell::Cell::new(None),
         }
@@ -1004,6 +1064,7 @@ impl ChatWidget {
                 initial_images,
             ),
             token_info: None,
+            context_breakdown: None,
             rate_limit_snapshot: None,
             rate_limit_warnings: RateLimitWarningState::default(),
             stream_controller: None,
@@ -1020,8 +1081,8 @@ impl ChatWidget {
             suppress_session_configured_redraw: true,
             pending_notification: None,
             is_review_mode: false,
-            ghost_snapshots: Vec::new(),
-            ghost_snapshots_disabled: true,
+            undo_history: UndoHistory::new(config.cwd, MAX_TRACKED_GHOST_COMMITS),
+            ghost_snapshots_disabled: !beta_features_enabled(),
             needs_final_message_separator: false,
             last_rendered_width: std::cell::Cell::new(None),
         }
@@ -1084,17 +1145,27 @@ impl ChatWidget {
                         let user_message = UserMessage {
                             text,
                             image_paths: self.bottom_pane.take_recent_submission_images(),
+                            mcp_prompt: None,
                         };
-                        if self.bottom_pane.is_task_running() {
-                            self.queued_user_messages.push_back(user_message);
-                            self.refresh_queued_user_messages();
-                        } else {
-                            self.submit_user_message(user_message);
-                        }
+                        self.submit_or_queue_user_message(user_message);
+                    }
+                    InputResult::McpPrompt {
+                        command,
+                        invocation,
+                    } => {
+                        let user_message = UserMessage {
+                            text: command,
+                            image_paths: Vec::new(),
+                            mcp_prompt: Some(invocation),
+                        };
+                        self.submit_or_queue_user_message(user_message);
                     }
                     InputResult::Command(cmd) => {
                         self.dispatch_command(cmd);
                     }
+                    InputResult::CommandWithArgs(cmd, args) => {
+                        self.dispatch_command_with_args(cmd, &args);
+                    }
                     InputResult::None => {}
                 }
             }
@@ -1116,7 +1187,8 @@ impl ChatWidget {
         self.request_redraw();
     }
 
-    fn dispatch_command(&mut self, cmd: SlashCommand) {
+    /// Reports an error and returns `false` if `cmd` cannot run right now.
+    fn ensure_command_available(&mut self, cmd: SlashCommand) -> bool {
         if !cmd.available_during_task() && self.bottom_pane.is_task_running() {
             let message = format!(
                 "'/{}' is disabled while a task is in progress.",
@@ -1124,6 +1196,13 @@ impl ChatWidget {
             );
             self.add_to_history(history_cell::new_error_event(message));
             self.request_redraw();
+            return false;
+        }
+        true
+    }
+
+    fn dispatch_command(&mut self, cmd: SlashCommand) {
+        if !self.ensure_command_available(cmd) {
             return;
         }
         match cmd {
@@ -1144,7 +1223,16 @@ impl ChatWidget {
             }
             SlashCommand::Compact => {
                 self.clear_token_usage();
-                self.app_event_tx.send(AppEvent::CodexOp(Op::Compact));
+                self.app_event_tx
+                    .send(AppEvent::CodexOp(Op::Compact { instructions: None }));
+            }
+            SlashCommand::Pin => {
+                self.app_event_tx
+                    .send(AppEvent::CodexOp(Op::Pin { text: None }));
+                self.add_info_message(
+                    "Pinned the last response; compaction will keep it verbatim.".to_string(),
+                    None,
+                );
             }
             SlashCommand::Review => {
                 self.open_review_popup();
@@ -1155,6 +1243,9 @@ impl ChatWidget {
             SlashCommand::Approvals => {
                 self.open_approvals_popup();
             }
+            SlashCommand::Rules => {
+                self.open_approval_rules_popup();
+            }
             SlashCommand::Quit => {
                 self.app_event_tx.send(AppEvent::ExitRequest);
             }
@@ -1165,7 +1256,10 @@ impl ChatWidget {
                 self.app_event_tx.send(AppEvent::ExitRequest);
             }
             SlashCommand::Undo => {
-                self.undo_last_snapshot();
+                self.open_undo_timeline();
+            }
+            SlashCommand::Redo => {
+                self.redo_turns(1);
             }
             SlashCommand::Diff => {
                 self.add_diff_in_progress();
@@ -1256,6 +1350,44 @@ impl ChatWidget {
         }
     }
 
+    fn dispatch_command_with_args(&mut self, cmd: SlashCommand, args: &str) {
+        if !self.ensure_command_available(cmd) {
+            return;
+        }
+        match cmd {
+            SlashCommand::Undo | SlashCommand::Redo => {
+                let Some(steps) = args.parse::<usize>().ok().filter(|steps| *steps > 0) else {
+                    self.add_error_message(format!(
+                        "Usage: /{} [N], where N is a number of turns",
+                        cmd.command()
+                    ));
+                    return;
+                };
+                if cmd == SlashCommand::Undo {
+                    self.undo_turns(steps);
+                } else {
+                    self.redo_turns(steps);
+                }
+            }
+            SlashCommand::Compact => {
+                self.clear_token_usage();
+                self.app_event_tx.send(AppEvent::CodexOp(Op::Compact {
+                    instructions: Some(args.to_string()),
+                }));
+            }
+            SlashCommand::Pin => {
+                self.app_event_tx.send(AppEvent::CodexOp(Op::Pin {
+                    text: Some(args.to_string()),
+                }));
+                self.add_info_message(
+                    "Pinned; compaction will keep it verbatim.".to_string(),
+                    None,
+                );
+            }
+            _ => self.dispatch_command(cmd),
+        }
+    }
+
     fn flush_active_cell(&mut self) {
         if let Some(active) = self.active_cell.take() {
             self.needs_final_message_separator = true;
@@ -1276,18 +1408,47 @@ impl ChatWidget {
         self.app_event_tx.send(AppEvent::InsertHistoryCell(cell));
     }
 
+    /// If a task is running, queue the user input to be sent after the turn
+    /// completes; otherwise submit it right away.
+    fn submit_or_queue_user_message(&mut self, user_message: UserMessage) {
+        if self.bottom_pane.is_task_running() {
+            self.queued_user_messages.push_back(user_message);
+            self.refresh_queued_user_messages();
+        } else {
+            self.submit_user_message(user_message);
+        }
+    }
+
     fn submit_user_message(&mut self, user_message: UserMessage) {
-        let UserMessage { text, image_paths } = user_message;
+        let UserMessage {
+            text,
+            image_paths,
+            mcp_prompt,
+        } = user_message;
         if text.is_empty() && image_paths.is_empty() {
             return;
         }
 
-        self.capture_ghost_snapshot();
+        self.capture_ghost_snapshot(&text);
 
         let mut items: Vec<InputItem> = Vec::new();
 
-        if !text.is_empty() {
+        if let Some(McpPromptInvocation {
+            server,
+            name,
+            arguments,
+        }) = mcp_prompt
+        {
+            items.push(InputItem::McpPrompt {
+                server,
+                name,
+                arguments: (!arguments.is_empty()).then_some(arguments),
+            });
+        } else if !text.is_empty() {
             items.push(InputItem::Text { text: text.clone() });
+            for (server, uri) in self.bottom_pane.mcp_resource_mentions(&text) {
+                items.push(InputItem::McpResource { server, uri });
+            }
         }
 
         for path in image_paths {
@@ -1316,55 +1477,135 @@ impl ChatWidget {
         self.needs_final_message_separator = false;
     }
 
-    fn capture_ghost_snapshot(&mut self) {
+    fn capture_ghost_snapshot(&mut self, prompt: &str) {
         if self.ghost_snapshots_disabled {
             return;
         }
 
-        let options = CreateGhostCommitOptions::new(&self.config.cwd);
-        match create_ghost_commit(&options) {
-            Ok(commit) => {
-                self.ghost_snapshots.push(commit);
-                if self.ghost_snapshots.len() > MAX_TRACKED_GHOST_COMMITS {
-                    self.ghost_snapshots.remove(0);
-                }
-            }
-            Err(err) => {
-                self.ghost_snapshots_disabled = true;
-                let (message, hint) = match &err {
-                    GitToolingError::NotAGitRepository { .. } => (
-                        "Snapshots disabled: current directory is not a Git repository."
+        let label = prompt.lines().next().unwrap_or_default();
+        let label = if label.is_empty() { "(image)" } else { label };
+        if let Err(err) = self.undo_history.record_turn(label) {
+            self.ghost_snapshots_disabled = true;
+            let (message, hint) = match &err {
+                GitToolingError::NotAGitRepository { .. } => (
+                    "Snapshots disabled: current directory is not a Git repository.".to_string(),
+                    None,
+                ),
+                _ => (
+                    format!("Snapshots disabled after error: {err}"),
+                    Some(
+                        "Restart Codex after resolving the issue to re-enable snapshots."
                             .to_string(),
-                        None,
-                    ),
-                    _ => (
-                        format!("Snapshots disabled after error: {err}"),
-                        Some(
-                            "Restart Codex after resolving the issue to re-enable snapshots."
-                                .to_string(),
-                        ),
                     ),
-                };
-                self.add_info_message(message, hint);
-                tracing::warn!("failed to create ghost snapshot: {err}");
-            }
+                ),
+            };
+            self.add_info_message(message, hint);
+            tracing::warn!("failed to create ghost snapshot: {err}");
         }
     }
 
-    fn undo_last_snapshot(&mut self) {
-        let Some(commit) = self.ghost_snapshots.pop() else {
+    /// Shows every recorded turn, newest first, with the changes made during
+    /// the turn. Selecting a turn restores the workspace to before it ran.
+    fn open_undo_timeline(&mut self) {
+        if self.undo_history.turns().is_empty() {
             self.add_info_message("No snapshot available to undo.".to_string(), None);
             return;
+        }
+        let stats = match self.undo_history.turn_diff_stats() {
+            Ok(stats) => stats,
+            Err(err) => {
+                self.add_error_message(format!("Failed to compute turn diffs: {err}"));
+                return;
+            }
         };
 
-        if let Err(err) = restore_ghost_commit(&self.config.cwd, &commit) {
+        let turns = self.undo_history.turns();
+        let position = self.undo_history.position();
+        let latest = turns.len();
+        let mut items = vec![SelectionItem {
+            name: "Latest state".to_string(),
+            description: Some("keep every turn".to_string()),
+            is_current: position == latest,
+            actions: vec![Box::new(move |tx| {
+                tx.send(AppEvent::RestoreTurnSnapshot(latest));
+            })],
+            dismiss_on_select: true,
+            ..Default::default()
+        }];
+        for (index, (turn, stat)) in turns.iter().zip(stats).enumerate().rev() {
+            let description = if index >= position {
+                format!("{stat} (undone)")
+            } else {
+                stat.to_string()
+            };
+            items.push(SelectionItem {
+                name: format!("Turn {}: {}", index + 1, truncate_text(turn.label(), 50)),
+                description: Some(description),
+                is_current: position == index,
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::RestoreTurnSnapshot(index));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            });
+        }
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Undo History".to_string()),
+            subtitle: Some("Select a turn to undo it and every turn after it".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    /// Restores the workspace to before turn `position` of the undo history,
+    /// or to the latest state if `position` is past the last turn.
+    pub(crate) fn restore_turn_snapshot(&mut self, position: usize) {
+        if position == self.undo_history.position() {
+            return;
+        }
+        if let Err(err) = self.undo_history.move_to(position) {
             self.add_error_message(format!("Failed to restore snapshot: {err}"));
-            self.ghost_snapshots.push(commit);
             return;
         }
+        self.announce_undo_position();
+    }
+
+    fn undo_turns(&mut self, steps: usize) {
+        match self.undo_history.undo(steps) {
+            Ok(0) => self.add_info_message("No snapshot available to undo.".to_string(), None),
+            Ok(_) => self.announce_undo_position(),
+            Err(err) => self.add_error_message(format!("Failed to restore snapshot: {err}")),
+        }
+    }
 
-        let short_id: String = commit.id().chars().take(8).collect();
-        self.add_info_message(format!("Restored workspace to snapshot {short_id}"), None);
+    fn redo_turns(&mut self, steps: usize) {
+        match self.undo_history.redo(steps) {
+            Ok(0) => self.add_info_message("Nothing to redo.".to_string(), None),
+            Ok(_) => self.announce_undo_position(),
+            Err(err) => self.add_error_message(format!("Failed to restore snapshot: {err}")),
+        }
+    }
+
+    fn announce_undo_position(&mut self) {
+        let position = self.undo_history.position();
+        let redo_depth = self.undo_history.redo_depth();
+        match self.undo_history.turns().get(position) {
+            Some(turn) => {
+                let message = format!(
+                    "Restored workspace to before turn {}: {}",
+                    position + 1,
+                    truncate_text(turn.label(), 50)
+                );
+                let noun = if redo_depth == 1 { "turn" } else { "turns" };
+                let hint = format!("/redo reapplies {redo_depth} undone {noun}");
+                self.add_info_message(message, Some(hint));
+            }
+            None => {
+                self.add_info_message("Restored workspace to the latest turn.".to_string(), None)
+            }
+        }
     }
 
     /// Replay a subset of initial events into the UI to seed the transcript when
@@ -1425,6 +1666,9 @@ impl ChatWidget {
             EventMsg::TokenCount(ev) => {
                 self.set_token_info(ev.info);
                 self.on_rate_limit_snapshot(ev.rate_limits);
+                if ev.context_breakdown.is_some() {
+                    self.context_breakdown = ev.context_breakdown;
+                }
             }
             EventMsg::Error(ErrorEvent { message }) => self.on_error(message),
             EventMsg::TurnAborted(ev) => match ev.reason {
@@ -1458,6 +1702,8 @@ impl ChatWidget {
             EventMsg::WebSearchEnd(ev) => self.on_web_search_end(ev),
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
+            EventMsg::McpListResourcesResponse(ev) => self.on_list_mcp_resources(ev),
+            EventMsg::McpListPromptsResponse(ev) => self.on_list_mcp_prompts(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
             EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
@@ -1478,6 +1724,9 @@ impl ChatWidget {
                 self.on_entered_review_mode(review_request)
             }
             EventMsg::ExitedReviewMode(review) => self.on_exited_review_mode(review),
+            EventMsg::SubAgentBegin(ev) => self.on_sub_agent_begin(ev),
+            EventMsg::SubAgentEvent(ev) => self.on_sub_agent_event(ev),
+            EventMsg::SubAgentEnd(ev) => self.on_sub_agent_end(ev),
         }
     }
 
@@ -1533,7 +1782,8 @@ impl ChatWidget {
     fn on_user_message_event(&mut self, event: UserMessageEvent) {
         match event.kind {
             Some(InputMessageKind::EnvironmentContext)
-            | Some(InputMessageKind::UserInstructions) => {
+            | Some(InputMessageKind::UserInstructions)
+            | Some(InputMessageKind::PinnedContext) => {
                 // Skip XML‑wrapped context blocks in the transcript.
             }
             Some(InputMessageKind::Plain) | None => {
@@ -1619,6 +1869,7 @@ impl ChatWidget {
             context_usage,
             &self.conversation_id,
             self.rate_limit_snapshot.as_ref(),
+            self.context_breakdown.as_ref(),
         ));
     }
 
@@ -1853,6 +2104,64 @@ impl ChatWidget {
         });
     }
 
+    /// Open a popup listing persisted approval rules; selecting one revokes it.
+    pub(crate) fn open_approval_rules_popup(&mut self) {
+        let rules = match load_approval_rules(&self.config.codex_home) {
+            Ok(rules) => rules,
+            Err(err) => {
+                self.add_error_message(format!("Failed to load approval rules: {err}"));
+                return;
+            }
+        };
+        if rules.is_empty() {
+            self.add_info_message(
+                "No remembered approvals.".to_string(),
+                Some("Choose \"don't ask again for this command in this project\" when approving a command to add one.".to_string()),
+            );
+            return;
+        }
+
+        let items: Vec<SelectionItem> = rules
+            .into_iter()
+            .map(|rule| {
+                let description = match &rule.scope {
+                    ApprovalScope::Global => "global".to_string(),
+                    ApprovalScope::Project { path } => format!("project: {}", path.display()),
+                };
+                let id = rule.id.clone();
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::RevokeApprovalRule(id.clone()));
+                })];
+                SelectionItem {
+                    name: rule.matcher.describe(),
+                    description: Some(description),
+                    actions,
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Remembered Approvals".to_string()),
+            subtitle: Some("Select a rule to revoke it".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn revoke_approval_rule(&mut self, id: &str) {
+        match remove_approval_rule(&self.config.codex_home, id) {
+            Ok(Some(rule)) => self.add_info_message(
+                format!("Revoked approval for {}", rule.matcher.describe()),
+                None,
+            ),
+            Ok(None) => self.add_error_message(format!("No approval rule with id {id}")),
+            Err(err) => self.add_error_message(format!("Failed to revoke approval rule: {err}")),
+        }
+    }
+
     /// Set the approval policy in the widget's config copy.
     pub(crate) fn set_approval_policy(&mut self, policy: AskForApproval) {
         self.config.approval_policy = policy;
@@ -1956,6 +2265,41 @@ impl ChatWidget {
         ));
     }
 
+    fn on_list_mcp_resources(&mut self, ev: McpListResourcesResponseEvent) {
+        let McpListResourcesResponseEvent {
+            resources,
+            resource_templates,
+        } = ev;
+        let mut uris_by_server: HashMap<String, Vec<String>> = resources
+            .into_iter()
+            .map(|(server, resources)| {
+                let mut uris: Vec<String> = resources.into_iter().map(|r| r.uri).collect();
+                uris.sort();
+                (server, uris)
+            })
+            .collect();
+        // Servers with only templates still accept `@server:uri` mentions.
+        for server in resource_templates.into_keys() {
+            uris_by_server.entry(server).or_default();
+        }
+        self.bottom_pane.set_mcp_resources(uris_by_server);
+    }
+
+    fn on_list_mcp_prompts(&mut self, ev: McpListPromptsResponseEvent) {
+        let prompts: Vec<McpPromptCommand> = ev
+            .prompts
+            .into_iter()
+            .flat_map(|(server, prompts)| {
+                prompts.into_iter().map(move |prompt| McpPromptCommand {
+                    server: server.clone(),
+                    prompt,
+                })
+            })
+            .collect();
+        debug!("received {} MCP prompts", prompts.len());
+        self.bottom_pane.set_mcp_prompts(prompts);
+    }
+
     fn on_list_custom_prompts(&mut self, ev: ListCustomPromptsResponseEvent) {
         let len = ev.custom_prompts.len();
         debug!("received {len} custom prompts");
@@ -1981,16 +2325,15 @@ impl ChatWidget {
 
         items.push(SelectionItem {
             name: "Review uncommitted changes".to_string(),
-            actions: vec![Box::new(
-                move |tx: &AppEventSender| {
-                    tx.send(AppEvent::CodexOp(Op::Review {
-                        review_request: ReviewRequest {
-                            prompt: "Review the current code changes (staged, unstaged, and untracked files) and provide prioritized findings.".to_string(),
-                            user_facing_hint: "current changes".to_string(),
-                        },
-                    }));
-                },
-            )],
+            actions: vec![Box::new(move |tx: &AppEventSender| {
+                tx.send(AppEvent::CodexOp(Op::Review {
+                    review_request: ReviewRequest {
+                        prompt: String::new(),
+                        user_facing_hint: "current changes".to_string(),
+                        target: Some(ReviewTarget::UncommittedChanges),
+                    },
+                }));
+            })],
             dismiss_on_select: true,
             ..Default::default()
         });
@@ -2039,10 +2382,11 @@ impl ChatWidget {
                 actions: vec![Box::new(move |tx3: &AppEventSender| {
                     tx3.send(AppEvent::CodexOp(Op::Review {
                         review_request: ReviewRequest {
-                            prompt: format!(
-                                "Review the code changes against the base branch '{branch}'. Start by finding the merge diff between the current branch and {branch}'s upstream e.g. (`git merge-base HEAD \"$(git rev-parse --abbrev-ref \"{branch}@{{upstream}}\")\"`), then run `git diff` against that SHA to see what changes we would merge into the {branch} branch. Provide prioritized, actionable findings."
-                            ),
+                            prompt: String::new(),
                             user_facing_hint: format!("changes against '{branch}'"),
+                            target: Some(ReviewTarget::BaseBranch {
+                                branch: branch.clone(),
+                            }),
                         },
                     }));
                 })],
@@ -2075,14 +2419,11 @@ impl ChatWidget {
             items.push(SelectionItem {
                 name: subject.clone(),
                 actions: vec![Box::new(move |tx3: &AppEventSender| {
-                    let hint = format!("commit {short}");
-                    let prompt = format!(
-                        "Review the code changes introduced by commit {sha} (\"{subject}\"). Provide prioritized, actionable findings."
-                    );
                     tx3.send(AppEvent::CodexOp(Op::Review {
                         review_request: ReviewRequest {
-                            prompt,
-                            user_facing_hint: hint,
+                            prompt: String::new(),
+                            user_facing_hint: format!("commit {short}"),
+                            target: Some(ReviewTarget::Commit { sha: sha.clone() }),
                         },
                     }));
                 })],
@@ -2117,6 +2458,7 @@ impl ChatWidget {
                     review_request: ReviewRequest {
                         prompt: trimmed.clone(),
                         user_facing_hint: trimmed,
+                        target: None,
                     },
                 }));
             }),
@@ -2153,6 +2495,7 @@ impl ChatWidget {
 
     pub(crate) fn clear_token_usage(&mut self) {
         self.token_info = None;
+        self.context_breakdown = None;
     }
 
     pub fn cursor_pos(&self, area: Rect) -> Option<(u16, u16)> {
@@ -2299,14 +2642,11 @@ pub(crate) fn show_review_commit_picker_with_entries(
         items.push(SelectionItem {
             name: subject.clone(),
             actions: vec![Box::new(move |tx3: &AppEventSender| {
-                let hint = format!("commit {short}");
-                let prompt = format!(
-                    "Review the code changes introduced by commit {sha} (\"{subject}\"). Provide prioritized, actionable findings."
-                );
                 tx3.send(AppEvent::CodexOp(Op::Review {
                     review_request: ReviewRequest {
-                        prompt,
-                        user_facing_hint: hint,
+                        prompt: String::new(),
+                        user_facing_hint: format!("commit {short}"),
+                        target: Some(ReviewTarget::Commit { sha: sha.clone() }),
                     },
                 }));
             })],
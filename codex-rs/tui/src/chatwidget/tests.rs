@@ -150,6 +150,7 @@ fn entered_review_mode_uses_request_hint() {
         msg: EventMsg::EnteredReviewMode(ReviewRequest {
             prompt: "Review the latest changes".to_string(),
             user_facing_hint: "feature branch".to_string(),
+            target: None,
         }),
     });
 
@@ -169,6 +170,7 @@ fn entered_review_mode_defaults_to_current_changes_banner() {
         msg: EventMsg::EnteredReviewMode(ReviewRequest {
             prompt: "Review the current changes".to_string(),
             user_facing_hint: "current changes".to_string(),
+            target: None,
         }),
     });
 
@@ -269,6 +271,7 @@ fn make_chatwidget_manual() -> (
         session_header: SessionHeader::new(cfg.model),
         initial_user_message: None,
         token_info: None,
+        context_breakdown: None,
         rate_limit_snapshot: None,
         rate_limit_warnings: RateLimitWarningState::default(),
         stream_controller: None,
@@ -286,7 +289,7 @@ fn make_chatwidget_manual() -> (
         suppress_session_configured_redraw: false,
         pending_notification: None,
         is_review_mode: false,
-        ghost_snapshots: Vec::new(),
+        undo_history: UndoHistory::new(cfg.cwd, 100),
         ghost_snapshots_disabled: false,
         needs_final_message_separator: false,
         last_rendered_width: std::cell::Cell::new(None),
@@ -802,6 +805,33 @@ fn slash_init_skips_when_project_doc_exists() {
     );
 }
 
+#[test]
+fn slash_compact_and_pin_forward_their_arguments() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.dispatch_command_with_args(SlashCommand::Compact, "focus on the parser");
+    chat.dispatch_command_with_args(SlashCommand::Pin, "the API must stay stable");
+
+    let mut ops = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        if let AppEvent::CodexOp(op) = event {
+            ops.push(op);
+        }
+    }
+    match ops.as_slice() {
+        [
+            Op::Compact {
+                instructions: Some(instructions),
+            },
+            Op::Pin { text: Some(text) },
+        ] => {
+            assert_eq!(instructions, "focus on the parser");
+            assert_eq!(text, "the API must stay stable");
+        }
+        other => panic!("unexpected ops: {other:?}"),
+    }
+}
+
 /// The commit picker shows only commit subjects (no timestamps).
 #[test]
 fn review_commit_picker_shows_subjects_without_timestamps() {
@@ -2423,3 +2453,73 @@ printf 'fenced within fenced\n'
 
     assert_snapshot!(term.backend().vt100().screen().contents());
 }
+
+/// Initializes a git repository containing a single committed `notes.txt`.
+fn init_undo_test_repo(repo: &std::path::Path) {
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .current_dir(repo)
+            .args(args)
+            .status()
+            .expect("git command");
+        assert!(status.success(), "git command failed: {args:?}");
+    };
+    git(&["init", "--initial-branch=main"]);
+    std::fs::write(repo.join("notes.txt"), "v0\n").expect("write notes");
+    git(&["add", "notes.txt"]);
+    git(&[
+        "-c",
+        "user.name=Tester",
+        "-c",
+        "user.email=test@example.com",
+        "commit",
+        "-m",
+        "init",
+    ]);
+}
+
+#[test]
+fn undo_timeline_lists_turns_and_undo_redo_move_through_them() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    let repo = tempdir().expect("tempdir");
+    init_undo_test_repo(repo.path());
+    chat.config.cwd = repo.path().to_path_buf();
+    chat.undo_history = UndoHistory::new(repo.path(), 100);
+    let read_notes = || std::fs::read_to_string(repo.path().join("notes.txt")).expect("read");
+
+    for turn in 1..=3 {
+        chat.submit_user_message(UserMessage {
+            text: format!("rewrite the notes, take {turn}"),
+            image_paths: Vec::new(),
+            mcp_prompt: None,
+        });
+        let notes = (0..=turn)
+            .map(|line| format!("v{line}\n"))
+            .collect::<String>();
+        std::fs::write(repo.path().join("notes.txt"), notes).expect("write notes");
+    }
+
+    chat.open_undo_timeline();
+    assert_snapshot!("undo_timeline_popup", render_bottom_popup(&chat, 80));
+
+    chat.dispatch_command_with_args(SlashCommand::Undo, "2");
+    assert_eq!(read_notes(), "v0\nv1\n");
+    chat.dispatch_command(SlashCommand::Redo);
+    assert_eq!(read_notes(), "v0\nv1\nv2\n");
+    chat.restore_turn_snapshot(3);
+    assert_eq!(read_notes(), "v0\nv1\nv2\nv3\n");
+    chat.dispatch_command_with_args(SlashCommand::Redo, "two");
+
+    let history = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        history.contains("Restored workspace to before turn 2: rewrite the notes, take 2"),
+        "unexpected history: {history}"
+    );
+    assert!(history.contains("/redo reapplies 2 undone turns"));
+    assert!(history.contains("Restored workspace to before turn 3"));
+    assert!(history.contains("Restored workspace to the latest turn."));
+    assert!(history.contains("Usage: /redo [N], where N is a number of turns"));
+}
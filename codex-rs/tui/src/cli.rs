@@ -72,6 +72,10 @@ pub struct Cli {
     #[arg(long = "search", default_value_t = false)]
     pub web_search: bool,
 
+    /// Work in a new git worktree and branch instead of the current working tree.
+    #[arg(long = "worktree", default_value_t = false)]
+    pub worktree: bool,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 }
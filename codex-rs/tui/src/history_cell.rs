@@ -357,6 +357,19 @@ pub fn new_approval_decision_cell(
                 ],
             )
         }
+        ApprovedForProject => {
+            let snippet = Span::from(exec_snippet(&command)).dim();
+            (
+                "✔ ".green(),
+                vec![
+                    "You ".into(),
+                    "approved".bold(),
+                    " codex to run ".into(),
+                    snippet,
+                    " every time in this project".bold(),
+                ],
+            )
+        }
         Denied => {
             let snippet = Span::from(exec_snippet(&command)).dim();
             (
@@ -1183,6 +1196,34 @@ pub(crate) fn new_view_image_tool_call(path: PathBuf, cwd: &Path) -> PlainHistor
     PlainHistoryCell { lines }
 }
 
+pub(crate) fn new_sub_agent_begin(prompt: &str) -> PlainHistoryCell {
+    let prompt = prompt.lines().next().unwrap_or_default().to_string();
+    let lines: Vec<Line<'static>> = vec![
+        vec!["• ".dim(), "Started sub-agent".bold()].into(),
+        vec!["  └ ".dim(), prompt.dim()].into(),
+    ];
+
+    PlainHistoryCell { lines }
+}
+
+pub(crate) fn new_sub_agent_end(
+    success: bool,
+    last_agent_message: Option<&str>,
+) -> PlainHistoryCell {
+    let (bullet, title) = if success {
+        ("• ".dim(), "Sub-agent finished".bold())
+    } else {
+        ("• ".red().bold(), "Sub-agent failed".bold())
+    };
+    let mut lines: Vec<Line<'static>> = vec![vec![bullet, title].into()];
+    if let Some(message) = last_agent_message.and_then(|m| m.lines().find(|l| !l.trim().is_empty()))
+    {
+        lines.push(vec!["  └ ".dim(), message.to_string().dim()].into());
+    }
+
+    PlainHistoryCell { lines }
+}
+
 pub(crate) fn new_reasoning_summary_block(
     full_reasoning_buffer: String,
     config: &Config,
@@ -15,12 +15,14 @@ use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::find_conversation_path_by_id_str;
 use codex_core::protocol::AskForApproval;
+use codex_git_tooling::SessionWorktree;
 use codex_ollama::DEFAULT_OSS_MODEL;
 use codex_protocol::config_types::SandboxMode;
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use std::fs::OpenOptions;
 use std::path::PathBuf;
 use tracing::error;
+use tracing::info;
 use tracing_appender::non_blocking;
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::prelude::*;
@@ -176,6 +178,7 @@ pub async fn run_main(
         include_view_image_tool: None,
         show_raw_agent_reasoning: cli.oss.then_some(true),
         tools_web_search_request: cli.web_search.then_some(true),
+        worktree: cli.worktree.then_some(true),
     };
     let raw_overrides = cli.config_overrides.raw_overrides.clone();
     let overrides_cli = codex_common::CliConfigOverrides { raw_overrides };
@@ -227,7 +230,8 @@ pub async fn run_main(
         .with_filter(env_filter());
 
     if cli.oss {
-        codex_ollama::ensure_oss_ready(&config)
+        let mut reporter = codex_ollama::TuiProgressReporter::default();
+        codex_ollama::ensure_oss_ready(&config, &mut reporter)
             .await
             .map_err(|e| std::io::Error::other(format!("OSS setup failed: {e}")))?;
     }
@@ -298,6 +302,7 @@ async fn run_ratatui_app(
                         token_usage: codex_core::protocol::TokenUsage::default(),
                         conversation_id: None,
                         update_action: Some(action),
+                        session_worktree: None,
                     });
                 }
             }
@@ -393,6 +398,7 @@ async fn run_ratatui_app(
                 token_usage: codex_core::protocol::TokenUsage::default(),
                 conversation_id: None,
                 update_action: None,
+                session_worktree: None,
             });
         }
         // if the user acknowledged windows or made an explicit decision ato trust the directory, reload the config accordingly
@@ -444,6 +450,7 @@ async fn run_ratatui_app(
                     token_usage: codex_core::protocol::TokenUsage::default(),
                     conversation_id: None,
                     update_action: None,
+                    session_worktree: None,
                 });
             }
             other => other,
@@ -452,6 +459,22 @@ async fn run_ratatui_app(
         resume_picker::ResumeSelection::StartFresh
     };
 
+    // Created only once nothing can exit early, so that the worktree always
+    // reaches `AppExitInfo` or is cleaned up below.
+    let mut config = config;
+    let session_worktree = if config.worktree {
+        let worktree = codex_core::worktree::enter_session_worktree(&mut config)
+            .map_err(|err| color_eyre::eyre::eyre!("Failed to create a git worktree: {err}"))?;
+        info!(
+            "Running session in worktree {} on branch {}",
+            worktree.path().display(),
+            worktree.branch()
+        );
+        Some(worktree)
+    } else {
+        None
+    };
+
     let Cli { prompt, images, .. } = cli;
 
     let app_result = App::run(
@@ -469,7 +492,38 @@ async fn run_ratatui_app(
     // Mark the end of the recorded session.
     session_log::log_session_end();
     // ignore error when collecting usage – report underlying error instead
-    app_result
+    match app_result {
+        Ok(exit_info) => Ok(AppExitInfo {
+            session_worktree,
+            ..exit_info
+        }),
+        Err(err) => {
+            if let Some(worktree) = session_worktree {
+                keep_session_worktree_changes(&worktree);
+            }
+            Err(err)
+        }
+    }
+}
+
+/// Keeps whatever a session that ended in an error left in its worktree on
+/// the session's branch, since there is no exit prompt to ask about it.
+#[expect(
+    clippy::print_stderr,
+    reason = "TUI should no longer be displayed, so we can write to stderr."
+)]
+fn keep_session_worktree_changes(worktree: &SessionWorktree) {
+    let branch = worktree.branch();
+    match worktree.keep_if_changed("Codex session changes") {
+        Ok(true) => {
+            eprintln!("Changes are on branch {branch}; run `git merge {branch}` to merge them.")
+        }
+        Ok(false) => {}
+        Err(err) => eprintln!(
+            "failed to clean up the worktree at {}: {err}",
+            worktree.path().display()
+        ),
+    }
 }
 
 /// Get the update action from the environment.
@@ -10,6 +10,8 @@ use codex_core::ConversationsPage;
 use codex_core::Cursor;
 use codex_core::INTERACTIVE_SESSION_SOURCES;
 use codex_core::RolloutRecorder;
+use codex_core::SessionSearchQuery;
+use codex_core::SessionSearchResult;
 use color_eyre::eyre::Result;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
@@ -37,6 +39,7 @@ use codex_protocol::protocol::USER_MESSAGE_BEGIN;
 
 const PAGE_SIZE: usize = 25;
 const LOAD_NEAR_THRESHOLD: usize = 5;
+const CONTENT_SEARCH_LIMIT: usize = 50;
 
 #[derive(Debug, Clone)]
 pub enum ResumeSelection {
@@ -55,17 +58,31 @@ struct PageLoadRequest {
 
 type PageLoader = Arc<dyn Fn(PageLoadRequest) + Send + Sync>;
 
+#[derive(Clone)]
+struct ContentSearchRequest {
+    codex_home: PathBuf,
+    query: String,
+}
+
+type ContentSearcher = Arc<dyn Fn(ContentSearchRequest) + Send + Sync>;
+
 enum BackgroundEvent {
     PageLoaded {
         request_token: usize,
         search_token: Option<usize>,
         page: std::io::Result<ConversationsPage>,
     },
+    ContentSearchCompleted {
+        query: String,
+        results: std::io::Result<Vec<SessionSearchResult>>,
+    },
 }
 
 /// Interactive session picker that lists recorded rollout files with simple
 /// search and pagination. Shows the first user input as the preview, relative
-/// time (e.g., "5 seconds ago"), and the absolute path.
+/// time (e.g., "5 seconds ago"), and the absolute path. Searching matches the
+/// previews of loaded sessions and, through the session search index, the
+/// full contents of every session.
 pub async fn run_resume_picker(tui: &mut Tui, codex_home: &Path) -> Result<ResumeSelection> {
     let alt = AltScreenGuard::enter(tui);
     let (bg_tx, bg_rx) = mpsc::unbounded_channel();
@@ -89,11 +106,30 @@ pub async fn run_resume_picker(tui: &mut Tui, codex_home: &Path) -> Result<Resum
         });
     });
 
+    let searcher_tx = bg_tx.clone();
+    let content_searcher: ContentSearcher = Arc::new(move |request: ContentSearchRequest| {
+        let tx = searcher_tx.clone();
+        tokio::spawn(async move {
+            let query = SessionSearchQuery {
+                text: request.query.clone(),
+                kinds: Vec::new(),
+                limit: CONTENT_SEARCH_LIMIT,
+                allowed_sources: INTERACTIVE_SESSION_SOURCES.to_vec(),
+            };
+            let results = RolloutRecorder::search_conversations(&request.codex_home, query).await;
+            let _ = tx.send(BackgroundEvent::ContentSearchCompleted {
+                query: request.query,
+                results,
+            });
+        });
+    });
+
     let mut state = PickerState::new(
         codex_home.to_path_buf(),
         alt.tui.frame_requester(),
         page_loader,
     );
+    state.content_searcher = Some(content_searcher);
     state.load_initial_page().await?;
     state.request_frame();
 
@@ -157,6 +193,8 @@ struct PickerState {
     requester: FrameRequester,
     pagination: PaginationState,
     all_rows: Vec<Row>,
+    /// Full-text search results for `query` that are shown after the preview matches.
+    content_rows: Vec<Row>,
     filtered_rows: Vec<Row>,
     seen_paths: HashSet<PathBuf>,
     selected: usize,
@@ -166,6 +204,7 @@ struct PickerState {
     next_request_token: usize,
     next_search_token: usize,
     page_loader: PageLoader,
+    content_searcher: Option<ContentSearcher>,
     view_rows: Option<usize>,
 }
 
@@ -224,6 +263,8 @@ struct Row {
     preview: String,
     created_at: Option<DateTime<Utc>>,
     updated_at: Option<DateTime<Utc>>,
+    /// Why a full-text search matched this session, e.g. `command: cargo test`.
+    snippet: Option<String>,
 }
 
 impl PickerState {
@@ -238,6 +279,7 @@ impl PickerState {
                 loading: LoadingState::Idle,
             },
             all_rows: Vec::new(),
+            content_rows: Vec::new(),
             filtered_rows: Vec::new(),
             seen_paths: HashSet::new(),
             selected: 0,
@@ -247,6 +289,7 @@ impl PickerState {
             next_request_token: 0,
             next_search_token: 0,
             page_loader,
+            content_searcher: None,
             view_rows: None,
         }
     }
@@ -363,6 +406,21 @@ impl PickerState {
                 let completed_token = pending.search_token.or(search_token);
                 self.continue_search_if_token_matches(completed_token);
             }
+            BackgroundEvent::ContentSearchCompleted { query, results } => {
+                if query != self.query {
+                    return Ok(());
+                }
+                match results {
+                    Ok(results) => {
+                        self.content_rows = results.into_iter().map(search_result_to_row).collect();
+                        self.apply_filter();
+                        if !self.filtered_rows.is_empty() {
+                            self.search_state = SearchState::Idle;
+                        }
+                    }
+                    Err(err) => tracing::warn!("session search failed: {err}"),
+                }
+            }
         }
         Ok(())
     }
@@ -409,6 +467,14 @@ impl PickerState {
                 .filter(|r| r.preview.to_lowercase().contains(&q))
                 .cloned()
                 .collect();
+            let shown: HashSet<PathBuf> =
+                self.filtered_rows.iter().map(|r| r.path.clone()).collect();
+            self.filtered_rows.extend(
+                self.content_rows
+                    .iter()
+                    .filter(|r| !shown.contains(&r.path))
+                    .cloned(),
+            );
         }
         if self.selected >= self.filtered_rows.len() {
             self.selected = self.filtered_rows.len().saturating_sub(1);
@@ -426,6 +492,8 @@ impl PickerState {
         }
         self.query = new_query;
         self.selected = 0;
+        self.content_rows.clear();
+        self.request_content_search();
         self.apply_filter();
         if self.query.is_empty() {
             self.search_state = SearchState::Idle;
@@ -444,6 +512,18 @@ impl PickerState {
         self.load_more_if_needed(LoadTrigger::Search { token });
     }
 
+    fn request_content_search(&self) {
+        if self.query.trim().is_empty() {
+            return;
+        }
+        if let Some(searcher) = &self.content_searcher {
+            searcher(ContentSearchRequest {
+                codex_home: self.codex_home.clone(),
+                query: self.query.clone(),
+            });
+        }
+    }
+
     fn continue_search_if_needed(&mut self) {
         let Some(token) = self.search_state.active_token() else {
             return;
@@ -596,6 +676,33 @@ fn head_to_row(item: &ConversationItem) -> Row {
         preview,
         created_at,
         updated_at,
+        snippet: None,
+    }
+}
+
+fn search_result_to_row(result: SessionSearchResult) -> Row {
+    let created_at = result.created_at.as_deref().and_then(parse_timestamp_str);
+    let updated_at = result
+        .updated_at
+        .as_deref()
+        .and_then(parse_timestamp_str)
+        .or(created_at);
+    let preview = result
+        .preview
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| String::from("(no message yet)"));
+    let snippet = result
+        .hits
+        .first()
+        .map(|hit| format!("{}: {}", hit.kind, hit.snippet));
+
+    Row {
+        path: result.path,
+        preview,
+        created_at,
+        updated_at,
+        snippet,
     }
 }
 
@@ -763,6 +870,13 @@ fn render_list(
             preview_width = preview_width.saturating_sub(2);
         }
         let preview = truncate_text(&row.preview, preview_width);
+        let snippet_width =
+            preview_width.saturating_sub(UnicodeWidthStr::width(preview.as_str()) + 2);
+        let snippet = row
+            .snippet
+            .as_ref()
+            .filter(|_| snippet_width > 0)
+            .map(|snippet| truncate_text(snippet, snippet_width));
         let mut spans: Vec<Span> = vec![marker];
         if let Some(created) = created_span {
             spans.push(created);
@@ -776,6 +890,10 @@ fn render_list(
             spans.push("  ".into());
         }
         spans.push(preview.into());
+        if let Some(snippet) = snippet {
+            spans.push("  ".into());
+            spans.push(snippet.dim());
+        }
 
         let line: Line = spans.into();
         let rect = Rect::new(area.x, y, area.width, 1);
@@ -1089,18 +1207,21 @@ mod tests {
                 preview: String::from("Fix resume picker timestamps"),
                 created_at: Some(now - Duration::minutes(16)),
                 updated_at: Some(now - Duration::seconds(42)),
+                snippet: None,
             },
             Row {
                 path: PathBuf::from("/tmp/b.jsonl"),
                 preview: String::from("Investigate lazy pagination cap"),
                 created_at: Some(now - Duration::hours(1)),
                 updated_at: Some(now - Duration::minutes(35)),
+                snippet: None,
             },
             Row {
                 path: PathBuf::from("/tmp/c.jsonl"),
                 preview: String::from("Explain the codebase"),
                 created_at: Some(now - Duration::hours(2)),
                 updated_at: Some(now - Duration::hours(2)),
+                snippet: None,
             },
         ];
         state.all_rows = rows.clone();
@@ -1298,6 +1419,89 @@ mod tests {
         assert_eq!(state.selected, state.filtered_rows.len().saturating_sub(2));
     }
 
+    #[test]
+    fn content_search_results_follow_preview_matches() {
+        let loader: PageLoader = Arc::new(|_| {});
+        let searches: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        let search_sink = searches.clone();
+        let mut state =
+            PickerState::new(PathBuf::from("/tmp"), FrameRequester::test_dummy(), loader);
+        state.content_searcher = Some(Arc::new(move |req: ContentSearchRequest| {
+            search_sink.lock().unwrap().push(req.query);
+        }));
+        state.reset_pagination();
+        state.ingest_page(page(
+            vec![
+                make_item("/tmp/a.jsonl", "2025-01-02T00:00:00Z", "migration flake"),
+                make_item("/tmp/b.jsonl", "2025-01-01T00:00:00Z", "docs"),
+            ],
+            None,
+            2,
+            false,
+        ));
+
+        state.set_query("migration".to_string());
+        assert_eq!(*searches.lock().unwrap(), vec!["migration".to_string()]);
+
+        let hit = |path: &str, kind, snippet: &str| SessionSearchResult {
+            path: PathBuf::from(path),
+            conversation_id: None,
+            created_at: Some("2025-01-01T00:00:00Z".to_string()),
+            updated_at: None,
+            cwd: None,
+            preview: Some("docs".to_string()),
+            hits: vec![codex_core::SearchHit {
+                kind,
+                snippet: snippet.to_string(),
+            }],
+        };
+        // Results for a stale query are ignored.
+        state
+            .handle_background_event(BackgroundEvent::ContentSearchCompleted {
+                query: "migr".to_string(),
+                results: Ok(vec![hit(
+                    "/tmp/b.jsonl",
+                    codex_core::SearchHitKind::Command,
+                    "x",
+                )]),
+            })
+            .unwrap();
+        assert_eq!(state.filtered_rows.len(), 1);
+
+        state
+            .handle_background_event(BackgroundEvent::ContentSearchCompleted {
+                query: "migration".to_string(),
+                results: Ok(vec![
+                    hit(
+                        "/tmp/a.jsonl",
+                        codex_core::SearchHitKind::UserMessage,
+                        "migration flake",
+                    ),
+                    hit(
+                        "/tmp/b.jsonl",
+                        codex_core::SearchHitKind::Command,
+                        "cargo test migration",
+                    ),
+                ]),
+            })
+            .unwrap();
+        let shown: Vec<(PathBuf, Option<String>)> = state
+            .filtered_rows
+            .iter()
+            .map(|r| (r.path.clone(), r.snippet.clone()))
+            .collect();
+        assert_eq!(
+            shown,
+            vec![
+                (PathBuf::from("/tmp/a.jsonl"), None),
+                (
+                    PathBuf::from("/tmp/b.jsonl"),
+                    Some("command: cargo test migration".to_string())
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn set_query_loads_until_match_and_respects_scan_cap() {
         let recorded_requests: Arc<Mutex<Vec<PageLoadRequest>>> = Arc::new(Mutex::new(Vec::new()));
@@ -14,11 +14,14 @@ pub enum SlashCommand {
     // more frequently used commands should be listed first.
     Model,
     Approvals,
+    Rules,
     Review,
     New,
     Init,
     Compact,
+    Pin,
     Undo,
+    Redo,
     Diff,
     Mention,
     Status,
@@ -36,14 +39,17 @@ impl SlashCommand {
             SlashCommand::New => "start a new chat during a conversation",
             SlashCommand::Init => "create an AGENTS.md file with instructions for Codex",
             SlashCommand::Compact => "summarize conversation to prevent hitting the context limit",
+            SlashCommand::Pin => "pin text, or the last response, so compaction keeps it verbatim",
             SlashCommand::Review => "review my current changes and find issues",
-            SlashCommand::Undo => "restore the workspace to the last Codex snapshot",
+            SlashCommand::Undo => "undo the last N turns, or pick a turn to restore",
+            SlashCommand::Redo => "redo the last N undone turns",
             SlashCommand::Quit => "exit Codex",
             SlashCommand::Diff => "show git diff (including untracked files)",
             SlashCommand::Mention => "mention a file",
             SlashCommand::Status => "show current session configuration and token usage",
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Approvals => "choose what Codex can do without approval",
+            SlashCommand::Rules => "list or revoke remembered command approvals",
             SlashCommand::Mcp => "list configured MCP tools",
             SlashCommand::Logout => "log out of Codex",
             #[cfg(debug_assertions)]
@@ -64,20 +70,32 @@ impl SlashCommand {
             | SlashCommand::Init
             | SlashCommand::Compact
             | SlashCommand::Undo
+            | SlashCommand::Redo
             | SlashCommand::Model
             | SlashCommand::Approvals
             | SlashCommand::Review
             | SlashCommand::Logout => false,
             SlashCommand::Diff
+            | SlashCommand::Rules
             | SlashCommand::Mention
             | SlashCommand::Status
             | SlashCommand::Mcp
+            | SlashCommand::Pin
             | SlashCommand::Quit => true,
 
             #[cfg(debug_assertions)]
             SlashCommand::TestApproval => true,
         }
     }
+
+    /// Whether text typed after the command name is passed to the command
+    /// instead of preventing it from being dispatched.
+    pub fn takes_args(self) -> bool {
+        matches!(
+            self,
+            SlashCommand::Compact | SlashCommand::Pin | SlashCommand::Undo | SlashCommand::Redo
+        )
+    }
 }
 
 /// Return all built-in commands in a Vec paired with their command string.
@@ -86,7 +104,7 @@ pub fn built_in_slash_commands() -> Vec<(&'static str, SlashCommand)> {
 
     SlashCommand::iter()
         .filter(|cmd| {
-            if *cmd == SlashCommand::Undo {
+            if matches!(cmd, SlashCommand::Undo | SlashCommand::Redo) {
                 show_beta_features
             } else {
                 true
@@ -96,6 +114,6 @@ pub fn built_in_slash_commands() -> Vec<(&'static str, SlashCommand)> {
         .collect()
 }
 
-fn beta_features_enabled() -> bool {
+pub(crate) fn beta_features_enabled() -> bool {
     std::env::var_os("BETA_FEATURE").is_some()
 }
@@ -5,6 +5,7 @@ use crate::history_cell::with_border_with_inner_width;
 use crate::version::CODEX_CLI_VERSION;
 use codex_common::create_config_summary_entries;
 use codex_core::config::Config;
+use codex_core::protocol::ContextBreakdown;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::TokenUsage;
 use codex_protocol::ConversationId;
@@ -55,6 +56,8 @@ struct StatusHistoryCell {
     account: Option<StatusAccountDisplay>,
     session_id: Option<String>,
     token_usage: StatusTokenUsageData,
+    /// Estimated tokens per kind of content in the last request, largest first.
+    context_breakdown: Vec<(&'static str, u64)>,
     rate_limits: StatusRateLimitData,
 }
 
@@ -64,9 +67,17 @@ pub(crate) fn new_status_output(
     context_usage: Option<&TokenUsage>,
     session_id: &Option<ConversationId>,
     rate_limits: Option<&RateLimitSnapshotDisplay>,
+    context_breakdown: Option<&ContextBreakdown>,
 ) -> CompositeHistoryCell {
     let command = PlainHistoryCell::new(vec!["/status".magenta().into()]);
-    let card = StatusHistoryCell::new(config, total_usage, context_usage, session_id, rate_limits);
+    let card = StatusHistoryCell::new(
+        config,
+        total_usage,
+        context_usage,
+        session_id,
+        rate_limits,
+        context_breakdown,
+    );
 
     CompositeHistoryCell::new(vec![Box::new(command), Box::new(card)])
 }
@@ -78,6 +89,7 @@ impl StatusHistoryCell {
         context_usage: Option<&TokenUsage>,
         session_id: &Option<ConversationId>,
         rate_limits: Option<&RateLimitSnapshotDisplay>,
+        context_breakdown: Option<&ContextBreakdown>,
     ) -> Self {
         let config_entries = create_config_summary_entries(config);
         let (model_name, model_details) = compose_model_display(config, &config_entries);
@@ -108,6 +120,9 @@ impl StatusHistoryCell {
             output: total_usage.output_tokens,
             context_window,
         };
+        let context_breakdown = context_breakdown
+            .map(compose_context_breakdown)
+            .unwrap_or_default();
         let rate_limits = compose_rate_limit_data(rate_limits);
 
         Self {
@@ -120,6 +135,7 @@ impl StatusHistoryCell {
             account,
             session_id,
             token_usage,
+            context_breakdown,
             rate_limits,
         }
     }
@@ -158,6 +174,31 @@ impl StatusHistoryCell {
         ])
     }
 
+    fn context_breakdown_lines(&self, formatter: &FieldFormatter) -> Vec<Line<'static>> {
+        let total: u64 = self
+            .context_breakdown
+            .iter()
+            .map(|(_, tokens)| tokens)
+            .sum();
+        self.context_breakdown
+            .iter()
+            .enumerate()
+            .map(|(index, (label, tokens))| {
+                let percent = (*tokens as f64 / total.max(1) as f64 * 100.0).round();
+                let spans = vec![
+                    Span::from(format!("{label} ")),
+                    Span::from(format_tokens_compact(*tokens)),
+                    Span::from(format!(" ({percent}%)")).dim(),
+                ];
+                if index == 0 {
+                    formatter.line("Context usage", spans)
+                } else {
+                    formatter.continuation(spans)
+                }
+            })
+            .collect()
+    }
+
     fn rate_limit_lines(
         &self,
         available_inner_width: usize,
@@ -273,6 +314,9 @@ impl HistoryCell for StatusHistoryCell {
         if self.token_usage.context_window.is_some() {
             push_label(&mut labels, &mut seen, "Context window");
         }
+        if !self.context_breakdown.is_empty() {
+            push_label(&mut labels, &mut seen, "Context usage");
+        }
         self.collect_rate_limit_labels(&mut seen, &mut labels);
 
         let formatter = FieldFormatter::from_labels(labels.iter().map(String::as_str));
@@ -310,6 +354,7 @@ impl HistoryCell for StatusHistoryCell {
         if let Some(spans) = self.context_window_spans() {
             lines.push(formatter.line("Context window", spans));
         }
+        lines.extend(self.context_breakdown_lines(&formatter));
 
         lines.extend(self.rate_limit_lines(available_inner_width, &formatter));
 
@@ -323,3 +368,17 @@ impl HistoryCell for StatusHistoryCell {
         with_border_with_inner_width(truncated_lines, inner_width)
     }
 }
+
+fn compose_context_breakdown(breakdown: &ContextBreakdown) -> Vec<(&'static str, u64)> {
+    let mut entries: Vec<(&'static str, u64)> = vec![
+        ("instructions", breakdown.instructions),
+        ("tools", breakdown.tools),
+        ("user messages", breakdown.user_messages),
+        ("assistant messages", breakdown.assistant_messages),
+        ("reasoning", breakdown.reasoning),
+        ("tool calls", breakdown.tool_calls),
+    ];
+    entries.retain(|(_, tokens)| *tokens > 0);
+    entries.sort_by(|a, b| b.1.cmp(&a.1));
+    entries
+}
@@ -0,0 +1,254 @@
+//! Local token counting for model input.
+//!
+//! The BPE vocabularies are compiled into the binary, so counting works
+//! offline and without a round trip to the model provider.
+
+use std::fmt;
+
+use tiktoken_rs::CoreBPE;
+use tiktoken_rs::Rank;
+
+/// Upper bound on the bytes handed to the encoder at once. BPE merging is
+/// quadratic in the length of a pre-tokenized piece, so long runs without
+/// whitespace (base64 blobs, minified code) are split to keep encoding
+/// roughly linear in the size of the input.
+const MAX_CHUNK_BYTES: usize = 1024;
+
+/// BPE vocabularies bundled with this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EncodingKind {
+    /// Used by the GPT-4o, GPT-4.1, GPT-5 and o-series families.
+    O200kBase,
+    /// Used by GPT-4 and GPT-3.5.
+    Cl100kBase,
+}
+
+impl EncodingKind {
+    /// Picks the vocabulary for `model`, falling back to `o200k_base` for
+    /// models that are unknown or newer than the ones listed here.
+    pub fn for_model(model: &str) -> Self {
+        let model = model.rsplit('/').next().unwrap_or(model);
+        if (model.starts_with("gpt-4")
+            && !model.starts_with("gpt-4o")
+            && !model.starts_with("gpt-4.1"))
+            || model.starts_with("gpt-3.5")
+        {
+            Self::Cl100kBase
+        } else {
+            Self::O200kBase
+        }
+    }
+}
+
+impl fmt::Display for EncodingKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::O200kBase => f.write_str("o200k_base"),
+            Self::Cl100kBase => f.write_str("cl100k_base"),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("failed to load {kind} encoding: {message}")]
+pub struct TokenizerError {
+    kind: EncodingKind,
+    message: String,
+}
+
+/// A byte-pair encoder for one of the bundled vocabularies.
+///
+/// Building a tokenizer parses the whole vocabulary, so callers should
+/// construct it once and share it.
+pub struct Tokenizer {
+    kind: EncodingKind,
+    bpe: CoreBPE,
+}
+
+impl fmt::Debug for Tokenizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tokenizer")
+            .field("kind", &self.kind)
+            .finish()
+    }
+}
+
+impl Tokenizer {
+    pub fn new(kind: EncodingKind) -> Result<Self, TokenizerError> {
+        let bpe = match kind {
+            EncodingKind::O200kBase => tiktoken_rs::o200k_base(),
+            EncodingKind::Cl100kBase => tiktoken_rs::cl100k_base(),
+        }
+        .map_err(|err| TokenizerError {
+            kind,
+            message: err.to_string(),
+        })?;
+        Ok(Self { kind, bpe })
+    }
+
+    pub fn for_model(model: &str) -> Result<Self, TokenizerError> {
+        Self::new(EncodingKind::for_model(model))
+    }
+
+    pub fn kind(&self) -> EncodingKind {
+        self.kind
+    }
+
+    /// Encodes `text` as ordinary text; special-token markers such as
+    /// `<|endoftext|>` are tokenized literally.
+    pub fn encode(&self, text: &str) -> Vec<Rank> {
+        split_chunks(text)
+            .flat_map(|chunk| self.bpe.encode_ordinary(chunk))
+            .collect()
+    }
+
+    pub fn count(&self, text: &str) -> u64 {
+        self.encode(text).len() as u64
+    }
+
+    /// Decodes `tokens`, replacing byte sequences that are not valid UTF-8
+    /// (e.g. a character split across a token boundary) with U+FFFD.
+    pub fn decode_lossy(&self, tokens: &[Rank]) -> String {
+        String::from_utf8_lossy(&self.decode_bytes(tokens)).into_owned()
+    }
+
+    /// Truncates the middle of `text` so that at most `max_tokens` tokens of
+    /// the original remain, split evenly between the beginning and the end.
+    /// Returns the possibly truncated text and `Some(original_token_count)`
+    /// if truncation occurred.
+    pub fn truncate_middle(&self, text: &str, max_tokens: usize) -> (String, Option<u64>) {
+        let tokens = self.encode(text);
+        if tokens.len() <= max_tokens {
+            return (text.to_string(), None);
+        }
+
+        let total = tokens.len() as u64;
+        let head_len = max_tokens / 2;
+        let tail_len = max_tokens - head_len;
+        let head = self.decode_bytes(&tokens[..head_len]);
+        let tail = self.decode_bytes(&tokens[tokens.len() - tail_len..]);
+        let head = valid_utf8_prefix(&head);
+        let tail = valid_utf8_suffix(&tail);
+        let removed = total - max_tokens as u64;
+
+        let mut out = String::with_capacity(head.len() + tail.len() + 32);
+        out.push_str(head);
+        if !head.is_empty() && !head.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str(&format!("…{removed} tokens truncated…"));
+        if !tail.is_empty() && !tail.starts_with('\n') {
+            out.push('\n');
+        }
+        out.push_str(tail);
+        (out, Some(total))
+    }
+
+    fn decode_bytes(&self, tokens: &[Rank]) -> Vec<u8> {
+        self.bpe
+            ._decode_native_and_split(tokens.to_vec())
+            .flatten()
+            .collect()
+    }
+}
+
+/// Splits `text` into chunks of at most [`MAX_CHUNK_BYTES`], preferring to
+/// cut right before whitespace so the chunks tokenize like the whole text.
+fn split_chunks(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let mut end = rest.len();
+        if end > MAX_CHUNK_BYTES {
+            end = MAX_CHUNK_BYTES;
+            while !rest.is_char_boundary(end) {
+                end -= 1;
+            }
+            if let Some(space) = rest[..end].rfind(char::is_whitespace)
+                && space > 0
+            {
+                end = space;
+            }
+        }
+        let (chunk, tail) = rest.split_at(end);
+        rest = tail;
+        Some(chunk)
+    })
+}
+
+fn valid_utf8_prefix(bytes: &[u8]) -> &str {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(err) => std::str::from_utf8(&bytes[..err.valid_up_to()]).unwrap_or_default(),
+    }
+}
+
+fn valid_utf8_suffix(bytes: &[u8]) -> &str {
+    // A UTF-8 character is at most four bytes, so at most three leading
+    // continuation bytes belong to a character cut off by the token boundary.
+    for skip in 0..bytes.len().min(4) {
+        if let Ok(text) = std::str::from_utf8(&bytes[skip..]) {
+            return text;
+        }
+    }
+    ""
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn counts_and_round_trips_text() {
+        let tokenizer = Tokenizer::new(EncodingKind::O200kBase).expect("load o200k_base");
+        let text = "Hello, world! Ünïcødé and <|endoftext|> stay intact.";
+        let tokens = tokenizer.encode(text);
+
+        assert_eq!(tokenizer.count("hello world"), 2);
+        assert_eq!(tokenizer.decode_lossy(&tokens), text);
+    }
+
+    #[test]
+    fn truncate_middle_keeps_both_ends_within_budget() {
+        let tokenizer = Tokenizer::new(EncodingKind::O200kBase).expect("load o200k_base");
+        let text = (0..200)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let (out, original) = tokenizer.truncate_middle(&text, 20);
+
+        assert_eq!(original, Some(tokenizer.count(&text)));
+        assert!(out.starts_with("line 0\n"), "{out}");
+        assert!(out.ends_with("line 199"), "{out}");
+        assert!(out.contains("tokens truncated"), "{out}");
+        assert_eq!(
+            tokenizer.truncate_middle("short", 20),
+            ("short".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn picks_encoding_by_model() {
+        assert_eq!(EncodingKind::for_model("gpt-4"), EncodingKind::Cl100kBase);
+        assert_eq!(
+            EncodingKind::for_model("gpt-3.5-turbo"),
+            EncodingKind::Cl100kBase
+        );
+        assert_eq!(
+            EncodingKind::for_model("gpt-4o-mini"),
+            EncodingKind::O200kBase
+        );
+        assert_eq!(
+            EncodingKind::for_model("gpt-5-codex"),
+            EncodingKind::O200kBase
+        );
+        assert_eq!(
+            EncodingKind::for_model("openai/gpt-oss-20b"),
+            EncodingKind::O200kBase
+        );
+    }
+}